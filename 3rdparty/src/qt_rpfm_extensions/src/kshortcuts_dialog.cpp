@@ -21,6 +21,8 @@ extern "C" void shortcut_collection_init(QWidget* parent, QList<QObject*>* short
     new_action(pack_menu_actions, "save_pack_as", "Save Pack As", Qt::ShortcutContext::ApplicationShortcut, QKeySequence::listFromString("Ctrl+Shift+S"), "document-save-as");
     new_action(pack_menu_actions, "install_pack", "Install Pack", Qt::ShortcutContext::ApplicationShortcut, QKeySequence::listFromString("Ctrl+Shift+I"), "format-align-vertical-top");
     new_action(pack_menu_actions, "uninstall_pack", "Uninstall Pack", Qt::ShortcutContext::ApplicationShortcut, QKeySequence::listFromString("Ctrl+Shift+U"), "format-align-vertical-bottom");
+    new_action(pack_menu_actions, "undo_pack_edit", "Undo Pack Edit", Qt::ShortcutContext::ApplicationShortcut, QKeySequence::listFromString("Ctrl+Alt+Z"), "edit-undo");
+    new_action(pack_menu_actions, "redo_pack_edit", "Redo Pack Edit", Qt::ShortcutContext::ApplicationShortcut, QKeySequence::listFromString("Ctrl+Alt+Y"), "edit-redo");
     new_action(pack_menu_actions, "load_all_ca_packs", "Load All CA Packs", Qt::ShortcutContext::ApplicationShortcut, QKeySequence::listFromString("Ctrl+G"), "dialog-object-properties");
     new_action(pack_menu_actions, "settings", "Settings", Qt::ShortcutContext::ApplicationShortcut, QKeySequence::listFromString("Ctrl+P"), "settings-configure");
     new_action(pack_menu_actions, "quit", "Quit", Qt::ShortcutContext::ApplicationShortcut, QKeySequence::listFromString(""), "gtk-quit");
@@ -97,6 +99,7 @@ extern "C" void shortcut_collection_init(QWidget* parent, QList<QObject*>* short
     new_action(pack_tree_actions, "add_file", "Add File", Qt::ShortcutContext::WidgetShortcut, QKeySequence::listFromString("Ctrl+A"), "document-new-symbolic");
     new_action(pack_tree_actions, "add_folder", "Add Folder", Qt::ShortcutContext::WidgetShortcut, QKeySequence::listFromString("Ctrl+Shift+A"), "tab-new-symbolic");
     new_action(pack_tree_actions, "add_from_pack", "Add From Pack", Qt::ShortcutContext::WidgetShortcut, QKeySequence::listFromString("Ctrl+Alt+A"), "labplot-workbook-new");
+    new_action(pack_tree_actions, "add_from_assembly_kit", "Add From Assembly Kit", Qt::ShortcutContext::WidgetShortcut, QKeySequence::listFromString(""), "labplot-workbook-new");
     new_action(pack_tree_actions, "new_folder", "New Folder", Qt::ShortcutContext::WidgetShortcut, QKeySequence::listFromString("Ctrl+F"), "albumfolder-new");
     new_action(pack_tree_actions, "new_animpack", "New AnimPack", Qt::ShortcutContext::WidgetShortcut, QKeySequence::listFromString(""), "document-new");
     new_action(pack_tree_actions, "new_db", "New DB", Qt::ShortcutContext::WidgetShortcut, QKeySequence::listFromString("Ctrl+D"), "document-new");
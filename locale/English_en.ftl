@@ -66,6 +66,8 @@ save_packfile = Save PackFile
 save_packfile_as = Save PackFile As…
 packfile_install = Install
 packfile_uninstall = Uninstall
+packfile_undo_edit = Undo Pack Edit
+packfile_redo_edit = Redo Pack Edit
 load_all_ca_packfiles = Load All CA PackFiles
 settings = Settings
 quit = Quit
@@ -190,6 +192,8 @@ tt_packfile_save_packfile = Save the changes made in the currently open PackFile
 tt_packfile_save_packfile_as = Save the currently open PackFile as a new PackFile, instead of overwriting the original one.
 tt_packfile_install = Copy the currently selected PackFile into the data folder of the GameSelected.
 tt_packfile_uninstall = Removes the currently selected PackFile from the data folder of the GameSelected.
+tt_packfile_undo_edit = Undoes the last edition done to multiple files of the Pack at once, like a cascade edition.
+tt_packfile_redo_edit = Redoes the last edition undone with "Undo Pack Edit".
 tt_packfile_load_all_ca_packfiles = Try to load every PackedFile from every vanilla PackFile of the selected game into RPFM at the same time, using lazy-loading to load the PackedFiles. Keep in mind that if you try to save it, your PC may die.
 tt_packfile_settings = Open the Settings dialog.
 tt_packfile_quit = Exit the Program.
@@ -306,6 +310,7 @@ treeview_collapse_all = Collapse All
 tt_context_menu_add_file = Add one or more files to the currently open PackFile. Existing files are not overwritten!
 tt_context_menu_add_folder = Add a folder to the currently open PackFile. Existing files are not overwritten!
 tt_context_menu_add_from_packfile = Add files from another PackFile to the currently open PackFile. Existing files are not overwritten!
+tt_context_menu_add_from_assembly_kit = Import tables from the Assembly Kit of the game selected as DB files into the currently open PackFile.
 tt_context_menu_check_tables = Check all the DB Tables of the currently open PackFile for dependency errors.
 tt_context_menu_new_folder = Open the dialog to create an empty folder. Due to how the PackFiles are done, these are NOT KEPT ON SAVING if they stay empty.
 tt_context_menu_new_packed_file_anim_pack = Open the dialog to create an AnimPack.
@@ -368,6 +373,7 @@ context_menu_add_files = Add File/s
 context_menu_add_folder = Add Folder
 context_menu_add_folders = Add Folder/s
 context_menu_add_from_packfile = Add from PackFile
+context_menu_add_from_assembly_kit = Add from Assembly Kit
 context_menu_select_packfile = Select PackFile
 context_menu_extract_packfile = Extract PackFile
 
@@ -426,6 +432,9 @@ settings_paths_mymod_ph = This is the folder where you want to store all "MyMod"
 settings_paths_zip = 7Zip Exe's Path
 settings_paths_zip_ph = This is the full path to 7Zip's executable.
 
+settings_paths_installed_mods = Installed Mods as Dependencies
+settings_paths_installed_mods_ph = Sources of installed mods to use as dependencies, separated by ';' and in order of priority. Use 'data', 'local_mods' or the path of a folder with Packs.
+
 settings_game_label = Game Folder
 settings_asskit_label = Assembly Kit Folder
 settings_game_line_ph = This is the folder where you have {"{"}{"}"} installed, where the .exe is.
@@ -913,6 +922,13 @@ invalid_dependency_pack_file_name_explanation = One of the PackFile Names in the
 
 pfs_button_apply = Apply Settings
 cascade_edition_dialog = Rename References
+cascade_edition_preview_dialog = Rename References - Review Changes
+cascade_edition_preview_instructions = These are the changes that will be done to the Pack. Accept to apply them, or cancel to leave the Pack as it is. They can be undone later with "Undo Pack Edit".
+cascade_edition_preview_cell = {"{"}{"}"}: row {"{"}{"}"}, column {"{"}{"}"}: '{"{"}{"}"}' → '{"{"}{"}"}'
+cascade_edition_preview_file = {"{"}{"}"}: text replaced
+cascade_edition_preview_rename = {"{"}{"}"} → {"{"}{"}"}
+cascade_edition_preview_cancel = Cancel
+cascade_edition_preview_external = These files outside the Pack use the renamed values. They will not be updated, so review them after applying the changes:
 template_load_final_message = And with that, the template is done. Make sure to follow the steps here in case the template needs them.
 is_required = Is Required
 context_menu_generate_ids = Generate Ids
@@ -1093,6 +1109,7 @@ global_search_source_packfile = Packfile
 global_search_source_parent = Parent Files
 global_search_source_game = Game Files
 global_search_source_asskit = Assembly Kit Tables
+global_search_source_installed_mods = Installed Mods
 menu_bar_tools = Tools
 tools_faction_painter = Faction Painter
 faction_painter_title = Faction Painter
@@ -1106,6 +1123,9 @@ restore_vanilla_values = Restore Vanilla Values
 packed_file_name = PackedFile Name
 tools_unit_editor = Unit Editor
 unit_editor_title = Unit Editor
+tools_mod_conflicts = Mod Conflicts
+mod_conflicts_title = Mod Conflicts
+mod_conflicts_select_packs = Select the Packs to check for conflicts
 
 settings_enable_esf_editor = Enable ESF/CCD/SAVE Editor (EXPERIMENTAL):
 tt_settings_debug_enable_esf_editor = This setting allows you to enable the new ESF editor (experimental), but beware of issues.
@@ -1196,9 +1216,21 @@ import_schema_patch = Import Schema Patch
 import_schema_patch_title = Import Schema Patch
 import_schema_patch_button = Import Patch
 import_schema_patch_success = Patch imported correctly.
+upload_schema_overlay = Upload Local Schema Overlay
+upload_schema_overlay_success = Local definitions and patches uploaded correctly.
 label_value_cannot_be_empty = Value Cannot be Empty:
 value_cannot_be_empty_explanation = The value of this column cannot be empty. This basically means your game may crash if you leave a value of this column empty.
     If you think this is a false positive, feel free to submit a schema patch to fix it.
+label_validation_rule_broken = Validation Rule Broken:
+validation_rule_broken_explanation = The value of this column breaks one of the validation rules the schema patches define for it, like ranges, patterns or allowed values. These rules usually come from values known to crash the game.
+label_invalid_validation_rule = Invalid Validation Rule:
+invalid_validation_rule_explanation = One of the validation rules the schema patches define for this table cannot be used, like a regex that doesn't compile or a rule referring to a column that doesn't exist. Until it's fixed, none of the validation rules of the table are checked.
+    If you think this is a false positive, feel free to submit a schema patch to fix it.
+label_outdated_game_build = Outdated Game Build:
+outdated_game_build_explanation = This Pack was made for an older build of the game. Its tables may use definitions from that build, and may need to be updated to work with the current one.
+label_dependencies_cache_baseline = Dependencies Cache Baseline:
+dependencies_cache_baseline_explanation = The version of the game whose vanilla data these diagnostics were checked against. Packs made for an older version of the game are checked against the dependencies cache of said version, if there is one.
+    Generating the dependencies cache after a game update doesn't replace the ones for older versions of the game, so they're kept for this.
 
 context_menu_patch_column = Patch Column Definition
 new_schema_patch_dialog = Schema Patcher
@@ -1322,3 +1354,9 @@ new_portrait_settings_copy_to_column = Copy values to this entry
 live_export_success = Script and UI folders exported correctly.
 include_base_folder_on_add_from_folder = Include Parent Folder when Adding a Folder
 settings_include_base_folder_on_add_from_folder = When using Add From Folder, it adds the selected folder instead of it's parent contents to the Pack.
+
+assembly_kit_import_title = Add from Assembly Kit
+assembly_kit_import_instructions = Select the Assembly Kit tables to import. Each one will be added as a DB file with the following name.
+assembly_kit_import_file_name = Name of the new DB files
+assembly_kit_import_accept = Import
+assembly_kit_import_problems = The tables have been imported, but some data could not be imported as it was: {"{"}{"}"}
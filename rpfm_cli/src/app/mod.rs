@@ -18,6 +18,7 @@ use std::path::PathBuf;
 
 use rpfm_lib::games::pfh_file_type::PFHFileType;
 use rpfm_lib::games::supported_games::SupportedGames;
+use rpfm_lib::utils::game_version_from_string;
 
 //---------------------------------------------------------------------------//
 //                          Struct/Enum Definitions
@@ -161,6 +162,26 @@ pub enum CommandsDependencies {
     Generate {
 
         /// Path where the dependencies cache will be saved.
+        ///
+        /// If it's a folder, the cache is saved in it with the file name for the installed version of the game, so caches for other versions are kept.
+        #[arg(short = 'P', long, required = true, value_name = "PAK2_PATH")]
+        pak_path: PathBuf,
+
+        /// Path of the game the dependencies cache is for.
+        #[arg(short, long, required = true, value_name = "GAME_PATH")]
+        game_path: PathBuf,
+
+        /// Path of the assembly kit the dependencies cache is for.
+        ///
+        /// Optional.
+        #[arg(short, long, required = false, value_name = "ASSEMBLY_KIT_PATH")]
+        assembly_kit_path: Option<PathBuf>,
+    },
+
+    /// Update a dependencies cache generated from a game install, re-reading only the CA Packs that changed since it was generated.
+    Update {
+
+        /// Path of the dependencies cache to update.
         #[arg(short = 'P', long, required = true, value_name = "PAK2_PATH")]
         pak_path: PathBuf,
 
@@ -168,11 +189,71 @@ pub enum CommandsDependencies {
         #[arg(short, long, required = true, value_name = "GAME_PATH")]
         game_path: PathBuf,
 
+        /// Path of the assembly kit the dependencies cache is for.
+        ///
+        /// Optional. If not provided, the tables only in the assembly kit are reused from the current cache.
+        #[arg(short, long, required = false, value_name = "ASSEMBLY_KIT_PATH")]
+        assembly_kit_path: Option<PathBuf>,
+    },
+
+    /// Generate the dependencies cache for a specific game from a list of CA Packs, without needing the game installed.
+    GenerateFromPacks {
+
+        /// Path where the dependencies cache will be saved.
+        ///
+        /// If it's a folder, the cache is saved in it with the file name for the provided game version, so caches for other versions are kept.
+        #[arg(short = 'P', long, required = true, value_name = "PAK2_PATH")]
+        pak_path: PathBuf,
+
+        /// Path of the CA Packs the dependencies cache is generated from. It can be either a Pack or a folder with Packs.
+        #[arg(short, long, required = true, num_args = 1.., value_name = "PACK_PATH")]
+        pack_path: Vec<PathBuf>,
+
+        /// Version of the game the Packs are from, like `1.4.1`.
+        ///
+        /// Optional. If not provided, the cache is marked as for an unknown version.
+        #[arg(long, value_parser = game_version_from_str, value_name = "GAME_VERSION")]
+        game_version: Option<u32>,
+
+        /// Language of the game, like `en`. If provided, the `local_` Packs of other languages are skipped.
+        ///
+        /// Optional.
+        #[arg(short, long, required = false, value_name = "LANGUAGE")]
+        language: Option<String>,
+
         /// Path of the assembly kit the dependencies cache is for.
         ///
         /// Optional.
         #[arg(short, long, required = false, value_name = "ASSEMBLY_KIT_PATH")]
         assembly_kit_path: Option<PathBuf>,
+    },
+
+    /// List the dependencies caches for a specific game in a folder, with the game version each one is for.
+    List {
+
+        /// Path of the folder with the dependencies caches.
+        #[arg(short = 'P', long, required = true, value_name = "PAK2_FOLDER_PATH")]
+        pak_path: PathBuf,
+    },
+
+    /// Compare the vanilla data of two versions of a game, listing the files, DB rows and Loc keys added, removed or changed.
+    Diff {
+
+        /// Path of the schema to decode the tables with.
+        #[arg(short, long, required = true, value_name = "SCHEMA_PATH")]
+        schema_path: PathBuf,
+
+        /// Path of the dependencies cache of the old version. If it's a folder, it's used as the game folder of the old version instead.
+        #[arg(short, long, required = true, value_name = "OLD_PATH")]
+        old_path: PathBuf,
+
+        /// Path of the dependencies cache of the new version. If it's a folder, it's used as the game folder of the new version instead.
+        #[arg(short, long, required = true, value_name = "NEW_PATH")]
+        new_path: PathBuf,
+
+        /// Format to print the changelog in.
+        #[arg(short, long, default_value = "markdown", value_name = "FORMAT", value_parser = PossibleValuesParser::new(["markdown", "json", "tsv"]))]
+        format: String,
     }
 }
 
@@ -291,6 +372,7 @@ pub enum CommandsPack {
 
         /// Path of the dependencies cache to be used.
         ///
+        /// If it's a folder, the cache for the game version the Pack was made for is picked from it.
         /// If you don't have one, generate it with the `dependencies generate` command.
         #[arg(short = 'P', long, required = true, value_name = "PAK2_PATH")]
         pak_path: PathBuf,
@@ -304,6 +386,18 @@ pub enum CommandsPack {
         /// You can specify multiple packs to perform a diagnostics check over all of them.
         #[arg(short, long, required = true, num_args = 1.., value_name = "PACK_PATH")]
         pack_path: Vec<PathBuf>,
+
+        /// Game version to pick the dependencies cache for, as `major.minor.patch.build` or as a raw number, instead of the one the Pack was made for.
+        ///
+        /// Only used if the dependencies cache path is a folder.
+        #[arg(long, value_parser = game_version_from_str, value_name = "GAME_VERSION")]
+        game_version: Option<u32>,
+
+        /// Sources of installed mods to use as dependencies, in order of priority.
+        ///
+        /// Use `data` for the data folder of the game, `local_mods` for the folder of local mods, or a path to any folder with Packs.
+        #[arg(short, long, num_args = 1.., value_name = "INSTALLED_MODS_SOURCE")]
+        installed_mods: Vec<String>,
     },
 
     /// Merges all the Packs provided into a single Pack and saves it to the provided save path.
@@ -319,6 +413,180 @@ pub enum CommandsPack {
         #[arg(short, long, required = true, num_args = 1.., value_name = "SOURCE_PACK_PATHS")]
         source_pack_paths: Vec<PathBuf>,
     },
+
+    /// Imports tables from the raw data of an Assembly Kit into the Pack, as binary DB files.
+    ImportAssemblyKitTables {
+
+        /// Path of the Pack this operation will use.
+        #[arg(short, long, required = true, value_name = "PACK_PATH")]
+        pack_path: PathBuf,
+
+        /// Path of the schema for the game the Pack is for.
+        #[arg(short, long, required = true, value_name = "SCHEMA_PATH")]
+        schema_path: PathBuf,
+
+        /// Path of the folder with the raw tables of the Assembly Kit. Usually `assembly_kit/raw_data/db`.
+        #[arg(short, long, required = true, value_name = "RAW_DB_PATH")]
+        raw_db_path: PathBuf,
+
+        /// Name of the table to import, with the `_tables` suffix.
+        ///
+        /// This can be repeated as many times as tables you want to import.
+        #[arg(short, long, required = true, num_args = 1.., value_name = "TABLE_NAME")]
+        table_name: Vec<String>,
+
+        /// Name of the DB files to create inside the folder of each table.
+        #[arg(short, long, required = true, value_name = "FILE_NAME")]
+        file_name: String,
+    },
+
+    /// Exports the DB tables of the Pack to the raw data of an Assembly Kit, as raw tables and definitions.
+    ExportAssemblyKitTables {
+
+        /// Path of the Pack this operation will use.
+        #[arg(short, long, required = true, value_name = "PACK_PATH")]
+        pack_path: PathBuf,
+
+        /// Path of the schema for the game the Pack is for.
+        #[arg(short, long, required = true, value_name = "SCHEMA_PATH")]
+        schema_path: PathBuf,
+
+        /// Path of the folder to export the raw tables to. Usually `assembly_kit/raw_data/db`.
+        #[arg(short, long, required = true, value_name = "RAW_DB_PATH")]
+        raw_db_path: PathBuf,
+
+        /// Name of the table to export, with the `_tables` suffix. If not provided, all the tables in the Pack are exported.
+        ///
+        /// This can be repeated as many times as tables you want to export.
+        #[arg(short, long, num_args = 1.., value_name = "TABLE_NAME")]
+        table_name: Vec<String>,
+    },
+
+    /// Computes the order in which the game loads the provided Packs, and checks it for problems with their dependencies.
+    LoadOrder {
+
+        /// Path of the Packs this operation will use.
+        #[arg(short, long, required = true, num_args = 1.., value_name = "PACK_PATHS")]
+        pack_path: Vec<PathBuf>,
+
+        /// Path of a `used_mods.txt` file with the order of the mods. If not provided, mods are sorted by name.
+        #[arg(short, long, value_name = "USED_MODS_PATH")]
+        used_mods_path: Option<PathBuf>,
+
+        /// Path where the resulting load order will be saved, in the format of a `used_mods.txt` file.
+        #[arg(short, long, value_name = "OUTPUT_PATH")]
+        output_path: Option<PathBuf>,
+
+        /// Path of the game the Packs are for. Used to not add its data folder as working directory in the saved load order.
+        #[arg(short, long, value_name = "GAME_PATH")]
+        game_path: Option<PathBuf>,
+    },
+
+    /// Checks the provided Packs for files, table rows and loc keys defined in more than one of them.
+    Conflicts {
+
+        /// Path of the schema for the game the Packs are for.
+        #[arg(short, long, required = true, value_name = "SCHEMA_PATH")]
+        schema_path: PathBuf,
+
+        /// Path of the Packs this operation will use.
+        #[arg(short, long, required = true, num_args = 1.., value_name = "PACK_PATHS")]
+        pack_path: Vec<PathBuf>,
+
+        /// Path of a `used_mods.txt` file with the order of the mods. If not provided, mods are sorted by name.
+        #[arg(short, long, value_name = "USED_MODS_PATH")]
+        used_mods_path: Option<PathBuf>,
+
+        /// Return the results in json, instead of as text.
+        #[arg(short, long)]
+        json: bool,
+    },
+
+    /// Finds everything using a key of a table in the Pack/s, their parent mods and the game files: rows referencing it, loc entries derived from them,
+    /// and text files mentioning it. The results will be returned in json.
+    References {
+
+        /// Path of the game the Pack is for.
+        #[arg(short, long, required = true, value_name = "GAME_PATH")]
+        game_path: PathBuf,
+
+        /// Path of the dependencies cache to be used.
+        ///
+        /// If it's a folder, the cache for the game version the Pack was made for is picked from it.
+        #[arg(short = 'P', long, required = true, value_name = "PAK2_PATH")]
+        pak_path: PathBuf,
+
+        /// Path of the schema for the game the Pack/s is for.
+        #[arg(short, long, required = true, value_name = "SCHEMA_PATH")]
+        schema_path: PathBuf,
+
+        /// Path of the Pack this operation will use.
+        #[arg(short, long, required = true, num_args = 1.., value_name = "PACK_PATH")]
+        pack_path: Vec<PathBuf>,
+
+        /// Name of the table with the key, like `land_units_tables`.
+        #[arg(short, long, required = true, value_name = "TABLE_NAME")]
+        table_name: String,
+
+        /// Name of the column with the key.
+        #[arg(short, long, required = true, value_name = "COLUMN_NAME")]
+        column_name: String,
+
+        /// The key to look for.
+        #[arg(short, long, required = true, value_name = "KEY")]
+        key: String,
+    },
+
+    /// Imports a row from the parent mods and the game files into a Pack, along with every row and loc entry related to it,
+    /// so it can be used as the base of something new, like a new unit.
+    ImportRelated {
+
+        /// Path of the game the Pack is for.
+        #[arg(short, long, required = true, value_name = "GAME_PATH")]
+        game_path: PathBuf,
+
+        /// Path of the dependencies cache to be used.
+        ///
+        /// If it's a folder, the cache for the game version the Pack was made for is picked from it.
+        #[arg(short = 'P', long, required = true, value_name = "PAK2_PATH")]
+        pak_path: PathBuf,
+
+        /// Path of the schema for the game the Pack is for.
+        #[arg(short, long, required = true, value_name = "SCHEMA_PATH")]
+        schema_path: PathBuf,
+
+        /// Path of the Pack the rows will be imported into.
+        #[arg(short, long, required = true, value_name = "PACK_PATH")]
+        pack_path: PathBuf,
+
+        /// Name of the table with the row, like `land_units_tables`.
+        #[arg(short, long, required = true, value_name = "TABLE_NAME")]
+        table_name: String,
+
+        /// Name of the column with the key.
+        #[arg(short, long, required = true, value_name = "COLUMN_NAME")]
+        column_name: String,
+
+        /// Key of the row to import.
+        #[arg(short, long, required = true, value_name = "KEY")]
+        key: String,
+
+        /// New key for the imported rows. It replaces the key in their key columns, in the columns referencing them, and in their loc keys.
+        ///
+        /// Only whole values are replaced. Referenced rows are imported as they are.
+        ///
+        /// Optional. If not provided, the rows are imported as they are.
+        #[arg(short, long, required = false, value_name = "NEW_KEY")]
+        new_key: Option<String>,
+
+        /// Import also the rows referenced by the related rows through other keys, like shared ones. Their references are not followed.
+        #[arg(short, long)]
+        referenced: bool,
+
+        /// Name of the tables the rows are imported into.
+        #[arg(short, long, default_value = "imported", value_name = "FILE_NAME")]
+        file_name: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -330,7 +598,150 @@ pub enum CommandsSchemas {
         /// Path where the schemas will be downloaded.
         #[arg(short, long, required = true, value_name = "SCHEMA_PATH")]
         schema_path: PathBuf,
-    }
+    },
+
+    /// Compare two revisions of a schema and print a changelog with the differences.
+    Diff {
+
+        /// Path of the old revision of the schema.
+        #[arg(short, long, required = true, value_name = "OLD_SCHEMA_PATH")]
+        old_schema_path: PathBuf,
+
+        /// Path of the new revision of the schema.
+        #[arg(short, long, required = true, value_name = "NEW_SCHEMA_PATH")]
+        new_schema_path: PathBuf,
+
+        /// If enabled, the changelog will be printed as JSON instead of Markdown.
+        #[arg(short, long)]
+        json: bool,
+    },
+
+    /// Test a schema by decoding every vanilla table of a dependencies cache with it.
+    ///
+    /// It fails if any vanilla table cannot be decoded. Unused definitions are only reported.
+    Test {
+
+        /// Path of the schema to test.
+        #[arg(short, long, required = true, value_name = "SCHEMA_PATH")]
+        schema_path: PathBuf,
+
+        /// Path of the dependencies cache to test the schema against.
+        #[arg(short = 'P', long, required = true, value_name = "PAK2_PATH")]
+        pak_path: PathBuf,
+    },
+
+    /// Find the localisation data of the vanilla tables of a dependencies cache: the order in which the keys of multikeyed tables form
+    /// their loc keys, and the localised fields of tables without them in the schema.
+    ///
+    /// The results are printed, along with the tables that cannot be resolved, and can be saved as schema patches.
+    LocKeyOrder {
+
+        /// Path of the schema to use.
+        #[arg(short, long, required = true, value_name = "SCHEMA_PATH")]
+        schema_path: PathBuf,
+
+        /// Path of the dependencies cache to check.
+        #[arg(short = 'P', long, required = true, value_name = "PAK2_PATH")]
+        pak_path: PathBuf,
+
+        /// Path to save the results to, as schema patches. Only the data that differs from the schema is saved.
+        #[arg(short, long, value_name = "PATCHES_PATH")]
+        patches_path: Option<PathBuf>,
+    },
+
+    /// Export every table definition of a schema to a standard format, one file per table and version.
+    Export {
+
+        /// Path of the schema to export.
+        #[arg(short, long, required = true, value_name = "SCHEMA_PATH")]
+        schema_path: PathBuf,
+
+        /// Folder where the exported files will be saved.
+        #[arg(short, long, required = true, value_name = "OUTPUT_PATH")]
+        output_path: PathBuf,
+
+        /// Format to export the definitions to.
+        #[arg(short, long, required = true, value_name = "FORMAT", value_parser = PossibleValuesParser::new(["json_schema", "sqlite", "postgresql", "typescript"]))]
+        format: String,
+    },
+
+    /// Export the reference graph of a schema, with the tables and the references between them.
+    ///
+    /// If a Pack is provided, the graph is built from its data instead, with the rows and the values referencing other rows.
+    Graph {
+
+        /// Path of the schema to use.
+        #[arg(short, long, required = true, value_name = "SCHEMA_PATH")]
+        schema_path: PathBuf,
+
+        /// Path of the file the graph will be saved to.
+        #[arg(short, long, required = true, value_name = "OUTPUT_PATH")]
+        output_path: PathBuf,
+
+        /// Format to save the graph in.
+        #[arg(short, long, required = true, value_name = "FORMAT", value_parser = PossibleValuesParser::new(["dot", "graphml", "json"]))]
+        format: String,
+
+        /// Path of the Pack to build a row-level graph from.
+        #[arg(short, long, value_name = "PACK_PATH")]
+        pack_path: Option<PathBuf>,
+
+        /// Only keep references from or to tables starting with this prefix.
+        ///
+        /// This can be repeated as many times as prefixes you want to keep.
+        #[arg(short, long, required = false, num_args = 1.., value_name = "TABLE_PREFIX")]
+        table_prefix: Vec<String>,
+    },
+
+    /// Infer the possible definitions of a table of a Pack with a version not in the schema, and print them ranked from most to least plausible.
+    ///
+    /// The newest definition of the table in the schema is used as template. Candidates should always be reviewed before adding them to a schema.
+    Infer {
+
+        /// Path of the schema to use.
+        #[arg(short, long, required = true, value_name = "SCHEMA_PATH")]
+        schema_path: PathBuf,
+
+        /// Path of the Pack containing the table.
+        #[arg(short, long, required = true, value_name = "PACK_PATH")]
+        pack_path: PathBuf,
+
+        /// Path of the table within the Pack.
+        #[arg(short, long, required = true, value_name = "TABLE_PATH")]
+        table_path: String,
+
+        /// Maximum amount of candidates to print.
+        #[arg(short, long, default_value_t = 5, value_name = "AMOUNT")]
+        candidates: usize,
+
+        /// If enabled, the most plausible candidate is added to the schema.
+        #[arg(short, long)]
+        add: bool,
+    },
+
+    /// List the contents of a local schema overlay, with the local definitions and patches of a game.
+    Overlay {
+
+        /// Path of the upstream schema the overlay goes on top of.
+        #[arg(short, long, required = true, value_name = "SCHEMA_PATH")]
+        schema_path: PathBuf,
+
+        /// Path of the overlay.
+        #[arg(short, long, required = true, value_name = "OVERLAY_PATH")]
+        overlay_path: PathBuf,
+
+        /// If enabled, the entries the upstream schema already covers are removed from the overlay.
+        #[arg(short, long)]
+        prune: bool,
+
+        /// Path to export a copy of the overlay to, to share it.
+        #[arg(short, long, value_name = "EXPORT_PATH")]
+        export_path: Option<PathBuf>,
+
+        /// If enabled, the contents of the overlay are uploaded, so they can be added to the upstream schema.
+        #[arg(short, long)]
+        upload: bool,
+    },
 }
 
 //---------------------------------------------------------------------------//
@@ -438,6 +849,11 @@ fn extract_from_csv(src: &str) -> Result<(String, PathBuf)> {
     Ok((String::new(), PathBuf::new()))
 }
 
+/// Game version validation function.
+fn game_version_from_str(src: &str) -> Result<u32> {
+    game_version_from_string(src).ok_or_else(|| anyhow!("Incorrect game version."))
+}
+
 /// Function to get the supported game keys.
 fn game_keys() -> Vec<&'static str> {
     let supported_games = SupportedGames::default();
@@ -15,7 +15,10 @@ use anyhow::{anyhow, Result};
 use std::path::{Path, PathBuf};
 
 use rpfm_extensions::dependencies::Dependencies;
+use rpfm_extensions::vanilla_diff::VanillaDiff;
 use rpfm_lib::integrations::log::*;
+use rpfm_lib::schema::Schema;
+use rpfm_lib::utils::game_version_to_string;
 
 use crate::config::Config;
 
@@ -33,8 +36,90 @@ pub fn generate(config: &Config, pak_path: &Path, game_path: &Path, assembly_kit
         Some(game_info) => {
 
             let mut dependencies = Dependencies::generate_dependencies_cache(game_info, game_path, assembly_kit_path)?;
+
+            // If we got a folder, save it with the file name of its game version, so the caches for other versions are kept.
+            let pak_path = if pak_path.is_dir() {
+                pak_path.join(game_info.dependencies_cache_file_name_by_game_version(*dependencies.game_version()))
+            } else {
+                pak_path.to_path_buf()
+            };
+
+            dependencies.save(&pak_path)?;
+
+            if config.verbose {
+                info!("Dependencies generated at path {}.", pak_path.to_string_lossy().to_string());
+            }
+
+            // Caches of the old split format are replaced by the one we just generated.
+            if let Some(folder_path) = pak_path.parent() {
+                Dependencies::remove_legacy_caches(folder_path, game_info)?;
+            }
+
+            Ok(())
+        }
+        None => Err(anyhow!("No Game provided.")),
+    }
+}
+
+/// This function updates the dependencies cache at the provided path with the changes in the CA Packs of the game_path provided.
+pub fn update(config: &Config, pak_path: &Path, game_path: &Path, assembly_kit_path: &Option<PathBuf>) -> Result<()> {
+    if config.verbose {
+        info!("Updating dependencies at the following path: {}.", pak_path.to_string_lossy().to_string());
+    }
+
+    match &config.game {
+        Some(game_info) => {
+
+            // Only caches generated from the game install can be updated. Check it before touching the file.
+            let dependencies = Dependencies::load(pak_path, &None)?;
+            if *dependencies.from_packs() {
+                return Err(anyhow!("The dependencies cache was generated from a list of Packs, not from the game install. Generate it again instead."));
+            }
+
+            if !dependencies.needs_updating(game_info, game_path)? && assembly_kit_path.is_none() {
+                if config.verbose {
+                    info!("Dependencies already up to date.");
+                }
+
+                return Ok(());
+            }
+
+            // Drop the loaded cache before replacing its file.
+            drop(dependencies);
+
+            let mut dependencies = Dependencies::update_dependencies_cache(pak_path, game_info, game_path, assembly_kit_path)?;
             dependencies.save(pak_path)?;
 
+            if config.verbose {
+                info!("Dependencies updated at path {}.", pak_path.to_string_lossy().to_string());
+            }
+
+            Ok(())
+        }
+        None => Err(anyhow!("No Game provided.")),
+    }
+}
+
+/// This function generates a dependencies cache from the CA Packs provided, without needing the game installed, and saves it to a file.
+pub fn generate_from_packs(config: &Config, pak_path: &Path, pack_paths: &[PathBuf], game_version: Option<u32>, language: &Option<String>, assembly_kit_path: &Option<PathBuf>) -> Result<()> {
+    if config.verbose {
+        info!("Generating dependencies from Packs at the following path: {}.", pak_path.to_string_lossy().to_string());
+    }
+
+    match &config.game {
+        Some(game_info) => {
+
+            let mut dependencies = Dependencies::generate_dependencies_cache_from_packs(game_info, pack_paths, language, game_version.unwrap_or(0), assembly_kit_path)?;
+
+            // If we got a folder, save it with the file name of its game version, so the caches for other versions are kept.
+            let pak_path = if pak_path.is_dir() {
+                pak_path.join(game_info.dependencies_cache_file_name_by_game_version(*dependencies.game_version()))
+            } else {
+                pak_path.to_path_buf()
+            };
+
+            dependencies.save(&pak_path)?;
+
             if config.verbose {
                 info!("Dependencies generated at path {}.", pak_path.to_string_lossy().to_string());
             }
@@ -44,3 +129,47 @@ pub fn generate(config: &Config, pak_path: &Path, game_path: &Path, assembly_kit
         None => Err(anyhow!("No Game provided.")),
     }
 }
+
+/// This function lists the dependencies caches for the game selected in the provided folder, with the game version each one is for.
+pub fn list(config: &Config, pak_path: &Path) -> Result<()> {
+    if config.verbose {
+        info!("Listing dependencies caches in the following folder: {}.", pak_path.to_string_lossy().to_string());
+    }
+
+    match &config.game {
+        Some(game_info) => {
+            for (game_version, path) in Dependencies::available_caches(pak_path, game_info)? {
+                let game_version = if game_version == 0 { "unknown".to_owned() } else { game_version_to_string(game_version) };
+                println!("{}\t{}", game_version, path.to_string_lossy());
+            }
+
+            Ok(())
+        }
+        None => Err(anyhow!("No Game provided.")),
+    }
+}
+
+/// This function compares the vanilla data of two versions of a game, from their dependencies caches or their game folders, and prints the changelog.
+pub fn diff(config: &Config, schema_path: &Path, old_path: &Path, new_path: &Path, format: &str) -> Result<()> {
+    if config.verbose {
+        info!("Comparing the vanilla data of {} and {}…", old_path.to_string_lossy().to_string(), new_path.to_string_lossy().to_string());
+    }
+
+    let schema = Schema::load(schema_path)?;
+    let diff = if old_path.is_dir() && new_path.is_dir() {
+        match &config.game {
+            Some(game_info) => VanillaDiff::from_game_paths(game_info, old_path, new_path, &schema)?,
+            None => return Err(anyhow!("No Game provided.")),
+        }
+    } else {
+        VanillaDiff::from_caches(old_path, new_path, &schema)?
+    };
+
+    match format {
+        "json" => println!("{}", diff.to_json()?),
+        "tsv" => print!("{}", diff.to_tsv()),
+        _ => println!("{}", diff.to_markdown()),
+    }
+
+    Ok(())
+}
@@ -14,17 +14,19 @@ use anyhow::{anyhow, Result};
 use rayon::prelude::*;
 
 use std::collections::BTreeMap;
-use std::io::{BufReader, BufWriter};
+use std::io::{BufReader, BufWriter, Write};
 use std::fs::File;
 use std::path::{Path, PathBuf};
 
-use rpfm_extensions::dependencies::Dependencies;
+use rpfm_extensions::conflicts::ConflictReport;
+use rpfm_extensions::dependencies::{Dependencies, InstalledModsSource};
 use rpfm_extensions::diagnostics::Diagnostics;
+use rpfm_extensions::load_order::LoadOrder;
 
 use rpfm_lib::binary::ReadBytes;
 use rpfm_lib::files::{ContainerPath, Container, Decodeable, DecodeableExtraData, Encodeable, EncodeableExtraData, FileType, pack::Pack};
-use rpfm_lib::games::pfh_file_type::PFHFileType;
-use rpfm_lib::integrations::log::*;
+use rpfm_lib::games::{GameInfo, pfh_file_type::PFHFileType};
+use rpfm_lib::integrations::{assembly_kit::{export_raw_tables, import_raw_tables}, log::*};
 use rpfm_lib::schema::Schema;
 use rpfm_lib::utils::last_modified_time_from_file;
 
@@ -264,7 +266,7 @@ pub fn extract(config: &Config, schema_path: &Option<PathBuf>, pack_path: &Path,
 
 
 /// This function diagnose problems in the provided Packs.
-pub fn diagnose(config: &Config, game_path: &Path, pak_path: &Path, schema_path: &Path, pack_paths: &[PathBuf]) -> Result<()> {
+pub fn diagnose(config: &Config, game_path: &Path, pak_path: &Path, schema_path: &Path, pack_paths: &[PathBuf], game_version: Option<u32>, installed_mods: &[String]) -> Result<()> {
     if config.verbose {
         info!("Diagnosing problems in the following Packs:");
         for pack_path in pack_paths {
@@ -282,6 +284,8 @@ pub fn diagnose(config: &Config, game_path: &Path, pak_path: &Path, schema_path:
     // Prepare the table's extra data,
     let mut extra_data = DecodeableExtraData::default();
     extra_data.set_schema(Some(&schema));
+    extra_data.set_table_index(true);
+    extra_data.set_game_build(config.game.as_ref().and_then(|game_info| pack.game_build(game_info, game_path)));
     let table_extra_data = Some(extra_data);
 
     // Decode the tables and locs from the packs, and return a list of the decoded tables for later.
@@ -300,8 +304,14 @@ pub fn diagnose(config: &Config, game_path: &Path, pak_path: &Path, schema_path:
         Some(game_info) => {
 
             // Build the dependencies cache for the game and generate the references for our specific Pack.
+            // The diagnosed Packs are never loaded as installed mods, as that would hide their own problems.
+            let installed_mods = installed_mods.iter().map(|source| InstalledModsSource::from(source.as_str())).collect::<Vec<_>>();
+            let pack_names = pack_paths.iter().filter_map(|path| path.file_name()).map(|name| name.to_string_lossy().to_string()).collect::<Vec<_>>();
+
+            let pak_path = dependencies_cache_path(config, game_info, game_path, pak_path, game_version.unwrap_or_else(|| pack.game_version()));
             let mut dependencies = Dependencies::default();
-            dependencies.rebuild(&Some(schema.clone()), pack.dependencies(), Some(pak_path), game_info, game_path)?;
+            dependencies.set_installed_mods(&installed_mods, &pack_names);
+            dependencies.rebuild(&Some(schema.clone()), pack.dependencies(), Some(&pak_path), game_info, game_path)?;
             dependencies.generate_local_db_references(&pack, &tables);
 
             // Trigger a diagnostics check.
@@ -347,3 +357,251 @@ pub fn merge(config: &Config, save_pack_path: &Path, source_pack_paths: &[PathBu
         None => Err(anyhow!("No Game provided.")),
     }
 }
+
+/// This function imports the provided tables from the raw data of an Assembly Kit into the provided Pack.
+pub fn import_assembly_kit_tables(config: &Config, pack_path: &Path, schema_path: &Path, raw_db_path: &Path, table_names: &[String], file_name: &str) -> Result<()> {
+    if config.verbose {
+        info!("Importing Assembly Kit tables into a Pack at {}.", pack_path.to_string_lossy().to_string());
+    }
+
+    match &config.game {
+        Some(game) => {
+            let schema = Schema::load(schema_path)?;
+
+            let pack_path_str = pack_path.to_string_lossy().to_string();
+            let mut reader = BufReader::new(File::open(pack_path)?);
+            let mut extra_data = DecodeableExtraData::default();
+
+            extra_data.set_disk_file_path(Some(&pack_path_str));
+            extra_data.set_timestamp(last_modified_time_from_file(reader.get_ref())?);
+            extra_data.set_data_size(reader.len()?);
+
+            let mut pack = Pack::decode(&mut reader, &Some(extra_data))?;
+            let report = import_raw_tables(&mut pack, &schema, raw_db_path, game.raw_db_version(), table_names, file_name)?;
+
+            if config.verbose {
+                for path in report.paths() {
+                    info!("Imported table: {}", path.path_raw());
+                }
+            }
+
+            if report.has_problems() {
+                warn!("Some data could not be imported as it was:\n{}", report);
+            }
+
+            pack.preload()?;
+
+            let mut writer = BufWriter::new(File::create(pack_path)?);
+            pack.encode(&mut writer, &None)?;
+
+            if config.verbose {
+                info!("Assembly Kit tables imported.");
+            }
+
+            Ok(())
+        }
+        None => Err(anyhow!("No Game provided.")),
+    }
+}
+
+/// This function exports the DB tables of the provided Pack to the raw data of an Assembly Kit.
+pub fn export_assembly_kit_tables(config: &Config, pack_path: &Path, schema_path: &Path, raw_db_path: &Path, table_names: &[String]) -> Result<()> {
+    if config.verbose {
+        info!("Exporting tables from a Pack at {} to the Assembly Kit.", pack_path.to_string_lossy().to_string());
+    }
+
+    match &config.game {
+        Some(game) => {
+            let schema = Schema::load(schema_path)?;
+
+            let pack_path_str = pack_path.to_string_lossy().to_string();
+            let mut reader = BufReader::new(File::open(pack_path)?);
+            let mut extra_data = DecodeableExtraData::default();
+
+            extra_data.set_disk_file_path(Some(&pack_path_str));
+            extra_data.set_timestamp(last_modified_time_from_file(reader.get_ref())?);
+            extra_data.set_data_size(reader.len()?);
+
+            let mut pack = Pack::decode(&mut reader, &Some(extra_data))?;
+            let paths = export_raw_tables(&mut pack, &schema, raw_db_path, game.raw_db_version(), table_names)?;
+
+            if config.verbose {
+                for path in &paths {
+                    info!("Exported file: {}", path.to_string_lossy());
+                }
+
+                info!("Tables exported to the Assembly Kit.");
+            }
+
+            Ok(())
+        }
+        None => Err(anyhow!("No Game provided.")),
+    }
+}
+
+/// This function computes the load order of the provided Packs, and reports the problems found in it.
+pub fn load_order(config: &Config, pack_paths: &[PathBuf], used_mods_path: &Option<PathBuf>, output_path: &Option<PathBuf>, game_path: &Option<PathBuf>) -> Result<()> {
+    if config.verbose {
+        info!("Computing the load order of the following Packs:");
+        for pack_path in pack_paths {
+            info!(" - {}", pack_path.to_string_lossy().to_string());
+        }
+    }
+
+    let mod_order = match used_mods_path {
+        Some(used_mods_path) => Some(LoadOrder::mod_order_from_used_mods(&std::fs::read_to_string(used_mods_path)?)),
+        None => None,
+    };
+
+    let load_order = LoadOrder::from_paths(pack_paths, mod_order.as_deref())?;
+    print!("{load_order}");
+
+    if let Some(output_path) = output_path {
+        let data_path = match (&config.game, game_path) {
+            (Some(game), Some(game_path)) => Some(game.data_path(game_path)?),
+            _ => None,
+        };
+
+        let mut file = BufWriter::new(File::create(output_path)?);
+        file.write_all(load_order.used_mods(data_path.as_deref()).as_bytes())?;
+
+        if config.verbose {
+            info!("Load order saved to {}.", output_path.to_string_lossy());
+        }
+    }
+
+    Ok(())
+}
+
+/// This function checks the provided Packs for conflicts between them, in the order the game loads them.
+pub fn conflicts(config: &Config, schema_path: &Path, pack_paths: &[PathBuf], used_mods_path: &Option<PathBuf>, json: bool) -> Result<()> {
+    if config.verbose {
+        info!("Checking conflicts between the following Packs:");
+        for pack_path in pack_paths {
+            info!(" - {}", pack_path.to_string_lossy().to_string());
+        }
+    }
+
+    let mod_order = match used_mods_path {
+        Some(used_mods_path) => Some(LoadOrder::mod_order_from_used_mods(&std::fs::read_to_string(used_mods_path)?)),
+        None => None,
+    };
+
+    let schema = Schema::load(schema_path)?;
+    let load_order = LoadOrder::from_paths(pack_paths, mod_order.as_deref())?;
+    let report = ConflictReport::from_load_order(&load_order, &schema)?;
+
+    if json {
+        println!("{}", report.json()?);
+    } else {
+        print!("{report}");
+    }
+
+    Ok(())
+}
+
+/// This function finds everything using the provided key in the provided Packs, their parent mods and the game files, and prints it as json.
+#[allow(clippy::too_many_arguments)]
+pub fn references(config: &Config, game_path: &Path, pak_path: &Path, schema_path: &Path, pack_paths: &[PathBuf], table_name: &str, column_name: &str, key: &str) -> Result<()> {
+    if config.verbose {
+        info!("Looking for references to {} in {}/{}.", key, table_name, column_name);
+    }
+
+    match &config.game {
+        Some(game_info) => {
+            let schema = Schema::load(schema_path)?;
+            let mut pack = Pack::read_and_merge(pack_paths, true, false)?;
+
+            let mut extra_data = DecodeableExtraData::default();
+            extra_data.set_schema(Some(&schema));
+            extra_data.set_table_index(true);
+            extra_data.set_game_build(pack.game_build(game_info, game_path));
+            let extra_data = Some(extra_data);
+
+            pack.files_by_type_mut(&[FileType::DB, FileType::Loc])
+                .par_iter_mut()
+                .for_each(|file| { let _ = file.decode(&extra_data, true, false); });
+
+            let pak_path = dependencies_cache_path(config, game_info, game_path, pak_path, pack.game_version());
+            let mut dependencies = Dependencies::default();
+            dependencies.rebuild(&Some(schema.clone()), pack.dependencies(), Some(&pak_path), game_info, game_path)?;
+
+            let references = dependencies.references_to_key(&pack, &schema, table_name, column_name, key);
+            println!("{}", references.json()?);
+
+            Ok(())
+        }
+        None => Err(anyhow!("No Game provided.")),
+    }
+}
+
+/// This function imports the rows related to a key of a table from the parent mods and the game files into the provided Pack, optionally renaming the key.
+#[allow(clippy::too_many_arguments)]
+pub fn import_related(config: &Config, game_path: &Path, pak_path: &Path, schema_path: &Path, pack_path: &Path, table_name: &str, column_name: &str, key: &str, new_key: &Option<String>, include_referenced: bool, file_name: &str) -> Result<()> {
+    if config.verbose {
+        info!("Importing the rows related to {} in {}/{} into the Pack at {}.", key, table_name, column_name, pack_path.to_string_lossy().to_string());
+    }
+
+    match &config.game {
+        Some(game_info) => {
+            let schema = Schema::load(schema_path)?;
+
+            let pack_path_str = pack_path.to_string_lossy().to_string();
+            let mut reader = BufReader::new(File::open(pack_path)?);
+            let mut extra_data = DecodeableExtraData::default();
+
+            extra_data.set_disk_file_path(Some(&pack_path_str));
+            extra_data.set_timestamp(last_modified_time_from_file(reader.get_ref())?);
+            extra_data.set_data_size(reader.len()?);
+
+            let mut pack = Pack::decode(&mut reader, &Some(extra_data))?;
+
+            let mut extra_data = DecodeableExtraData::default();
+            extra_data.set_schema(Some(&schema));
+            extra_data.set_table_index(true);
+            extra_data.set_game_build(pack.game_build(game_info, game_path));
+            let extra_data = Some(extra_data);
+
+            pack.files_by_type_mut(&[FileType::DB, FileType::Loc])
+                .par_iter_mut()
+                .for_each(|file| { let _ = file.decode(&extra_data, true, false); });
+
+            let pak_path = dependencies_cache_path(config, game_info, game_path, pak_path, pack.game_version());
+            let mut dependencies = Dependencies::default();
+            dependencies.rebuild(&Some(schema.clone()), pack.dependencies(), Some(&pak_path), game_info, game_path)?;
+
+            let paths = dependencies.import_related_rows(&mut pack, &schema, table_name, column_name, key, new_key.as_deref(), include_referenced, file_name)?;
+            if paths.is_empty() {
+                return Err(anyhow!("No rows found for the key {} in {}/{}.", key, table_name, column_name));
+            }
+
+            pack.preload()?;
+
+            let mut writer = BufWriter::new(File::create(pack_path)?);
+            pack.encode(&mut writer, &None)?;
+
+            if config.verbose {
+                for path in &paths {
+                    info!("Imported: {}", path.path_raw());
+                }
+            }
+
+            Ok(())
+        }
+        None => Err(anyhow!("No Game provided.")),
+    }
+}
+
+/// This function returns the path of the dependencies cache to use. If the provided path is a folder of caches, the one for the provided game version is picked.
+fn dependencies_cache_path(config: &Config, game_info: &GameInfo, game_path: &Path, pak_path: &Path, game_version: u32) -> PathBuf {
+    if pak_path.is_dir() {
+        let (_, pak_path) = Dependencies::cache_for_game_version(pak_path, game_info, game_path, game_version);
+        if config.verbose {
+            info!("Using the dependencies cache at the following path: {}.", pak_path.to_string_lossy().to_string());
+        }
+
+        pak_path
+    } else {
+        pak_path.to_path_buf()
+    }
+}
@@ -10,12 +10,20 @@
 
 //! This module contains the `Schema` command functions.
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
-use std::path::Path;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
 
+use rpfm_extensions::dependencies::Dependencies;
+use rpfm_extensions::loc_key_order::LocKeyOrderReport;
+use rpfm_extensions::schema_check::SchemaCheckReport;
+use rpfm_lib::binary::ReadBytes;
+use rpfm_lib::files::{Container, Decodeable, DecodeableExtraData, pack::Pack};
 use rpfm_lib::integrations::{git::*, log::*};
-use rpfm_lib::schema::*;
+use rpfm_lib::schema::{*, diff::SchemaDiff, export::{export_schema, ExportFormat, SqlDialect}, graph::{GraphFormat, ReferenceGraph}, inference::infer_definitions, overlay::SchemaOverlay};
+use rpfm_lib::utils::last_modified_time_from_file;
 
 use crate::config::Config;
 
@@ -39,6 +47,182 @@ pub fn update(config: &Config, schema_path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// This function prints the changes between two revisions of a schema, as Markdown or JSON.
+pub fn diff(config: &Config, old_schema_path: &Path, new_schema_path: &Path, json: bool) -> Result<()> {
+    if config.verbose {
+        info!("Comparing schemas…");
+    }
+
+    let old_schema = Schema::load(old_schema_path)?;
+    let new_schema = Schema::load(new_schema_path)?;
+    let diff = SchemaDiff::new(&old_schema, &new_schema);
+
+    if json {
+        println!("{}", diff.to_json()?);
+    } else {
+        println!("{}", diff.to_markdown());
+    }
+
+    Ok(())
+}
+
+/// This function decodes every vanilla table in the provided dependencies cache with the provided schema, and prints the results.
+pub fn test(config: &Config, schema_path: &Path, pak_path: &Path) -> Result<()> {
+    if config.verbose {
+        info!("Testing schema against the dependencies cache at {}…", pak_path.to_string_lossy().to_string());
+    }
+
+    let schema = Schema::load(schema_path)?;
+    let dependencies = Dependencies::load(pak_path, &None)?;
+    let report = SchemaCheckReport::new(&schema, &dependencies);
+
+    print!("{report}");
+
+    if report.errors().is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!("{} vanilla tables cannot be decoded with this schema.", report.errors().len()))
+    }
+}
+
+/// This function finds the localisation data of the vanilla tables in the provided dependencies cache, prints it, and optionally saves it as schema patches.
+pub fn loc_key_order(config: &Config, schema_path: &Path, pak_path: &Path, patches_path: &Option<PathBuf>) -> Result<()> {
+    if config.verbose {
+        info!("Checking loc keys of the dependencies cache at {}…", pak_path.to_string_lossy().to_string());
+    }
+
+    let schema = Schema::load(schema_path)?;
+    let dependencies = Dependencies::load(pak_path, &Some(schema.clone()))?;
+    let report = LocKeyOrderReport::new(&schema, &dependencies)?;
+
+    print!("{report}");
+
+    if let Some(patches_path) = patches_path {
+        let patches = report.patches(&schema);
+        let mut file = BufWriter::new(File::create(patches_path)?);
+        file.write_all(Schema::export_patches_to_str(&patches)?.as_bytes())?;
+
+        if config.verbose {
+            info!("{} table patches saved to {}.", patches.len(), patches_path.to_string_lossy());
+        }
+    }
+
+    Ok(())
+}
+
+/// This function exports every definition of the provided schema to the provided format.
+pub fn export(config: &Config, schema_path: &Path, output_path: &Path, format: &str) -> Result<()> {
+    if config.verbose {
+        info!("Exporting schema to {}…", output_path.to_string_lossy().to_string());
+    }
+
+    let format = match format {
+        "json_schema" => ExportFormat::JsonSchema,
+        "sqlite" => ExportFormat::Sql(SqlDialect::SQLite),
+        "postgresql" => ExportFormat::Sql(SqlDialect::PostgreSQL),
+        "typescript" => ExportFormat::TypeScript,
+        _ => return Err(anyhow!("Unknown export format: {}.", format)),
+    };
+
+    let schema = Schema::load(schema_path)?;
+    export_schema(&schema, format, output_path)?;
+
+    if config.verbose {
+        info!("Schema exported.");
+    }
+
+    Ok(())
+}
+
+/// This function saves the reference graph of the provided schema, or of the data of the provided Pack, to the provided format.
+pub fn graph(config: &Config, schema_path: &Path, output_path: &Path, format: &str, pack_path: &Option<PathBuf>, table_prefixes: &[String]) -> Result<()> {
+    if config.verbose {
+        info!("Exporting reference graph to {}…", output_path.to_string_lossy().to_string());
+    }
+
+    let format = match format {
+        "dot" => GraphFormat::Dot,
+        "graphml" => GraphFormat::GraphML,
+        "json" => GraphFormat::Json,
+        _ => return Err(anyhow!("Unknown graph format: {}.", format)),
+    };
+
+    let schema = Schema::load(schema_path)?;
+    let graph = match pack_path {
+        Some(pack_path) => {
+            let pack_path_str = pack_path.to_string_lossy().to_string();
+            let mut reader = BufReader::new(File::open(pack_path)?);
+            let mut extra_data = DecodeableExtraData::default();
+
+            extra_data.set_disk_file_path(Some(&pack_path_str));
+            extra_data.set_timestamp(last_modified_time_from_file(reader.get_ref())?);
+            extra_data.set_data_size(reader.len()?);
+
+            let mut pack = Pack::decode(&mut reader, &Some(extra_data))?;
+            ReferenceGraph::from_pack(&mut pack, &schema, table_prefixes)?
+        }
+        None => ReferenceGraph::from_schema(&schema, table_prefixes),
+    };
+
+    let mut file = BufWriter::new(File::create(output_path)?);
+    file.write_all(graph.to_format(format)?.as_bytes())?;
+
+    if config.verbose {
+        info!("Graph exported, with {} nodes and {} edges.", graph.nodes().len(), graph.edges().len());
+    }
+
+    Ok(())
+}
+
+/// This function prints the possible definitions of a table of the provided Pack, and optionally adds the most plausible one to the schema.
+pub fn infer(config: &Config, schema_path: &Path, pack_path: &Path, table_path: &str, candidates: usize, add: bool) -> Result<()> {
+    if config.verbose {
+        info!("Inferring definitions for {}…", table_path);
+    }
+
+    let mut schema = Schema::load(schema_path)?;
+
+    let pack_path_str = pack_path.to_string_lossy().to_string();
+    let mut reader = BufReader::new(File::open(pack_path)?);
+    let mut extra_data = DecodeableExtraData::default();
+
+    extra_data.set_disk_file_path(Some(&pack_path_str));
+    extra_data.set_timestamp(last_modified_time_from_file(reader.get_ref())?);
+    extra_data.set_data_size(reader.len()?);
+
+    let mut pack = Pack::decode(&mut reader, &Some(extra_data))?;
+    let file = pack.file_mut(table_path).ok_or_else(|| anyhow!("File {} not found in the Pack.", table_path))?;
+    let table_name = file.db_table_name_from_path().ok_or_else(|| anyhow!("File {} is not a DB table.", table_path))?.to_owned();
+    file.load()?;
+
+    let previous_definition = schema.definitions_by_table_name(&table_name)
+        .and_then(|definitions| definitions.iter().max_by_key(|definition| *definition.version()))
+        .cloned();
+
+    let inferred = infer_definitions(file.cached()?, previous_definition.as_ref(), None, &Default::default(), candidates)?;
+    if inferred.is_empty() {
+        return Err(anyhow!("No definition can decode {}.", table_path));
+    }
+
+    for (index, candidate) in inferred.iter().enumerate() {
+        println!("Candidate {} (version {}, score {:.2}, {} edits):", index + 1, candidate.definition().version(), candidate.score(), candidate.edits());
+        for field in candidate.definition().fields() {
+            println!("  - {}: {:?}{}", field.name(), field.field_type(), if field.is_key(None) { " (key)" } else { "" });
+        }
+    }
+
+    if add {
+        schema.add_definition(&table_name, inferred[0].definition());
+        schema.save(schema_path)?;
+
+        if config.verbose {
+            info!("Definition for version {} of {} added to the schema.", inferred[0].definition().version(), table_name);
+        }
+    }
+
+    Ok(())
+}
+
 /*
 pub fn to_json(config: &Config) -> Result<()> {
     if config.verbosity_level > 0 {
@@ -66,3 +250,47 @@ pub fn to_xml(config: &Config) -> Result<()> {
     result
 }
 */
+
+/// This function prints the contents of a local schema overlay, and optionally prunes, exports or uploads it.
+pub fn overlay(config: &Config, schema_path: &Path, overlay_path: &Path, prune: bool, export_path: &Option<PathBuf>, upload: bool) -> Result<()> {
+    let mut overlay = SchemaOverlay::load(overlay_path)?;
+
+    if prune {
+        let schema = Schema::load(schema_path)?;
+        let removed = overlay.prune(&schema);
+        overlay.save(overlay_path)?;
+
+        if config.verbose {
+            info!("Removed {} entries already covered by the upstream schema.", removed);
+        }
+    }
+
+    print!("{overlay}");
+
+    if let Some(export_path) = export_path {
+        overlay.save(export_path)?;
+
+        if config.verbose {
+            info!("Overlay exported to {}.", export_path.to_string_lossy());
+        }
+    }
+
+    if upload {
+        match &config.game {
+            Some(game) => {
+                if overlay.is_empty() {
+                    return Err(anyhow!("The overlay is empty. There is nothing to upload."));
+                }
+
+                overlay.upload(&crate::SENTRY_GUARD.read().unwrap(), game.display_name())?;
+
+                if config.verbose {
+                    info!("Overlay uploaded.");
+                }
+            }
+            None => return Err(anyhow!("No Game provided.")),
+        }
+    }
+
+    Ok(())
+}
@@ -86,6 +86,10 @@ fn main() {
 
         Commands::Dependencies { commands } => match commands {
             CommandsDependencies::Generate { pak_path, game_path, assembly_kit_path } => crate::commands::dependencies::generate(&config, &pak_path, &game_path, &assembly_kit_path),
+            CommandsDependencies::Update { pak_path, game_path, assembly_kit_path } => crate::commands::dependencies::update(&config, &pak_path, &game_path, &assembly_kit_path),
+            CommandsDependencies::GenerateFromPacks { pak_path, pack_path, game_version, language, assembly_kit_path } => crate::commands::dependencies::generate_from_packs(&config, &pak_path, &pack_path, game_version, &language, &assembly_kit_path),
+            CommandsDependencies::List { pak_path } => crate::commands::dependencies::list(&config, &pak_path),
+            CommandsDependencies::Diff { schema_path, old_path, new_path, format } => crate::commands::dependencies::diff(&config, &schema_path, &old_path, &new_path, &format),
         }
 
         Commands::Pack { commands } => match commands {
@@ -95,12 +99,25 @@ fn main() {
             CommandsPack::Delete { pack_path, file_path, folder_path } => crate::commands::pack::delete(&config, &pack_path, &file_path, &folder_path),
             CommandsPack::Extract { pack_path, tables_as_tsv, file_path, folder_path } => crate::commands::pack::extract(&config, &tables_as_tsv, &pack_path, &file_path, &folder_path),
             CommandsPack::SetFileType { pack_path, file_type } => crate::commands::pack::set_pack_type(&config, &pack_path, file_type),
-            CommandsPack::Diagnose { game_path, pak_path, schema_path, pack_path } => crate::commands::pack::diagnose(&config, &game_path, &pak_path, &schema_path, &pack_path),
+            CommandsPack::Diagnose { game_path, pak_path, schema_path, pack_path, game_version, installed_mods } => crate::commands::pack::diagnose(&config, &game_path, &pak_path, &schema_path, &pack_path, game_version, &installed_mods),
             CommandsPack::Merge { save_pack_path, source_pack_paths } => crate::commands::pack::merge(&config, &save_pack_path, &source_pack_paths),
+            CommandsPack::ImportAssemblyKitTables { pack_path, schema_path, raw_db_path, table_name, file_name } => crate::commands::pack::import_assembly_kit_tables(&config, &pack_path, &schema_path, &raw_db_path, &table_name, &file_name),
+            CommandsPack::ExportAssemblyKitTables { pack_path, schema_path, raw_db_path, table_name } => crate::commands::pack::export_assembly_kit_tables(&config, &pack_path, &schema_path, &raw_db_path, &table_name),
+            CommandsPack::LoadOrder { pack_path, used_mods_path, output_path, game_path } => crate::commands::pack::load_order(&config, &pack_path, &used_mods_path, &output_path, &game_path),
+            CommandsPack::Conflicts { schema_path, pack_path, used_mods_path, json } => crate::commands::pack::conflicts(&config, &schema_path, &pack_path, &used_mods_path, json),
+            CommandsPack::References { game_path, pak_path, schema_path, pack_path, table_name, column_name, key } => crate::commands::pack::references(&config, &game_path, &pak_path, &schema_path, &pack_path, &table_name, &column_name, &key),
+            CommandsPack::ImportRelated { game_path, pak_path, schema_path, pack_path, table_name, column_name, key, new_key, referenced, file_name } => crate::commands::pack::import_related(&config, &game_path, &pak_path, &schema_path, &pack_path, &table_name, &column_name, &key, &new_key, referenced, &file_name),
         }
 
         Commands::Schemas { commands } => match commands {
             CommandsSchemas::Update { schema_path } => crate::commands::schema::update(&config, &schema_path),
+            CommandsSchemas::Diff { old_schema_path, new_schema_path, json } => crate::commands::schema::diff(&config, &old_schema_path, &new_schema_path, json),
+            CommandsSchemas::Test { schema_path, pak_path } => crate::commands::schema::test(&config, &schema_path, &pak_path),
+            CommandsSchemas::LocKeyOrder { schema_path, pak_path, patches_path } => crate::commands::schema::loc_key_order(&config, &schema_path, &pak_path, &patches_path),
+            CommandsSchemas::Export { schema_path, output_path, format } => crate::commands::schema::export(&config, &schema_path, &output_path, &format),
+            CommandsSchemas::Graph { schema_path, output_path, format, pack_path, table_prefix } => crate::commands::schema::graph(&config, &schema_path, &output_path, &format, &pack_path, &table_prefix),
+            CommandsSchemas::Infer { schema_path, pack_path, table_path, candidates, add } => crate::commands::schema::infer(&config, &schema_path, &pack_path, &table_path, candidates, add),
+            CommandsSchemas::Overlay { schema_path, overlay_path, prune, export_path, upload } => crate::commands::schema::overlay(&config, &schema_path, &overlay_path, prune, &export_path, upload),
         }
     };
 
@@ -0,0 +1,177 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! Benchmarks for reference resolution over a large synthetic Pack.
+//!
+//! It builds a fake game install with a big parent Pack, and measures:
+//! - Key lookups on a table, by scanning its rows vs using its index.
+//! - Reference data generation for many tables pointing to the same column, without and with the reference cache.
+//!
+//! Run it with `cargo bench -p rpfm_extensions --bench references`.
+
+use std::collections::BTreeMap;
+use std::fs::{DirBuilder, File, remove_dir_all};
+use std::time::{Duration, Instant};
+
+use rpfm_lib::files::{Container, db::DB, pack::Pack, RFile, RFileDecoded, table::DecodedData};
+use rpfm_lib::games::{pfh_version::PFHVersion, supported_games::SupportedGames};
+use rpfm_lib::schema::{Definition, Field, FieldType, Schema};
+
+use rpfm_extensions::dependencies::Dependencies;
+
+const GAME_KEY: &str = "warhammer_3";
+const PARENT_PACK_NAME: &str = "bench_parent.pack";
+
+const UNITS_TABLE_ROWS: usize = 200_000;
+const REFERENCING_TABLES: usize = 40;
+const REFERENCING_TABLE_ROWS: usize = 5_000;
+const KEY_LOOKUPS: usize = 2_000;
+
+fn main() {
+    let game_path = std::env::temp_dir().join("rpfm_bench_references");
+    let _ = remove_dir_all(&game_path);
+    DirBuilder::new().recursive(true).create(game_path.join("data")).unwrap();
+
+    let games = SupportedGames::default();
+    let game_info = games.game(GAME_KEY).unwrap();
+
+    // The executable is needed so the install type (and with it, the data path) is detected correctly.
+    File::create(game_path.join("Warhammer3.exe")).unwrap();
+
+    let schema = schema();
+    let mut parent_pack = Pack::new_with_name_and_version(PARENT_PACK_NAME, PFHVersion::PFH5);
+    parent_pack.insert(units_table(&schema)).unwrap();
+    parent_pack.save(Some(&game_path.join("data").join(PARENT_PACK_NAME)), game_info, &None).unwrap();
+
+    let mut local_pack = Pack::new_with_name_and_version("bench_local.pack", PFHVersion::PFH5);
+    let mut table_names = vec![];
+    for index in 0..REFERENCING_TABLES {
+        let (table_name, file) = referencing_table(&schema, index);
+        local_pack.insert(file).unwrap();
+        table_names.push(table_name);
+    }
+
+    let mut dependencies = Dependencies::default();
+    dependencies.rebuild(&Some(schema), &[PARENT_PACK_NAME.to_owned()], None, game_info, &game_path).unwrap();
+
+    bench_key_lookups(&mut dependencies);
+    bench_references(&mut dependencies, &local_pack, &table_names);
+
+    let _ = remove_dir_all(&game_path);
+}
+
+/// Key lookups over the big table, scanning the rows vs using the table index.
+fn bench_key_lookups(dependencies: &mut Dependencies) {
+    let path = "db/bench_units_tables/data";
+    let keys = (0..KEY_LOOKUPS).map(|index| unit_key(index * (UNITS_TABLE_ROWS / KEY_LOOKUPS))).collect::<Vec<_>>();
+
+    let db = match dependencies.file(path, false, true, false).unwrap().decoded().unwrap() {
+        RFileDecoded::DB(db) => db.clone(),
+        _ => unreachable!(),
+    };
+
+    let scan = measure(|| {
+        let data = db.data(&None).unwrap();
+        for key in &keys {
+            assert!(data.iter().any(|row| row[0].data_to_string() == *key));
+        }
+    });
+
+    let mut db = db;
+    let build = measure(|| db.enable_index(&[]).unwrap());
+    let index = measure(|| {
+        let index = db.index().unwrap();
+        for key in &keys {
+            assert!(index.contains(0, key));
+        }
+    });
+
+    println!("Key lookups ({KEY_LOOKUPS} keys over {UNITS_TABLE_ROWS} rows):");
+    println!(" - Scanning rows:      {scan:?}");
+    println!(" - Building the index: {build:?}");
+    println!(" - Using the index:    {index:?}");
+}
+
+/// Reference data generation for many tables referencing the same column, without and with the reference cache.
+fn bench_references(dependencies: &mut Dependencies, local_pack: &Pack, table_names: &[String]) {
+    let uncached = measure(|| {
+        for table_name in table_names {
+            dependencies.clear_cached_references();
+            dependencies.generate_local_db_references(local_pack, &[table_name.to_owned()]);
+        }
+    });
+
+    dependencies.clear_cached_references();
+    let cached = measure(|| dependencies.generate_local_db_references(local_pack, table_names));
+
+    println!("Reference data ({REFERENCING_TABLES} tables referencing a {UNITS_TABLE_ROWS} rows table):");
+    println!(" - Without cache: {uncached:?}");
+    println!(" - With cache:    {cached:?}");
+}
+
+fn measure<F: FnMut()>(mut function: F) -> Duration {
+    let start = Instant::now();
+    function();
+    start.elapsed()
+}
+
+fn schema() -> Schema {
+    let mut schema = Schema::default();
+
+    let mut definition = Definition::new(1, None);
+    definition.set_fields(vec![
+        field("key", true, None, None),
+        field("name", false, None, None),
+    ]);
+    schema.add_definition("bench_units_tables", &definition);
+
+    for index in 0..REFERENCING_TABLES {
+        let mut definition = Definition::new(1, None);
+        definition.set_fields(vec![
+            field("key", true, None, None),
+            field("unit", false, Some(("bench_units".to_owned(), "key".to_owned())), Some(vec!["name".to_owned()])),
+        ]);
+        schema.add_definition(&format!("bench_referencing_{index}_tables"), &definition);
+    }
+
+    schema
+}
+
+fn field(name: &str, is_key: bool, is_reference: Option<(String, String)>, lookup: Option<Vec<String>>) -> Field {
+    Field::new(name.to_owned(), FieldType::StringU8, is_key, None, false, None, is_reference, lookup, String::new(), -1, 0, BTreeMap::new(), None)
+}
+
+fn unit_key(index: usize) -> String {
+    format!("bench_unit_{index}")
+}
+
+fn units_table(schema: &Schema) -> RFile {
+    let definition = &schema.definitions_by_table_name("bench_units_tables").unwrap()[0];
+    let mut db = DB::new(definition, None, "bench_units_tables", false);
+    let data = (0..UNITS_TABLE_ROWS)
+        .map(|index| vec![DecodedData::StringU8(unit_key(index)), DecodedData::StringU8(format!("Unit {index}"))])
+        .collect::<Vec<_>>();
+    db.set_data(None, &data).unwrap();
+
+    RFile::new_from_decoded(&RFileDecoded::DB(db), 0, "db/bench_units_tables/data")
+}
+
+fn referencing_table(schema: &Schema, index: usize) -> (String, RFile) {
+    let table_name = format!("bench_referencing_{index}_tables");
+    let definition = &schema.definitions_by_table_name(&table_name).unwrap()[0];
+    let mut db = DB::new(definition, None, &table_name, false);
+    let data = (0..REFERENCING_TABLE_ROWS)
+        .map(|row| vec![DecodedData::StringU8(format!("{table_name}_{row}")), DecodedData::StringU8(unit_key(row * index % UNITS_TABLE_ROWS))])
+        .collect::<Vec<_>>();
+    db.set_data(None, &data).unwrap();
+
+    let path = format!("db/{table_name}/data");
+    (table_name, RFile::new_from_decoded(&RFileDecoded::DB(db), 0, &path))
+}
@@ -0,0 +1,124 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! Module containing tests for the conflict analysis between Packs.
+
+use std::collections::BTreeMap;
+
+use rpfm_lib::files::{db::DB, loc::Loc, RFile, table::DecodedData, text::Text};
+use rpfm_lib::games::pfh_version::PFHVersion;
+use rpfm_lib::schema::{Definition, Field, FieldType};
+
+use super::*;
+
+fn definition() -> Definition {
+    let mut definition = Definition::new(1, None);
+    definition.set_fields(vec![
+        Field::new("key".to_owned(), FieldType::StringU8, true, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None),
+        Field::new("cost".to_owned(), FieldType::I32, false, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None),
+    ]);
+    definition
+}
+
+fn table(keys: &[&str]) -> RFileDecoded {
+    let mut table = DB::new(&definition(), None, "units_tables", false);
+    table.set_data(None, &keys.iter().map(|key| vec![DecodedData::StringU8(key.to_string()), DecodedData::I32(1)]).collect::<Vec<_>>()).unwrap();
+    RFileDecoded::DB(table)
+}
+
+fn loc(keys: &[&str]) -> RFileDecoded {
+    let mut loc = Loc::new(false);
+    let rows = keys.iter().map(|key| {
+        let mut row = loc.new_row();
+        row[0] = DecodedData::StringU16(key.to_string());
+        row
+    }).collect::<Vec<_>>();
+    loc.set_data(&rows).unwrap();
+    RFileDecoded::Loc(loc)
+}
+
+fn pack(name: &str, files: Vec<(&str, RFileDecoded)>) -> Pack {
+    let mut pack = Pack::new_with_name_and_version(name, PFHVersion::PFH5);
+    for (path, decoded) in files {
+        pack.insert(RFile::new_from_decoded(&decoded, 0, path)).unwrap();
+    }
+    pack
+}
+
+fn sources(sources: &[ConflictSource]) -> Vec<(&str, &str)> {
+    sources.iter().map(|source| (source.pack_name().as_str(), source.path().as_str())).collect()
+}
+
+fn packs() -> Vec<Pack> {
+    vec![
+        pack("a.pack", vec![
+            ("db/units_tables/a_units", table(&["spear", "sword"])),
+            ("db/units_tables/data", table(&["bow"])),
+            ("text/db/a.loc", loc(&["units_name_spear", "units_name_sword"])),
+            ("script/shared.lua", RFileDecoded::Text(Text::default())),
+        ]),
+        pack("b.pack", vec![
+            ("db/units_tables/b_units", table(&["spear", "axe"])),
+            ("db/units_tables/data", table(&["axe"])),
+            ("db/units_tables/z_units", table(&["axe"])),
+            ("text/db/b.loc", loc(&["units_name_spear", "units_name_axe"])),
+            ("script/shared.lua", RFileDecoded::Text(Text::default())),
+        ]),
+    ]
+}
+
+#[test]
+fn test_conflicts_files() {
+    let mut schema = Schema::default();
+    schema.add_definition("units_tables", &definition());
+
+    let report = ConflictReport::new(&mut packs(), &schema).unwrap();
+    assert!(!report.is_empty());
+
+    // The last Pack loaded wins, so it goes first.
+    assert_eq!(report.files().keys().collect::<Vec<_>>(), vec!["db/units_tables/data", "script/shared.lua"]);
+    assert_eq!(sources(report.files().get("db/units_tables/data").unwrap()), vec![("b.pack", "db/units_tables/data"), ("a.pack", "db/units_tables/data")]);
+}
+
+#[test]
+fn test_conflicts_rows_and_loc_keys() {
+    let mut schema = Schema::default();
+    schema.add_definition("units_tables", &definition());
+
+    let report = ConflictReport::new(&mut packs(), &schema).unwrap();
+
+    // Rows of overwritten files are not loaded, and keys repeated within the same Pack are not conflicts.
+    let rows = report.rows().get("units_tables").unwrap();
+    assert_eq!(rows.keys().collect::<Vec<_>>(), vec!["spear"]);
+    assert_eq!(sources(rows.get("spear").unwrap()), vec![("a.pack", "db/units_tables/a_units"), ("b.pack", "db/units_tables/b_units")]);
+
+    assert_eq!(report.loc_keys().keys().collect::<Vec<_>>(), vec!["units_name_spear"]);
+    assert_eq!(sources(report.loc_keys().get("units_name_spear").unwrap()), vec![("a.pack", "text/db/a.loc"), ("b.pack", "text/db/b.loc")]);
+
+    let text = report.to_string();
+    assert!(text.contains("- db/units_tables/data: b.pack wins over a.pack\n"));
+    assert!(text.contains("  - spear: db/units_tables/a_units (a.pack) wins over db/units_tables/b_units (b.pack)\n"));
+    assert!(text.contains("Loc keys in more than one Pack: 1.\n"));
+}
+
+#[test]
+fn test_conflicts_none() {
+    let mut schema = Schema::default();
+    schema.add_definition("units_tables", &definition());
+
+    let mut packs = vec![
+        pack("a.pack", vec![("db/units_tables/a_units", table(&["spear"]))]),
+        pack("b.pack", vec![("db/units_tables/b_units", table(&["axe"]))]),
+    ];
+
+    let report = ConflictReport::new(&mut packs, &schema).unwrap();
+    assert!(report.is_empty());
+    assert!(report.json().is_ok());
+}
@@ -0,0 +1,234 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! This module contains the conflict analysis between the Packs of a load order.
+//!
+//! It reports three kinds of conflicts:
+//! - Files with the same path in more than one Pack. Only the one in the last Pack loaded is used.
+//! - DB rows with the same key in more than one Pack. All the tables of the same type are loaded, sorted by their path,
+//!   and if multiple rows share a key, the game uses the one in the table that comes first.
+//! - Loc keys in more than one Pack. Same as with DB rows, the one in the Loc file that comes first is used.
+
+use getset::Getters;
+use itertools::Itertools;
+use rayon::prelude::*;
+use serde_derive::{Serialize, Deserialize};
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display};
+
+use rpfm_lib::error::Result;
+use rpfm_lib::files::{Container, DecodeableExtraData, FileType, pack::Pack, RFileDecoded};
+use rpfm_lib::schema::Schema;
+
+use crate::load_order::LoadOrder;
+
+#[cfg(test)] mod conflicts_test;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct contains the conflicts found between the Packs of a load order.
+///
+/// Each conflict contains the sources of the conflicting item, with the one the game uses first.
+#[derive(Clone, Debug, Default, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct ConflictReport {
+
+    /// Files in more than one Pack, by path.
+    files: BTreeMap<String, Vec<ConflictSource>>,
+
+    /// Rows with the same key in more than one Pack, by table name and key.
+    rows: BTreeMap<String, BTreeMap<String, Vec<ConflictSource>>>,
+
+    /// Loc keys in more than one Pack, by key.
+    loc_keys: BTreeMap<String, Vec<ConflictSource>>,
+}
+
+/// This struct represents where a conflicting item comes from.
+#[derive(Clone, Debug, PartialEq, Eq, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct ConflictSource {
+
+    /// Name of the Pack containing the item.
+    pack_name: String,
+
+    /// Path of the file containing the item within the Pack.
+    path: String,
+}
+
+//---------------------------------------------------------------------------//
+//                       Enum & Structs Implementations
+//---------------------------------------------------------------------------//
+
+impl ConflictReport {
+
+    /// This function checks the provided Packs for conflicts between them.
+    ///
+    /// The Packs must be in the order they're loaded. Their tables are decoded with the provided schema.
+    pub fn new(packs: &mut [Pack], schema: &Schema) -> Result<Self> {
+        for pack in packs.iter_mut() {
+            let mut extra_data = DecodeableExtraData::default();
+            extra_data.set_schema(Some(schema));
+            extra_data.set_game_build(Some(pack.game_version()).filter(|game_version| *game_version != 0));
+            let extra_data = Some(extra_data);
+
+            pack.files_by_type_mut(&[FileType::DB, FileType::Loc])
+                .par_iter_mut()
+                .for_each(|file| { let _ = file.decode(&extra_data, true, false); });
+        }
+
+        let pack_names = packs.iter().map(|pack| pack.disk_file_name()).collect::<Vec<_>>();
+
+        // Files with the same path. The last Pack with the file is the one that gets its file loaded.
+        let mut paths: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (index, pack) in packs.iter().enumerate() {
+            for path in pack.files().keys() {
+                paths.entry(path).or_default().push(index);
+            }
+        }
+
+        let mut report = Self::default();
+        for (path, indexes) in &paths {
+            if indexes.len() > 1 {
+                let sources = indexes.iter()
+                    .rev()
+                    .map(|index| ConflictSource::new(&pack_names[*index], path))
+                    .collect();
+
+                report.files.insert(path.to_string(), sources);
+            }
+        }
+
+        // Only the files that get loaded can have conflicting rows, and they're loaded sorted by path.
+        let mut loaded_files = paths.iter()
+            .filter_map(|(path, indexes)| {
+                let index = *indexes.last()?;
+                let file = packs[index].files().get(*path)?;
+                Some((index, file))
+            })
+            .collect::<Vec<_>>();
+        loaded_files.sort_by(|(_, a), (_, b)| a.path_in_container_raw().cmp(b.path_in_container_raw()));
+
+        let mut rows: BTreeMap<String, BTreeMap<String, Vec<ConflictSource>>> = BTreeMap::new();
+        let mut loc_keys: BTreeMap<String, Vec<ConflictSource>> = BTreeMap::new();
+        for (index, file) in loaded_files {
+            let source = ConflictSource::new(&pack_names[index], file.path_in_container_raw());
+            match file.decoded() {
+                Ok(RFileDecoded::DB(table)) => {
+                    let key_positions = table.definition().fields_processed().iter()
+                        .enumerate()
+                        .filter_map(|(position, field)| if field.is_key(Some(table.patches())) { Some(position) } else { None })
+                        .collect::<Vec<_>>();
+
+                    if key_positions.is_empty() {
+                        continue;
+                    }
+
+                    let table_rows = rows.entry(table.table_name().to_owned()).or_default();
+                    for row in table.data(&None)?.iter() {
+                        let key = key_positions.iter().map(|position| row[*position].data_to_string()).join("|");
+                        let sources = table_rows.entry(key).or_default();
+                        if !sources.contains(&source) {
+                            sources.push(source.clone());
+                        }
+                    }
+                }
+                Ok(RFileDecoded::Loc(table)) => {
+                    for row in table.data(&None)?.iter() {
+                        let sources = loc_keys.entry(row[0].data_to_string().to_string()).or_default();
+                        if !sources.contains(&source) {
+                            sources.push(source.clone());
+                        }
+                    }
+                }
+                _ => continue,
+            }
+        }
+
+        // Only keep the items defined by more than one Pack. Multiple files of the same Pack defining the same key are not a conflict between mods.
+        let from_many_packs = |sources: &Vec<ConflictSource>| sources.iter().map(|source| &source.pack_name).unique().count() > 1;
+
+        report.rows = rows.into_iter()
+            .map(|(table_name, mut table_rows)| {
+                table_rows.retain(|_, sources| from_many_packs(sources));
+                (table_name, table_rows)
+            })
+            .filter(|(_, table_rows)| !table_rows.is_empty())
+            .collect();
+
+        loc_keys.retain(|_, sources| from_many_packs(sources));
+        report.loc_keys = loc_keys;
+
+        Ok(report)
+    }
+
+    /// This function reads the Packs of the provided load order, and checks them for conflicts between them.
+    ///
+    /// Check [ConflictReport::new] for more info.
+    pub fn from_load_order(load_order: &LoadOrder, schema: &Schema) -> Result<Self> {
+        let mut packs = load_order.entries()
+            .iter()
+            .map(|entry| Pack::read_and_merge(&[entry.path().to_path_buf()], true, false))
+            .collect::<Result<Vec<_>>>()?;
+
+        Self::new(&mut packs, schema)
+    }
+
+    /// This function returns if no conflicts were found.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty() && self.rows.is_empty() && self.loc_keys.is_empty()
+    }
+
+    /// This function converts the entire report into a JSon string.
+    pub fn json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(From::from)
+    }
+}
+
+impl ConflictSource {
+    fn new(pack_name: &str, path: &str) -> Self {
+        Self {
+            pack_name: pack_name.to_owned(),
+            path: path.to_owned(),
+        }
+    }
+}
+
+impl Display for ConflictReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Files in more than one Pack: {}.", self.files.len())?;
+        for (path, sources) in &self.files {
+            writeln!(f, "- {}: {} wins over {}", path, sources[0].pack_name, sources[1..].iter().map(|source| &source.pack_name).join(", "))?;
+        }
+
+        writeln!(f, "Tables with rows in more than one Pack: {}.", self.rows.len())?;
+        for (table_name, rows) in &self.rows {
+            writeln!(f, "- {}: {} keys.", table_name, rows.len())?;
+            for (key, sources) in rows {
+                writeln!(f, "  - {}: {} wins over {}", key, sources[0], sources[1..].iter().join(", "))?;
+            }
+        }
+
+        writeln!(f, "Loc keys in more than one Pack: {}.", self.loc_keys.len())?;
+        for (key, sources) in &self.loc_keys {
+            writeln!(f, "- {}: {} wins over {}", key, sources[0], sources[1..].iter().join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for ConflictSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.path, self.pack_name)
+    }
+}
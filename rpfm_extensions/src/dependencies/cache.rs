@@ -0,0 +1,309 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! This module contains the on-disk format of the dependencies cache.
+//!
+//! The cache is a single file with the following structure:
+//! - Header: signature, version of the format, and position and size of the index.
+//! - Sections: one per CA Pack, in the order they're loaded. Each one contains the raw data of the DB and Loc files of its Pack,
+//!   uncompressed and unencrypted, followed by the list of the files of said Pack.
+//! - Index: build date, game version, if it was generated from a list of CA Packs, the CA Packs with the position of their sections, and the precomputed lists of tables, locs and folders.
+//!
+//! When loading it, only the header, the index and the file lists are read from disk. Tables and locs are lazy-loaded: each one is read
+//! from its chunk of the cache file and decoded the first time it's used. The rest of the files are lazy-loaded from their CA Packs when used.
+//! Like any other lazy-loaded file, a chunk is only read if the cache's last modified date didn't change since it was opened.
+//!
+//! Caches are never modified in place. New caches are written to a temporary file with a unique name, which is then renamed over the old cache,
+//! so a process with the old cache open keeps reading the old data, or fails to lazy-load it if the file got replaced, but never reads a half-written cache.
+//!
+//! As each CA Pack has its own section, updating the cache after a game update only needs to re-read the Packs that changed.
+
+use rayon::prelude::*;
+use serde_derive::{Serialize, Deserialize};
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, DirBuilder, File};
+use std::io::{BufReader, BufWriter, Cursor, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::process;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use rpfm_lib::binary::{ReadBytes, WriteBytes};
+use rpfm_lib::error::{Result, RLibError};
+use rpfm_lib::files::{Container, db::DB, FileType, pack::Pack, RFile};
+use rpfm_lib::utils::last_modified_time_from_file;
+
+use super::{Dependencies, VanillaPack};
+
+/// Signature at the start of every dependencies cache.
+const SIGNATURE: &[u8; 8] = b"RPFMDEPS";
+
+/// Version of the format of the dependencies cache. Bump it on format changes, so old caches get regenerated.
+const FORMAT_VERSION: u32 = 4;
+
+/// Size of the header: signature, format version, and position and size of the index.
+const HEADER_SIZE: u64 = 28;
+
+/// Amount of tables read in parallel when writing a section, so we don't need to keep all the tables of a Pack in memory.
+const WRITE_CHUNK_SIZE: usize = 256;
+
+/// Counter for the names of the temporary files of the caches being written, so two writers never share one.
+static TEMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+//-------------------------------------------------------------------------------//
+//                              Enums & Structs
+//-------------------------------------------------------------------------------//
+
+/// This struct represents the files of a CA Pack, used to write its section of the cache.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct CacheSection {
+
+    /// The CA Pack these files are from.
+    pub(crate) pack: VanillaPack,
+
+    /// Files of the Pack, except the DB and Loc ones. Lazy-loaded from the Pack.
+    pub(crate) files: Vec<RFile>,
+
+    /// DB and Loc files of the Pack. Their data is stored in the cache.
+    pub(crate) tables: Vec<RFile>,
+}
+
+/// This struct represents an open dependencies cache file, with its index read.
+pub(crate) struct CacheFile {
+
+    /// Path of the cache on disk.
+    path: String,
+
+    /// Last modified date of the cache, to detect if it changed before reading more data from it.
+    timestamp: u64,
+
+    /// Size of the cache, to validate the positions in its index.
+    size: u64,
+
+    /// Index of the cache.
+    pub(crate) index: CacheIndex,
+}
+
+/// Index of a dependencies cache.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct CacheIndex {
+    pub(crate) build_date: u64,
+    pub(crate) game_version: u32,
+    pub(crate) from_packs: bool,
+    pub(crate) sections: Vec<SectionIndex>,
+    pub(crate) vanilla_tables: HashMap<String, Vec<String>>,
+    pub(crate) vanilla_locs: HashSet<String>,
+    pub(crate) vanilla_folders: HashSet<String>,
+    pub(crate) asskit_only_db_tables: HashMap<String, DB>,
+}
+
+/// Position of the file list of a section within the cache.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct SectionIndex {
+    pub(crate) pack: VanillaPack,
+    files_start: u64,
+    files_size: u64,
+}
+
+/// Position of the data of a DB or Loc file within the cache.
+#[derive(Debug, Serialize, Deserialize)]
+struct TableEntry {
+    path: String,
+    start: u64,
+    size: u64,
+}
+
+//-------------------------------------------------------------------------------//
+//                             Implementations
+//-------------------------------------------------------------------------------//
+
+impl CacheSection {
+
+    /// This function reads the files of the CA Pack at the provided path.
+    pub(crate) fn from_pack(pack_path: &Path) -> Result<Self> {
+        let mut pack = Pack::read_and_merge(&[pack_path.to_path_buf()], true, false)?;
+        let mut section = Self {
+            pack: VanillaPack {
+                name: pack.disk_file_name(),
+                path: pack.disk_file_path().to_owned(),
+                timestamp: *pack.local_timestamp(),
+                pfh_file_type: pack.pfh_file_type(),
+            },
+            ..Default::default()
+        };
+
+        for (_, mut file) in std::mem::take(pack.files_mut()) {
+            let _ = file.guess_file_type();
+
+            match file.file_type() {
+                FileType::DB |
+                FileType::Loc => section.tables.push(file),
+                _ => section.files.push(file),
+            }
+        }
+
+        // Keep them sorted, so the same Pack always results in the same section.
+        section.files.sort_by(|a, b| a.path_in_container_raw().cmp(b.path_in_container_raw()));
+        section.tables.sort_by(|a, b| a.path_in_container_raw().cmp(b.path_in_container_raw()));
+
+        Ok(section)
+    }
+}
+
+impl CacheFile {
+
+    /// This function opens the dependencies cache at the provided path, and reads its index.
+    pub(crate) fn open(file_path: &Path) -> Result<Self> {
+        let mut file = BufReader::new(File::open(file_path)?);
+        let timestamp = last_modified_time_from_file(file.get_ref())?;
+        let size = file.get_ref().metadata()?.len();
+
+        let mut header = vec![0; HEADER_SIZE as usize];
+        if size < HEADER_SIZE || file.read_exact(&mut header).is_err() || &header[..SIGNATURE.len()] != SIGNATURE {
+            return Err(RLibError::DependenciesCacheUnsupportedFormat);
+        }
+
+        let mut header = Cursor::new(&header[SIGNATURE.len()..]);
+        if header.read_u32()? != FORMAT_VERSION {
+            return Err(RLibError::DependenciesCacheUnsupportedFormat);
+        }
+
+        let index_start = header.read_u64()?;
+        let index_size = header.read_u64()?;
+
+        let mut cache = Self {
+            path: file_path.to_string_lossy().replace('\\', "/"),
+            timestamp,
+            size,
+            index: CacheIndex::default(),
+        };
+
+        cache.index = bincode::deserialize(&cache.read(&mut file, index_start, index_size)?)?;
+        Ok(cache)
+    }
+
+    /// This function reads the section at the provided position in the index, with its tables lazy-loaded from the cache.
+    ///
+    /// It fails if the cache changed on disk since it was opened.
+    pub(crate) fn section(&self, index: usize) -> Result<CacheSection> {
+        let mut file = BufReader::new(File::open(&self.path)?);
+        if last_modified_time_from_file(file.get_ref())? != self.timestamp {
+            return Err(RLibError::DependenciesCacheNotGeneratedorOutOfDate);
+        }
+
+        let section = &self.index.sections[index];
+        let (files, entries): (Vec<RFile>, Vec<TableEntry>) = bincode::deserialize(&self.read(&mut file, section.files_start, section.files_size)?)?;
+
+        let tables = entries.iter()
+            .map(|entry| {
+                let mut table = RFile::new_from_file_chunk(&self.path, self.timestamp, entry.start, entry.size, &entry.path, Some(section.pack.name.to_owned()));
+                table.guess_file_type().map(|_| table)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(CacheSection {
+            pack: section.pack.clone(),
+            files,
+            tables,
+        })
+    }
+
+    /// This function reads a chunk of the cache, or returns an error if it's out of bounds.
+    fn read<R: Read + Seek>(&self, file: &mut R, start: u64, size: u64) -> Result<Vec<u8>> {
+        match start.checked_add(size) {
+            Some(end) if end <= self.size => {
+                let mut data = vec![0; size as usize];
+                file.seek(SeekFrom::Start(start))?;
+                file.read_exact(&mut data)?;
+                Ok(data)
+            }
+            _ => Err(RLibError::DependenciesCacheUnsupportedFormat),
+        }
+    }
+}
+
+/// This function writes the vanilla data of the provided dependencies to a cache at the provided path.
+pub(crate) fn write(file_path: &Path, dependencies: &Dependencies) -> Result<()> {
+    if let Some(folder_path) = file_path.parent() {
+        DirBuilder::new().recursive(true).create(folder_path)?;
+    }
+
+    // Write to a temporary file first, as the tables of unchanged Packs may be read from the cache we're replacing.
+    // Its name is unique, so other processes or threads writing the same cache don't write over it.
+    let temp_name = format!("{}.{}_{}.tmp", file_path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default(), process::id(), TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed));
+    let temp_path = file_path.with_file_name(temp_name);
+    let result = write_to(&temp_path, dependencies).and_then(|_| fs::rename(&temp_path, file_path).map_err(From::from));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+
+    result
+}
+
+/// This function writes the vanilla data of the provided dependencies to a new file at the provided path.
+fn write_to(file_path: &Path, dependencies: &Dependencies) -> Result<()> {
+    let mut file = BufWriter::new(File::create(file_path)?);
+    file.write_all(&[0; HEADER_SIZE as usize])?;
+
+    let mut position = HEADER_SIZE;
+    let mut sections = Vec::with_capacity(dependencies.vanilla_sections.len());
+    for section in &dependencies.vanilla_sections {
+        let mut entries = Vec::with_capacity(section.tables.len());
+        for tables in section.tables.chunks(WRITE_CHUNK_SIZE) {
+            let data = tables.par_iter()
+                .map(|table| table.clone().encode(&None, false, false, true).map(|data| data.unwrap_or_default()))
+                .collect::<Result<Vec<_>>>()?;
+
+            for (table, data) in tables.iter().zip(data) {
+                file.write_all(&data)?;
+                entries.push(TableEntry {
+                    path: table.path_in_container_raw().to_owned(),
+                    start: position,
+                    size: data.len() as u64,
+                });
+
+                position += data.len() as u64;
+            }
+        }
+
+        let files = bincode::serialize(&(&section.files, &entries))?;
+        file.write_all(&files)?;
+        sections.push(SectionIndex {
+            pack: section.pack.clone(),
+            files_start: position,
+            files_size: files.len() as u64,
+        });
+
+        position += files.len() as u64;
+    }
+
+    // Same layout as CacheIndex.
+    let index = bincode::serialize(&(
+        &dependencies.build_date,
+        &dependencies.game_version,
+        &dependencies.from_packs,
+        &sections,
+        &dependencies.vanilla_tables,
+        &dependencies.vanilla_locs,
+        &dependencies.vanilla_folders,
+        &dependencies.asskit_only_db_tables,
+    ))?;
+    file.write_all(&index)?;
+
+    // Now that we know where the index is, write the header.
+    file.seek(SeekFrom::Start(0))?;
+    file.write_all(SIGNATURE)?;
+    file.write_u32(FORMAT_VERSION)?;
+    file.write_u64(position)?;
+    file.write_u64(index.len() as u64)?;
+    file.flush()?;
+    Ok(())
+}
+
@@ -0,0 +1,645 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! Module containing tests for the dependencies manager.
+
+use std::collections::BTreeSet;
+use std::env::temp_dir;
+use std::fs::{self, create_dir_all, remove_dir_all};
+
+use rpfm_lib::files::text::Text;
+use rpfm_lib::games::{pfh_version::PFHVersion, supported_games::{KEY_WARHAMMER_3, SupportedGames}};
+use rpfm_lib::schema::FieldType;
+
+use super::*;
+
+fn text(contents: &str) -> RFileDecoded {
+    let mut text = Text::default();
+    text.set_contents(contents.to_owned());
+    RFileDecoded::Text(text)
+}
+
+fn save_pack(folder: &Path, name: &str, pfh_file_type: PFHFileType, files: &[(&str, &str)], game_info: &GameInfo) -> PathBuf {
+    let path = folder.join(name);
+    let mut pack = Pack::new_with_name_and_version(name, PFHVersion::PFH5);
+    pack.set_pfh_file_type(pfh_file_type);
+    for (file_path, contents) in files {
+        pack.insert(RFile::new_from_decoded(&text(contents), 0, file_path)).unwrap();
+    }
+
+    pack.save(Some(&path), game_info, &None).unwrap();
+    path
+}
+
+fn schema() -> Schema {
+    let mut definition = Definition::new(1, None);
+    definition.set_fields(vec![Field::new("key".to_owned(), FieldType::StringU8, true, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None)]);
+
+    let mut schema = Schema::default();
+    schema.add_definition("units_tables", &definition);
+    schema
+}
+
+fn db(schema: &Schema, key: &str) -> RFileDecoded {
+    let definition = schema.definitions_by_table_name("units_tables").unwrap().first().unwrap();
+    let mut table = DB::new(definition, None, "units_tables", false);
+    table.set_data(None, &[vec![DecodedData::StringU8(key.to_owned())]]).unwrap();
+    RFileDecoded::DB(table)
+}
+
+fn loc(key: &str, text: &str) -> RFileDecoded {
+    let mut loc = Loc::new(false);
+    loc.set_data(&[vec![DecodedData::StringU16(key.to_owned()), DecodedData::StringU16(text.to_owned()), DecodedData::Boolean(false)]]).unwrap();
+    RFileDecoded::Loc(loc)
+}
+
+fn save_ca_pack(folder: &Path, name: &str, files: &[(&str, RFileDecoded)], game_info: &GameInfo) -> PathBuf {
+    let path = folder.join(name);
+    let mut pack = Pack::new_with_name_and_version(name, PFHVersion::PFH5);
+    pack.set_pfh_file_type(PFHFileType::Release);
+    for (file_path, decoded) in files {
+        pack.insert(RFile::new_from_decoded(decoded, 0, file_path)).unwrap();
+    }
+
+    pack.save(Some(&path), game_info, &None).unwrap();
+    path
+}
+
+fn first_value(file: &RFile) -> Option<String> {
+    let data = match file.decoded().ok()? {
+        RFileDecoded::DB(table) => table.data(&None).ok()?.to_vec(),
+        RFileDecoded::Loc(loc) => loc.data(&None).ok()?.to_vec(),
+        _ => return None,
+    };
+
+    Some(data.first()?.get(1).or_else(|| data.first()?.first())?.data_to_string().to_string())
+}
+
+fn contents(dependencies: &Dependencies, path: &str) -> String {
+    let mut file = dependencies.file(path, true, true, false).unwrap().clone();
+    match file.decode(&None, false, true).unwrap() {
+        Some(RFileDecoded::Text(text)) => text.contents().to_owned(),
+        _ => panic!("{path} is not a text file"),
+    }
+}
+
+#[test]
+fn test_installed_mods() {
+    let games = SupportedGames::default();
+    let game_info = games.game(KEY_WARHAMMER_3).unwrap();
+
+    let folder = temp_dir().join("rpfm_test_installed_mods");
+    let _ = remove_dir_all(&folder);
+    let first = folder.join("first");
+    let second = folder.join("second");
+    create_dir_all(&first).unwrap();
+    create_dir_all(&second).unwrap();
+
+    save_pack(&first, "a.pack", PFHFileType::Mod, &[("script/shared.lua", "first_a"), ("script/a.lua", "a")], game_info);
+    save_pack(&first, "b.pack", PFHFileType::Mod, &[("script/shared.lua", "first_b"), ("script/b.lua", "b")], game_info);
+    save_pack(&first, "movie.pack", PFHFileType::Movie, &[("script/movie.lua", "movie")], game_info);
+    save_pack(&second, "a.pack", PFHFileType::Mod, &[("script/shared.lua", "second_a"), ("script/second.lua", "second")], game_info);
+    save_pack(&second, "c.pack", PFHFileType::Mod, &[("script/shared.lua", "second_c"), ("script/c.lua", "c")], game_info);
+    save_pack(&second, "edited.pack", PFHFileType::Mod, &[("script/edited.lua", "edited")], game_info);
+
+    let mut dependencies = Dependencies::default();
+    dependencies.set_installed_mods(&[InstalledModsSource::Folder(first.to_path_buf()), InstalledModsSource::Folder(second.to_path_buf())], &["edited.pack".to_owned()]);
+    dependencies.rebuild(&None, &[], None, game_info, &folder).unwrap();
+
+    // The first sources and the first Packs by name win. Packs already loaded from a previous source, ignored Packs and non-mod Packs are skipped.
+    assert_eq!(contents(&dependencies, "script/shared.lua"), "first_a");
+    assert!(dependencies.file_exists("script/c.lua", false, true, false));
+    assert!(!dependencies.file_exists("script/second.lua", false, true, false));
+    assert!(!dependencies.file_exists("script/movie.lua", false, true, false));
+    assert!(!dependencies.file_exists("script/edited.lua", false, true, false));
+
+    // Their files have their own source, even if they're loaded as parent files.
+    assert_eq!(dependencies.parent_file_source("script/a.lua"), SearchSource::InstalledMods);
+    assert_eq!(dependencies.parent_file_source("script/not_installed.lua"), SearchSource::ParentFiles);
+
+    remove_dir_all(&folder).unwrap();
+}
+
+#[test]
+fn test_installed_mods_cache() {
+    let games = SupportedGames::default();
+    let game_info = games.game(KEY_WARHAMMER_3).unwrap();
+
+    let folder = temp_dir().join("rpfm_test_installed_mods_cache");
+    let _ = remove_dir_all(&folder);
+    create_dir_all(&folder).unwrap();
+    let path = save_pack(&folder, "a.pack", PFHFileType::Mod, &[("script/a.lua", "a")], game_info);
+
+    let mut dependencies = Dependencies::default();
+    dependencies.set_installed_mods(&[InstalledModsSource::Folder(folder.to_path_buf())], &[]);
+    dependencies.rebuild(&None, &[], None, game_info, &folder).unwrap();
+    assert_eq!(dependencies.installed_mods_packs.keys().collect::<Vec<_>>(), vec![&path]);
+
+    // Unchanged Packs are reused from the cache instead of being read again.
+    let (_, cached_pack) = dependencies.installed_mods_packs.get_mut(&path).unwrap();
+    cached_pack.insert(RFile::new_from_decoded(&text("cached"), 0, "script/cached.lua")).unwrap();
+    dependencies.rebuild(&None, &[], None, game_info, &folder).unwrap();
+    assert_eq!(contents(&dependencies, "script/cached.lua"), "cached");
+
+    // Changed ones are read again.
+    dependencies.installed_mods_packs.get_mut(&path).unwrap().0 = 0;
+    dependencies.rebuild(&None, &[], None, game_info, &folder).unwrap();
+    assert!(!dependencies.file_exists("script/cached.lua", false, true, false));
+    assert_eq!(contents(&dependencies, "script/a.lua"), "a");
+
+    // Packs no longer installed are dropped, along with their files.
+    dependencies.set_installed_mods(&[], &[]);
+    dependencies.rebuild(&None, &[], None, game_info, &folder).unwrap();
+    assert!(dependencies.installed_mods_packs.is_empty());
+    assert!(!dependencies.file_exists("script/a.lua", false, true, false));
+
+    remove_dir_all(&folder).unwrap();
+}
+
+#[test]
+fn test_dependencies_cache_save_and_load() {
+    let games = SupportedGames::default();
+    let game_info = games.game(KEY_WARHAMMER_3).unwrap();
+    let schema = schema();
+
+    let folder = temp_dir().join("rpfm_test_dependencies_cache_save_and_load");
+    let _ = remove_dir_all(&folder);
+    let packs_folder = folder.join("packs");
+    create_dir_all(&packs_folder).unwrap();
+
+    save_ca_pack(&packs_folder, "data.pack", &[("db/units_tables/data", db(&schema, "data_unit")), ("text/db/data.loc", loc("data_key", "Data"))], game_info);
+    save_ca_pack(&packs_folder, "patch.pack", &[("db/units_tables/data", db(&schema, "patch_unit")), ("script/patch.lua", text("patch"))], game_info);
+
+    let cache_path = folder.join("test.pak2");
+    let mut generated = Dependencies::generate_dependencies_cache_from_packs(game_info, &[packs_folder], &None, 0, &None).unwrap();
+    generated.save(&cache_path).unwrap();
+
+    // Tables are only decoded when used.
+    let mut loaded = Dependencies::load(&cache_path, &Some(schema.clone())).unwrap();
+    assert_eq!(loaded.vanilla_packs(), generated.vanilla_packs());
+    assert_eq!(loaded.vanilla_tables(), generated.vanilla_tables());
+    assert_eq!(loaded.vanilla_locs(), generated.vanilla_locs());
+    assert_eq!(loaded.vanilla_folders(), generated.vanilla_folders());
+    assert!(loaded.vanilla_files().get("db/units_tables/data").unwrap().decoded().is_err());
+
+    let tables = loaded.db_data("units_tables", true, false).unwrap();
+    assert_eq!(tables.len(), 1);
+    assert_eq!(first_value(tables[0]).unwrap(), "patch_unit");
+    assert_eq!(contents(&loaded, "script/patch.lua"), "patch");
+
+    // Loaded caches can be saved again, even over themselves, and their unused tables are then read from the new cache.
+    loaded.save(&cache_path).unwrap();
+    assert_eq!(first_value(loaded.loc_data(true, false).unwrap()[0]).unwrap(), "Data");
+
+    let reloaded = Dependencies::load(&cache_path, &Some(schema.clone())).unwrap();
+    assert_eq!(reloaded.vanilla_files().keys().collect::<BTreeSet<_>>(), generated.vanilla_files().keys().collect::<BTreeSet<_>>());
+    assert_eq!(first_value(reloaded.file("db/units_tables/data", true, false, false).unwrap()).unwrap(), "patch_unit");
+
+    // Without schema, tables are left for the user to decode.
+    let mut undecoded = Dependencies::load(&cache_path, &None).unwrap();
+    assert!(undecoded.db_data("units_tables", true, false).unwrap()[0].decoded().is_err());
+    undecoded.decode_tables(&Some(schema));
+    assert_eq!(first_value(undecoded.db_data("units_tables", true, false).unwrap()[0]).unwrap(), "patch_unit");
+
+    remove_dir_all(&folder).unwrap();
+}
+
+#[test]
+fn test_dependencies_cache_legacy_files() {
+    let games = SupportedGames::default();
+    let game_info = games.game(KEY_WARHAMMER_3).unwrap();
+    let schema = schema();
+
+    let folder = temp_dir().join("rpfm_test_dependencies_cache_legacy_files");
+    let _ = remove_dir_all(&folder);
+    let packs_folder = folder.join("packs");
+    create_dir_all(&packs_folder).unwrap();
+    save_ca_pack(&packs_folder, "data.pack", &[("db/units_tables/data", db(&schema, "data_unit"))], game_info);
+
+    let cache_path = folder.join(game_info.dependencies_cache_file_name());
+    let legacy_paths = vec![cache_path.with_extension("pak1"), cache_path.with_extension("pak3")];
+    for legacy_path in &legacy_paths {
+        fs::write(legacy_path, b"legacy").unwrap();
+    }
+
+    // Saving a cache doesn't touch other files, and leaves no temporary files behind.
+    let mut generated = Dependencies::generate_dependencies_cache_from_packs(game_info, &[packs_folder], &None, 0, &None).unwrap();
+    generated.save(&cache_path).unwrap();
+    assert!(legacy_paths.iter().all(|legacy_path| legacy_path.is_file()));
+    assert!(!fs::read_dir(&folder).unwrap().any(|entry| entry.unwrap().file_name().to_string_lossy().ends_with(".tmp")));
+
+    // The legacy files are only removed by the explicit migration.
+    assert_eq!(Dependencies::remove_legacy_caches(&folder, game_info).unwrap(), legacy_paths);
+    assert!(legacy_paths.iter().all(|legacy_path| !legacy_path.exists()));
+    assert!(cache_path.is_file());
+    assert!(Dependencies::remove_legacy_caches(&folder, game_info).unwrap().is_empty());
+
+    // Once a cache is replaced, the sections of the old one cannot be read from it anymore.
+    let cache_file = CacheFile::open(&cache_path).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    generated.save(&cache_path).unwrap();
+    assert!(cache_file.section(0).is_err());
+    assert!(CacheFile::open(&cache_path).unwrap().section(0).is_ok());
+
+    remove_dir_all(&folder).unwrap();
+}
+
+#[test]
+fn test_dependencies_cache_asskit_only_tables() {
+    let games = SupportedGames::default();
+    let game_info = games.game(KEY_WARHAMMER_3).unwrap();
+    let schema = schema();
+
+    let folder = temp_dir().join("rpfm_test_dependencies_cache_asskit_only_tables");
+    let _ = remove_dir_all(&folder);
+    create_dir_all(&folder).unwrap();
+
+    let pack_path = save_ca_pack(&folder, "data.pack", &[("db/units_tables/data", db(&schema, "data_unit"))], game_info);
+    let mut generated = Dependencies::generate_dependencies_cache_from_packs(game_info, &[pack_path], &None, 0, &None).unwrap();
+
+    // Tables only in the Assembly Kit are stored with their definitions, with and without game builds.
+    let mut with_builds = schema.definitions_by_table_name("units_tables").unwrap()[0].clone();
+    with_builds.set_game_builds(Some((0x01000000, 0x010100FF)));
+    let mut abilities = DB::new(&with_builds, None, "abilities_tables", false);
+    abilities.set_data(None, &[vec![DecodedData::StringU8("ability".to_owned())]]).unwrap();
+
+    let effects = match db(&schema, "effect") {
+        RFileDecoded::DB(table) => table,
+        _ => unreachable!(),
+    };
+
+    generated.asskit_only_db_tables.insert("abilities_tables".to_owned(), abilities);
+    generated.asskit_only_db_tables.insert("effects_tables".to_owned(), effects);
+
+    let cache_path = folder.join("test.pak2");
+    generated.save(&cache_path).unwrap();
+
+    let loaded = Dependencies::load(&cache_path, &Some(schema)).unwrap();
+    assert_eq!(loaded.asskit_only_db_tables, generated.asskit_only_db_tables);
+    assert_eq!(*loaded.asskit_only_db_tables["abilities_tables"].definition().game_builds(), Some((0x01000000, 0x010100FF)));
+
+    remove_dir_all(&folder).unwrap();
+}
+
+#[test]
+fn test_dependencies_cache_unsupported_format() {
+    let games = SupportedGames::default();
+    let game_info = games.game(KEY_WARHAMMER_3).unwrap();
+
+    let folder = temp_dir().join("rpfm_test_dependencies_cache_unsupported_format");
+    let _ = remove_dir_all(&folder);
+    create_dir_all(&folder).unwrap();
+
+    let pack_path = save_ca_pack(&folder, "data.pack", &[("text/db/data.loc", loc("data_key", "Data"))], game_info);
+    let cache_path = folder.join("test.pak2");
+    Dependencies::generate_dependencies_cache_from_packs(game_info, &[pack_path], &None, 0, &None).unwrap().save(&cache_path).unwrap();
+    assert!(Dependencies::load(&cache_path, &None).is_ok());
+
+    // Caches of other versions of the format must be regenerated.
+    let mut data = fs::read(&cache_path).unwrap();
+    data[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+    fs::write(&cache_path, &data).unwrap();
+    assert!(matches!(Dependencies::load(&cache_path, &None), Err(RLibError::DependenciesCacheUnsupportedFormat)));
+
+    fs::write(&cache_path, b"not a cache").unwrap();
+    assert!(matches!(Dependencies::load(&cache_path, &None), Err(RLibError::DependenciesCacheUnsupportedFormat)));
+
+    remove_dir_all(&folder).unwrap();
+}
+
+#[test]
+fn test_dependencies_cache_update() {
+    let games = SupportedGames::default();
+    let game_info = games.game(KEY_WARHAMMER_3).unwrap();
+    let schema = schema();
+
+    let folder = temp_dir().join("rpfm_test_dependencies_cache_update");
+    let _ = remove_dir_all(&folder);
+    create_dir_all(&folder).unwrap();
+
+    let a_path = save_ca_pack(&folder, "a.pack", &[("text/db/a.loc", loc("a_key", "A"))], game_info);
+    let b_path = save_ca_pack(&folder, "b.pack", &[("db/units_tables/b", db(&schema, "b_unit"))], game_info);
+
+    // Mark the data of the first Pack, so we know if it's reused from the cache or read again from the Pack.
+    let cache_path = folder.join("test.pak2");
+    let mut generated = Dependencies::generate_dependencies_cache_from_pack_paths(game_info, &[a_path.to_path_buf(), b_path], 0, &None).unwrap();
+    let section = generated.vanilla_sections.iter_mut().find(|section| section.pack.name == "a.pack").unwrap();
+    section.tables = vec![RFile::new_from_decoded(&loc("a_key", "Cached A"), 0, "text/db/a.loc")];
+    generated.save(&cache_path).unwrap();
+
+    let c_path = save_ca_pack(&folder, "c.pack", &[("db/units_tables/c", db(&schema, "c_unit"))], game_info);
+    let mut updated = Dependencies::update_dependencies_cache_from_pack_paths(&cache_path, game_info, &[a_path, c_path], 0, &None).unwrap();
+    updated.save(&cache_path).unwrap();
+
+    let loaded = Dependencies::load(&cache_path, &Some(schema)).unwrap();
+    assert_eq!(loaded.vanilla_packs().iter().map(|pack| pack.name.as_str()).collect::<Vec<_>>(), vec!["a.pack", "c.pack"]);
+    assert_eq!(first_value(loaded.loc_data(true, false).unwrap()[0]).unwrap(), "Cached A");
+    assert_eq!(first_value(loaded.db_data("units_tables", true, false).unwrap()[0]).unwrap(), "c_unit");
+    assert!(!loaded.file_exists("db/units_tables/b", true, false, false));
+
+    remove_dir_all(&folder).unwrap();
+}
+
+#[test]
+fn test_dependencies_cache_from_packs() {
+    let games = SupportedGames::default();
+    let game_info = games.game(KEY_WARHAMMER_3).unwrap();
+
+    let folder = temp_dir().join("rpfm_test_dependencies_cache_from_packs");
+    let _ = remove_dir_all(&folder);
+    let packs_folder = folder.join("packs");
+    create_dir_all(&packs_folder).unwrap();
+
+    save_ca_pack(&packs_folder, "data.pack", &[("script/data.lua", text("data"))], game_info);
+    save_ca_pack(&packs_folder, "local_en.pack", &[("text/db/en.loc", loc("en_key", "English"))], game_info);
+    save_ca_pack(&packs_folder, "local_fr.pack", &[("text/db/fr.loc", loc("fr_key", "French"))], game_info);
+    fs::write(packs_folder.join("notes.txt"), "not a pack").unwrap();
+    let extra_pack = save_ca_pack(&folder, "extra.pack", &[("script/extra.lua", text("extra"))], game_info);
+
+    // Folders are searched for Packs, and the Packs of other languages are skipped.
+    let cache_path = folder.join("test.pak2");
+    let mut generated = Dependencies::generate_dependencies_cache_from_packs(game_info, &[packs_folder.to_path_buf(), extra_pack], &Some("en".to_owned()), 12345, &None).unwrap();
+    assert_eq!(generated.vanilla_packs().iter().map(|pack| pack.name.as_str()).collect::<BTreeSet<_>>(), BTreeSet::from(["data.pack", "extra.pack", "local_en.pack"]));
+    assert!(generated.file_exists("text/db/en.loc", true, false, false));
+    assert!(!generated.file_exists("text/db/fr.loc", true, false, false));
+    assert!(*generated.from_packs());
+
+    // The game version and the source of the cache are kept on disk.
+    generated.save(&cache_path).unwrap();
+    let loaded = Dependencies::load(&cache_path, &None).unwrap();
+    assert_eq!(*loaded.game_version(), 12345);
+    assert!(*loaded.from_packs());
+
+    // Paths that don't exist are reported.
+    assert!(matches!(Dependencies::generate_dependencies_cache_from_packs(game_info, &[folder.join("missing")], &None, 0, &None), Err(RLibError::FileNotFound(_))));
+
+    remove_dir_all(&folder).unwrap();
+}
+
+#[test]
+fn test_rebuild_with_cache_from_packs() {
+    let games = SupportedGames::default();
+    let game_info = games.game(KEY_WARHAMMER_3).unwrap();
+
+    let folder = temp_dir().join("rpfm_test_rebuild_with_cache_from_packs");
+    let _ = remove_dir_all(&folder);
+    let game_path = folder.join("game");
+    create_dir_all(&game_path).unwrap();
+
+    let pack_path = save_ca_pack(&folder, "data.pack", &[("script/data.lua", text("data"))], game_info);
+
+    // Caches generated from a list of Packs are used as they are, even without the game installed.
+    let cache_path = folder.join("from_packs.pak2");
+    Dependencies::generate_dependencies_cache_from_packs(game_info, &[pack_path.to_path_buf()], &None, 0, &None).unwrap().save(&cache_path).unwrap();
+
+    let mut dependencies = Dependencies::default();
+    dependencies.rebuild(&None, &[], Some(&cache_path), game_info, &game_path).unwrap();
+    assert_eq!(contents(&dependencies, "script/data.lua"), "data");
+    assert!(*dependencies.from_packs());
+
+    // Caches from the game install are not updated on rebuild, even if the game install changed.
+    let cache_path = folder.join("from_game.pak2");
+    Dependencies::generate_dependencies_cache_from_pack_paths(game_info, &[pack_path], 0, &None).unwrap().save(&cache_path).unwrap();
+    let cache_data = fs::read(&cache_path).unwrap();
+
+    let mut dependencies = Dependencies::default();
+    dependencies.rebuild(&None, &[], Some(&cache_path), game_info, &game_path).unwrap();
+    assert_eq!(contents(&dependencies, "script/data.lua"), "data");
+    assert!(!*dependencies.from_packs());
+    assert_eq!(fs::read(&cache_path).unwrap(), cache_data);
+
+    remove_dir_all(&folder).unwrap();
+}
+
+fn references_schema() -> Schema {
+    let field = |name: &str, is_key: bool, reference: Option<(&str, &str)>| Field::new(name.to_owned(), FieldType::StringU8, is_key, None, false, None, reference.map(|(table, column)| (table.to_owned(), column.to_owned())), None, String::new(), 0, 0, BTreeMap::new(), None);
+
+    let mut units = Definition::new(1, None);
+    units.set_fields(vec![field("key", true, None), field("icon", false, None), field("category", false, None)]);
+    units.set_localised_fields(vec![Field::new("onscreen_name".to_owned(), FieldType::StringU16, false, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None)]);
+    units.set_localised_key_order(vec![0]);
+
+    let mut abilities = Definition::new(1, None);
+    abilities.set_fields(vec![field("key", true, None)]);
+
+    let mut unit_abilities = Definition::new(1, None);
+    unit_abilities.set_fields(vec![field("unit", true, Some(("units", "key"))), field("ability", true, Some(("abilities", "key")))]);
+
+    let mut ability_effects = Definition::new(1, None);
+    ability_effects.set_fields(vec![field("unit_ability", true, Some(("unit_abilities", "unit")))]);
+
+    let mut schema = Schema::default();
+    schema.add_definition("units_tables", &units);
+    schema.add_definition("abilities_tables", &abilities);
+    schema.add_definition("unit_abilities_tables", &unit_abilities);
+    schema.add_definition("ability_effects_tables", &ability_effects);
+    schema
+}
+
+fn table(schema: &Schema, table_name: &str, path: &str, rows: &[&[&str]]) -> RFile {
+    let definition = schema.definitions_by_table_name(table_name).unwrap().first().unwrap();
+    let mut table = DB::new(definition, None, table_name, false);
+    table.set_data(None, &rows.iter().map(|row| row.iter().map(|value| DecodedData::StringU8(value.to_string())).collect()).collect::<Vec<_>>()).unwrap();
+    RFile::new_from_decoded(&RFileDecoded::DB(table), 0, path)
+}
+
+#[test]
+fn test_references_to_key() {
+    let schema = references_schema();
+
+    let mut pack = Pack::default();
+    pack.insert(table(&schema, "units_tables", "db/units_tables/mod", &[&["knight", "", ""], &["archer", "", ""]])).unwrap();
+    pack.insert(table(&schema, "unit_abilities_tables", "db/unit_abilities_tables/mod", &[&["archer", "volley"], &["knight", "charge"]])).unwrap();
+    pack.insert(RFile::new_from_decoded(&loc("units_onscreen_name_knight", "Knight"), 0, "text/mod.loc")).unwrap();
+    pack.insert(RFile::new_from_decoded(&text("local knights = 2\nlocal unit = \"knight\""), 0, "script/mod.lua")).unwrap();
+
+    let mut dependencies = Dependencies::default();
+    dependencies.parent_files.insert("db/ability_effects_tables/parent".to_owned(), table(&schema, "ability_effects_tables", "db/ability_effects_tables/parent", &[&["knight"]]));
+    dependencies.parent_tables.insert("ability_effects_tables".to_owned(), vec!["db/ability_effects_tables/parent".to_owned()]);
+    dependencies.vanilla_files.insert("text/vanilla.loc".to_owned(), RFile::new_from_decoded(&loc("units_onscreen_name_knight", "Vanilla Knight"), 0, "text/vanilla.loc"));
+    dependencies.vanilla_locs.insert("text/vanilla.loc".to_owned());
+    dependencies.vanilla_files.insert("script/vanilla.lua".to_owned(), RFile::new_from_decoded(&text("knight"), 0, "script/vanilla.lua"));
+
+    let references = dependencies.references_to_key(&pack, &schema, "units", "key", "knight");
+
+    // Key rows, and rows referencing the key directly or through another referencing column.
+    assert_eq!(references.key_rows().iter().map(|row| (row.path().as_str(), *row.row_index())).collect::<Vec<_>>(), vec![("db/units_tables/mod", 0)]);
+    assert_eq!(references.referencing_rows().iter().map(|row| (row.source().clone(), row.path().as_str(), row.column_name().as_str(), *row.row_index())).collect::<Vec<_>>(), vec![
+        (SearchSource::ParentFiles, "db/ability_effects_tables/parent", "unit_ability", 0),
+        (SearchSource::Pack, "db/unit_abilities_tables/mod", "unit", 1),
+    ]);
+
+    // Loc entries derived from the key, from every source.
+    assert_eq!(references.loc_rows().iter().map(|row| (row.source().clone(), row.path().as_str(), row.key().as_str())).collect::<Vec<_>>(), vec![
+        (SearchSource::Pack, "text/mod.loc", "units_onscreen_name_knight"),
+        (SearchSource::GameFiles, "text/vanilla.loc", "units_onscreen_name_knight"),
+    ]);
+
+    // Text files mentioning the key as a whole word, with their lines.
+    assert_eq!(references.text_files().iter().map(|text| (text.source().clone(), text.path().as_str(), text.lines().to_vec())).collect::<Vec<_>>(), vec![
+        (SearchSource::Pack, "script/mod.lua", vec![2]),
+        (SearchSource::GameFiles, "script/vanilla.lua", vec![1]),
+    ]);
+
+    // Values not used anywhere have no references.
+    let references = dependencies.references_to_key(&pack, &schema, "units_tables", "key", "cavalry");
+    assert!(references.key_rows().is_empty() && references.referencing_rows().is_empty() && references.loc_rows().is_empty() && references.text_files().is_empty());
+}
+
+#[test]
+fn test_references_to_key_vanilla_texts_cache() {
+    let schema = references_schema();
+    let pack = Pack::default();
+
+    let mut dependencies = Dependencies::default();
+    dependencies.vanilla_files.insert("script/vanilla.lua".to_owned(), RFile::new_from_decoded(&text("knight"), 0, "script/vanilla.lua"));
+    assert_eq!(dependencies.references_to_key(&pack, &schema, "units", "key", "knight").text_files().len(), 1);
+
+    // The contents of the vanilla texts are decoded once, and reused on later searches.
+    dependencies.vanilla_files.insert("script/vanilla.lua".to_owned(), RFile::new_from_decoded(&text("archer"), 0, "script/vanilla.lua"));
+    assert_eq!(dependencies.references_to_key(&pack, &schema, "units", "key", "knight").text_files().len(), 1);
+    assert!(dependencies.references_to_key(&pack, &schema, "units", "key", "archer").text_files().is_empty());
+
+    // Until the vanilla files are edited.
+    dependencies.file_mut("script/vanilla.lua", true, false).unwrap();
+    assert!(dependencies.references_to_key(&pack, &schema, "units", "key", "knight").text_files().is_empty());
+    assert_eq!(dependencies.references_to_key(&pack, &schema, "units", "key", "archer").text_files().len(), 1);
+}
+
+fn related_rows_dependencies(schema: &Schema) -> Dependencies {
+    let mut dependencies = Dependencies::default();
+    for (table_name, rows) in [
+        ("units_tables", vec![vec!["knight", "knight_icon", "knight"], vec!["archer", "archer_icon", "archer"]]),
+        ("abilities_tables", vec![vec!["charge"], vec!["volley"]]),
+        ("unit_abilities_tables", vec![vec!["knight", "charge"], vec!["archer", "charge"], vec!["archer", "volley"]]),
+        ("ability_effects_tables", vec![vec!["knight"], vec!["archer"]]),
+    ] {
+        let path = format!("db/{table_name}/data");
+        let rows = rows.iter().map(|row| row.as_slice()).collect::<Vec<_>>();
+        dependencies.vanilla_files.insert(path.to_owned(), table(schema, table_name, &path, &rows));
+        dependencies.vanilla_tables.insert(table_name.to_owned(), vec![path]);
+    }
+
+    dependencies.vanilla_files.insert("text/db/data.loc".to_owned(), RFile::new_from_decoded(&loc("units_onscreen_name_knight", "Knight"), 0, "text/db/data.loc"));
+    dependencies.vanilla_locs.insert("text/db/data.loc".to_owned());
+    dependencies
+}
+
+fn table_rows(pack: &Pack, path: &str) -> Vec<Vec<String>> {
+    let data = match pack.files().get(path).unwrap().decoded().unwrap() {
+        RFileDecoded::DB(table) => table.data(&None).unwrap().to_vec(),
+        RFileDecoded::Loc(loc) => loc.data(&None).unwrap().to_vec(),
+        _ => panic!("{path} is not a table"),
+    };
+
+    data.iter().map(|row| row.iter().map(|cell| cell.data_to_string().to_string()).collect()).collect()
+}
+
+#[test]
+fn test_related_rows() {
+    let schema = references_schema();
+    let dependencies = related_rows_dependencies(&schema);
+    let pack = Pack::default();
+    let rows = |rows: &[RowReference]| rows.iter().map(|row| (row.table_name().to_owned(), *row.row_index())).collect::<Vec<_>>();
+
+    // Rows with the key, and the ones referencing it, directly or through another row with the key.
+    let related_rows = dependencies.related_rows(&pack, &schema, "units", "key", "knight", false);
+    assert_eq!(rows(related_rows.rows()), vec![
+        ("ability_effects_tables".to_owned(), 0),
+        ("unit_abilities_tables".to_owned(), 0),
+        ("units_tables".to_owned(), 0),
+    ]);
+    assert!(related_rows.referenced_rows().is_empty());
+
+    // Keys with other values of multi-key rows are shared, so the rows using them are only referenced, and not followed.
+    let related_rows = dependencies.related_rows(&pack, &schema, "units_tables", "key", "knight", true);
+    assert_eq!(rows(related_rows.rows()).len(), 3);
+    assert_eq!(rows(related_rows.referenced_rows()), vec![("abilities_tables".to_owned(), 0)]);
+
+    // Starting from the shared key only gets the rows using it, not the rest of their keys.
+    let related_rows = dependencies.related_rows(&pack, &schema, "abilities", "key", "charge", true);
+    assert_eq!(rows(related_rows.rows()), vec![
+        ("abilities_tables".to_owned(), 0),
+        ("unit_abilities_tables".to_owned(), 0),
+        ("unit_abilities_tables".to_owned(), 1),
+    ]);
+    assert_eq!(rows(related_rows.referenced_rows()), vec![("units_tables".to_owned(), 0), ("units_tables".to_owned(), 1)]);
+
+    assert_eq!(dependencies.related_rows(&pack, &schema, "units", "key", "", true), RelatedRows::default());
+}
+
+#[test]
+fn test_import_related_rows() {
+    let schema = references_schema();
+    let dependencies = related_rows_dependencies(&schema);
+
+    // Without a new value, the rows are imported as they are.
+    let mut pack = Pack::default();
+    let paths = dependencies.import_related_rows(&mut pack, &schema, "units", "key", "knight", None, false, "new").unwrap();
+    assert_eq!(paths.len(), 4);
+    assert_eq!(table_rows(&pack, "db/units_tables/new"), vec![vec!["knight", "knight_icon", "knight"]]);
+    assert_eq!(table_rows(&pack, "text/db/new.loc"), vec![vec!["units_onscreen_name_knight", "Knight", "false"]]);
+
+    // With a new value, only whole values in our keys and in the columns referencing them are renamed. Referenced rows are kept as they are.
+    let mut pack = Pack::default();
+    dependencies.import_related_rows(&mut pack, &schema, "units", "key", "knight", Some("paladin"), true, "new").unwrap();
+    assert_eq!(table_rows(&pack, "db/units_tables/new"), vec![vec!["paladin", "knight_icon", "knight"]]);
+    assert_eq!(table_rows(&pack, "db/unit_abilities_tables/new"), vec![vec!["paladin", "charge"]]);
+    assert_eq!(table_rows(&pack, "db/ability_effects_tables/new"), vec![vec!["paladin"]]);
+    assert_eq!(table_rows(&pack, "db/abilities_tables/new"), vec![vec!["charge"]]);
+    assert_eq!(table_rows(&pack, "text/db/new.loc"), vec![vec!["units_onscreen_name_paladin", "Knight", "false"]]);
+
+    // Rows already in the Pack are not imported again.
+    dependencies.import_related_rows(&mut pack, &schema, "units", "key", "knight", Some("paladin"), true, "new").unwrap();
+    assert_eq!(table_rows(&pack, "db/units_tables/new").len(), 1);
+    assert_eq!(table_rows(&pack, "db/abilities_tables/new").len(), 1);
+    assert_eq!(table_rows(&pack, "text/db/new.loc").len(), 1);
+}
+
+#[test]
+fn test_reference_lookups() {
+    let schema = references_schema();
+    let dependencies = related_rows_dependencies(&schema);
+    let reference = |lookup: Vec<&str>| Field::new("unit".to_owned(), FieldType::StringU8, true, None, false, None, Some(("units".to_owned(), "key".to_owned())), Some(lookup.iter().map(|column| column.to_string()).collect()), String::new(), 0, 0, BTreeMap::new(), None);
+
+    // Lookups from columns of the referenced table, and from its loc entries. Empty lookups are skipped.
+    let field = reference(vec!["onscreen_name", "icon"]);
+    let lookups = dependencies.reference_lookups(None, &field);
+    assert_eq!(lookups.get("knight").map(|lookup| lookup.as_str()), Some("Knight knight_icon"));
+    assert_eq!(lookups.get("archer").map(|lookup| lookup.as_str()), Some("archer_icon"));
+    assert_eq!(dependencies.reference_display_value(None, &field, "knight"), "knight (Knight knight_icon)");
+    assert_eq!(dependencies.reference_display_value(None, &field, "cavalry"), "cavalry");
+
+    // The lookups are the same reference data the table views use.
+    let definition = schema.definitions_by_table_name("unit_abilities_tables").unwrap().first().unwrap().clone();
+    let mut definition_with_lookups = definition.clone();
+    definition_with_lookups.set_fields(vec![field.clone()]);
+    assert_eq!(dependencies.generate_references(&definition_with_lookups).get(&0).unwrap().data().get("knight").map(|lookup| lookup.as_str()), Some("Knight knight_icon"));
+
+    // The Pack has priority over the dependencies, for both rows and loc entries.
+    let mut pack = Pack::default();
+    pack.insert(table(&schema, "units_tables", "db/units_tables/mod", &[&["archer", "mod_icon", ""], &["paladin", "", ""]])).unwrap();
+    pack.insert(RFile::new_from_decoded(&loc("units_onscreen_name_paladin", "Mod Paladin"), 0, "text/mod.loc")).unwrap();
+    let field = reference(vec!["onscreen_name"]);
+    assert_eq!(dependencies.reference_lookup(Some(&pack), &field, "paladin").as_deref(), Some("Mod Paladin"));
+    assert_eq!(dependencies.reference_lookup(Some(&pack), &field, "knight").as_deref(), Some("Knight"));
+    assert_eq!(dependencies.reference_lookup(Some(&pack), &reference(vec!["icon"]), "archer").as_deref(), Some("mod_icon"));
+
+    // Lookups of all the reference columns of a table, by column.
+    assert!(dependencies.table_reference_lookups(None, &definition).is_empty());
+
+    let mut definition = definition;
+    definition.set_fields(vec![field.clone(), reference(vec![])]);
+    let lookups = dependencies.table_reference_lookups(None, &definition);
+    assert_eq!(lookups.keys().collect::<Vec<_>>(), vec![&0]);
+
+    assert_eq!(display_value("knight", Some("knight")), "knight");
+    assert_eq!(display_value("knight", Some("")), "knight");
+}
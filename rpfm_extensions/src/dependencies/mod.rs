@@ -12,31 +12,55 @@
 
 use getset::{Getters, MutGetters};
 use itertools::Itertools;
+use once_cell::sync::OnceCell;
 use rayon::prelude::*;
 use serde_derive::{Serialize, Deserialize};
 
-use std::collections::{HashMap, HashSet};
-use std::fs::{DirBuilder, File};
-use std::io::{BufReader, Read, Write};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::{self, Display};
 use std::path::{Path, PathBuf};
-use std::thread::{JoinHandle, spawn};
+use std::sync::{Arc, RwLock};
 
 use rpfm_lib::error::{Result, RLibError};
-use rpfm_lib::files::{Container, ContainerPath, db::DB, DecodeableExtraData, FileType, pack::Pack, RFile, RFileDecoded};
-use rpfm_lib::games::GameInfo;
+use rpfm_lib::files::{Container, ContainerPath, db::DB, DecodeableExtraData, FileType, loc::Loc, pack::Pack, RFile, RFileDecoded, table::DecodedData};
+use rpfm_lib::games::{GameInfo, pfh_file_type::PFHFileType};
 use rpfm_lib::integrations::{assembly_kit::table_data::RawTable, log::info};
-use rpfm_lib::schema::{Definition, Schema};
-use rpfm_lib::utils::{current_time, last_modified_time_from_files, starts_with_case_insensitive};
+use rpfm_lib::schema::{Definition, Field, Schema};
+use rpfm_lib::utils::{current_time, files_from_subdir, last_modified_time_from_files, starts_with_case_insensitive};
+
+use crate::loc_key_order::LocKeyOrderReport;
+use crate::search::SearchSource;
+
+use self::cache::{CacheFile, CacheSection};
+
+mod cache;
+
+#[cfg(test)] mod dependencies_test;
 
 //-------------------------------------------------------------------------------//
 //                              Enums & Structs
 //-------------------------------------------------------------------------------//
 
+/// Key of the reference data cache, in a (referenced table, referenced column, lookup columns) format.
+type ReferencesCacheKey = (String, String, Vec<String>);
+
+/// Reference data of a column before resolving the loc entries of its lookups, in a "key, lookup parts" format.
+type ReferenceData = Vec<(String, Vec<LookupPart>)>;
+
+/// Part of a lookup value, before resolving its loc entries.
+enum LookupPart {
+    Text(String),
+    LocKey(String),
+}
+
 /// This struct represents a dependencies manager for all dependencies relevant of a Pack.
 ///
 /// As even I am getting a bit confused by how this works (and it has caused a few bugs):
-/// - First, these ones are serialized to disk and do not change unless we regenerate the dependencies:
+/// - First, these ones are serialized to disk and do not change unless we regenerate or update the dependencies:
 ///     - asskit_only_db_tables.
+///     - game_version.
+///     - from_packs.
+///     - vanilla_packs.
 ///     - vanilla_files.
 ///     - vanilla_tables.
 ///     - vanilla_locs.
@@ -45,9 +69,22 @@ use rpfm_lib::utils::{current_time, last_modified_time_from_files, starts_with_c
 ///     - parent_tables.
 ///     - parent_locs.
 ///     - local_tables_references.
+///     - installed_mods_paths.
+///
+/// - Then, we have the ones that are kept between rebuilds, and only change when set:
+///     - installed_mods_sources.
+///     - installed_mods_ignored.
+///     - installed_mods_packs.
 ///
 /// - Then, on runtime, we add decoded table's reference data to this one, so we don't need to recalculate it again.
 ///     - local_tables_references,
+///     - cached_references,
+///
+/// - And the vanilla tables of caches loaded from disk are decoded on first access, and kept here:
+///     - vanilla_lazy_tables,
+///
+/// - Same for the contents of the vanilla text files, decoded on the first search over them:
+///     - vanilla_texts,
 #[derive(Default, Debug, Clone, Getters, Serialize, Deserialize)]
 #[getset(get = "pub")]
 pub struct Dependencies {
@@ -55,9 +92,46 @@ pub struct Dependencies {
     /// Date of the generation of this dependencies cache. For checking if it needs an update.
     build_date: u64,
 
+    /// Build of the game this dependencies cache was generated from. 0 if it's unknown.
+    game_version: u32,
+
+    /// If this dependencies cache was generated from a list of CA Packs instead of from a game install.
+    ///
+    /// These caches are not updated from the game install, as they may not match it.
+    from_packs: bool,
+
+    /// CA Packs the vanilla files are from, in the order they're loaded.
+    vanilla_packs: Vec<VanillaPack>,
+
+    /// Files of each CA Pack, used to write the cache to disk.
+    ///
+    /// Only available on caches generated or updated in this session. Not serialized.
+    #[serde(skip_serializing, skip_deserializing)]
+    #[getset(skip)]
+    vanilla_sections: Vec<CacheSection>,
+
     /// Data to quickly load CA dependencies from disk.
+    ///
+    /// Tables in caches loaded with a schema are kept undecoded here. Use the getters of this struct to get them decoded.
     vanilla_files: HashMap<String, RFile>,
 
+    /// Vanilla DB and Loc files of a cache loaded with a schema, decoded on first access.
+    ///
+    /// Not serialized, regenerated on load.
+    #[serde(skip_serializing, skip_deserializing)]
+    #[getset(skip)]
+    vanilla_lazy_tables: HashMap<String, OnceCell<RFile>>,
+
+    /// Schema used to decode the lazy-loaded vanilla tables.
+    #[serde(skip_serializing, skip_deserializing)]
+    #[getset(skip)]
+    vanilla_lazy_schema: Option<Schema>,
+
+    /// Contents of the vanilla text files, by path, decoded on the first search over them.
+    #[serde(skip_serializing, skip_deserializing)]
+    #[getset(skip)]
+    vanilla_texts: OnceCell<HashMap<String, String>>,
+
     /// Data to quickly load dependencies from parent mods from disk.
     ///
     /// Not serialized, regenerated from parent Packs on rebuild.
@@ -95,8 +169,72 @@ pub struct Dependencies {
     #[serde(skip_serializing, skip_deserializing)]
     local_tables_references: HashMap<String, HashMap<i32, TableReferences>>,
 
+    /// Cached reference data from the vanilla, parent and assembly kit tables, in a (table, column, lookup columns) -> references format.
+    ///
+    /// This is for runtime caching, shared between all tables referencing the same column, and it must not be serialized to disk.
+    #[serde(skip_serializing, skip_deserializing)]
+    #[getset(skip)]
+    cached_references: Arc<RwLock<HashMap<ReferencesCacheKey, TableReferences>>>,
+
     /// DB Files only available on the assembly kit. Usable only for references. Do not use them as the base for new tables.
     asskit_only_db_tables: HashMap<String, DB>,
+
+    /// Sources of installed mods to load as parent files, in order of priority. Parent Packs have priority over them.
+    ///
+    /// Not serialized, kept between rebuilds.
+    #[serde(skip_serializing, skip_deserializing)]
+    installed_mods_sources: Vec<InstalledModsSource>,
+
+    /// Names of the Packs to never load from the installed mods sources, like the Pack we're editing.
+    ///
+    /// Not serialized, kept between rebuilds.
+    #[serde(skip_serializing, skip_deserializing)]
+    installed_mods_ignored: Vec<String>,
+
+    /// Packs read from the installed mods sources, with their last modified date, so the unchanged ones are not read again on rebuild.
+    ///
+    /// Not serialized, kept between rebuilds.
+    #[serde(skip_serializing, skip_deserializing)]
+    #[getset(skip)]
+    installed_mods_packs: HashMap<PathBuf, (u64, Pack)>,
+
+    /// Paths of the parent files that come from the installed mods, to tell them apart from the ones of the parent Packs.
+    ///
+    /// Not serialized, regenerated on rebuild.
+    #[serde(skip_serializing, skip_deserializing)]
+    installed_mods_paths: HashSet<String>,
+}
+
+/// This struct represents a CA Pack the vanilla files of a dependencies cache are from.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct VanillaPack {
+
+    /// File name of the Pack.
+    name: String,
+
+    /// Path of the Pack on disk.
+    path: String,
+
+    /// Last modified date of the Pack when it was read into the cache.
+    timestamp: u64,
+
+    /// Type of the Pack. Used to sort the Packs in the order the game loads them.
+    pfh_file_type: PFHFileType,
+}
+
+/// This enum represents a source of installed mods to use as dependencies.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InstalledModsSource {
+
+    /// The `/data` folder of the game.
+    DataFolder,
+
+    /// The folder where local mods are installed during development. Usually the same as `/data`.
+    LocalModsFolder,
+
+    /// Any folder with Packs.
+    Folder(PathBuf),
 }
 
 /// This holds the reference data for a table's column.
@@ -117,6 +255,67 @@ pub struct TableReferences {
     data: HashMap<String, String>,
 }
 
+/// This struct contains everything using a key of a table, from the Pack, the parent files and the game files.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct KeyReferences {
+
+    /// Rows of the referenced table with the key.
+    key_rows: Vec<RowReference>,
+
+    /// Rows referencing the key, directly or through columns referencing a column that references it.
+    referencing_rows: Vec<RowReference>,
+
+    /// Loc entries whose keys are derived from any of the rows above.
+    loc_rows: Vec<LocReference>,
+
+    /// Text files, including scripts, mentioning the key.
+    text_files: Vec<TextReference>,
+}
+
+/// This struct represents a row using a key.
+#[derive(Clone, Debug, PartialEq, Eq, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct RowReference {
+    source: SearchSource,
+    path: String,
+    table_name: String,
+    column_name: String,
+    column_index: usize,
+    row_index: usize,
+}
+
+/// This struct represents a loc entry derived from a row using a key.
+#[derive(Clone, Debug, PartialEq, Eq, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct LocReference {
+    source: SearchSource,
+    path: String,
+    key: String,
+    row_index: usize,
+}
+
+/// This struct represents a text file mentioning a key, with the lines mentioning it, starting at 1.
+#[derive(Clone, Debug, PartialEq, Eq, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct TextReference {
+    source: SearchSource,
+    path: String,
+    lines: Vec<usize>,
+}
+
+/// This struct contains the rows related to a key of a table, from the parent files and the game files.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct RelatedRows {
+
+    /// Rows with the key, and rows referencing them through it, directly or through other rows with the key.
+    rows: Vec<RowReference>,
+
+    /// Rows referenced by the rows above through other values. They're usually shared with other rows.
+    referenced_rows: Vec<RowReference>,
+}
+
 //-------------------------------------------------------------------------------//
 //                             Implementations
 //-------------------------------------------------------------------------------//
@@ -131,23 +330,46 @@ impl Dependencies {
     ///
     /// If a file path is passed, the dependencies cache at that path will be used, replacing the currently loaded dependencies cache.
     /// If a schema is not passed, no tables/locs will be pre-decoded. Make sure to decode them later with [decode_tables].
+    ///
+    /// The cache is used as it is, even if the CA Packs changed since it was generated. Use [Dependencies::needs_updating] to check that,
+    /// and [Dependencies::update_dependencies_cache] to update it.
     pub fn rebuild(&mut self, schema: &Option<Schema>, parent_pack_names: &[String], file_path: Option<&Path>, game_info: &GameInfo, game_path: &Path) -> Result<()> {
 
         // If we only want to reload the parent mods, not the full dependencies, we can skip this section.
         if let Some(file_path) = file_path {
 
             // First, clear the current data, so we're not left with broken data afterwards if the next operations fail.
-            *self = Self::default();
-
-            // Try to load the binary file and check if it's even valid.
+            // The installed mods config is kept, as it's not part of the cache.
+            *self = Self {
+                installed_mods_sources: std::mem::take(&mut self.installed_mods_sources),
+                installed_mods_ignored: std::mem::take(&mut self.installed_mods_ignored),
+                installed_mods_packs: std::mem::take(&mut self.installed_mods_packs),
+                ..Default::default()
+            };
+
+            // Try to load the binary file.
             let stored_data = Self::load(file_path, schema)?;
-            if !stored_data.needs_updating(game_info, game_path)? {
-                *self = stored_data;
-            }
+            *self = Self {
+                installed_mods_sources: std::mem::take(&mut self.installed_mods_sources),
+                installed_mods_ignored: std::mem::take(&mut self.installed_mods_ignored),
+                installed_mods_packs: std::mem::take(&mut self.installed_mods_packs),
+                ..stored_data
+            };
         }
 
         // Clear the table's cached data, to ensure it gets rebuild properly when needed.
         self.local_tables_references.clear();
+        self.clear_cached_references();
+
+        // Clear the parent data, so files from installed mods no longer used do not linger.
+        self.parent_files.clear();
+        self.parent_tables.clear();
+        self.parent_locs.clear();
+        self.parent_folders.clear();
+        self.installed_mods_paths.clear();
+
+        // Preload the installed mods first, so the parent mods of the currently loaded Pack overwrite their files.
+        self.load_installed_mods(parent_pack_names, game_info, game_path);
 
         // Preload parent mods of the currently loaded Pack.
         self.load_parent_packs(parent_pack_names, game_info, game_path)?;
@@ -198,6 +420,7 @@ impl Dependencies {
         if let Some(schema) = schema {
             let mut decode_extra_data = DecodeableExtraData::default();
             decode_extra_data.set_schema(Some(schema));
+            decode_extra_data.set_table_index(true);
             let extra_data = Some(decode_extra_data);
 
             let mut files = self.parent_locs.iter().chain(self.parent_tables.values().flatten()).filter_map(|path| {
@@ -216,23 +439,134 @@ impl Dependencies {
 
     /// This function generates the dependencies cache for the game provided and returns it.
     pub fn generate_dependencies_cache(game_info: &GameInfo, game_path: &Path, asskit_path: &Option<PathBuf>) -> Result<Self> {
-        let mut cache = Self::default();
-        cache.build_date = current_time()?;
-        cache.vanilla_files = Pack::read_and_merge_ca_packs(game_info, game_path)?.files().clone();
+        let pack_paths = game_info.ca_packs_paths(game_path)?;
+        let game_version = game_info.game_build(game_path).unwrap_or(0);
+        Self::generate_dependencies_cache_from_pack_paths(game_info, &pack_paths, game_version, asskit_path)
+    }
 
-        let cacheable = cache.vanilla_files.par_iter_mut()
-            .filter_map(|(_, file)| {
-                let _ = file.guess_file_type();
+    /// This function generates the dependencies cache for the game provided from a list of CA Packs, without needing the game installed, and returns it.
+    ///
+    /// Each path can be either a Pack or a folder with Packs. If a language is provided, the `local_` Packs of other languages are skipped.
+    /// As there is no game install to get it from, the game version the cache is for must be provided. Use 0 if it's unknown.
+    ///
+    /// The generated cache is never updated from the game install when rebuilding the dependencies with it.
+    pub fn generate_dependencies_cache_from_packs(game_info: &GameInfo, paths: &[PathBuf], language: &Option<String>, game_version: u32, asskit_path: &Option<PathBuf>) -> Result<Self> {
+        let mut pack_paths = vec![];
+        for path in paths {
+            if path.is_dir() {
+                pack_paths.extend(files_from_subdir(path, false)?.into_iter()
+                    .filter(|path| matches!(path.extension(), Some(extension) if extension.to_string_lossy().to_lowercase() == "pack")));
+            } else if path.is_file() {
+                pack_paths.push(path.to_path_buf());
+            } else {
+                return Err(RLibError::FileNotFound(path.to_string_lossy().to_string()));
+            }
+        }
 
-                match file.file_type() {
-                    FileType::DB |
-                    FileType::Loc => Some(file),
-                    _ => None,
+        // Filter out other language's Packs.
+        if let Some(language) = language {
+            let language_pack = format!("local_{language}");
+            pack_paths.retain(|path| match path.file_name() {
+                Some(name) => {
+                    let name = name.to_string_lossy().to_lowercase();
+                    !name.starts_with("local_") || name.starts_with(&language_pack)
+                }
+                None => false,
+            });
+        }
+
+        pack_paths.sort();
+        pack_paths.dedup();
+
+        let mut cache = Self::generate_dependencies_cache_from_pack_paths(game_info, &pack_paths, game_version, asskit_path)?;
+        cache.from_packs = true;
+        Ok(cache)
+    }
+
+    /// This function generates the dependencies cache for the game provided from the CA Packs at the provided paths, and returns it.
+    fn generate_dependencies_cache_from_pack_paths(game_info: &GameInfo, pack_paths: &[PathBuf], game_version: u32, asskit_path: &Option<PathBuf>) -> Result<Self> {
+        let sections = pack_paths
+            .par_iter()
+            .map(|path| CacheSection::from_pack(path))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut cache = Self::from_sections(sections)?;
+        cache.game_version = game_version;
+
+        // This one can fail, leaving the dependencies with only game data.
+        if let Some(path) = asskit_path {
+            let _ = cache.generate_asskit_only_db_tables(path, game_info.raw_db_version());
+        }
+
+        Ok(cache)
+    }
+
+    /// This function updates the dependencies cache at the provided path, re-reading only the CA Packs that changed since it was generated, and returns it.
+    ///
+    /// If an Assembly Kit path is provided, the tables only in the Assembly Kit are regenerated. If not, the ones in the cache are reused.
+    ///
+    /// The updated cache is not saved to disk. Use [Dependencies::save] for that.
+    pub fn update_dependencies_cache(file_path: &Path, game_info: &GameInfo, game_path: &Path, asskit_path: &Option<PathBuf>) -> Result<Self> {
+        let pack_paths = game_info.ca_packs_paths(game_path)?;
+        let game_version = game_info.game_build(game_path).unwrap_or(0);
+        Self::update_dependencies_cache_from_pack_paths(file_path, game_info, &pack_paths, game_version, asskit_path)
+    }
+
+    /// This function updates the dependencies cache at the provided path with the CA Packs at the provided paths, and returns it.
+    fn update_dependencies_cache_from_pack_paths(file_path: &Path, game_info: &GameInfo, pack_paths: &[PathBuf], game_version: u32, asskit_path: &Option<PathBuf>) -> Result<Self> {
+        let mut cache_file = CacheFile::open(file_path)?;
+        let sections = pack_paths
+            .par_iter()
+            .map(|path| {
+                let (path_str, timestamp) = VanillaPack::path_and_timestamp(path)?;
+                match cache_file.index.sections.iter().position(|section| section.pack.is_same_pack(&path_str, timestamp)) {
+                    Some(index) => cache_file.section(index),
+                    None => CacheSection::from_pack(path),
                 }
             })
-            .collect::<Vec<&mut RFile>>();
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut cache = Self::from_sections(sections)?;
+        cache.game_version = game_version;
+        match asskit_path {
+
+            // This one can fail, leaving the dependencies with only game data.
+            Some(path) => {
+                let _ = cache.generate_asskit_only_db_tables(path, game_info.raw_db_version());
+            }
 
-        cacheable.iter()
+            // Reuse the old ones, except the ones that are now in the game files.
+            None => {
+                cache.asskit_only_db_tables = std::mem::take(&mut cache_file.index.asskit_only_db_tables);
+                cache.asskit_only_db_tables.retain(|table_name, _| !cache.vanilla_tables.contains_key(table_name));
+            }
+        }
+
+        Ok(cache)
+    }
+
+    /// This function builds the vanilla data of a dependencies cache from the files of the provided CA Packs.
+    fn from_sections(mut sections: Vec<CacheSection>) -> Result<Self> {
+
+        // Same order used when merging Packs, so files in a Pack overwrite the ones in the Packs before it.
+        sections.sort_by(|a, b| a.pack.path.cmp(&b.pack.path));
+        sections.sort_by_key(|section| section.pack.pfh_file_type as u8);
+
+        let mut cache = Self {
+            build_date: current_time()?,
+            vanilla_packs: sections.iter().map(|section| section.pack.clone()).collect(),
+            ..Default::default()
+        };
+
+        for section in &sections {
+            cache.vanilla_files.extend(section.files.iter()
+                .chain(section.tables.iter())
+                .map(|file| (file.path_in_container_raw().to_owned(), file.clone())));
+        }
+
+        cache.vanilla_sections = sections;
+
+        cache.vanilla_files.values()
             .for_each(|file| {
                 match file.file_type() {
                     FileType::DB => {
@@ -270,11 +604,6 @@ impl Dependencies {
             }
         }).flatten().collect::<HashSet<String>>();
 
-        // This one can fail, leaving the dependencies with only game data.
-        if let Some(path) = asskit_path {
-            let _ = cache.generate_asskit_only_db_tables(path, game_info.raw_db_version());
-        }
-
         Ok(cache)
     }
 
@@ -299,16 +628,23 @@ impl Dependencies {
     ///
     /// NOTE: This function, like many others, assumes the tables are already decoded in the Pack. If they're not, they'll be ignored.
     pub fn generate_local_db_references(&mut self, pack: &Pack, table_names: &[String]) {
-
-        let local_tables_references = pack.files_by_type(&[FileType::DB]).par_iter().filter_map(|file| {
+        let definitions = pack.files_by_type(&[FileType::DB]).into_iter().filter_map(|file| {
             if let Ok(RFileDecoded::DB(db)) = file.decoded() {
 
                 // Only generate references for the tables you pass it, or for all if we pass the list of tables empty.
                 if table_names.is_empty() || table_names.iter().any(|x| x == db.table_name()) {
-                     Some((db.table_name().to_owned(), self.generate_references(db.definition())))
+                    Some((db.table_name(), db.definition()))
                 } else { None }
             } else { None }
-        }).collect::<HashMap<_, _>>();
+        }).collect::<Vec<_>>();
+
+        // Populate the cache first, so each referenced column is only processed once, even if many tables reference it.
+        let cache_keys = definitions.iter().flat_map(|(_, definition)| Self::referenced_columns(definition)).map(|(_, _, cache_key)| cache_key).collect::<Vec<_>>();
+        self.cache_references(cache_keys.iter());
+
+        let local_tables_references = definitions.par_iter()
+            .map(|(table_name, definition)| (table_name.to_string(), self.generate_references(definition)))
+            .collect::<HashMap<_, _>>();
 
         self.local_tables_references.extend(local_tables_references);
     }
@@ -318,155 +654,245 @@ impl Dependencies {
         self.local_tables_references.insert(table_name.to_owned(), self.generate_references(definition));
     }
 
+    /// This function clears the cached reference data of the vanilla, parent and assembly kit tables.
+    ///
+    /// Use it if you edited the dependencies' tables without going through the mutable getters of this struct.
+    pub fn clear_cached_references(&mut self) {
+        self.cached_references = Arc::default();
+    }
+
     /// This function builds the local db references data for the table with the definition you pass to, and returns it.
+    ///
+    /// The reference data of each referenced column is cached, so it's only calculated once for all tables referencing it.
     pub fn generate_references(&self, definition: &Definition) -> HashMap<i32, TableReferences> {
-        definition.fields_processed().into_iter().enumerate().filter_map(|(column, field)| {
-            if let Some((ref ref_table, ref ref_column)) = field.is_reference() {
-                if !ref_table.is_empty() && !ref_column.is_empty() {
-                    let ref_table = format!("{ref_table}_tables");
+        let referenced_columns = Self::referenced_columns(definition);
+        self.cache_references(referenced_columns.iter().map(|(_, _, cache_key)| cache_key));
+
+        let cached_references = self.cached_references.read().unwrap();
+        referenced_columns.into_iter().filter_map(|(column, field_name, cache_key)| {
+            let mut references = cached_references.get(&cache_key)?.clone();
+            *references.field_name_mut() = field_name;
+            Some((column as i32, references))
+        }).collect::<HashMap<_, _>>()
+    }
 
-                    // Get his lookup data if it has it.
-                    let lookup_data = if let Some(ref data) = field.lookup() { data.to_vec() } else { Vec::with_capacity(0) };
-                    let mut references = TableReferences::default();
-                    *references.field_name_mut() = field.name().to_owned();
+    /// This function calculates the reference data of the provided columns that are not yet cached, and caches it.
+    fn cache_references<'a>(&self, cache_keys: impl Iterator<Item = &'a ReferencesCacheKey>) {
+        let cache_keys = {
+            let cached_references = self.cached_references.read().unwrap();
+            cache_keys.filter(|cache_key| !cached_references.contains_key(*cache_key))
+                .cloned()
+                .collect::<HashSet<_>>()
+        };
 
-                    let fake_found = self.db_reference_data_from_asskit_tables(&mut references, (&ref_table, ref_column, &lookup_data));
-                    let real_found = self.db_reference_data_from_from_vanilla_and_modded_tables(&mut references, (&ref_table, ref_column, &lookup_data));
+        if cache_keys.is_empty() {
+            return;
+        }
 
-                    if fake_found && real_found.is_none() {
-                        references.referenced_table_is_ak_only = true;
-                    }
+        let new_references = cache_keys.into_par_iter().map(|cache_key| {
+            let (ref_table, ref_column, lookup_data) = &cache_key;
+            let mut references = TableReferences::default();
+            let mut reference_data = vec![];
+            let fake_found = self.db_reference_data_from_asskit_tables(&mut reference_data, (ref_table, ref_column, lookup_data));
+            let real_found = self.db_reference_data_from_from_vanilla_and_modded_tables(&mut reference_data, (ref_table, ref_column, lookup_data));
 
-                    if let Some(ref_definition) = real_found {
-                        if ref_definition.localised_fields().iter().any(|x| x.name() == ref_column) {
-                            references.referenced_column_is_localised = true;
-                        }
-                    }
+            if fake_found && real_found.is_none() {
+                references.referenced_table_is_ak_only = true;
+            }
 
-                    Some((column as i32, references))
-                } else { None }
-            } else { None }
-        }).collect::<HashMap<_, _>>()
-    }
+            if let Some(ref_definition) = real_found {
+                if ref_definition.localised_fields().iter().any(|x| x.name() == ref_column) {
+                    references.referenced_column_is_localised = true;
+                }
+            }
 
-    /// This function tries to load dependencies from the path provided.
-    pub fn load(file_path: &Path, schema: &Option<Schema>) -> Result<Self> {
+            (cache_key, references, reference_data)
+        }).collect::<Vec<_>>();
 
-        // Optimization: Instead of a big file, we split the dependencies in 3 files. Why?
-        // Because bincode is not multithreaded and, while reading 3 medium files is slower than a big one,
-        // deserializing 3 medium files in 3 separate threads is way faster than 1 big file in 1 thread.
-        let mut file_path_1 = file_path.to_path_buf();
-        let handle_1: JoinHandle<Result<(u64, Vec<RFile>)>> = spawn(move || {
-            file_path_1.set_extension("pak1");
-            let mut file = BufReader::new(File::open(&file_path_1)?);
-            let mut data = Vec::with_capacity(file.get_ref().metadata()?.len() as usize);
-            file.read_to_end(&mut data)?;
-
-            // Never deserialize directly from the file. It's bloody slow!!!
-            bincode::deserialize(&data).map_err(From::from)
-        });
+        // Loc entries are read once for all the new columns, and only if any of them needs them.
+        let loc_keys = new_references.iter()
+            .flat_map(|(_, _, reference_data)| Self::reference_data_loc_keys(reference_data))
+            .collect::<HashSet<_>>();
+        let loc_texts = self.loc_texts(None, &loc_keys);
 
-        let mut file_path_2 = file_path.to_path_buf();
-        let handle_2: JoinHandle<Result<Vec<RFile>>> = spawn(move || {
-            file_path_2.set_extension("pak2");
-            let mut file = BufReader::new(File::open(&file_path_2)?);
-            let mut data = Vec::with_capacity(file.get_ref().metadata()?.len() as usize);
-            file.read_to_end(&mut data)?;
+        let new_references = new_references.into_iter().map(|(cache_key, mut references, reference_data)| {
+            Self::add_reference_data(&mut references, reference_data, &loc_texts);
+            (cache_key, references)
+        }).collect::<Vec<_>>();
 
-            // Never deserialize directly from the file. It's bloody slow!!!
-            bincode::deserialize(&data).map_err(From::from)
-        });
+        self.cached_references.write().unwrap().extend(new_references);
+    }
+
+    /// This function returns the columns of a definition referencing other tables, with their name and their key in the reference cache.
+    fn referenced_columns(definition: &Definition) -> Vec<(usize, String, ReferencesCacheKey)> {
+        definition.fields_processed().into_iter().enumerate().filter_map(|(column, field)| {
+            Self::reference_cache_key(&field).map(|cache_key| (column, field.name().to_owned(), cache_key))
+        }).collect()
+    }
 
-        let mut file_path_3 = file_path.to_path_buf();
-        let handle_3: JoinHandle<Result<(HashMap<String, Vec<String>>, HashSet<String>, HashSet<String>, HashMap<String, DB>)>> = spawn(move || {
-            file_path_3.set_extension("pak3");
-            let mut file = BufReader::new(File::open(&file_path_3)?);
-            let mut data = Vec::with_capacity(file.get_ref().metadata()?.len() as usize);
-            file.read_to_end(&mut data)?;
+    /// This function returns the key in the reference cache of a column, if it references another table.
+    fn reference_cache_key(field: &Field) -> Option<ReferencesCacheKey> {
+        if let Some((ref ref_table, ref ref_column)) = field.is_reference() {
+            if !ref_table.is_empty() && !ref_column.is_empty() {
+                let ref_table = format!("{ref_table}_tables");
 
-            // Never deserialize directly from the file. It's bloody slow!!!
-            bincode::deserialize(&data).map_err(From::from)
-        });
+                // Get his lookup data if it has it.
+                let lookup_data = if let Some(ref data) = field.lookup() { data.to_vec() } else { Vec::with_capacity(0) };
+                Some((ref_table, ref_column.to_owned(), lookup_data))
+            } else { None }
+        } else { None }
+    }
 
-        // Get the thread's data in reverse, as 1 and 2 are actually the slower to process.
-        let mut dependencies = Self::default();
-        let data_3 = handle_3.join().unwrap()?;
-        let data_2 = handle_2.join().unwrap()?;
-        let data_1 = handle_1.join().unwrap()?;
+    /// This function tries to load dependencies from the path provided.
+    ///
+    /// Only the index and file lists of the cache are read. If a schema is provided, the tables are decoded
+    /// the first time they're used. The rest of the files are lazy-loaded from the CA Packs when used.
+    pub fn load(file_path: &Path, schema: &Option<Schema>) -> Result<Self> {
+        let mut cache_file = CacheFile::open(file_path)?;
+
+        // Each section has its own file list, so we can deserialize them in parallel.
+        let sections = (0..cache_file.index.sections.len())
+            .into_par_iter()
+            .map(|index| cache_file.section(index))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut dependencies = Self {
+            build_date: cache_file.index.build_date,
+            game_version: cache_file.index.game_version,
+            from_packs: cache_file.index.from_packs,
+            vanilla_packs: cache_file.index.sections.iter().map(|section| section.pack.clone()).collect(),
+            vanilla_tables: std::mem::take(&mut cache_file.index.vanilla_tables),
+            vanilla_locs: std::mem::take(&mut cache_file.index.vanilla_locs),
+            vanilla_folders: std::mem::take(&mut cache_file.index.vanilla_folders),
+            asskit_only_db_tables: std::mem::take(&mut cache_file.index.asskit_only_db_tables),
+            ..Default::default()
+        };
 
-        // The vanilla file list is stored in a Vec format instead of a hashmap, because a vec can be splited,
-        // and that list is more than 100mb long in some games. Here we turn it back to HashMap and merge it.
-        let mut vanilla_files: HashMap<_,_> = data_1.1.into_par_iter().map(|file| (file.path_in_container_raw().to_owned(), file)).collect();
-        vanilla_files.par_extend(data_2.into_par_iter().map(|file| (file.path_in_container_raw().to_owned(), file)));
+        // Sections are stored in load order, so files in a section overwrite the ones in the sections before it.
+        // The sections are kept, so the cache can be saved again.
+        for section in sections {
+            dependencies.vanilla_files.extend(section.files.iter()
+                .chain(section.tables.iter())
+                .map(|file| (file.path_in_container_raw().to_owned(), file.clone())));
 
-        dependencies.build_date = data_1.0;
-        dependencies.vanilla_files = vanilla_files;
-        dependencies.vanilla_tables = data_3.0;
-        dependencies.vanilla_locs = data_3.1;
-        dependencies.vanilla_folders = data_3.2;
-        dependencies.asskit_only_db_tables = data_3.3;
+            dependencies.vanilla_sections.push(section);
+        }
 
         // Only decode the tables if we passed a schema. If not, it's responsability of the user to decode them later.
+        // Tables are only decoded when first used, as most of them are never used.
         if let Some(schema) = schema {
-            let mut decode_extra_data = DecodeableExtraData::default();
-            decode_extra_data.set_schema(Some(schema));
-            let extra_data = Some(decode_extra_data);
-
-            let mut files = dependencies.vanilla_locs.iter().chain(dependencies.vanilla_tables.values().flatten()).filter_map(|path| {
-                dependencies.vanilla_files.remove(path).map(|file| (path.to_owned(), file))
-            }).collect::<Vec<_>>();
-
-            files.par_iter_mut().for_each(|(_, file)| {
-                let _ = file.decode(&extra_data, true, false);
-            });
-
-            dependencies.vanilla_files.par_extend(files);
+            dependencies.vanilla_lazy_schema = Some(schema.clone());
+            dependencies.vanilla_lazy_tables = dependencies.vanilla_locs.iter()
+                .chain(dependencies.vanilla_tables.values().flatten())
+                .filter(|path| dependencies.vanilla_files.contains_key(*path))
+                .map(|path| (path.to_owned(), OnceCell::new()))
+                .collect();
         }
 
         Ok(dependencies)
     }
 
     /// This function saves a dependencies cache to the provided path.
+    ///
+    /// After saving, the undecoded vanilla tables are lazy-loaded from the saved cache, as it may have replaced the one they were loaded from.
     pub fn save(&mut self, file_path: &Path) -> Result<()> {
-        let mut folder_path = file_path.to_owned();
-        folder_path.pop();
-        DirBuilder::new().recursive(true).create(&folder_path)?;
-
-        let mut file_path_1 = file_path.to_path_buf();
-        let mut file_path_2 = file_path.to_path_buf();
-        let mut file_path_3 = file_path.to_path_buf();
-
-        file_path_1.set_extension("pak1");
-        file_path_2.set_extension("pak2");
-        file_path_3.set_extension("pak3");
+        if self.vanilla_sections.len() != self.vanilla_packs.len() {
+            return Err(RLibError::DependenciesCacheNotGeneratedorOutOfDate);
+        }
 
-        let mut file_1 = File::create(&file_path_1)?;
-        let mut file_2 = File::create(&file_path_2)?;
-        let mut file_3 = File::create(&file_path_3)?;
+        cache::write(file_path, self)?;
 
-        // Split the vanilla file's list in half and turn it into a vec, so it's faster when loading.
-        // NOTE: While the HashMap -> Vec conversion only keeping values thing is slower to read
-        // than serializing/loading the keys directly, it saves about 40mb of data on disk.
-        let mut vanilla_files_1 = self.vanilla_files.par_iter().map(|(_, b)| b.clone()).collect::<Vec<RFile>>();
-        let vanilla_files_2 = vanilla_files_1.split_off(self.vanilla_files.len() / 2);
+        let cache_file = CacheFile::open(file_path)?;
+        for index in 0..self.vanilla_sections.len() {
+            let section = cache_file.section(index)?;
+            for table in &section.tables {
+                if let Some(file) = self.vanilla_files.get_mut(table.path_in_container_raw()) {
+                    if file.decoded().is_err() {
+                        *file = table.clone();
+                    }
+                }
+            }
 
-        // Never serialize directly into the file. It's bloody slow!!!
-        let serialized_1: Vec<u8> = bincode::serialize(&(&self.build_date, &vanilla_files_1))?;
-        let serialized_2: Vec<u8> = bincode::serialize(&vanilla_files_2)?;
-        let serialized_3: Vec<u8> = bincode::serialize(&(&self.vanilla_tables, &self.vanilla_locs, &self.vanilla_folders, &self.asskit_only_db_tables))?;
+            self.vanilla_sections[index] = section;
+        }
 
-        file_1.write_all(&serialized_1).map_err(RLibError::from)?;
-        file_2.write_all(&serialized_2).map_err(RLibError::from)?;
-        file_3.write_all(&serialized_3).map_err(From::from)
+        Ok(())
     }
 
-    /// This function is used to check if the game files used to generate the dependencies cache have changed, requiring an update.
+    /// This function is used to check if the CA Packs used to generate the dependencies cache have changed, requiring an update.
+    ///
+    /// Packs added, removed, moved or modified since the cache was generated all require an update.
     pub fn needs_updating(&self, game_info: &GameInfo, game_path: &Path) -> Result<bool> {
         let ca_paths = game_info.ca_packs_paths(game_path)?;
-        let last_date = last_modified_time_from_files(&ca_paths)?;
-        Ok(last_date > self.build_date)
+        if ca_paths.len() != self.vanilla_packs.len() {
+            return Ok(true);
+        }
+
+        for path in &ca_paths {
+            let (path_str, timestamp) = VanillaPack::path_and_timestamp(path)?;
+            if !self.vanilla_packs.iter().any(|pack| pack.is_same_pack(&path_str, timestamp)) {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// This function returns the dependencies caches of the provided game in the provided folder, with the game version each one is for, sorted by said version.
+    ///
+    /// Game version 0 is used by caches whose game build is unknown.
+    pub fn available_caches(folder_path: &Path, game_info: &GameInfo) -> Result<Vec<(u32, PathBuf)>> {
+        let mut caches = files_from_subdir(folder_path, false)?
+            .into_iter()
+            .filter_map(|path| {
+                let file_name = path.file_name()?.to_str()?;
+                game_info.game_version_from_dependencies_cache_file_name(file_name).map(|game_version| (game_version, path))
+            })
+            .collect::<Vec<_>>();
+
+        caches.sort_by_key(|(game_version, _)| *game_version);
+        Ok(caches)
     }
 
+    /// This function returns the game version and path of the dependencies cache to use for a Pack made for the provided game version.
+    ///
+    /// Packs made for the installed version of the game (or a newer one, or an unknown one) use the cache of the installed version, whether it exists or not.
+    /// Packs made for older versions use the cache of their version or, if there's none, the newest cache older than it.
+    /// If there are none, the cache of the installed version is used.
+    ///
+    /// The installed version is the build of the game's exe, so caches are kept per build for every game, not only for the ones whose Packs report their version.
+    pub fn cache_for_game_version(folder_path: &Path, game_info: &GameInfo, game_path: &Path, game_version: u32) -> (u32, PathBuf) {
+        let installed_version = game_info.game_build(game_path).unwrap_or(0);
+        let installed_cache = (installed_version, folder_path.join(game_info.dependencies_cache_file_name_by_game_version(installed_version)));
+        if installed_version == 0 || game_version == 0 || game_version >= installed_version {
+            return installed_cache;
+        }
+
+        Self::available_caches(folder_path, game_info)
+            .unwrap_or_default()
+            .into_iter()
+            .rfind(|(cache_version, _)| *cache_version != 0 && *cache_version <= game_version)
+            .unwrap_or(installed_cache)
+    }
+
+    /// This function removes from the provided folder the files of the dependencies cache of the provided game in the old split format,
+    /// as they're no longer used. It returns the paths of the removed files.
+    ///
+    /// It's a migration step, so it's never done as part of generating or saving a cache.
+    pub fn remove_legacy_caches(folder_path: &Path, game_info: &GameInfo) -> Result<Vec<PathBuf>> {
+        let cache_path = folder_path.join(game_info.dependencies_cache_file_name());
+        let legacy_paths = ["pak1", "pak2", "pak3"].iter()
+            .map(|extension| cache_path.with_extension(extension))
+            .filter(|legacy_path| *legacy_path != cache_path && legacy_path.is_file())
+            .collect::<Vec<_>>();
+
+        for legacy_path in &legacy_paths {
+            info!("Removing legacy dependencies cache file {}.", legacy_path.to_string_lossy());
+            std::fs::remove_file(legacy_path)?;
+        }
+
+        Ok(legacy_paths)
+    }
 
     /// This function loads all the parent [Packs](rpfm_lib::files::pack::Pack) provided as `parent_pack_names` as dependencies,
     /// taking care of also loading all dependencies of all of them, if they're not already loaded.
@@ -497,6 +923,7 @@ impl Dependencies {
                 if let Ok(pack) = Pack::read_and_merge(&[path.to_path_buf()], true, false) {
                     already_loaded.push(pack_name.to_owned());
                     pack.dependencies().iter().for_each(|pack_name| self.load_parent_pack(pack_name, already_loaded, data_paths, external_path));
+                    pack.files().keys().for_each(|path| { self.installed_mods_paths.remove(path); });
                     self.parent_files.extend(pack.files().clone());
                 }
             }
@@ -507,6 +934,7 @@ impl Dependencies {
                     if let Ok(pack) = Pack::read_and_merge(&[path.to_path_buf()], true, false) {
                         already_loaded.push(pack_name.to_owned());
                         pack.dependencies().iter().for_each(|pack_name| self.load_parent_pack(pack_name, already_loaded, data_paths, external_path));
+                        pack.files().keys().for_each(|path| { self.installed_mods_paths.remove(path); });
                         self.parent_files.extend(pack.files().clone());
                     }
                 }
@@ -514,17 +942,98 @@ impl Dependencies {
         }
     }
 
+    /// This function sets the sources of installed mods to load as dependencies, in order of priority.
+    ///
+    /// Packs in the ignored list are never loaded from these sources. The changes are applied on the next rebuild.
+    pub fn set_installed_mods(&mut self, sources: &[InstalledModsSource], ignored_pack_names: &[String]) {
+        self.installed_mods_sources = sources.to_vec();
+        self.installed_mods_ignored = ignored_pack_names.to_vec();
+    }
+
+    /// This function loads the `Mod` Packs from the installed mods sources as parent files.
+    ///
+    /// Files in the first sources overwrite the ones in the last ones. Within the same source, Packs are sorted by name,
+    /// and the first ones overwrite the last ones. Parent Packs are skipped, as they're loaded later.
+    ///
+    /// Packs not modified since the last time they were loaded are reused instead of being read again.
+    fn load_installed_mods(&mut self, parent_pack_names: &[String], game_info: &GameInfo, game_path: &Path) {
+        let mut pack_paths: Vec<PathBuf> = vec![];
+        for source in &self.installed_mods_sources {
+            let mut paths = source.pack_paths(game_info, game_path);
+            paths.sort_by_key(|path| path.file_name().map(|name| name.to_os_string()));
+
+            for path in paths {
+                let pack_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+                let is_loaded = pack_paths.iter().any(|loaded_path| loaded_path.file_name() == path.file_name());
+                if !is_loaded && !self.installed_mods_ignored.contains(&pack_name) && !parent_pack_names.contains(&pack_name) {
+                    pack_paths.push(path);
+                }
+            }
+        }
+
+        // Reverse them so the ones with more priority are loaded last.
+        let mut packs = HashMap::with_capacity(pack_paths.len());
+        for path in pack_paths.iter().rev() {
+            let timestamp = match last_modified_time_from_files(&[path.to_path_buf()]) {
+                Ok(timestamp) => timestamp,
+                Err(_) => continue,
+            };
+
+            let pack = match self.installed_mods_packs.remove(path) {
+                Some((cached_timestamp, pack)) if cached_timestamp == timestamp => pack,
+                _ => match Pack::read_and_merge(&[path.to_path_buf()], true, false) {
+                    Ok(pack) => pack,
+                    Err(_) => continue,
+                }
+            };
+
+            if pack.pfh_file_type() == PFHFileType::Mod {
+                self.installed_mods_paths.extend(pack.files().keys().cloned());
+                self.parent_files.extend(pack.files().clone());
+            }
+
+            packs.insert(path.to_path_buf(), (timestamp, pack));
+        }
+
+        // Packs no longer in the sources are dropped from the cache.
+        self.installed_mods_packs = packs;
+    }
+
+    /// This function returns the source of a parent file: [SearchSource::InstalledMods] if it comes from the installed mods,
+    /// or [SearchSource::ParentFiles] if it comes from the parent Packs.
+    pub fn parent_file_source(&self, path: &str) -> SearchSource {
+        if self.installed_mods_paths.contains(path) {
+            SearchSource::InstalledMods
+        } else {
+            SearchSource::ParentFiles
+        }
+    }
+
+    /// This function returns the source of a file found in the provided source, telling apart the parent files coming from the installed mods.
+    fn file_source(&self, source: &SearchSource, path: &str) -> SearchSource {
+        match source {
+            SearchSource::ParentFiles => self.parent_file_source(path),
+            _ => source.clone(),
+        }
+    }
+
     /// Function to force-decode all tables/locs in the dependencies.
     ///
     /// Many operations require them to be decoded, so if you did not decoded them on load, make sure to call this to decode them after load.
     pub fn decode_tables(&mut self, schema: &Option<Schema>) {
         if let Some(schema) = schema {
+            self.clear_cached_references();
 
             let mut decode_extra_data = DecodeableExtraData::default();
             decode_extra_data.set_schema(Some(schema));
+            decode_extra_data.set_table_index(true);
+            decode_extra_data.set_game_build(Some(self.game_version).filter(|game_version| *game_version != 0));
             let extra_data = Some(decode_extra_data);
 
-            // Vanilla files.
+            // Vanilla files. These are decoded with the new schema, so the lazy-loaded ones are no longer needed.
+            self.vanilla_lazy_tables.clear();
+            self.vanilla_lazy_schema = None;
+
             let mut files = self.vanilla_locs.iter().chain(self.vanilla_tables.values().flatten()).filter_map(|path| {
                 self.vanilla_files.remove(path).map(|file| (path.to_owned(), file))
             }).collect::<Vec<_>>();
@@ -548,15 +1057,81 @@ impl Dependencies {
         }
     }
 
-    //-----------------------------------//
-    // Getters
-    //-----------------------------------//
+    /// This function returns the provided vanilla file, decoding it first if it's a lazy-loaded table not yet decoded.
+    ///
+    /// Tables that cannot be decoded are returned undecoded.
+    fn vanilla_file<'a>(&'a self, path: &str, file: &'a RFile) -> &'a RFile {
+        match self.vanilla_lazy_tables.get(path) {
+            Some(decoded_file) => decoded_file.get_or_init(|| self.decode_vanilla_lazy_table(file)),
+            None => file,
+        }
+    }
 
-    /// This function returns a reference to a specific file from the cache, if exists.
-    pub fn file(&self, file_path: &str, include_vanilla: bool, include_parent: bool, case_insensitive: bool) -> Result<&RFile> {
-        if include_parent {
-            if case_insensitive {
-                if let Some(file) = self.parent_files.par_iter().find_map_first(|(path, file)| if caseless::canonical_caseless_match_str(path, file_path) { Some(file) } else { None }) {
+    /// This function decodes a lazy-loaded vanilla table.
+    fn decode_vanilla_lazy_table(&self, file: &RFile) -> RFile {
+        let mut decode_extra_data = DecodeableExtraData::default();
+        decode_extra_data.set_schema(self.vanilla_lazy_schema.as_ref());
+        decode_extra_data.set_table_index(true);
+        decode_extra_data.set_game_build(Some(self.game_version).filter(|game_version| *game_version != 0));
+        let extra_data = Some(decode_extra_data);
+
+        let mut decoded_file = file.clone();
+        let _ = decoded_file.decode(&extra_data, true, false);
+        decoded_file
+    }
+
+    /// This function moves the provided lazy-loaded vanilla table to the vanilla files, decoding it if it wasn't already decoded.
+    fn take_vanilla_lazy_table(&mut self, path: &str) {
+        if let Some(decoded_file) = self.vanilla_lazy_tables.remove(path) {
+            if let Some(file) = self.vanilla_files.get(path) {
+                let decoded_file = decoded_file.into_inner().unwrap_or_else(|| self.decode_vanilla_lazy_table(file));
+                self.vanilla_files.insert(path.to_owned(), decoded_file);
+            }
+        }
+    }
+
+    /// This function moves all the lazy-loaded vanilla tables to the vanilla files, decoding the ones that weren't already decoded.
+    fn take_vanilla_lazy_tables(&mut self) {
+        let lazy_tables = std::mem::take(&mut self.vanilla_lazy_tables);
+        let files = lazy_tables.into_par_iter()
+            .filter_map(|(path, decoded_file)| {
+                let file = self.vanilla_files.get(&path)?;
+                Some((path, decoded_file.into_inner().unwrap_or_else(|| self.decode_vanilla_lazy_table(file))))
+            })
+            .collect::<Vec<_>>();
+
+        self.vanilla_files.extend(files);
+    }
+
+    /// This function returns the contents of the vanilla text files, by path, decoding them on the first call.
+    ///
+    /// Text files that cannot be decoded are skipped.
+    fn vanilla_text_contents(&self) -> &HashMap<String, String> {
+        self.vanilla_texts.get_or_init(|| self.vanilla_files.par_iter()
+            .filter(|(_, file)| file.file_type() == FileType::Text)
+            .filter_map(|(path, file)| {
+                let decoded = match file.decoded() {
+                    Ok(decoded) => Some(decoded.clone()),
+                    Err(_) => RFile::clone(file).decode(&None, false, true).ok().flatten(),
+                };
+
+                match decoded {
+                    Some(RFileDecoded::Text(text)) => Some((path.to_owned(), text.contents().to_owned())),
+                    _ => None,
+                }
+            })
+            .collect())
+    }
+
+    //-----------------------------------//
+    // Getters
+    //-----------------------------------//
+
+    /// This function returns a reference to a specific file from the cache, if exists.
+    pub fn file(&self, file_path: &str, include_vanilla: bool, include_parent: bool, case_insensitive: bool) -> Result<&RFile> {
+        if include_parent {
+            if case_insensitive {
+                if let Some(file) = self.parent_files.par_iter().find_map_first(|(path, file)| if caseless::canonical_caseless_match_str(path, file_path) { Some(file) } else { None }) {
                     return Ok(file);
                 }
             } else if let Some(file) = self.parent_files.get(file_path) {
@@ -566,11 +1141,11 @@ impl Dependencies {
 
         if include_vanilla {
             if case_insensitive {
-                if let Some(file) = self.vanilla_files.par_iter().find_map_first(|(path, file)| if caseless::canonical_caseless_match_str(path, file_path) { Some(file) } else { None }) {
+                if let Some(file) = self.vanilla_files.par_iter().find_map_first(|(path, file)| if caseless::canonical_caseless_match_str(path, file_path) { Some(self.vanilla_file(path, file)) } else { None }) {
                     return Ok(file);
                 }
             } else if let Some(file) = self.vanilla_files.get(file_path) {
-                return Ok(file);
+                return Ok(self.vanilla_file(file_path, file));
             }
         }
 
@@ -578,7 +1153,16 @@ impl Dependencies {
     }
 
     /// This function returns a mutable reference to a specific file from the cache, if exists.
+    ///
+    /// As the file may be edited, this clears the cached reference data.
     pub fn file_mut(&mut self, file_path: &str, include_vanilla: bool, include_parent: bool) -> Result<&mut RFile> {
+        self.clear_cached_references();
+
+        if include_vanilla {
+            self.take_vanilla_lazy_table(file_path);
+            self.vanilla_texts = OnceCell::new();
+        }
+
         if include_parent {
             if let Some(file) = self.parent_files.get_mut(file_path) {
                 return Ok(file);
@@ -605,7 +1189,7 @@ impl Dependencies {
                     if include_vanilla {
                         if folder_path.is_empty() {
                             files.extend(self.vanilla_files.par_iter()
-                                .map(|(path, file)| (path.to_owned(), file))
+                                .map(|(path, file)| (path.to_owned(), self.vanilla_file(path, file)))
                                 .collect::<HashMap<_,_>>());
                         } else {
                             files.extend(self.vanilla_files.par_iter()
@@ -616,7 +1200,7 @@ impl Dependencies {
                                         path.starts_with(&folder_path)
                                     }
                                 })
-                                .map(|(path, file)| (path.to_owned(), file))
+                                .map(|(path, file)| (path.to_owned(), self.vanilla_file(path, file)))
                                 .collect::<HashMap<_,_>>());
                         }
                     }
@@ -652,7 +1236,7 @@ impl Dependencies {
         if include_vanilla {
             files.extend(self.vanilla_files.par_iter()
                 .filter(|(_, file)| file_types.contains(&file.file_type()))
-                .map(|(path, file)| (path.to_owned(), file))
+                .map(|(path, file)| (path.to_owned(), self.vanilla_file(path, file)))
                 .collect::<HashMap<_,_>>());
         }
 
@@ -667,11 +1251,16 @@ impl Dependencies {
     }
 
     /// This function returns a mutable reference to all files of the specified FileTypes from the cache, if any, along with their path.
+    ///
+    /// As the files may be edited, this clears the cached reference data.
     pub fn files_by_types_mut(&mut self, file_types: &[FileType], include_vanilla: bool, include_parent: bool) -> HashMap<String, &mut RFile> {
+        self.clear_cached_references();
         let mut files = HashMap::new();
 
         // Vanilla first, so if parent files are found, they overwrite vanilla files.
         if include_vanilla {
+            self.take_vanilla_lazy_tables();
+            self.vanilla_texts = OnceCell::new();
             files.extend(self.vanilla_files.par_iter_mut()
                 .filter(|(_, file)| file_types.contains(&file.file_type()))
                 .map(|(path, file)| (path.to_owned(), file))
@@ -700,7 +1289,7 @@ impl Dependencies {
 
             for path in &vanilla_locs {
                 if let Some(file) = self.vanilla_files.get(*path) {
-                    cache.push(file);
+                    cache.push(self.vanilla_file(path, file));
                 }
             }
         }
@@ -734,7 +1323,7 @@ impl Dependencies {
 
                 for path in &vanilla_tables {
                     if let Some(file) = self.vanilla_files.get(path) {
-                        cache.push(file);
+                        cache.push(self.vanilla_file(path, file));
                     }
                 }
             }
@@ -769,7 +1358,7 @@ impl Dependencies {
 
                 for path in &vanilla_tables {
                     if let Some(file) = self.vanilla_files.get(*path) {
-                        cache.push(file);
+                        cache.push(self.vanilla_file(path, file));
                     }
                 }
             }
@@ -780,7 +1369,7 @@ impl Dependencies {
 
                 for path in &vanilla_locs {
                     if let Some(file) = self.vanilla_files.get(*path) {
-                        cache.push(file);
+                        cache.push(self.vanilla_file(path, file));
                     }
                 }
             }
@@ -837,7 +1426,7 @@ impl Dependencies {
                     let mut references = TableReferences::default();
                     *references.field_name_mut() = field.name().to_owned();
 
-                    let _local_found = Self::db_reference_data_from_local_pack(&mut references, (ref_table, ref_column, &lookup_data), pack);
+                    let _local_found = self.db_reference_data_from_local_pack(&mut references, (ref_table, ref_column, &lookup_data), pack);
 
                     Some((column as i32, references))
                 } else { None }
@@ -856,37 +1445,14 @@ impl Dependencies {
     /// This function returns the reference/lookup data of all relevant columns of a DB Table from the vanilla/parent data.
     ///
     /// If reference data was found, the most recent definition of said data is returned.
-    fn db_reference_data_from_from_vanilla_and_modded_tables(&self, references: &mut TableReferences, reference_info: (&str, &str, &[String])) -> Option<Definition> {
+    fn db_reference_data_from_from_vanilla_and_modded_tables(&self, reference_data: &mut ReferenceData, reference_info: (&str, &str, &[String])) -> Option<Definition> {
         let mut data_found: Option<Definition> = None;
         let ref_table = reference_info.0;
-        let ref_column = reference_info.1;
-        let ref_lookup_columns = reference_info.2;
 
         if let Ok(files) = self.db_data(ref_table, true, true) {
             files.iter().for_each(|file| {
                 if let Ok(RFileDecoded::DB(db)) = file.decoded() {
-                    let fields_processed = db.definition().fields_processed();
-                    let ref_column_index = fields_processed.iter().position(|x| x.name() == ref_column);
-                    let ref_lookup_columns_index = ref_lookup_columns.iter().map(|column| fields_processed.iter().position(|x| x.name() == column)).collect::<Vec<_>>();
-
-                    if let Ok(data) = db.data(&None) {
-
-                        for row in &*data {
-                            let mut reference_data = String::new();
-                            let mut lookup_data = vec![];
-
-                            // First, we get the reference data.
-                            if let Some(index) = ref_column_index {
-                                reference_data = row[index].data_to_string().to_string();
-                            }
-
-                            // Then, we get the lookup data.
-                            for column in ref_lookup_columns_index.iter().flatten() {
-                                lookup_data.push(row[*column].data_to_string());
-                            }
-
-                            references.data.insert(reference_data, lookup_data.join(" "));
-                        }
+                    if Self::db_reference_data_from_table(reference_data, reference_info, db) {
 
                         // Once done with the table, check if we should return its definition.
                         match data_found {
@@ -908,35 +1474,10 @@ impl Dependencies {
     /// This function returns the reference/lookup data of all relevant columns of a DB Table from the assembly kit data.
     ///
     /// It returns true if data is found, otherwise it returns false.
-    fn db_reference_data_from_asskit_tables(&self, references: &mut TableReferences, reference_info: (&str, &str, &[String])) -> bool {
-        let ref_table = reference_info.0;
-        let ref_column = reference_info.1;
-        let ref_lookup_columns = reference_info.2;
-
-        match self.asskit_only_db_tables.get(ref_table) {
+    fn db_reference_data_from_asskit_tables(&self, reference_data: &mut ReferenceData, reference_info: (&str, &str, &[String])) -> bool {
+        match self.asskit_only_db_tables.get(reference_info.0) {
             Some(table) => {
-                if let Ok(data) = table.data(&None) {
-                    let fields_processed = table.definition().fields_processed();
-                    let ref_column_index = fields_processed.iter().position(|x| x.name() == ref_column);
-                    let ref_lookup_columns_index = ref_lookup_columns.iter().map(|column| fields_processed.iter().position(|x| x.name() == column)).collect::<Vec<_>>();
-
-                    for row in &*data {
-                        let mut reference_data = String::new();
-                        let mut lookup_data = vec![];
-
-                        // First, we get the reference data.
-                        if let Some(index) = ref_column_index {
-                            reference_data = row[index].data_to_string().to_string();
-                        }
-
-                        // Then, we get the lookup data.
-                        for column in ref_lookup_columns_index.iter().flatten() {
-                            lookup_data.push(row[*column].data_to_string());
-                        }
-
-                        references.data.insert(reference_data, lookup_data.join(" "));
-                    }
-                }
+                Self::db_reference_data_from_table(reference_data, reference_info, table);
                 true
             },
             None => false,
@@ -944,47 +1485,114 @@ impl Dependencies {
     }
 
     /// This function returns the reference/lookup data of all relevant columns of a DB Table from the provided Pack.
-    fn db_reference_data_from_local_pack(references: &mut TableReferences, reference_info: (&str, &str, &[String]), pack: &Pack) -> bool {
-
+    ///
+    /// Loc entries of the Pack have priority over the ones in the dependencies when resolving the lookups.
+    fn db_reference_data_from_local_pack(&self, references: &mut TableReferences, reference_info: (&str, &str, &[String]), pack: &Pack) -> bool {
         let mut data_found = false;
-        let ref_table = reference_info.0;
-        let ref_column = reference_info.1;
-        let ref_lookup_columns = reference_info.2;
+        let mut reference_data = vec![];
+        let ref_table = format!("{}_tables", reference_info.0);
 
-        pack.files_by_path(&ContainerPath::Folder(format!("db/{ref_table}_tables")), false).iter()
+        pack.files_by_path(&ContainerPath::Folder(format!("db/{ref_table}")), false).iter()
             .for_each(|file| {
             if let Ok(RFileDecoded::DB(db)) = file.decoded() {
-                let fields_processed = db.definition().fields_processed();
-                let ref_column_index = fields_processed.iter().position(|x| x.name() == ref_column);
-                let ref_lookup_columns_index = ref_lookup_columns.iter().map(|column| fields_processed.iter().position(|x| x.name() == column)).collect::<Vec<_>>();
+                if Self::db_reference_data_from_table(&mut reference_data, (&ref_table, reference_info.1, reference_info.2), db) {
+                    data_found = true;
+                }
+            }
+        });
 
-                if let Ok(data) = db.data(&None) {
+        let loc_texts = self.loc_texts(Some(pack), &Self::reference_data_loc_keys(&reference_data));
+        Self::add_reference_data(references, reference_data, &loc_texts);
+        data_found
+    }
 
-                    for row in &*data {
-                        let mut reference_data = String::new();
-                        let mut lookup_data = vec![];
+    /// This function returns the reference/lookup data of the rows of a DB Table, in a "key, lookup parts" format.
+    ///
+    /// If the referenced column or any of the lookup columns were moved into a loc file, their parts are the keys of their loc entries,
+    /// and the key of the rows is their localised key. It returns true if the data of the table could be read.
+    fn db_reference_data_from_table(reference_data: &mut ReferenceData, reference_info: (&str, &str, &[String]), db: &DB) -> bool {
+        let ref_table = reference_info.0.strip_suffix("_tables").unwrap_or(reference_info.0);
+        let ref_column = reference_info.1;
+        let ref_lookup_columns = reference_info.2;
 
-                        // First, we get the reference data.
-                        if let Some(index) = ref_column_index {
-                            reference_data = row[index].data_to_string().to_string();
-                        }
+        let definition = db.definition();
+        let fields_processed = definition.fields_processed();
+        let localised_key_order = definition.localised_key_order();
+
+        // Without a key order we cannot build the loc keys of the rows, so the loc fields are ignored.
+        let localised_fields: &[Field] = if localised_key_order.is_empty() { &[] } else { definition.localised_fields() };
+        let ref_column_is_localised = localised_fields.iter().any(|field| field.name() == ref_column);
+        let ref_column_index = fields_processed.iter().position(|x| x.name() == ref_column);
+        let ref_lookup_columns_index = ref_lookup_columns.iter()
+            .filter_map(|column| match fields_processed.iter().position(|x| x.name() == column) {
+                Some(index) => Some((column, Some(index))),
+                None if localised_fields.iter().any(|field| field.name() == column) => Some((column, None)),
+                None => None,
+            })
+            .collect::<Vec<_>>();
 
-                        // Then, we get the lookup data.
-                        for column in ref_lookup_columns_index.iter().flatten() {
-                            lookup_data.push(row[*column].data_to_string());
-                        }
+        let data = match db.data(&None) {
+            Ok(data) => data,
+            Err(_) => return false,
+        };
 
-                        references.data.insert(reference_data, lookup_data.join(" "));
+        for row in &*data {
+            let localised_key = if localised_fields.is_empty() { String::new() } else {
+                localised_key_order.iter().filter_map(|position| row.get(*position as usize)).map(|cell| cell.data_to_string()).join("")
+            };
 
-                    }
+            let mut lookup_data = vec![];
 
-                    if !&data.is_empty() && !data_found {
-                        data_found = true;
-                    }
+            // First, we get the reference data.
+            let key = if ref_column_is_localised {
+                lookup_data.push(LookupPart::LocKey(format!("{ref_table}_{ref_column}_{localised_key}")));
+                localised_key.to_owned()
+            } else if let Some(index) = ref_column_index {
+                row[index].data_to_string().to_string()
+            } else {
+                String::new()
+            };
+
+            // Then, we get the lookup data.
+            for (column, index) in &ref_lookup_columns_index {
+                match index {
+                    Some(index) => lookup_data.push(LookupPart::Text(row[*index].data_to_string().to_string())),
+                    None => lookup_data.push(LookupPart::LocKey(format!("{ref_table}_{column}_{localised_key}"))),
                 }
             }
-        });
-        data_found
+
+            reference_data.push((key, lookup_data));
+        }
+
+        true
+    }
+
+    /// This function returns the keys of the loc entries used by the provided reference/lookup data.
+    fn reference_data_loc_keys(reference_data: &ReferenceData) -> HashSet<&str> {
+        reference_data.iter()
+            .flat_map(|(_, lookup_data)| lookup_data.iter())
+            .filter_map(|part| match part {
+                LookupPart::LocKey(key) => Some(key.as_str()),
+                LookupPart::Text(_) => None,
+            })
+            .collect()
+    }
+
+    /// This function adds the provided reference/lookup data to the references, replacing the loc keys of the lookups with their texts.
+    ///
+    /// Later rows overwrite the lookups of earlier ones, as they have priority.
+    fn add_reference_data(references: &mut TableReferences, reference_data: ReferenceData, loc_texts: &HashMap<String, String>) {
+        for (key, lookup_data) in reference_data {
+            let lookup = lookup_data.iter()
+                .filter_map(|part| match part {
+                    LookupPart::Text(text) => Some(text.as_str()),
+                    LookupPart::LocKey(key) => loc_texts.get(key).map(|text| text.as_str()),
+                })
+                .filter(|text| !text.is_empty())
+                .join(" ");
+
+            references.data.insert(key, lookup);
+        }
     }
 
     /// This function returns the table/column/key from the provided loc key.
@@ -1035,6 +1643,572 @@ impl Dependencies {
         None
     }
 
+    //-----------------------------------//
+    // Reverse references.
+    //-----------------------------------//
+
+    /// This function returns everything using the provided value of a column of a table, from the provided Pack, the parent files and the game files.
+    ///
+    /// References are followed transitively: if a column references ours, the columns referencing that column are checked too,
+    /// as they hold the same value. Loc entries derived from the rows found are included, as are text files mentioning the value as a whole word.
+    ///
+    /// Tables must be decoded. Text files are read from disk if needed.
+    pub fn references_to_key(&self, pack: &Pack, schema: &Schema, table_name: &str, column_name: &str, value: &str) -> KeyReferences {
+        if value.is_empty() {
+            return KeyReferences::default();
+        }
+
+        let table_name = if table_name.ends_with("_tables") { table_name.to_owned() } else { format!("{table_name}_tables") };
+        let mut columns = BTreeMap::new();
+        columns.insert(table_name.to_owned(), vec![column_name.to_owned()]);
+
+        let key_rows = self.rows_with_value(pack, &columns, value);
+        let referencing_rows = self.rows_with_value(pack, &Self::columns_referencing(schema, &table_name, column_name), value);
+
+        // Loc keys are derived from the keys of the rows, so only rows where the value is part of the loc key are relevant.
+        let loc_keys = self.loc_keys_from_rows(pack, key_rows.iter().chain(referencing_rows.iter()));
+        let mut loc_rows = vec![];
+        if !loc_keys.is_empty() {
+            let pack_locs = pack.files_by_type(&[FileType::Loc]);
+            let parent_locs = self.loc_data(false, true).unwrap_or_default();
+            let vanilla_locs = self.loc_data(true, false).unwrap_or_default();
+
+            for (source, files) in [(SearchSource::Pack, pack_locs), (SearchSource::ParentFiles, parent_locs), (SearchSource::GameFiles, vanilla_locs)] {
+                loc_rows.par_extend(files.par_iter()
+                    .filter_map(|file| match file.decoded() {
+                        Ok(RFileDecoded::Loc(table)) => table.data(&None).ok().map(|data| data.iter()
+                            .enumerate()
+                            .filter(|(_, row)| loc_keys.contains(&*row[0].data_to_string()))
+                            .map(|(row_index, row)| LocReference {
+                                source: self.file_source(&source, file.path_in_container_raw()),
+                                path: file.path_in_container_raw().to_owned(),
+                                key: row[0].data_to_string().to_string(),
+                                row_index,
+                            })
+                            .collect::<Vec<_>>()),
+                        _ => None,
+                    })
+                    .flatten());
+            }
+        }
+
+        let pack_texts = pack.files_by_type(&[FileType::Text]);
+        let parent_texts = self.parent_files.values().filter(|file| file.file_type() == FileType::Text).collect::<Vec<_>>();
+
+        let mut text_files = vec![];
+        for (source, files) in [(SearchSource::Pack, pack_texts), (SearchSource::ParentFiles, parent_texts)] {
+            text_files.par_extend(files.par_iter()
+                .filter_map(|file| {
+                    let decoded = match file.decoded() {
+                        Ok(decoded) => Some(decoded.clone()),
+                        Err(_) => RFile::clone(file).decode(&None, false, true).ok().flatten(),
+                    };
+
+                    match decoded {
+                        Some(RFileDecoded::Text(text)) => {
+                            let lines = lines_mentioning(text.contents(), value);
+                            if lines.is_empty() {
+                                None
+                            } else {
+                                Some(TextReference {
+                                    source: self.file_source(&source, file.path_in_container_raw()),
+                                    path: file.path_in_container_raw().to_owned(),
+                                    lines,
+                                })
+                            }
+                        }
+                        _ => None,
+                    }
+                }));
+        }
+
+        // Vanilla texts are a lot, so their contents are decoded only once.
+        text_files.par_extend(self.vanilla_text_contents().par_iter()
+            .filter_map(|(path, contents)| {
+                let lines = lines_mentioning(contents, value);
+                if lines.is_empty() {
+                    None
+                } else {
+                    Some(TextReference {
+                        source: SearchSource::GameFiles,
+                        path: path.to_owned(),
+                        lines,
+                    })
+                }
+            }));
+
+        // Keep the results stable, as they're collected in parallel.
+        loc_rows.sort_by(|a, b| (&a.path, a.row_index).cmp(&(&b.path, b.row_index)));
+        text_files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        KeyReferences {
+            key_rows,
+            referencing_rows,
+            loc_rows,
+            text_files,
+        }
+    }
+
+    /// This function returns the columns referencing the provided column, by table name, following the references transitively.
+    fn columns_referencing(schema: &Schema, table_name: &str, column_name: &str) -> BTreeMap<String, Vec<String>> {
+        let mut columns: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut pending = vec![(table_name.to_owned(), column_name.to_owned())];
+
+        while let Some((table_name, column_name)) = pending.pop() {
+            let short_table_name = table_name.strip_suffix("_tables").unwrap_or(&table_name);
+            for (ref_table_name, definitions) in schema.definitions() {
+
+                // As this applies to all versions of a table, skip repeated fields.
+                for field in definitions.iter().flat_map(|definition| definition.fields_processed()) {
+                    if let Some((ref_table, ref_column)) = field.is_reference() {
+                        if ref_table == short_table_name && ref_column == &column_name {
+                            let ref_columns = columns.entry(ref_table_name.to_owned()).or_default();
+                            if !ref_columns.iter().any(|name| name == field.name()) {
+                                ref_columns.push(field.name().to_owned());
+                                pending.push((ref_table_name.to_owned(), field.name().to_owned()));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        columns
+    }
+
+    /// This function returns the rows with the provided value in any of the provided columns, from the Pack, the parent files and the game files.
+    fn rows_with_value(&self, pack: &Pack, columns: &BTreeMap<String, Vec<String>>, value: &str) -> Vec<RowReference> {
+        let mut rows = vec![];
+        for (table_name, column_names) in columns {
+            let pack_tables = pack.files_by_paths(&[ContainerPath::Folder(format!("db/{table_name}"))], true);
+            let parent_tables = self.db_data(table_name, false, true).unwrap_or_default();
+            let vanilla_tables = self.db_data(table_name, true, false).unwrap_or_default();
+
+            for (source, files) in [(SearchSource::Pack, pack_tables), (SearchSource::ParentFiles, parent_tables), (SearchSource::GameFiles, vanilla_tables)] {
+                rows.par_extend(files.par_iter()
+                    .filter_map(|file| match file.decoded() {
+                        Ok(RFileDecoded::DB(table)) => Some(column_names.iter()
+                            .filter_map(|column_name| table.table().rows_containing_data(column_name, value)
+                                .map(|(column_index, row_indexes)| row_indexes.into_iter()
+                                    .map(|row_index| RowReference {
+                                        source: self.file_source(&source, file.path_in_container_raw()),
+                                        path: file.path_in_container_raw().to_owned(),
+                                        table_name: table_name.to_owned(),
+                                        column_name: column_name.to_owned(),
+                                        column_index,
+                                        row_index,
+                                    })
+                                    .collect::<Vec<_>>()))
+                            .flatten()
+                            .collect::<Vec<_>>()),
+                        _ => None,
+                    })
+                    .flatten());
+            }
+        }
+
+        rows.sort_by(|a, b| (&a.table_name, &a.path, a.row_index, a.column_index).cmp(&(&b.table_name, &b.path, b.row_index, b.column_index)));
+        rows
+    }
+
+    /// This function returns the loc keys derived from the provided rows, if the column with the value is part of the loc key of their table.
+    fn loc_keys_from_rows<'a>(&self, pack: &Pack, rows: impl Iterator<Item = &'a RowReference>) -> HashSet<String> {
+        let mut loc_keys = HashSet::new();
+        for row in rows {
+            if let Some(table) = self.row_table(pack, row) {
+                let definition = table.definition();
+                let localised_order = definition.localised_key_order();
+                if definition.localised_fields().is_empty() || !localised_order.contains(&(row.column_index as u32)) {
+                    continue;
+                }
+
+                if let Some(data) = table.data(&None).ok().and_then(|data| data.get(row.row_index).cloned()) {
+                    let key = localised_order.iter().map(|position| data[*position as usize].data_to_string()).join("");
+                    let table_name = table.table_name_without_tables();
+                    for field in definition.localised_fields() {
+                        loc_keys.insert(format!("{}_{}_{}", table_name, field.name(), key));
+                    }
+                }
+            }
+        }
+
+        loc_keys
+    }
+
+    //-----------------------------------//
+    // Related rows import.
+    //-----------------------------------//
+
+    /// This function returns the rows related to the rows with the provided value in a column of a table, from the parent files and the game files.
+    ///
+    /// Starting from said rows, the rows referencing any of their keys with the provided value are collected, and so on with the rows found.
+    /// Keys with other values are not followed, as they may be shared with rows unrelated to the value.
+    /// If `include_referenced` is true, the rows referenced by the collected rows through other values are returned as referenced rows, without following their references.
+    ///
+    /// Tables must be decoded.
+    pub fn related_rows(&self, pack: &Pack, schema: &Schema, table_name: &str, column_name: &str, value: &str, include_referenced: bool) -> RelatedRows {
+        if value.is_empty() {
+            return RelatedRows::default();
+        }
+
+        let table_name = if table_name.ends_with("_tables") { table_name.to_owned() } else { format!("{table_name}_tables") };
+        let mut columns = BTreeMap::new();
+        columns.insert(table_name, vec![column_name.to_owned()]);
+
+        let mut rows: Vec<RowReference> = vec![];
+        let mut referenced_rows: Vec<RowReference> = vec![];
+        let mut visited_rows = HashSet::new();
+        let mut visited_keys = HashSet::new();
+        let mut pending = self.dependencies_rows_with_value(pack, &columns, value);
+
+        while let Some(row) = pending.pop() {
+            if !visited_rows.insert((row.path.to_owned(), row.row_index)) {
+                continue;
+            }
+
+            if let Some(table) = self.row_table(pack, &row) {
+                if let Some(data) = table.data(&None).ok().and_then(|data| data.get(row.row_index).cloned()) {
+                    for (column, field) in table.definition().fields_processed().iter().enumerate() {
+                        let cell = data[column].data_to_string();
+                        if cell.is_empty() {
+                            continue;
+                        }
+
+                        // Rows referencing our keys with the value are part of the same thing, so we follow them.
+                        if field.is_key(Some(table.patches())) && cell == value {
+                            if visited_keys.insert((row.table_name.to_owned(), field.name().to_owned())) {
+                                let columns = Self::columns_referencing(schema, &row.table_name, field.name());
+                                pending.extend(self.dependencies_rows_with_value(pack, &columns, value));
+                            }
+                        }
+
+                        // Rows we reference through other values are only included, not followed, as they're usually shared with other rows.
+                        else if include_referenced {
+                            if let Some((ref_table, ref_column)) = field.is_reference() {
+                                let mut columns = BTreeMap::new();
+                                columns.insert(format!("{ref_table}_tables"), vec![ref_column.to_owned()]);
+                                referenced_rows.extend(self.dependencies_rows_with_value(pack, &columns, &cell));
+                            }
+                        }
+                    }
+                }
+            }
+
+            rows.push(row);
+        }
+
+        referenced_rows.retain(|row| visited_rows.insert((row.path.to_owned(), row.row_index)));
+
+        rows.sort_by(|a, b| (&a.table_name, &a.path, a.row_index).cmp(&(&b.table_name, &b.path, b.row_index)));
+        referenced_rows.sort_by(|a, b| (&a.table_name, &a.path, a.row_index).cmp(&(&b.table_name, &b.path, b.row_index)));
+
+        RelatedRows {
+            rows,
+            referenced_rows,
+        }
+    }
+
+    /// This function imports the rows related to the rows with the provided value in a column of a table into the provided Pack,
+    /// along with the loc entries of said rows. Check [Dependencies::related_rows] for what's considered related.
+    ///
+    /// If a new value is provided, it replaces the value in the keys of the related rows and in their columns referencing said keys,
+    /// so the rows can be used as the base of something new without overwriting the originals. Only whole values are replaced,
+    /// and referenced rows are imported as they are.
+    ///
+    /// The rows are saved in tables called `file_name`, one per table, in the latest version available. If said tables already exist in the Pack,
+    /// the rows are added to them. Rows from the parent files take priority over the ones with the same keys in the installed mods,
+    /// and these over the ones in the game files.
+    ///
+    /// It returns the paths of the files added or edited.
+    #[allow(clippy::too_many_arguments)]
+    pub fn import_related_rows(&self, pack: &mut Pack, schema: &Schema, table_name: &str, column_name: &str, value: &str, new_value: Option<&str>, include_referenced: bool, file_name: &str) -> Result<Vec<ContainerPath>> {
+        let related_rows = self.related_rows(pack, schema, table_name, column_name, value, include_referenced);
+
+        // Keys renamed in the related rows, so columns referencing them can be renamed too.
+        let mut renamed_keys = HashSet::new();
+        if new_value.is_some() {
+            for row in related_rows.rows() {
+                if let Some(table) = self.row_table(pack, row) {
+                    if let Some(data) = table.data(&None).ok().and_then(|data| data.get(row.row_index).cloned()) {
+                        renamed_keys.extend(table.definition().fields_processed().iter()
+                            .zip(data.iter())
+                            .filter(|(field, cell)| field.is_key(Some(table.patches())) && cell.data_to_string() == value)
+                            .map(|(field, _)| (table.table_name_without_tables().to_owned(), field.name().to_owned())));
+                    }
+                }
+            }
+        }
+
+        // Parent files first, then installed mods, so their rows are the ones kept if there are duplicates.
+        let mut rows = related_rows.rows().iter().map(|row| (row, true))
+            .chain(related_rows.referenced_rows().iter().map(|row| (row, false)))
+            .collect::<Vec<_>>();
+
+        rows.sort_by_key(|(row, _)| match row.source {
+            SearchSource::ParentFiles => 0,
+            SearchSource::InstalledMods => 1,
+            _ => 2,
+        });
+
+        let mut tables: BTreeMap<String, DB> = BTreeMap::new();
+        let mut keys: HashSet<(String, Vec<String>)> = HashSet::new();
+        let mut loc_keys: Vec<(String, String)> = vec![];
+
+        for (row, is_related) in rows {
+            let source_table = match self.row_table(pack, row) {
+                Some(table) => table,
+                None => continue,
+            };
+
+            let row_data = match source_table.data(&None).ok().and_then(|data| data.get(row.row_index).cloned()) {
+                Some(row_data) => row_data,
+                None => continue,
+            };
+
+            // Use the newest definition we have for the table. If it already exists in the Pack, use the one of the existing table.
+            let path = format!("db/{}/{}", row.table_name, file_name);
+            let table = match tables.get_mut(&row.table_name) {
+                Some(table) => table,
+                None => {
+                    let table = match pack.files().get(&path).map(|file| file.decoded()) {
+                        Some(Ok(RFileDecoded::DB(table))) => table.clone(),
+                        Some(_) => return Err(RLibError::DecodingDBNotADBTable),
+                        None => {
+                            let definition = self.db_version(&row.table_name)
+                                .and_then(|version| schema.definition_by_name_and_version(&row.table_name, version))
+                                .unwrap_or_else(|| source_table.definition());
+                            DB::new(definition, schema.patches_for_table(&row.table_name), &row.table_name, false)
+                        }
+                    };
+
+                    tables.entry(row.table_name.to_owned()).or_insert(table)
+                }
+            };
+
+            // Move the row to the definition of the destination table.
+            let mut converted = DB::new(source_table.definition(), Some(source_table.patches()), &row.table_name, false);
+            converted.set_data(None, &[row_data])?;
+            converted.set_definition(table.definition());
+            let mut row_data = match converted.data(&None)?.first() {
+                Some(row_data) => row_data.clone(),
+                None => continue,
+            };
+
+            let definition = table.definition().clone();
+            let key_positions = definition.fields_processed().iter()
+                .enumerate()
+                .filter(|(_, field)| field.is_key(Some(table.patches())))
+                .map(|(position, _)| position)
+                .collect::<Vec<_>>();
+
+            let old_loc_key = definition.localised_key_order().iter().map(|position| row_data[*position as usize].data_to_string()).join("");
+
+            // Only rename our keys, and the columns referencing them. Other columns with the value may be unrelated to it.
+            if let Some(new_value) = new_value.filter(|_| is_related) {
+                for (cell, field) in row_data.iter_mut().zip(definition.fields_processed().iter()) {
+                    if cell.data_to_string() != value {
+                        continue;
+                    }
+
+                    let is_renamed_key = field.is_key(Some(table.patches())) || matches!(field.is_reference(),
+                        Some((ref_table, ref_column)) if renamed_keys.contains(&(ref_table.to_owned(), ref_column.to_owned())));
+
+                    if is_renamed_key {
+                        cell.set_data(new_value)?;
+                    }
+                }
+            }
+
+            // Skip rows already in the destination table, or already imported from another source.
+            let row_keys = key_positions.iter().map(|position| row_data[*position].data_to_string().to_string()).collect::<Vec<_>>();
+            if !key_positions.is_empty() && !keys.insert((row.table_name.to_owned(), row_keys.to_vec())) {
+                continue;
+            }
+
+            if !key_positions.is_empty() && table.data(&None)?.iter().any(|existing| key_positions.iter().map(|position| existing[*position].data_to_string().to_string()).collect::<Vec<_>>() == row_keys) {
+                continue;
+            }
+
+            if !definition.localised_fields().is_empty() && !definition.localised_key_order().is_empty() {
+                let new_loc_key = definition.localised_key_order().iter().map(|position| row_data[*position as usize].data_to_string()).join("");
+                let short_table_name = table.table_name_without_tables();
+                for field in definition.localised_fields() {
+                    loc_keys.push((
+                        format!("{}_{}_{}", short_table_name, field.name(), old_loc_key),
+                        format!("{}_{}_{}", short_table_name, field.name(), new_loc_key),
+                    ));
+                }
+            }
+
+            table.data_mut()?.push(row_data);
+        }
+
+        let mut paths = vec![];
+        for (table_name, table) in tables {
+            if table.data(&None)?.is_empty() {
+                continue;
+            }
+
+            let path = format!("db/{table_name}/{file_name}");
+            let file = RFile::new_from_decoded(&RFileDecoded::DB(table), 0, &path);
+            if let Some(path) = pack.insert(file)? {
+                paths.push(path);
+            }
+        }
+
+        // Loc entries from the parent files take priority over the ones from the game files.
+        if !loc_keys.is_empty() {
+            let old_keys = loc_keys.iter().map(|(old_key, _)| old_key.as_str()).collect::<HashSet<_>>();
+            let mut loc_rows: HashMap<String, Vec<DecodedData>> = HashMap::new();
+            for file in self.loc_data(false, true).unwrap_or_default().into_iter().chain(self.loc_data(true, false).unwrap_or_default()) {
+                if let Ok(RFileDecoded::Loc(loc)) = file.decoded() {
+                    for loc_row in loc.data(&None)?.iter() {
+                        let key = loc_row[0].data_to_string();
+                        if old_keys.contains(&*key) && !loc_rows.contains_key(&*key) {
+                            loc_rows.insert(key.to_string(), loc_row.clone());
+                        }
+                    }
+                }
+            }
+
+            let path = format!("text/db/{file_name}.loc");
+            let mut loc = match pack.files().get(&path).map(|file| file.decoded()) {
+                Some(Ok(RFileDecoded::Loc(loc))) => loc.clone(),
+                Some(_) => return Err(RLibError::DecodingLocNotALocTable),
+                None => Loc::new(false),
+            };
+
+            let data = loc.data_mut()?;
+            let mut existing_keys = data.iter().map(|loc_row| loc_row[0].data_to_string().to_string()).collect::<HashSet<_>>();
+            for (old_key, new_key) in &loc_keys {
+                if let Some(loc_row) = loc_rows.get(old_key) {
+                    if existing_keys.insert(new_key.to_owned()) {
+                        let mut loc_row = loc_row.clone();
+                        loc_row[0].set_data(new_key)?;
+                        data.push(loc_row);
+                    }
+                }
+            }
+
+            if !data.is_empty() {
+                let file = RFile::new_from_decoded(&RFileDecoded::Loc(loc), 0, &path);
+                if let Some(path) = pack.insert(file)? {
+                    paths.push(path);
+                }
+            }
+        }
+
+        Ok(paths)
+    }
+
+    /// This function returns the rows with the provided value in any of the provided columns, from the parent files and the game files.
+    fn dependencies_rows_with_value(&self, pack: &Pack, columns: &BTreeMap<String, Vec<String>>, value: &str) -> Vec<RowReference> {
+        let mut rows = self.rows_with_value(pack, columns, value);
+        rows.retain(|row| row.source != SearchSource::Pack);
+        rows
+    }
+
+    /// This function returns the table the provided row is from.
+    fn row_table<'a>(&'a self, pack: &'a Pack, row: &RowReference) -> Option<&'a DB> {
+        let file = match row.source {
+            SearchSource::Pack => pack.files().get(&row.path),
+            SearchSource::ParentFiles | SearchSource::InstalledMods => self.parent_files.get(&row.path),
+            _ => self.vanilla_files.get(&row.path).map(|file| self.vanilla_file(&row.path, file)),
+        };
+
+        match file.map(|file| file.decoded()) {
+            Some(Ok(RFileDecoded::DB(table))) => Some(table),
+            _ => None,
+        }
+    }
+
+    //-----------------------------------//
+    // Lookup values.
+    //-----------------------------------//
+
+    /// This function returns the lookup values of the values a reference column can have, in a "value, lookup" format.
+    ///
+    /// These are the same lookups shown in the table views: the reference data of the column from the assembly kit tables, the game files,
+    /// the parent files and, if provided, the Pack, in that order of priority. Lookup columns moved to loc files are resolved through their loc entries.
+    ///
+    /// Tables and locs must be decoded. Values without lookup data are not included.
+    pub fn reference_lookups(&self, pack: Option<&Pack>, field: &Field) -> HashMap<String, String> {
+        let cache_key = match Self::reference_cache_key(field) {
+            Some(cache_key) => cache_key,
+            None => return HashMap::new(),
+        };
+
+        self.cache_references(std::iter::once(&cache_key));
+        let mut references = self.cached_references.read().unwrap().get(&cache_key).cloned().unwrap_or_default();
+
+        if let Some(pack) = pack {
+            if let Some((ref_table, ref_column)) = field.is_reference() {
+                self.db_reference_data_from_local_pack(&mut references, (ref_table, ref_column, &cache_key.2), pack);
+            }
+        }
+
+        references.data.into_iter()
+            .filter(|(value, lookup)| !value.is_empty() && !lookup.is_empty())
+            .collect()
+    }
+
+    /// This function returns the lookup values of all the reference columns of a table, by column index. Check [Dependencies::reference_lookups] for details.
+    pub fn table_reference_lookups(&self, pack: Option<&Pack>, definition: &Definition) -> HashMap<usize, HashMap<String, String>> {
+        definition.fields_processed()
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| field.is_reference().is_some())
+            .map(|(column, field)| (column, self.reference_lookups(pack, field)))
+            .filter(|(_, lookups)| !lookups.is_empty())
+            .collect()
+    }
+
+    /// This function returns the lookup value of a value of a reference column, if any. Check [Dependencies::reference_lookups] for details.
+    pub fn reference_lookup(&self, pack: Option<&Pack>, field: &Field, value: &str) -> Option<String> {
+        if value.is_empty() {
+            return None;
+        }
+
+        self.reference_lookups(pack, field).remove(value)
+    }
+
+    /// This function returns the value of a reference column in a human-readable format, like `wh_main_emp_inf_swordsmen (Swordsmen)`.
+    ///
+    /// If the value has no lookup data, the value is returned as it is.
+    pub fn reference_display_value(&self, pack: Option<&Pack>, field: &Field, value: &str) -> String {
+        display_value(value, self.reference_lookup(pack, field, value).as_deref())
+    }
+
+    /// This function returns the texts of the provided loc keys, from the game files, the parent files and, if provided, the Pack, in that order of priority.
+    fn loc_texts(&self, pack: Option<&Pack>, keys: &HashSet<&str>) -> HashMap<String, String> {
+        if keys.is_empty() {
+            return HashMap::new();
+        }
+
+        let mut files = self.loc_data(true, true).unwrap_or_default();
+        if let Some(pack) = pack {
+            let mut pack_files = pack.files_by_type(&[FileType::Loc]);
+            pack_files.sort_by(|a, b| a.path_in_container_raw().cmp(b.path_in_container_raw()));
+            files.extend(pack_files);
+        }
+
+        let mut texts = HashMap::new();
+        for file in files {
+            if let Ok(RFileDecoded::Loc(loc)) = file.decoded() {
+                if let Ok(data) = loc.data(&None) {
+                    for row in data.iter() {
+                        let key = row[0].data_to_string();
+                        if keys.contains(&*key) {
+                            texts.insert(key.to_string(), row[1].data_to_string().to_string());
+                        }
+                    }
+                }
+            }
+        }
+
+        texts
+    }
+
     //-----------------------------------//
     // Utility functions.
     //-----------------------------------//
@@ -1129,7 +2303,7 @@ impl Dependencies {
         let tables = self.vanilla_tables.get(table_name)?;
         for table_path in tables {
 
-            let table = self.vanilla_files.get(table_path)?;
+            let table = self.vanilla_files.get(table_path).map(|file| self.vanilla_file(table_path, file))?;
             if let RFileDecoded::DB(table) = table.decoded().ok()? {
                 return Some(*table.definition().version());
             }
@@ -1146,7 +2320,11 @@ impl Dependencies {
             for file in &files {
                 if let Ok(RFileDecoded::DB(table)) = file.decoded() {
                     if let Some(column) = table.definition().column_position_by_name(column_name) {
-                        if let Ok(data) = table.data(&None) {
+
+                        // If the table has the column indexed, use the index instead of going through all the rows.
+                        if let Some(index_column) = table.index().and_then(|index| index.column(column)) {
+                            values.extend(index_column.keys().cloned());
+                        } else if let Ok(data) = table.data(&None) {
                             for row in data.iter() {
                                 values.insert(row[column].data_to_string().to_string());
                             }
@@ -1160,7 +2338,11 @@ impl Dependencies {
             for file in &pack.files_by_path(&ContainerPath::Folder(format!("db/{table_name}")), true) {
                 if let Ok(RFileDecoded::DB(table)) = file.decoded() {
                     if let Some(column) = table.definition().column_position_by_name(column_name) {
-                        if let Ok(data) = table.data(&None) {
+
+                        // If the table has the column indexed, use the index instead of going through all the rows.
+                        if let Some(index_column) = table.index().and_then(|index| index.column(column)) {
+                            values.extend(index_column.keys().cloned());
+                        } else if let Ok(data) = table.data(&None) {
                             for row in data.iter() {
                                 values.insert(row[column].data_to_string().to_string());
                             }
@@ -1201,97 +2383,110 @@ impl Dependencies {
     }
 
     /// This function bruteforces the order in which multikeyed tables get their keys together for loc entries.
+    ///
+    /// For a report with the ambiguous tables, or to get the results as schema patches, use [LocKeyOrderReport] instead.
     pub fn bruteforce_loc_key_order(&self, schema: &mut Schema) -> Result<()> {
+        let report = LocKeyOrderReport::new(schema, self)?;
+        for table_name in report.key_orders().keys() {
+            info!("Bruteforce: loc key order found for table {}.", table_name);
+        }
 
-        // Get all vanilla loc keys into a big hashmap so we can check them fast.
-        let loc_files = self.loc_data(true, false)?;
-        let loc_table = loc_files.iter()
-            .filter_map(|file| if let Ok(RFileDecoded::Loc(loc)) = file.decoded() { Some(loc) } else { None })
-            .flat_map(|file| file.data(&None).unwrap().to_vec())
-            .map(|entry| (entry[0].data_to_string().to_string(), entry[1].data_to_string().to_string()))
-            .collect::<HashMap<_,_>>();
+        report.apply_key_orders(schema);
+        Ok(())
+    }
+}
 
-        // Get all the tables so we don't need to re-fetch each table individually.
-        let db_tables = self.db_and_loc_data(true, false, true, false)?
-            .iter()
-            .filter_map(|file| if let Ok(RFileDecoded::DB(table)) = file.decoded() { Some(table) } else { None })
-            .collect::<Vec<_>>();
 
-        for table in db_tables {
-            let definition = table.definition();
-
-            // Ignore tables without localisation. Note that we only consider keys those that come from the asskit.
-            // Custom loc keys we setup through patches are not taken into account for locs.
-            if !definition.localised_fields().is_empty() {
-                let fields = definition.fields_processed();
-                let key_fields = fields.iter()
-                    .enumerate()
-                    .filter(|(_, field)| field.is_key(None))
-                    .collect::<Vec<_>>();
-
-                // If we only have one key field, don't bother searching.
-                let order = if key_fields.len() == 1 {
-                    vec![key_fields[0].0 as u32]
-                }
+impl VanillaPack {
 
-                // If we have multiple key fields, we need to test for combinations.
-                else {
-                    let mut order = Vec::with_capacity(key_fields.len());
-                    let combos = key_fields.iter().permutations(key_fields.len());
-                    let short_table_name = table.table_name_without_tables();
-                    let table_data = table.data(&None)?;
-                    for combo in combos {
-
-                        // Many multikeyed tables admit empty values as part of the key. We need rows with no empty values.
-                        for row in table_data.iter() {
-                            let mut all_keys_populated = true;
-                            for (index, _) in &combo {
-                                if row[*index].data_to_string().is_empty() {
-                                    all_keys_populated = false;
-                                    break;
-                                }
-                            }
+    /// This function returns the path of a Pack in the format used by the cache, and its last modified date.
+    fn path_and_timestamp(path: &Path) -> Result<(String, u64)> {
+        let path_str = path.to_string_lossy().replace('\\', "/");
+        let timestamp = last_modified_time_from_files(&[path.to_path_buf()])?;
+        Ok((path_str, timestamp))
+    }
 
-                            if all_keys_populated {
-                                let mut combined_key = String::new();
-                                for (index, _) in &combo {
-                                    combined_key.push_str(&row[*index].data_to_string());
-                                }
+    /// This function checks if this is the Pack at the provided path, unchanged since it was read into the cache.
+    fn is_same_pack(&self, path: &str, timestamp: u64) -> bool {
+        self.path == path && self.timestamp == timestamp
+    }
+}
 
-                                for localised_field in definition.localised_fields() {
-                                    let localised_key = format!("{}_{}_{}", short_table_name, localised_field.name(), combined_key);
-                                    if loc_table.get(&localised_key).is_some() {
-                                        order = combo.iter().map(|(index, _)| *index as u32).collect();
-                                        break;
-                                    }
-                                }
-                            }
+impl InstalledModsSource {
 
-                            if !order.is_empty() {
-                                break;
-                            }
-                        }
+    /// This function returns the paths of the Packs in this source.
+    pub fn pack_paths(&self, game_info: &GameInfo, game_path: &Path) -> Vec<PathBuf> {
+        let folder = match self {
+            Self::DataFolder => return game_info.data_packs_paths(game_path).unwrap_or_default(),
+            Self::LocalModsFolder => match game_info.local_mods_path(game_path) {
+                Ok(path) => path,
+                Err(_) => return vec![],
+            },
+            Self::Folder(path) => path.to_path_buf(),
+        };
 
-                        if !order.is_empty() {
-                            break;
-                        } else {
-                            info!("Bruteforce: failed to find order for table {}, version {}, combo {:?}. Trying another combo (if any left to try).", table.table_name(), definition.version(), combo);
-                        }
-                    }
+        files_from_subdir(&folder, false)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|path| path.extension().map(|extension| extension == "pack").unwrap_or(false))
+            .collect()
+    }
+}
 
-                    order
-                };
+/// Implementation to parse sources from settings and command line arguments.
+///
+/// `data` and `local_mods` are the game folders. Anything else is a path to a folder.
+impl From<&str> for InstalledModsSource {
+    fn from(value: &str) -> Self {
+        match value.trim() {
+            "data" => Self::DataFolder,
+            "local_mods" => Self::LocalModsFolder,
+            path => Self::Folder(PathBuf::from(path)),
+        }
+    }
+}
 
-                if !order.is_empty() {
-                    info!("Bruteforce: loc key order found for table {}, version {}.", table.table_name(), definition.version());
-                    if let Some(schema_definition) = schema.definition_by_name_and_version_mut(table.table_name(), *definition.version()) {
-                        schema_definition.set_localised_key_order(order);
-                    }
-                }
-            }
+impl Display for InstalledModsSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::DataFolder => write!(f, "data"),
+            Self::LocalModsFolder => write!(f, "local_mods"),
+            Self::Folder(path) => write!(f, "{}", path.to_string_lossy()),
         }
+    }
+}
 
-        Ok(())
+impl KeyReferences {
+
+    /// This function returns if nothing uses the key.
+    pub fn is_empty(&self) -> bool {
+        self.key_rows.is_empty() && self.referencing_rows.is_empty() && self.loc_rows.is_empty() && self.text_files.is_empty()
+    }
+
+    /// This function converts the entire result into a JSon string.
+    pub fn json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(From::from)
     }
 }
 
+/// This function returns a value with its lookup in a human-readable format, like `wh_main_emp_inf_swordsmen (Swordsmen)`.
+///
+/// If there is no lookup, or it's empty, the value is returned as it is.
+pub fn display_value(value: &str, lookup: Option<&str>) -> String {
+    match lookup {
+        Some(lookup) if !lookup.is_empty() && lookup != value => format!("{value} ({lookup})"),
+        _ => value.to_owned(),
+    }
+}
+
+/// This function returns the lines of a text mentioning the provided value as a whole word, starting at 1.
+fn lines_mentioning(text: &str, value: &str) -> Vec<usize> {
+    let is_word_char = |character: Option<char>| character.map(|character| character.is_alphanumeric() || character == '_').unwrap_or(false);
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| line.match_indices(value).any(|(position, _)|
+            !is_word_char(line[..position].chars().next_back()) && !is_word_char(line[position + value.len()..].chars().next())
+        ))
+        .map(|(index, _)| index + 1)
+        .collect()
+}
@@ -15,6 +15,8 @@ use serde_derive::{Serialize, Deserialize};
 
 use std::{fmt, fmt::Display};
 
+use rpfm_lib::utils::game_version_to_string;
+
 use super::{DiagnosticLevel, DiagnosticReport};
 
 //-------------------------------------------------------------------------------//
@@ -40,6 +42,7 @@ pub enum ConfigDiagnosticReportType {
     DependenciesCacheNotGenerated,
     DependenciesCacheOutdated,
     DependenciesCacheCouldNotBeLoaded(String),
+    DependenciesCacheBaseline(u32, u32),
     IncorrectGamePath,
 }
 
@@ -59,6 +62,11 @@ impl DiagnosticReport for ConfigDiagnosticReport {
     fn message(&self) -> String {
         match self.report_type {
             ConfigDiagnosticReportType::DependenciesCacheNotGenerated => "Dependency Cache not generated for the currently selected game.".to_owned(),
+            ConfigDiagnosticReportType::DependenciesCacheBaseline(cache_version, installed_version) => if installed_version == 0 || cache_version == installed_version {
+                format!("Diagnostics checked against the Dependency Cache for the game version {}.", game_version_to_string(cache_version))
+            } else {
+                format!("Diagnostics checked against the Dependency Cache for the game version {}, not the installed one ({}).", game_version_to_string(cache_version), game_version_to_string(installed_version))
+            },
             ConfigDiagnosticReportType::DependenciesCacheOutdated => "Dependency Cache for the selected game is outdated and could not be loaded.".to_owned(),
             ConfigDiagnosticReportType::DependenciesCacheCouldNotBeLoaded(_) => "Dependency Cache couldn't be loaded for the game selected, due to errors reading the game's folder.".to_owned(),
             ConfigDiagnosticReportType::IncorrectGamePath => "Game Path for the current Game Selected is incorrect.".to_owned(),
@@ -70,6 +78,7 @@ impl DiagnosticReport for ConfigDiagnosticReport {
             ConfigDiagnosticReportType::DependenciesCacheNotGenerated => DiagnosticLevel::Error,
             ConfigDiagnosticReportType::DependenciesCacheOutdated => DiagnosticLevel::Error,
             ConfigDiagnosticReportType::DependenciesCacheCouldNotBeLoaded(_) => DiagnosticLevel::Error,
+            ConfigDiagnosticReportType::DependenciesCacheBaseline(_, _) => DiagnosticLevel::Info,
             ConfigDiagnosticReportType::IncorrectGamePath => DiagnosticLevel::Error,
         }
     }
@@ -81,6 +90,7 @@ impl Display for ConfigDiagnosticReportType {
             Self::DependenciesCacheNotGenerated => "DependenciesCacheNotGenerated",
             Self::DependenciesCacheOutdated => "DependenciesCacheOutdated",
             Self::DependenciesCacheCouldNotBeLoaded(_) => "DependenciesCacheCouldNotBeLoaded",
+            Self::DependenciesCacheBaseline(_, _) => "DependenciesCacheBaseline",
             Self::IncorrectGamePath => "IncorrectGamePath",
         }, f)
     }
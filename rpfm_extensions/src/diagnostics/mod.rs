@@ -29,10 +29,10 @@ use std::{fmt, fmt::Display};
 use std::cmp::Ordering;
 use std::collections::{BTreeMap, HashMap, HashSet};
 
-use rpfm_lib::error::Result;
+use rpfm_lib::error::{RLibError, Result};
 use rpfm_lib::files::{ContainerPath, Container, FileType, pack::Pack, RFile, RFileDecoded, table::DecodedData};
 use rpfm_lib::games::{GameInfo, VanillaDBTableNameLogic};
-use rpfm_lib::schema::{FieldType, Schema};
+use rpfm_lib::schema::{FieldType, Schema, validation::TableValidator};
 
 use crate::dependencies::{Dependencies, TableReferences};
 use crate::REGEX_INVALID_ESCAPES;
@@ -156,6 +156,7 @@ impl Diagnostics {
                             ConfigDiagnosticReportType::DependenciesCacheNotGenerated |
                             ConfigDiagnosticReportType::DependenciesCacheOutdated |
                             ConfigDiagnosticReportType::DependenciesCacheCouldNotBeLoaded(_) |
+                            ConfigDiagnosticReportType::DependenciesCacheBaseline(_, _) |
                             ConfigDiagnosticReportType::IncorrectGamePath => false,
                         }
                     );
@@ -306,7 +307,7 @@ impl Diagnostics {
             self.results_mut().push(diagnostics);
         }
 
-        if let Some(diagnostics) = Self::check_pack(pack) {
+        if let Some(diagnostics) = Self::check_pack(pack, game_info, game_path) {
             self.results_mut().push(diagnostics);
         }
 
@@ -564,6 +565,30 @@ impl Diagnostics {
                 }
             }
 
+            // Check the validation rules from the schema patches. If any of them cannot be used, none of them are checked, so we need to report it.
+            match TableValidator::new(table.definition(), schema_patches) {
+                Ok(validator) => {
+                    for (row, column, error) in validator.validate(&table_data) {
+                        let field_name = fields_processed[column].name();
+                        if !Self::ignore_diagnostic(global_ignored_diagnostics, Some(field_name), Some("ValidationRuleBroken"), ignored_fields, ignored_diagnostics, ignored_diagnostics_for_fields) {
+                            let result = TableDiagnosticReport::new(TableDiagnosticReportType::ValidationRuleBroken(field_name.to_owned(), error.to_string()), &[(row as i32, column as i32)]);
+                            diagnostic.results_mut().push(result);
+                        }
+                    }
+                }
+                Err(error) => {
+                    if !Self::ignore_diagnostic(global_ignored_diagnostics, None, Some("InvalidValidationRule"), ignored_fields, ignored_diagnostics, ignored_diagnostics_for_fields) {
+                        let column = match error {
+                            RLibError::InvalidValidationRule(ref field_name, _, _) => fields_processed.iter().position(|field| field.name() == field_name).map(|column| column as i32).unwrap_or(-1),
+                            _ => -1,
+                        };
+
+                        let result = TableDiagnosticReport::new(TableDiagnosticReportType::InvalidValidationRule(error.to_string()), &[(-1, column)]);
+                        diagnostic.results_mut().push(result);
+                    }
+                }
+            }
+
             // Checks that only need to be done once per table.
             if !Self::ignore_diagnostic(global_ignored_diagnostics, None, Some("NoReferenceTableFound"), ignored_fields, ignored_diagnostics, ignored_diagnostics_for_fields) {
                 for column in &columns_without_reference_table {
@@ -736,7 +761,7 @@ impl Diagnostics {
     }
 
     /// This function takes care of checking for PackFile-Related for errors.
-    fn check_pack(pack: &Pack) -> Option<DiagnosticType> {
+    fn check_pack(pack: &Pack, game_info: &GameInfo, game_path: &Path) -> Option<DiagnosticType> {
         let mut diagnostic = PackDiagnostic::default();
 
         let name = pack.disk_file_name();
@@ -745,6 +770,17 @@ impl Diagnostics {
             diagnostic.results_mut().push(result);
         }
 
+        // Packs without game version in their header cannot be checked.
+        let pack_build = pack.game_version();
+        if pack_build != 0 {
+            if let Some(game_build) = game_info.game_build(game_path) {
+                if pack_build < game_build {
+                    let result = PackDiagnosticReport::new(PackDiagnosticReportType::OutdatedGameBuild(pack_build, game_build));
+                    diagnostic.results_mut().push(result);
+                }
+            }
+        }
+
         if !diagnostic.results().is_empty() {
             Some(DiagnosticType::Pack(diagnostic))
         } else { None }
@@ -783,18 +819,29 @@ impl Diagnostics {
         }
 
         // If we have vanilla data, check if the dependencies need updating due to changes in the game files.
+        // Caches for other versions of the game are baselines for Packs made for said versions, so they're never outdated.
+        // Same with caches generated from a list of CA Packs, as they're not from the game install.
         else {
-            match dependencies.needs_updating(game_info, game_path) {
-                Ok(needs_updating) => {
-                    if needs_updating {
-                        diagnostic.results_mut().push(ConfigDiagnosticReport::new(ConfigDiagnosticReportType::DependenciesCacheOutdated));
+            let installed_version = game_info.game_build(game_path).unwrap_or(0);
+            let cache_version = *dependencies.game_version();
+            if !*dependencies.from_packs() && (cache_version == 0 || cache_version == installed_version) {
+                match dependencies.needs_updating(game_info, game_path) {
+                    Ok(needs_updating) => {
+                        if needs_updating {
+                            diagnostic.results_mut().push(ConfigDiagnosticReport::new(ConfigDiagnosticReportType::DependenciesCacheOutdated));
+                        }
                     }
-                }
 
-                Err(error) => {
-                    diagnostic.results_mut().push(ConfigDiagnosticReport::new(ConfigDiagnosticReportType::DependenciesCacheCouldNotBeLoaded(error.to_string())));
+                    Err(error) => {
+                        diagnostic.results_mut().push(ConfigDiagnosticReport::new(ConfigDiagnosticReportType::DependenciesCacheCouldNotBeLoaded(error.to_string())));
+                    }
                 }
             }
+
+            // Report which version of the game we're checking against, if known.
+            if cache_version != 0 {
+                diagnostic.results_mut().push(ConfigDiagnosticReport::new(ConfigDiagnosticReportType::DependenciesCacheBaseline(cache_version, installed_version)));
+            }
         }
 
         if !diagnostic.results().is_empty() {
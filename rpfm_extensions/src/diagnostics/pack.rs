@@ -15,6 +15,8 @@ use serde_derive::{Serialize, Deserialize};
 
 use std::{fmt, fmt::Display};
 
+use rpfm_lib::utils::game_version_to_string;
+
 use crate::diagnostics::DiagnosticReport;
 use super::DiagnosticLevel;
 
@@ -38,7 +40,10 @@ pub struct PackDiagnosticReport {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PackDiagnosticReportType {
-    InvalidPackName(String)
+    InvalidPackName(String),
+
+    /// The Pack was made for an older build of the game. Contains the game version of the Pack and the one of the game.
+    OutdatedGameBuild(u32, u32),
 }
 
 //-------------------------------------------------------------------------------//
@@ -57,12 +62,14 @@ impl DiagnosticReport for PackDiagnosticReport {
     fn message(&self) -> String {
         match &self.report_type {
             PackDiagnosticReportType::InvalidPackName(pack_name) => format!("Invalid Pack name: {pack_name}"),
+            PackDiagnosticReportType::OutdatedGameBuild(pack_build, game_build) => format!("Pack made for an older build of the game: {}, current build: {}", game_version_to_string(*pack_build), game_version_to_string(*game_build)),
         }
     }
 
     fn level(&self) -> DiagnosticLevel {
         match self.report_type {
             PackDiagnosticReportType::InvalidPackName(_) => DiagnosticLevel::Error,
+            PackDiagnosticReportType::OutdatedGameBuild(_, _) => DiagnosticLevel::Warning,
         }
     }
 }
@@ -71,6 +78,7 @@ impl Display for PackDiagnosticReportType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         Display::fmt(match self {
             Self::InvalidPackName(_) => "InvalidPackFileName",
+            Self::OutdatedGameBuild(_, _) => "OutdatedGameBuild",
         }, f)
     }
 }
@@ -63,6 +63,8 @@ pub enum TableDiagnosticReportType {
     FieldWithPathNotFound(Vec<String>),
     BannedTable,
     ValueCannotBeEmpty(String),
+    ValidationRuleBroken(String, String),
+    InvalidValidationRule(String),
 }
 
 //-------------------------------------------------------------------------------//
@@ -108,6 +110,8 @@ impl DiagnosticReport for TableDiagnosticReport {
             TableDiagnosticReportType::FieldWithPathNotFound(paths) => format!("Path not found: {}.", paths.iter().join(" || ")),
             TableDiagnosticReportType::BannedTable => "Banned table.".to_owned(),
             TableDiagnosticReportType::ValueCannotBeEmpty(field_name) => format!("Empty value for column \"{field_name}\"."),
+            TableDiagnosticReportType::ValidationRuleBroken(field_name, error) => format!("Invalid value for column \"{field_name}\": {error}"),
+            TableDiagnosticReportType::InvalidValidationRule(error) => format!("Validation rules not checked: {error}"),
         }
     }
 
@@ -131,6 +135,8 @@ impl DiagnosticReport for TableDiagnosticReport {
             TableDiagnosticReportType::FieldWithPathNotFound(_) => DiagnosticLevel::Warning,
             TableDiagnosticReportType::BannedTable => DiagnosticLevel::Error,
             TableDiagnosticReportType::ValueCannotBeEmpty(_) => DiagnosticLevel::Error,
+            TableDiagnosticReportType::ValidationRuleBroken(_,_) => DiagnosticLevel::Error,
+            TableDiagnosticReportType::InvalidValidationRule(_) => DiagnosticLevel::Warning,
         }
     }
 }
@@ -156,6 +162,8 @@ impl Display for TableDiagnosticReportType {
             Self::FieldWithPathNotFound(_) => "FieldWithPathNotFound",
             Self::BannedTable => "BannedTable",
             Self::ValueCannotBeEmpty(_) => "ValueCannotBeEmpty",
+            Self::ValidationRuleBroken(_,_) => "ValidationRuleBroken",
+            Self::InvalidValidationRule(_) => "InvalidValidationRule",
         }, f)
     }
 }
@@ -13,10 +13,15 @@
 use fancy_regex::Regex;
 use lazy_static::lazy_static;
 
+pub mod conflicts;
 pub mod dependencies;
 pub mod diagnostics;
+pub mod load_order;
+pub mod loc_key_order;
 pub mod optimizer;
+pub mod schema_check;
 pub mod search;
+pub mod vanilla_diff;
 
 lazy_static! {
 
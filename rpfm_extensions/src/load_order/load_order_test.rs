@@ -0,0 +1,95 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! Module containing tests for the load order resolver.
+
+use rpfm_lib::games::pfh_version::PFHVersion;
+
+use super::*;
+
+fn pack(path: &str, pfh_file_type: PFHFileType, dependencies: &[&str]) -> Pack {
+    let mut pack = Pack::new_with_name_and_version(path, PFHVersion::PFH5);
+    pack.set_pfh_file_type(pfh_file_type);
+    pack.set_dependencies(dependencies.iter().map(|dependency| dependency.to_string()).collect());
+    pack
+}
+
+fn names(load_order: &LoadOrder) -> Vec<&str> {
+    load_order.entries().iter().map(|entry| entry.name().as_str()).collect()
+}
+
+fn packs() -> Vec<Pack> {
+    vec![
+        pack("/game/data/movies.pack", PFHFileType::Movie, &[]),
+        pack("/game/data/z_mod.pack", PFHFileType::Mod, &["a_mod.pack"]),
+        pack("/game/data/data.pack", PFHFileType::Release, &[]),
+        pack("/game/data/a_mod.pack", PFHFileType::Mod, &["data.pack"]),
+        pack("/workshop/1/m_mod.pack", PFHFileType::Mod, &["missing.pack"]),
+        pack("/game/data/boot.pack", PFHFileType::Boot, &[]),
+    ]
+}
+
+#[test]
+fn test_load_order_sorted_by_type_and_name() {
+    let load_order = LoadOrder::new(&packs(), None);
+    assert_eq!(names(&load_order), vec!["boot.pack", "data.pack", "a_mod.pack", "m_mod.pack", "z_mod.pack", "movies.pack"]);
+    assert!(load_order.mods_not_found().is_empty());
+    assert!(load_order.mods_not_in_order().is_empty());
+    assert!(load_order.loaded_before_parents().is_empty());
+    assert_eq!(load_order.missing_dependencies().get("m_mod.pack").unwrap(), &vec!["missing.pack".to_owned()]);
+    assert!(!load_order.is_valid());
+}
+
+#[test]
+fn test_load_order_with_mod_order() {
+    let mod_order = LoadOrder::mod_order_from_used_mods("add_working_directory \"/workshop/1\";\nmod \"z_mod.pack\";\n  mod \"a_mod.pack\";\nmod \"gone.pack\";\n");
+    assert_eq!(mod_order, vec!["z_mod.pack".to_owned(), "a_mod.pack".to_owned(), "gone.pack".to_owned()]);
+
+    let load_order = LoadOrder::new(&packs(), Some(&mod_order));
+    assert_eq!(names(&load_order), vec!["boot.pack", "data.pack", "z_mod.pack", "a_mod.pack", "movies.pack"]);
+    assert_eq!(load_order.mods_not_found(), &vec!["gone.pack".to_owned()]);
+
+    // Mods left out of the order are reported, not dropped silently.
+    assert_eq!(load_order.mods_not_in_order(), &vec!["m_mod.pack".to_owned()]);
+    assert!(load_order.to_string().contains("Mods not in the mod order, so not loaded: 1.\n- m_mod.pack\n"));
+
+    // z_mod depends on a_mod, which is now loaded after it.
+    assert_eq!(load_order.loaded_before_parents().get("z_mod.pack").unwrap(), &vec!["a_mod.pack".to_owned()]);
+    assert!(load_order.missing_dependencies().is_empty());
+
+    assert_eq!(load_order.used_mods(Some(Path::new("/game/data"))), "mod \"z_mod.pack\";\nmod \"a_mod.pack\";\n");
+}
+
+#[test]
+fn test_load_order_used_mods_working_directories() {
+    let load_order = LoadOrder::new(&packs(), None);
+    assert_eq!(load_order.used_mods(Some(Path::new("/game/data"))), "add_working_directory \"/workshop/1\";\nmod \"a_mod.pack\";\nmod \"m_mod.pack\";\nmod \"z_mod.pack\";\n");
+}
+
+#[test]
+fn test_load_order_cycles() {
+    let packs = vec![
+        pack("a.pack", PFHFileType::Mod, &["b.pack"]),
+        pack("b.pack", PFHFileType::Mod, &["c.pack"]),
+        pack("c.pack", PFHFileType::Mod, &["a.pack"]),
+        pack("d.pack", PFHFileType::Mod, &["d.pack"]),
+        pack("e.pack", PFHFileType::Mod, &["a.pack"]),
+    ];
+
+    let load_order = LoadOrder::new(&packs, None);
+    assert_eq!(load_order.cycles(), &vec![
+        vec!["a.pack".to_owned(), "b.pack".to_owned(), "c.pack".to_owned()],
+        vec!["d.pack".to_owned()],
+    ]);
+    assert!(!load_order.is_valid());
+
+    let load_order = LoadOrder::new(&packs[4..], None);
+    assert!(load_order.cycles().is_empty());
+}
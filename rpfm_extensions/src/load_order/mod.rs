@@ -0,0 +1,352 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! This module contains the load order resolver, used to know in which order the game loads a set of Packs.
+//!
+//! The order is computed the same way the launcher does it: Packs are sorted by their [PFHFileType], and `Mod` Packs
+//! are sorted by name, unless a specific order for them is provided (like the one in a `used_mods.txt` file).
+//! The resulting order is then validated against the dependencies of each Pack.
+
+use getset::Getters;
+use serde_derive::{Serialize, Deserialize};
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display};
+use std::path::{Path, PathBuf};
+
+use rpfm_lib::error::Result;
+use rpfm_lib::files::{Container, pack::Pack};
+use rpfm_lib::games::pfh_file_type::PFHFileType;
+
+#[cfg(test)] mod load_order_test;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct contains the order in which a set of Packs is loaded, and the problems found in said order.
+///
+/// Packs are in the order they're loaded, so files in a Pack overwrite the ones with the same path in the Packs before it.
+#[derive(Clone, Debug, Default, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct LoadOrder {
+
+    /// Packs in the order they're loaded.
+    entries: Vec<LoadOrderEntry>,
+
+    /// Mods in the provided mod order that are not in the set of Packs.
+    mods_not_found: Vec<String>,
+
+    /// Mods in the set of Packs that are not in the provided mod order, so they're not loaded.
+    ///
+    /// These are not a problem by themselves, as they're usually disabled mods.
+    mods_not_in_order: Vec<String>,
+
+    /// Dependencies not in the set of Packs, by name of the Pack depending on them.
+    missing_dependencies: BTreeMap<String, Vec<String>>,
+
+    /// Dependencies loaded after the Pack depending on them, by name of said Pack.
+    loaded_before_parents: BTreeMap<String, Vec<String>>,
+
+    /// Cycles in the dependencies of the Packs, as the names of the Packs in each cycle.
+    cycles: Vec<Vec<String>>,
+}
+
+/// This struct represents a Pack within a load order.
+#[derive(Clone, Debug, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct LoadOrderEntry {
+
+    /// File name of the Pack.
+    name: String,
+
+    /// Path of the Pack on disk.
+    path: PathBuf,
+
+    /// Type of the Pack.
+    pfh_file_type: PFHFileType,
+
+    /// Packs this Pack requires to be loaded before itself.
+    dependencies: Vec<String>,
+}
+
+//---------------------------------------------------------------------------//
+//                       Enum & Structs Implementations
+//---------------------------------------------------------------------------//
+
+impl LoadOrder {
+
+    /// This function computes the load order of the provided Packs, and checks it for problems.
+    ///
+    /// If a mod order is provided, only the `Mod` Packs in it are loaded, in the provided order, and the rest are reported as not in the order.
+    /// If not, all `Mod` Packs are loaded, sorted by name.
+    ///
+    /// Dependencies on CA Packs are only found if said Packs are provided.
+    pub fn new(packs: &[Pack], mod_order: Option<&[String]>) -> Self {
+        let mut entries = packs.iter()
+            .map(|pack| LoadOrderEntry {
+                name: pack.disk_file_name(),
+                path: PathBuf::from(pack.disk_file_path()),
+                pfh_file_type: pack.pfh_file_type(),
+                dependencies: pack.dependencies().to_vec(),
+            })
+            .collect::<Vec<_>>();
+
+        // Same sorting as the one used when merging Packs, so each type has their own Packs sorted by name.
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        entries.sort_by_key(|entry| entry.pfh_file_type as u8);
+
+        let mut mods_not_found = vec![];
+        let mut mods_not_in_order = vec![];
+        if let Some(mod_order) = mod_order {
+            let (mods, mut entries_sorted): (Vec<_>, Vec<_>) = entries.into_iter().partition(|entry| entry.pfh_file_type == PFHFileType::Mod);
+            let mut mods = mods.into_iter()
+                .map(|entry| (entry.name.to_owned(), entry))
+                .collect::<HashMap<_, _>>();
+
+            // Mods go where the launcher puts them: after the CA Packs, and before the movie ones.
+            let position = entries_sorted.iter()
+                .position(|entry| entry.pfh_file_type as u8 > PFHFileType::Mod as u8)
+                .unwrap_or(entries_sorted.len());
+
+            let mut mods_sorted = vec![];
+            for name in mod_order {
+                match mods.remove(name) {
+                    Some(entry) => mods_sorted.push(entry),
+                    None => mods_not_found.push(name.to_owned()),
+                }
+            }
+
+            entries_sorted.splice(position..position, mods_sorted);
+            entries = entries_sorted;
+
+            mods_not_in_order = mods.into_keys().collect();
+            mods_not_in_order.sort();
+        }
+
+        let mut load_order = Self {
+            entries,
+            mods_not_found,
+            mods_not_in_order,
+            ..Default::default()
+        };
+
+        load_order.check_dependencies();
+        load_order.check_cycles();
+        load_order
+    }
+
+    /// This function reads the Packs at the provided paths, and computes their load order.
+    ///
+    /// Check [LoadOrder::new] for more info.
+    pub fn from_paths(pack_paths: &[PathBuf], mod_order: Option<&[String]>) -> Result<Self> {
+        let packs = pack_paths.iter()
+            .map(|path| Pack::read_and_merge(&[path.to_path_buf()], true, false))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self::new(&packs, mod_order))
+    }
+
+    /// This function returns the names of the mods in the provided `used_mods.txt` data, in the order they're in the file.
+    pub fn mod_order_from_used_mods(data: &str) -> Vec<String> {
+        data.lines()
+            .filter_map(|line| line.trim().strip_prefix("mod "))
+            .map(|line| line.trim().trim_end_matches(';').trim().trim_matches('"').to_owned())
+            .filter(|name| !name.is_empty())
+            .collect()
+    }
+
+    /// This function returns the `Mod` Packs of this load order in the format of a `used_mods.txt` file.
+    ///
+    /// Packs outside the provided data folder get their folder added as a working directory, so the game can find them.
+    pub fn used_mods(&self, data_path: Option<&Path>) -> String {
+        let mods = self.entries.iter()
+            .filter(|entry| entry.pfh_file_type == PFHFileType::Mod)
+            .collect::<Vec<_>>();
+
+        let mut working_directories = vec![];
+        for entry in &mods {
+            if let Some(parent) = entry.path.parent() {
+                let is_in_data = data_path.map(|data_path| parent == data_path).unwrap_or(false);
+                if !is_in_data && !parent.as_os_str().is_empty() && !working_directories.contains(&parent) {
+                    working_directories.push(parent);
+                }
+            }
+        }
+
+        let mut used_mods = String::new();
+        for working_directory in working_directories {
+            used_mods.push_str(&format!("add_working_directory \"{}\";\n", working_directory.to_string_lossy().replace('\\', "/")));
+        }
+
+        for entry in mods {
+            used_mods.push_str(&format!("mod \"{}\";\n", entry.name));
+        }
+
+        used_mods
+    }
+
+    /// This function returns if the load order has no problems.
+    pub fn is_valid(&self) -> bool {
+        self.mods_not_found.is_empty() &&
+            self.missing_dependencies.is_empty() &&
+            self.loaded_before_parents.is_empty() &&
+            self.cycles.is_empty()
+    }
+
+    /// This function checks for dependencies not found, or loaded after the Packs that depend on them.
+    fn check_dependencies(&mut self) {
+        let positions = self.entries.iter()
+            .enumerate()
+            .map(|(index, entry)| (entry.name.as_str(), index))
+            .collect::<HashMap<_, _>>();
+
+        for (index, entry) in self.entries.iter().enumerate() {
+            for dependency in &entry.dependencies {
+                match positions.get(dependency.as_str()) {
+                    Some(position) => if *position > index {
+                        self.loaded_before_parents.entry(entry.name.to_owned()).or_default().push(dependency.to_owned());
+                    },
+                    None => self.missing_dependencies.entry(entry.name.to_owned()).or_default().push(dependency.to_owned()),
+                }
+            }
+        }
+    }
+
+    /// This function checks for cycles in the dependencies of the Packs, using Tarjan's strongly connected components algorithm.
+    fn check_cycles(&mut self) {
+        let positions = self.entries.iter()
+            .enumerate()
+            .map(|(index, entry)| (entry.name.as_str(), index))
+            .collect::<HashMap<_, _>>();
+
+        let edges = self.entries.iter()
+            .map(|entry| entry.dependencies.iter()
+                .filter_map(|dependency| positions.get(dependency.as_str()).copied())
+                .collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let mut state = TarjanState {
+            indexes: vec![None; edges.len()],
+            low_links: vec![0; edges.len()],
+            on_stack: vec![false; edges.len()],
+            ..Default::default()
+        };
+
+        for node in 0..edges.len() {
+            if state.indexes[node].is_none() {
+                state.visit(node, &edges);
+            }
+        }
+
+        for component in state.components {
+            let is_cycle = component.len() > 1 || edges[component[0]].contains(&component[0]);
+            if is_cycle {
+                let mut cycle = component.iter().map(|index| self.entries[*index].name.to_owned()).collect::<Vec<_>>();
+                cycle.sort();
+                self.cycles.push(cycle);
+            }
+        }
+
+        self.cycles.sort();
+    }
+}
+
+/// Internal state of the search for strongly connected components.
+#[derive(Default)]
+struct TarjanState {
+    index: usize,
+    indexes: Vec<Option<usize>>,
+    low_links: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    components: Vec<Vec<usize>>,
+}
+
+impl TarjanState {
+    fn visit(&mut self, node: usize, edges: &[Vec<usize>]) {
+        self.indexes[node] = Some(self.index);
+        self.low_links[node] = self.index;
+        self.index += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+
+        for next in &edges[node] {
+            match self.indexes[*next] {
+                None => {
+                    self.visit(*next, edges);
+                    self.low_links[node] = self.low_links[node].min(self.low_links[*next]);
+                }
+                Some(index) => if self.on_stack[*next] {
+                    self.low_links[node] = self.low_links[node].min(index);
+                }
+            }
+        }
+
+        if Some(self.low_links[node]) == self.indexes[node] {
+            let mut component = vec![];
+            while let Some(member) = self.stack.pop() {
+                self.on_stack[member] = false;
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+
+            self.components.push(component);
+        }
+    }
+}
+
+impl Display for LoadOrder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Load order:")?;
+        for (index, entry) in self.entries.iter().enumerate() {
+            writeln!(f, "{:>4}. {} ({})", index + 1, entry.name, entry.pfh_file_type)?;
+        }
+
+        if !self.mods_not_found.is_empty() {
+            writeln!(f, "Mods not found: {}.", self.mods_not_found.len())?;
+            for name in &self.mods_not_found {
+                writeln!(f, "- {name}")?;
+            }
+        }
+
+        if !self.mods_not_in_order.is_empty() {
+            writeln!(f, "Mods not in the mod order, so not loaded: {}.", self.mods_not_in_order.len())?;
+            for name in &self.mods_not_in_order {
+                writeln!(f, "- {name}")?;
+            }
+        }
+
+        if !self.missing_dependencies.is_empty() {
+            writeln!(f, "Packs with missing dependencies: {}.", self.missing_dependencies.len())?;
+            for (name, dependencies) in &self.missing_dependencies {
+                writeln!(f, "- {}: {}", name, dependencies.join(", "))?;
+            }
+        }
+
+        if !self.loaded_before_parents.is_empty() {
+            writeln!(f, "Packs loaded before their dependencies: {}.", self.loaded_before_parents.len())?;
+            for (name, dependencies) in &self.loaded_before_parents {
+                writeln!(f, "- {}: {}", name, dependencies.join(", "))?;
+            }
+        }
+
+        if !self.cycles.is_empty() {
+            writeln!(f, "Dependency cycles: {}.", self.cycles.len())?;
+            for cycle in &self.cycles {
+                writeln!(f, "- {}", cycle.join(", "))?;
+            }
+        }
+
+        Ok(())
+    }
+}
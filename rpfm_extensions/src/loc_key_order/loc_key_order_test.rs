@@ -0,0 +1,132 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! Module containing tests for the discovery of the localisation data of tables.
+
+use std::collections::BTreeMap;
+
+use rpfm_lib::files::table::DecodedData;
+use rpfm_lib::schema::{Definition, Field, FieldType};
+
+use super::*;
+
+fn field(name: &str, is_key: bool) -> Field {
+    Field::new(name.to_owned(), FieldType::StringU8, is_key, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None)
+}
+
+/// This function returns a schema with a multikeyed units table without localised fields, and a table of it.
+fn units() -> (Schema, DB) {
+    let mut definition = Definition::new(1, None);
+    definition.set_fields(vec![field("unit", true), field("faction", true), field("category", false)]);
+
+    let mut factions = Definition::new(1, None);
+    factions.set_fields(vec![field("key", true)]);
+    factions.set_localised_fields(vec![field("onscreen_name", false)]);
+
+    let mut schema = Schema::default();
+    schema.add_definition("units_tables", &definition);
+    schema.add_definition("factions_tables", &factions);
+
+    let row = |unit: &str, faction: &str| vec![
+        DecodedData::StringU8(unit.to_owned()),
+        DecodedData::StringU8(faction.to_owned()),
+        DecodedData::StringU8("infantry".to_owned()),
+    ];
+
+    let mut table = DB::new(&definition, None, "units_tables", false);
+    table.set_data(None, &[row("spear", "emp"), row("sword", "brt"), row("bow", "")]).unwrap();
+    (schema, table)
+}
+
+fn loc_keys(keys: &[&str]) -> HashSet<String> {
+    keys.iter().map(|key| key.to_string()).collect()
+}
+
+#[test]
+fn test_check_table_key_order() {
+    let (schema, table) = units();
+    let known_localised_fields = HashSet::from(["onscreen_name", "category"]);
+
+    // Only known localised fields are inferred: not parts of them, not unknown prefixes, and not regular fields of the table.
+    let loc_keys = loc_keys(&["onscreen_name_empspear", "onscreen_name_brtsword", "category_empspear", "unknown_field_empspear"]);
+    match LocKeyOrderReport::check_table(&schema, &[&table], &loc_keys, &known_localised_fields) {
+        TableResult::Found(order, localised_fields) => {
+            assert_eq!(order, vec!["faction".to_owned(), "unit".to_owned()]);
+            assert_eq!(localised_fields, vec!["onscreen_name".to_owned()]);
+        }
+        _ => panic!("Key order not found."),
+    }
+
+    // Without known localised fields, nothing can be inferred.
+    assert!(matches!(LocKeyOrderReport::check_table(&schema, &[&table], &loc_keys, &HashSet::new()), TableResult::Skipped));
+}
+
+#[test]
+fn test_check_table_ambiguous_and_unresolved() {
+    let (mut schema, table) = units();
+    let known_localised_fields = HashSet::from(["onscreen_name"]);
+
+    // Keys that match in both orders are ambiguous.
+    let mut symmetric = table.clone();
+    symmetric.set_data(None, &[vec![
+        DecodedData::StringU8("emp".to_owned()),
+        DecodedData::StringU8("emp".to_owned()),
+        DecodedData::StringU8("infantry".to_owned()),
+    ]]).unwrap();
+
+    match LocKeyOrderReport::check_table(&schema, &[&symmetric], &loc_keys(&["onscreen_name_empemp"]), &known_localised_fields) {
+        TableResult::Ambiguous(orders) => assert_eq!(orders.len(), 2),
+        _ => panic!("Ambiguous key order not detected."),
+    }
+
+    // Tables with localised fields in the schema but no matching loc keys are unresolved.
+    let mut definition = schema.definitions_by_table_name("units_tables").unwrap()[0].clone();
+    definition.set_localised_fields(vec![field("onscreen_name", false)]);
+    schema.add_definition("units_tables", &definition);
+    assert!(matches!(LocKeyOrderReport::check_table(&schema, &[&table], &loc_keys(&["onscreen_name_nothing"]), &known_localised_fields), TableResult::Unresolved));
+}
+
+#[test]
+fn test_patches_and_localisation_patches() {
+    let (schema, _) = units();
+    let report = LocKeyOrderReport {
+        tables_checked: 1,
+        key_orders: BTreeMap::from([("units_tables".to_owned(), vec!["faction".to_owned(), "unit".to_owned()])]),
+        localised_fields: BTreeMap::from([("units_tables".to_owned(), vec!["onscreen_name".to_owned(), "category".to_owned()])]),
+        ..Default::default()
+    };
+
+    let patches = report.patches(&schema);
+    let table_patch = &patches["units_tables"]["-1"];
+    assert_eq!(table_patch["localised_key_order"], "faction,unit");
+    assert_eq!(table_patch["localised_fields"], "onscreen_name,category");
+
+    // Regular fields cannot be localised, and new localised fields are proper text fields.
+    let mut definition = schema.definitions_by_table_name("units_tables").unwrap()[0].clone();
+    definition.set_patches(patches["units_tables"].clone());
+    definition.apply_localisation_patches();
+    assert_eq!(definition.localised_fields(), &vec![Field::new("onscreen_name".to_owned(), FieldType::StringU8, false, None, false, None, None, None, String::new(), -1, 0, BTreeMap::new(), None)]);
+    assert_eq!(definition.localised_key_order(), &vec![1, 0]);
+
+    // Once the schema has the data, there's nothing to patch.
+    let mut patched_schema = schema.clone();
+    patched_schema.add_definition("units_tables", &definition);
+    let report = LocKeyOrderReport {
+        key_orders: report.key_orders.clone(),
+        ..Default::default()
+    };
+
+    assert!(report.patches(&patched_schema).is_empty());
+
+    // Applying the key orders only changes definitions with localised fields.
+    let mut schema_without_loc = schema.clone();
+    report.apply_key_orders(&mut schema_without_loc);
+    assert!(schema_without_loc.definitions_by_table_name("units_tables").unwrap()[0].localised_key_order().is_empty());
+}
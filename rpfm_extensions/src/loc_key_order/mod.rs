@@ -0,0 +1,333 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! This module contains the discovery of the localisation data of tables, checking the vanilla tables of a dependencies cache against its vanilla locs.
+//!
+//! It finds out the order in which the keys of multikeyed tables are joined to form their loc keys, and which fields are localised
+//! in tables the Assembly Kit doesn't describe. The results are provided as table-wide schema patches, so it can be run after every game update.
+
+use getset::Getters;
+use itertools::Itertools;
+use rayon::prelude::*;
+use serde_derive::Serialize;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::{self, Display};
+
+use rpfm_lib::error::Result;
+use rpfm_lib::files::{db::DB, RFileDecoded};
+use rpfm_lib::schema::{DefinitionPatch, Schema};
+
+use crate::dependencies::Dependencies;
+
+#[cfg(test)] mod loc_key_order_test;
+
+/// Max amount of key fields a table can have to be checked. Tables with more keys have too many key orders to try.
+const MAX_KEY_FIELDS: usize = 6;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct contains the localisation data found for the vanilla tables of a dependencies cache.
+#[derive(Clone, Debug, Default, Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct LocKeyOrderReport {
+
+    /// Amount of vanilla tables checked.
+    tables_checked: usize,
+
+    /// Key orders found, by table name, as the names of the key fields in the order they're joined.
+    key_orders: BTreeMap<String, Vec<String>>,
+
+    /// Localised fields found for tables without them in the schema, by table name.
+    localised_fields: BTreeMap<String, Vec<String>>,
+
+    /// Tables with more than one key order matching their loc keys, with the orders that match.
+    ambiguous: BTreeMap<String, Vec<Vec<String>>>,
+
+    /// Tables with localised fields in the schema, but no key order matching their loc keys.
+    unresolved: BTreeSet<String>,
+}
+
+/// Result of checking a single table.
+enum TableResult {
+    Found(Vec<String>, Vec<String>),
+    Ambiguous(Vec<Vec<String>>),
+    Unresolved,
+    Skipped,
+}
+
+//---------------------------------------------------------------------------//
+//                       Enum & Structs Implementations
+//---------------------------------------------------------------------------//
+
+impl LocKeyOrderReport {
+
+    /// This function checks every vanilla table in the provided dependencies against the vanilla locs, and reports the localisation data found.
+    ///
+    /// The dependencies need to have their tables decoded with the provided schema.
+    pub fn new(schema: &Schema, dependencies: &Dependencies) -> Result<Self> {
+
+        // Group the tables by name, keeping only the ones with the newest version, as their rows are decoded with the same definition.
+        let mut tables: HashMap<&str, Vec<&DB>> = HashMap::new();
+        for file in dependencies.db_and_loc_data(true, false, true, false)? {
+            if let Ok(RFileDecoded::DB(table)) = file.decoded() {
+                let tables = tables.entry(table.table_name()).or_default();
+                match tables.first().map(|first| first.definition().version().cmp(table.definition().version())) {
+                    Some(std::cmp::Ordering::Less) => *tables = vec![table],
+                    Some(std::cmp::Ordering::Equal) | None => tables.push(table),
+                    Some(std::cmp::Ordering::Greater) => {},
+                }
+            }
+        }
+
+        // Group the loc keys by every table name they may start with. The rest of the key is `{field}_{key}`.
+        let short_table_names = tables.keys()
+            .map(|table_name| table_name.strip_suffix("_tables").unwrap_or(table_name))
+            .collect::<HashSet<_>>();
+
+        let mut loc_keys: HashMap<&str, HashSet<String>> = HashMap::new();
+        for file in dependencies.loc_data(true, false)? {
+            if let Ok(RFileDecoded::Loc(loc)) = file.decoded() {
+                for row in loc.data(&None)?.iter() {
+                    let key = row[0].data_to_string();
+                    for (index, _) in key.match_indices('_') {
+                        if let Some(short_table_name) = short_table_names.get(&key[..index]) {
+                            loc_keys.entry(*short_table_name).or_default().insert(key[index + 1..].to_owned());
+                        }
+                    }
+                }
+            }
+        }
+
+        // Localised fields can only be inferred if other tables already use them, as any part of a loc key could be a field name otherwise.
+        let known_localised_fields = schema.definitions()
+            .values()
+            .flatten()
+            .flat_map(|definition| definition.localised_fields().iter().map(|field| field.name()))
+            .collect::<HashSet<_>>();
+
+        let results = tables.par_iter()
+            .map(|(table_name, tables)| {
+                let short_table_name = table_name.strip_suffix("_tables").unwrap_or(table_name);
+                let result = match loc_keys.get(short_table_name) {
+                    Some(loc_keys) => Self::check_table(schema, tables, loc_keys, &known_localised_fields),
+                    None => if tables[0].definition().localised_fields().is_empty() { TableResult::Skipped } else { TableResult::Unresolved },
+                };
+
+                (table_name.to_string(), result)
+            })
+            .collect::<Vec<_>>();
+
+        let mut report = Self {
+            tables_checked: results.len(),
+            ..Default::default()
+        };
+
+        for (table_name, result) in results {
+            match result {
+                TableResult::Found(order, localised_fields) => {
+                    report.key_orders.insert(table_name.clone(), order);
+                    if !localised_fields.is_empty() {
+                        report.localised_fields.insert(table_name, localised_fields);
+                    }
+                },
+                TableResult::Ambiguous(orders) => { report.ambiguous.insert(table_name, orders); },
+                TableResult::Unresolved => { report.unresolved.insert(table_name); },
+                TableResult::Skipped => {},
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// This function tries every key order of a table, and returns the one that matches the most loc keys.
+    ///
+    /// If the table has no localised fields in the schema, the fields are inferred from the loc keys matched. Only the names in
+    /// `known_localised_fields` that are not regular fields of the table can be inferred.
+    fn check_table(schema: &Schema, tables: &[&DB], loc_keys: &HashSet<String>, known_localised_fields: &HashSet<&str>) -> TableResult {
+        let definition = tables[0].definition();
+        let fields = definition.fields_processed();
+
+        // Note that we only consider keys those that come from the asskit. Custom keys we setup through patches are not taken into account for locs.
+        let key_fields = fields.iter()
+            .enumerate()
+            .filter(|(_, field)| field.is_key(None))
+            .collect::<Vec<_>>();
+
+        if key_fields.is_empty() || key_fields.len() > MAX_KEY_FIELDS {
+            return if definition.localised_fields().is_empty() { TableResult::Skipped } else { TableResult::Unresolved };
+        }
+
+        // Use the localised fields from the schema, not the ones from the tables, so patched fields are taken into account.
+        let localised_fields = schema.definition_by_name_and_version(tables[0].table_name(), *definition.version())
+            .unwrap_or(definition)
+            .localised_fields()
+            .iter()
+            .map(|field| field.name())
+            .collect::<HashSet<_>>();
+
+        let candidate_fields = if localised_fields.is_empty() {
+            known_localised_fields.iter()
+                .filter(|name| !fields.iter().any(|field| field.name() == **name))
+                .copied()
+                .collect::<HashSet<_>>()
+        } else {
+            localised_fields.iter().copied().collect()
+        };
+
+        let rows = tables.iter()
+            .filter_map(|table| table.data(&None).ok())
+            .collect::<Vec<_>>();
+
+        // For each order, count how many loc keys each field matches.
+        let mut matches = key_fields.iter()
+            .permutations(key_fields.len())
+            .map(|order| {
+
+                // Many multikeyed tables admit empty values as part of the key. We need rows with no empty values.
+                let combined_keys = rows.iter()
+                    .flat_map(|rows| rows.iter())
+                    .filter_map(|row| {
+                        let values = order.iter().map(|(index, _)| row[*index].data_to_string()).collect::<Vec<_>>();
+                        if values.iter().any(|value| value.is_empty()) {
+                            None
+                        } else {
+                            Some(values.concat())
+                        }
+                    })
+                    .collect::<HashSet<_>>();
+
+                let mut field_matches: BTreeMap<&str, usize> = BTreeMap::new();
+                for loc_key in loc_keys {
+                    for (index, _) in loc_key.match_indices('_') {
+                        let field = &loc_key[..index];
+                        if candidate_fields.contains(field) && combined_keys.contains(&loc_key[index + 1..]) {
+                            *field_matches.entry(field).or_default() += 1;
+                        }
+                    }
+                }
+
+                let names = order.iter().map(|(_, field)| field.name().to_owned()).collect::<Vec<_>>();
+                (names, field_matches)
+            })
+            .filter(|(_, field_matches)| !field_matches.is_empty())
+            .collect::<Vec<_>>();
+
+        let max_matches = matches.iter()
+            .map(|(_, field_matches)| field_matches.values().sum::<usize>())
+            .max()
+            .unwrap_or(0);
+
+        matches.retain(|(_, field_matches)| field_matches.values().sum::<usize>() == max_matches);
+
+        match matches.len() {
+            0 => if localised_fields.is_empty() { TableResult::Skipped } else { TableResult::Unresolved },
+            1 => {
+                let (order, field_matches) = matches.remove(0);
+                let inferred_fields = if localised_fields.is_empty() {
+                    field_matches.keys().map(|field| field.to_string()).collect()
+                } else {
+                    vec![]
+                };
+
+                TableResult::Found(order, inferred_fields)
+            }
+            _ => TableResult::Ambiguous(matches.into_iter().map(|(order, _)| order).collect()),
+        }
+    }
+
+    /// This function returns the localisation data found as table-wide schema patches.
+    ///
+    /// Only the data that differs from the provided schema is included.
+    pub fn patches(&self, schema: &Schema) -> HashMap<String, DefinitionPatch> {
+        let mut patches: HashMap<String, DefinitionPatch> = HashMap::new();
+
+        for (table_name, order) in &self.key_orders {
+            let definition = schema.definitions_by_table_name(table_name).and_then(|definitions| definitions.first());
+            let order_in_schema = definition.map(|definition| {
+                let fields = definition.fields_processed();
+                definition.localised_key_order().iter()
+                    .filter_map(|index| fields.get(*index as usize))
+                    .map(|field| field.name().to_owned())
+                    .collect::<Vec<_>>()
+            }).unwrap_or_default();
+
+            if *order != order_in_schema {
+                patches.entry(table_name.to_owned())
+                    .or_default()
+                    .entry("-1".to_owned())
+                    .or_default()
+                    .insert("localised_key_order".to_owned(), order.join(","));
+            }
+        }
+
+        for (table_name, localised_fields) in &self.localised_fields {
+            patches.entry(table_name.to_owned())
+                .or_default()
+                .entry("-1".to_owned())
+                .or_default()
+                .insert("localised_fields".to_owned(), localised_fields.join(","));
+        }
+
+        patches
+    }
+
+    /// This function applies the key orders found to the definitions of the provided schema.
+    ///
+    /// Definitions without localised fields are not changed, as the key order is useless without them.
+    pub fn apply_key_orders(&self, schema: &mut Schema) {
+        for (table_name, order) in &self.key_orders {
+            if let Some(definitions) = schema.definitions_by_table_name_mut(table_name) {
+                for definition in definitions {
+                    let fields = definition.fields_processed();
+                    let order = order.iter()
+                        .map(|name| fields.iter().position(|field| field.name() == name).map(|index| index as u32))
+                        .collect::<Option<Vec<_>>>();
+
+                    if let Some(order) = order {
+                        if !definition.localised_fields().is_empty() {
+                            definition.set_localised_key_order(order);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Display for LocKeyOrderReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Tables checked: {}.", self.tables_checked)?;
+
+        writeln!(f, "Tables with key order found: {}.", self.key_orders.len())?;
+        for (table_name, order) in &self.key_orders {
+            writeln!(f, "- {}: {}", table_name, order.join(", "))?;
+        }
+
+        writeln!(f, "Tables with localised fields inferred: {}.", self.localised_fields.len())?;
+        for (table_name, localised_fields) in &self.localised_fields {
+            writeln!(f, "- {}: {}", table_name, localised_fields.join(", "))?;
+        }
+
+        writeln!(f, "Ambiguous tables: {}.", self.ambiguous.len())?;
+        for (table_name, orders) in &self.ambiguous {
+            writeln!(f, "- {}: {}", table_name, orders.iter().map(|order| format!("[{}]", order.join(", "))).join(" | "))?;
+        }
+
+        writeln!(f, "Unresolved tables: {}.", self.unresolved.len())?;
+        for table_name in &self.unresolved {
+            writeln!(f, "- {table_name}")?;
+        }
+
+        Ok(())
+    }
+}
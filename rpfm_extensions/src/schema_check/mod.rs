@@ -0,0 +1,213 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! This module contains a check for schemas, testing their definitions against the vanilla tables of a dependencies cache.
+//!
+//! It's meant to be run after every game update, to know which tables need new definitions and which definitions are no longer needed.
+
+use getset::Getters;
+use rayon::prelude::*;
+use serde_derive::Serialize;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display};
+use std::io::Cursor;
+
+use rpfm_lib::error::RLibError;
+use rpfm_lib::files::{db::DB, Decodeable, DecodeableExtraData};
+use rpfm_lib::schema::Schema;
+
+use crate::dependencies::Dependencies;
+
+#[cfg(test)] mod schema_check_test;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct contains the results of testing a schema against the vanilla tables of a dependencies cache.
+#[derive(Clone, Debug, Default, Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct SchemaCheckReport {
+
+    /// Amount of vanilla tables tested.
+    tables_tested: usize,
+
+    /// Vanilla tables that cannot be decoded with the schema, sorted by path.
+    errors: Vec<SchemaCheckError>,
+
+    /// Definitions not used by any vanilla table, by table name.
+    ///
+    /// Tables only in the Assembly Kit are not included, as they cannot be tested.
+    unused_definitions: BTreeMap<String, Vec<i32>>,
+}
+
+/// This struct represents a vanilla table that failed the test.
+#[derive(Clone, Debug, Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct SchemaCheckError {
+
+    /// Path of the table in the game files.
+    path: String,
+
+    /// Name of the table.
+    table_name: String,
+
+    /// Version of the table.
+    version: i32,
+
+    /// What went wrong.
+    error_type: SchemaCheckErrorType,
+}
+
+/// This enum represents the ways a vanilla table can fail the test.
+#[derive(Clone, Debug, Serialize)]
+pub enum SchemaCheckErrorType {
+
+    /// There is no definition for the version of the table.
+    NoDefinition,
+
+    /// The definition failed to decode the table. Contains the decoding error.
+    DecodingFailed(String),
+
+    /// The definition decoded all the rows of the table, but there was data left after them. Contains the size mismatch.
+    LeftoverBytes(String),
+}
+
+//---------------------------------------------------------------------------//
+//                       Enum & Structs Implementations
+//---------------------------------------------------------------------------//
+
+impl SchemaCheckReport {
+
+    /// This function decodes every vanilla table in the provided dependencies with its definition from the schema,
+    /// and reports every table that cannot be decoded and every definition that's not used.
+    ///
+    /// The dependencies don't need to have their tables decoded.
+    pub fn new(schema: &Schema, dependencies: &Dependencies) -> Self {
+        let tables = dependencies.vanilla_tables().iter()
+            .flat_map(|(table_name, paths)| paths.iter().map(move |path| (table_name, path)))
+            .collect::<Vec<_>>();
+
+        // Returns the version of the table and the result of decoding it, if the file was found.
+        let results = tables.par_iter()
+            .filter_map(|(table_name, path)| {
+                let mut file = dependencies.vanilla_files().get(*path)?.clone();
+                let data = match file.cached() {
+                    Ok(data) => data.to_vec(),
+                    Err(_) => file.encode(&None, false, false, true).ok()??,
+                };
+
+                let mut reader = Cursor::new(data);
+                let version = match DB::read_header(&mut reader) {
+                    Ok((version, _, _, _)) => version,
+                    Err(error) => return Some((table_name.to_string(), path.to_string(), 0, Some(SchemaCheckErrorType::DecodingFailed(error.to_string())))),
+                };
+
+                let has_definition = schema.definitions_by_table_name(table_name)
+                    .map(|definitions| definitions.iter().any(|definition| if version == 0 { *definition.version() < 1 } else { *definition.version() == version }))
+                    .unwrap_or(false);
+
+                if !has_definition {
+                    return Some((table_name.to_string(), path.to_string(), version, Some(SchemaCheckErrorType::NoDefinition)));
+                }
+
+                let mut extra_data = DecodeableExtraData::default();
+                extra_data.set_schema(Some(schema));
+                extra_data.set_table_name(Some(table_name));
+
+                reader.set_position(0);
+                let error_type = match DB::decode(&mut reader, &Some(extra_data)) {
+                    Ok(_) => None,
+                    Err(RLibError::DecodingTableIncomplete(error, _)) => Some(SchemaCheckErrorType::LeftoverBytes(error)),
+                    Err(error) => Some(SchemaCheckErrorType::DecodingFailed(error.to_string())),
+                };
+
+                Some((table_name.to_string(), path.to_string(), version, error_type))
+            })
+            .collect::<Vec<_>>();
+
+        let mut used_versions: BTreeMap<&str, BTreeSet<i32>> = BTreeMap::new();
+        for (table_name, _, version, _) in &results {
+            used_versions.entry(table_name).or_default().insert(*version);
+        }
+
+        let unused_definitions = schema.definitions().iter()
+            .filter(|(table_name, _)| !dependencies.asskit_only_db_tables().contains_key(&table_name[..]))
+            .filter_map(|(table_name, definitions)| {
+                let used_versions = used_versions.get(&table_name[..]);
+
+                // Version 0 tables can be decoded by any definition with a version lower than 1.
+                let mut unused = definitions.iter()
+                    .map(|definition| *definition.version())
+                    .filter(|version| !used_versions.map(|used_versions| used_versions.contains(if *version < 1 { &0 } else { version })).unwrap_or(false))
+                    .collect::<Vec<_>>();
+
+                if unused.is_empty() {
+                    None
+                } else {
+                    unused.sort_by(|a, b| b.cmp(a));
+                    Some((table_name.to_owned(), unused))
+                }
+            })
+            .collect();
+
+        let tables_tested = results.len();
+        let mut errors = results.into_iter()
+            .filter_map(|(table_name, path, version, error_type)| error_type.map(|error_type| SchemaCheckError {
+                path,
+                table_name,
+                version,
+                error_type,
+            }))
+            .collect::<Vec<_>>();
+
+        errors.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Self {
+            tables_tested,
+            errors,
+            unused_definitions,
+        }
+    }
+
+    /// This function returns if every vanilla table was decoded correctly and every definition is used.
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty() && self.unused_definitions.is_empty()
+    }
+}
+
+impl Display for SchemaCheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Tables tested: {}.", self.tables_tested)?;
+        writeln!(f, "Tables with errors: {}.", self.errors.len())?;
+
+        for error in &self.errors {
+            writeln!(f, "- {}, version {}: {}", error.path, error.version, error.error_type)?;
+        }
+
+        writeln!(f, "Tables with unused definitions: {}.", self.unused_definitions.len())?;
+        for (table_name, versions) in &self.unused_definitions {
+            writeln!(f, "- {}: {}", table_name, versions.iter().map(|version| version.to_string()).collect::<Vec<_>>().join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for SchemaCheckErrorType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoDefinition => write!(f, "No definition found."),
+            Self::DecodingFailed(error) => write!(f, "Decoding failed: {error}"),
+            Self::LeftoverBytes(error) => write!(f, "Data left after decoding all rows: {error}"),
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! Module containing tests for checking schemas against vanilla tables.
+
+use std::collections::BTreeMap;
+use std::env::temp_dir;
+use std::fs::{create_dir_all, remove_dir_all};
+
+use rpfm_lib::files::{Container, pack::Pack, RFile, RFileDecoded, table::DecodedData};
+use rpfm_lib::games::{pfh_file_type::PFHFileType, pfh_version::PFHVersion, supported_games::{KEY_WARHAMMER_3, SupportedGames}};
+use rpfm_lib::schema::{Definition, Field, FieldType};
+
+use super::*;
+
+fn definition(version: i32, fields: &[(&str, FieldType)]) -> Definition {
+    let mut definition = Definition::new(version, None);
+    definition.set_fields(fields.iter()
+        .map(|(name, field_type)| Field::new(name.to_string(), field_type.clone(), *name == "key", None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None))
+        .collect());
+    definition
+}
+
+fn db(definition: &Definition, row: Vec<DecodedData>) -> RFileDecoded {
+    let mut table = DB::new(definition, None, "units_tables", false);
+    table.set_data(None, &[row]).unwrap();
+    RFileDecoded::DB(table)
+}
+
+#[test]
+fn test_schema_check() {
+    let folder = temp_dir().join("rpfm_test_schema_check");
+    let _ = remove_dir_all(&folder);
+    create_dir_all(&folder).unwrap();
+
+    // Vanilla tables with a fitting definition, with a definition with less fields than the data, and without definition.
+    let key = DecodedData::StringU8("key".to_owned());
+    let mut pack = Pack::new_with_name_and_version("data.pack", PFHVersion::PFH5);
+    pack.set_pfh_file_type(PFHFileType::Release);
+    for (path, decoded) in [
+        ("db/units_tables/a", db(&definition(1, &[("key", FieldType::StringU8)]), vec![key.clone()])),
+        ("db/units_tables/b", db(&definition(2, &[("key", FieldType::StringU8), ("cost", FieldType::I32)]), vec![key.clone(), DecodedData::I32(5)])),
+        ("db/units_tables/c", db(&definition(3, &[("key", FieldType::StringU8)]), vec![key])),
+    ] {
+        pack.insert(RFile::new_from_decoded(&decoded, 0, path)).unwrap();
+    }
+
+    let games = SupportedGames::default();
+    let game_info = games.game(KEY_WARHAMMER_3).unwrap();
+    let pack_path = folder.join("data.pack");
+    pack.save(Some(&pack_path), game_info, &None).unwrap();
+    let dependencies = Dependencies::generate_dependencies_cache_from_packs(game_info, &[pack_path], &None, 0, &None).unwrap();
+
+    let mut schema = Schema::default();
+    schema.add_definition("units_tables", &definition(1, &[("key", FieldType::StringU8)]));
+    schema.add_definition("units_tables", &definition(2, &[("key", FieldType::StringU8)]));
+    schema.add_definition("units_tables", &definition(4, &[("key", FieldType::StringU8)]));
+    schema.add_definition("abilities_tables", &definition(1, &[("key", FieldType::StringU8)]));
+
+    let report = SchemaCheckReport::new(&schema, &dependencies);
+    assert!(!report.is_ok());
+    assert_eq!(*report.tables_tested(), 3);
+    assert_eq!(report.errors().iter().map(|error| (error.path().as_str(), *error.version())).collect::<Vec<_>>(), vec![
+        ("db/units_tables/b", 2),
+        ("db/units_tables/c", 3),
+    ]);
+    assert!(matches!(report.errors()[0].error_type(), SchemaCheckErrorType::LeftoverBytes(_)));
+    assert!(matches!(report.errors()[1].error_type(), SchemaCheckErrorType::NoDefinition));
+
+    // Definitions of tables not in the game files, and versions no vanilla table uses, are unused.
+    assert_eq!(report.unused_definitions(), &BTreeMap::from([
+        ("abilities_tables".to_owned(), vec![1]),
+        ("units_tables".to_owned(), vec![4]),
+    ]));
+
+    let text = report.to_string();
+    assert!(text.contains("Tables with errors: 2.\n"));
+    assert!(text.contains("- db/units_tables/c, version 3: No definition found.\n"));
+    assert!(text.contains("- units_tables: 4\n"));
+
+    // With the right definitions and no unused ones, the schema passes.
+    let mut schema = Schema::default();
+    schema.add_definition("units_tables", &definition(1, &[("key", FieldType::StringU8)]));
+    schema.add_definition("units_tables", &definition(2, &[("key", FieldType::StringU8), ("cost", FieldType::I32)]));
+    schema.add_definition("units_tables", &definition(3, &[("key", FieldType::StringU8)]));
+    assert!(SchemaCheckReport::new(&schema, &dependencies).is_ok());
+
+    remove_dir_all(&folder).unwrap();
+}
@@ -16,6 +16,7 @@ This module contains the code needed to get a `GlobalSearch` over an entire `Pac
 
 use regex::{RegexBuilder, Regex};
 use rayon::prelude::*;
+use serde_derive::{Serialize, Deserialize};
 
 use rpfm_lib::files::{Container, ContainerPath};
 use rpfm_lib::files::{FileType, pack::Pack, RFileDecoded};
@@ -120,12 +121,15 @@ pub enum MatchHolder {
 }
 
 /// This enum is specifies the source where the search should be performed.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SearchSource {
     Pack,
     ParentFiles,
     GameFiles,
     AssKitFiles,
+
+    /// Files of the installed mods loaded as dependencies. See [Dependencies::set_installed_mods](crate::dependencies::Dependencies::set_installed_mods).
+    InstalledMods,
 }
 
 //---------------------------------------------------------------p----------------//
@@ -276,11 +280,13 @@ impl GlobalSearch {
                     ).collect();
                 }
             }
-            SearchSource::ParentFiles => {
+            // Installed mods are loaded as parent files, so both share the same files, filtered by where each file comes from.
+            SearchSource::ParentFiles | SearchSource::InstalledMods => {
 
                 if self.search_on_dbs {
                     if let Ok(files) = dependencies.db_and_loc_data(true, false, false, true) {
                         self.matches_db = files.par_iter()
+                            .filter(|file| dependencies.parent_file_source(file.path_in_container_raw()) == self.source)
                             .filter_map(|file| {
                                 if let Ok(RFileDecoded::DB(table)) = file.decoded() {
                                     let result = table.search(file.path_in_container_raw(), &self.pattern, self.case_sensitive, &matching_mode);
@@ -300,6 +306,7 @@ impl GlobalSearch {
                 if self.search_on_locs {
                     if let Ok(files) = dependencies.db_and_loc_data(false, true, false, true) {
                         self.matches_loc = files.par_iter()
+                            .filter(|file| dependencies.parent_file_source(file.path_in_container_raw()) == self.source)
                             .filter_map(|file| {
                                 if let Ok(RFileDecoded::Loc(table)) = file.decoded() {
                                     let result = table.search(file.path_in_container_raw(), &self.pattern, self.case_sensitive, &matching_mode);
@@ -317,7 +324,10 @@ impl GlobalSearch {
                 }
 
                 if self.search_on_texts {
+                    let installed_mods_paths = dependencies.installed_mods_paths().clone();
                     let mut files = dependencies.files_by_types_mut(&[FileType::Text], false, true);
+                    files.retain(|path, _| installed_mods_paths.contains(path) == (self.source == SearchSource::InstalledMods));
+
                     self.matches_text = files.par_iter_mut()
                         .filter_map(|(path, file)| {
                             if let Ok(RFileDecoded::Text(text)) = file.decode(&None, false, true).transpose().unwrap() {
@@ -0,0 +1,737 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! This module contains the logic to compare the vanilla data of two versions of a game.
+//!
+//! The comparison is done over two [Dependencies] with their vanilla tables decoded, and reports:
+//! - Files added or removed.
+//! - DB rows added, removed or changed, per table. Rows are matched by their key fields and, if the table has none, by all their fields.
+//!   If the definition of a table changed between versions, fields are matched by name, so only the fields in both definitions are compared.
+//!   Fields whose type changed are reported once, and their values are compared by what they represent, so `1` and `1.0000` are the same.
+//! - Loc keys added, removed or changed.
+//!
+//! Like the game, if more than one row has the same key, only the first one loaded is used. Keys whose amount of rows changed between
+//! versions are reported, as that usually means rows got merged or split, like when a key field is added to a table.
+//! Keys of more than one field are shown as JSON arrays.
+//!
+//! Changed values of reference columns include their lookups in each version, so the Markdown changelog shows them in a human-readable format,
+//! like `wh_main_emp_inf_swordsmen (Swordsmen)`.
+//!
+//! The result can be rendered as JSON with [VanillaDiff::to_json], as a Markdown changelog with [VanillaDiff::to_markdown],
+//! or as TSV with [VanillaDiff::to_tsv].
+
+use getset::Getters;
+use itertools::Itertools;
+use rayon::prelude::*;
+use serde_derive::{Serialize, Deserialize};
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use rpfm_lib::error::Result;
+use rpfm_lib::files::{db::DB, RFileDecoded};
+use rpfm_lib::games::GameInfo;
+use rpfm_lib::schema::{FieldType, Schema};
+use rpfm_lib::utils::{game_version_to_string, parse_str_as_bool};
+
+use crate::dependencies::{Dependencies, display_value};
+
+#[cfg(test)] mod vanilla_diff_test;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct contains the differences between the vanilla data of two versions of a game.
+#[derive(Clone, Debug, Default, PartialEq, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct VanillaDiff {
+
+    /// Game version of the old data. 0 if unknown.
+    old_game_version: u32,
+
+    /// Game version of the new data. 0 if unknown.
+    new_game_version: u32,
+
+    /// Files only in the new data.
+    added_files: Vec<String>,
+
+    /// Files only in the old data.
+    removed_files: Vec<String>,
+
+    /// Tables with differences, by table name.
+    tables: BTreeMap<String, TableDataDiff>,
+
+    /// Differences in the Loc data.
+    locs: LocDataDiff,
+
+    /// Tables that couldn't be compared because they couldn't be decoded in one of the versions.
+    undecoded_tables: Vec<String>,
+}
+
+/// This struct contains the differences between the data of a table in two versions of a game.
+#[derive(Clone, Debug, Default, PartialEq, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct TableDataDiff {
+
+    /// Version of the definition of the table in the old data. -1 if the table is not in the old data.
+    old_version: i32,
+
+    /// Version of the definition of the table in the new data. -1 if the table is not in the new data.
+    new_version: i32,
+
+    /// Fields of the old definition, in the order of the values of the removed rows.
+    old_fields: Vec<String>,
+
+    /// Fields of the new definition, in the order of the values of the added rows.
+    new_fields: Vec<String>,
+
+    /// Fields in both definitions whose type changed, with their old and new type.
+    changed_field_types: BTreeMap<String, (String, String)>,
+
+    /// Rows only in the new data, by key.
+    added_rows: BTreeMap<String, Vec<String>>,
+
+    /// Rows only in the old data, by key.
+    removed_rows: BTreeMap<String, Vec<String>>,
+
+    /// Rows in both versions with different values, by key.
+    changed_rows: BTreeMap<String, Vec<CellChange>>,
+
+    /// Keys with more than one row in any of the versions, and a different amount of rows in each one, with the amount of rows
+    /// with the key in the old and new data. Only the first row with each key is compared.
+    duplicated_keys: BTreeMap<String, (usize, usize)>,
+}
+
+/// This struct represents a change in the value of a field of a row.
+#[derive(Clone, Debug, Default, PartialEq, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct CellChange {
+    field: String,
+    old_value: String,
+    new_value: String,
+
+    /// Lookup of the old value, if the field is a reference column with lookup data. Empty otherwise.
+    old_lookup: String,
+
+    /// Lookup of the new value, if the field is a reference column with lookup data. Empty otherwise.
+    new_lookup: String,
+}
+
+/// This struct contains the differences between the Loc data of two versions of a game.
+#[derive(Clone, Debug, Default, PartialEq, Getters, Serialize, Deserialize)]
+#[getset(get = "pub")]
+pub struct LocDataDiff {
+
+    /// Keys only in the new data, with their text.
+    added: BTreeMap<String, String>,
+
+    /// Keys only in the old data, with their text.
+    removed: BTreeMap<String, String>,
+
+    /// Keys in both versions with different text, with their old and new text.
+    changed: BTreeMap<String, (String, String)>,
+}
+
+/// Rows of a table by key, with the names of their fields.
+struct TableRows {
+    version: i32,
+    fields: Vec<String>,
+    field_types: Vec<FieldType>,
+    key_fields: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+//---------------------------------------------------------------------------//
+//                       Enum & Structs Implementations
+//---------------------------------------------------------------------------//
+
+impl VanillaDiff {
+
+    /// This function compares the vanilla data of two dependencies, returning what changed from the old one to the new one.
+    ///
+    /// The vanilla tables of both dependencies must be decoded. Use [Dependencies::decode_tables] if they're not.
+    pub fn new(old_dependencies: &Dependencies, new_dependencies: &Dependencies) -> Result<Self> {
+        let old_files = old_dependencies.vanilla_files().keys().collect::<BTreeSet<_>>();
+        let new_files = new_dependencies.vanilla_files().keys().collect::<BTreeSet<_>>();
+
+        let mut diff = Self {
+            old_game_version: *old_dependencies.game_version(),
+            new_game_version: *new_dependencies.game_version(),
+            added_files: new_files.difference(&old_files).map(|path| path.to_string()).collect(),
+            removed_files: old_files.difference(&new_files).map(|path| path.to_string()).collect(),
+            ..Default::default()
+        };
+
+        let table_names = old_dependencies.vanilla_tables().keys()
+            .chain(new_dependencies.vanilla_tables().keys())
+            .collect::<BTreeSet<_>>();
+
+        let tables = table_names.par_iter()
+            .map(|table_name| {
+                let old_rows = TableRows::from_dependencies(old_dependencies, table_name)?;
+                let new_rows = TableRows::from_dependencies(new_dependencies, table_name)?;
+                let mut table_diff = TableDataDiff::new(old_rows, new_rows);
+                table_diff.add_lookups(table_name, old_dependencies, new_dependencies);
+                Some((table_name.to_string(), table_diff))
+            })
+            .collect::<Vec<_>>();
+
+        for (table_name, table_diff) in table_names.iter().zip(tables) {
+            match table_diff {
+                Some((table_name, table_diff)) => if !table_diff.is_empty() {
+                    diff.tables.insert(table_name, table_diff);
+                },
+                None => diff.undecoded_tables.push(table_name.to_string()),
+            }
+        }
+
+        diff.locs = LocDataDiff::new(&loc_rows(old_dependencies)?, &loc_rows(new_dependencies)?);
+
+        Ok(diff)
+    }
+
+    /// This function compares the vanilla data of the dependencies caches at the provided paths.
+    pub fn from_caches(old_cache_path: &Path, new_cache_path: &Path, schema: &Schema) -> Result<Self> {
+        let schema = Some(schema.clone());
+        let old_dependencies = Dependencies::load(old_cache_path, &schema)?;
+        let new_dependencies = Dependencies::load(new_cache_path, &schema)?;
+
+        Self::new(&old_dependencies, &new_dependencies)
+    }
+
+    /// This function compares the vanilla data of the game installations at the provided paths.
+    pub fn from_game_paths(game_info: &GameInfo, old_game_path: &Path, new_game_path: &Path, schema: &Schema) -> Result<Self> {
+        let schema = Some(schema.clone());
+        let mut old_dependencies = Dependencies::generate_dependencies_cache(game_info, old_game_path, &None::<PathBuf>)?;
+        let mut new_dependencies = Dependencies::generate_dependencies_cache(game_info, new_game_path, &None::<PathBuf>)?;
+        old_dependencies.decode_tables(&schema);
+        new_dependencies.decode_tables(&schema);
+
+        Self::new(&old_dependencies, &new_dependencies)
+    }
+
+    /// This function returns if both versions had the same data.
+    pub fn is_empty(&self) -> bool {
+        self.added_files.is_empty() && self.removed_files.is_empty() && self.tables.is_empty() && self.locs.is_empty() && self.undecoded_tables.is_empty()
+    }
+
+    /// This function renders the diff as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(From::from)
+    }
+
+    /// This function renders the diff as a human-readable changelog in Markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("# Vanilla Data Changelog: {} to {}\n", version_to_string(self.old_game_version), version_to_string(self.new_game_version));
+        if self.is_empty() {
+            markdown.push_str("\nNo changes.\n");
+            return markdown;
+        }
+
+        if !self.added_files.is_empty() {
+            markdown.push_str("\n## Added Files\n\n");
+            self.added_files.iter().for_each(|path| { let _ = writeln!(markdown, "- {}", markdown_code(path)); });
+        }
+
+        if !self.removed_files.is_empty() {
+            markdown.push_str("\n## Removed Files\n\n");
+            self.removed_files.iter().for_each(|path| { let _ = writeln!(markdown, "- {}", markdown_code(path)); });
+        }
+
+        if !self.tables.is_empty() {
+            markdown.push_str("\n## Changed Tables\n");
+            for (table_name, table_diff) in &self.tables {
+                let _ = writeln!(markdown, "\n### {}\n", markdown_code(table_name));
+
+                if table_diff.old_version != table_diff.new_version || !table_diff.changed_field_types.is_empty() {
+                    let added_fields = table_diff.new_fields.iter().filter(|field| !table_diff.old_fields.contains(field)).map(|field| markdown_code(field)).join(", ");
+                    let removed_fields = table_diff.old_fields.iter().filter(|field| !table_diff.new_fields.contains(field)).map(|field| markdown_code(field)).join(", ");
+                    let changed_field_types = table_diff.changed_field_types.iter().map(|(field, (old_type, new_type))| format!("{} from {old_type} to {new_type}", markdown_code(field))).join(", ");
+                    if table_diff.old_version != table_diff.new_version {
+                        let _ = writeln!(markdown, "Version changed from {} to {}.", table_diff.old_version, table_diff.new_version);
+                    }
+                    if !added_fields.is_empty() {
+                        let _ = writeln!(markdown, "- Added fields: {added_fields}.");
+                    }
+                    if !removed_fields.is_empty() {
+                        let _ = writeln!(markdown, "- Removed fields: {removed_fields}.");
+                    }
+                    if !changed_field_types.is_empty() {
+                        let _ = writeln!(markdown, "- Changed field types: {changed_field_types}.");
+                    }
+                    markdown.push('\n');
+                }
+
+                for (key, row) in &table_diff.added_rows {
+                    let _ = writeln!(markdown, "- Added {}: {}", markdown_code(key), row_to_string(&table_diff.new_fields, row));
+                }
+
+                for (key, row) in &table_diff.removed_rows {
+                    let _ = writeln!(markdown, "- Removed {}: {}", markdown_code(key), row_to_string(&table_diff.old_fields, row));
+                }
+
+                for (key, changes) in &table_diff.changed_rows {
+                    let _ = writeln!(markdown, "- Changed {}:", markdown_code(key));
+                    for change in changes {
+                        let old_value = display_value(&change.old_value, Some(&change.old_lookup));
+                        let new_value = display_value(&change.new_value, Some(&change.new_lookup));
+                        let _ = writeln!(markdown, "    - {}: {} to {}", markdown_code(&change.field), markdown_code(&old_value), markdown_code(&new_value));
+                    }
+                }
+
+                for (key, (old_count, new_count)) in &table_diff.duplicated_keys {
+                    let _ = writeln!(markdown, "- Key {} has {old_count} rows in the old data and {new_count} in the new one. Only the first one is compared.", markdown_code(key));
+                }
+            }
+        }
+
+        if !self.locs.is_empty() {
+            markdown.push_str("\n## Changed Loc Keys\n\n");
+            for (key, text) in &self.locs.added {
+                let _ = writeln!(markdown, "- Added {}: \"{}\"", markdown_code(key), markdown_text(text));
+            }
+
+            for (key, text) in &self.locs.removed {
+                let _ = writeln!(markdown, "- Removed {}: \"{}\"", markdown_code(key), markdown_text(text));
+            }
+
+            for (key, (old_text, new_text)) in &self.locs.changed {
+                let _ = writeln!(markdown, "- Changed {}: \"{}\" to \"{}\"", markdown_code(key), markdown_text(old_text), markdown_text(new_text));
+            }
+        }
+
+        if !self.undecoded_tables.is_empty() {
+            markdown.push_str("\n## Tables Not Compared\n\nThese tables couldn't be decoded in one of the versions:\n\n");
+            self.undecoded_tables.iter().for_each(|table_name| { let _ = writeln!(markdown, "- {}", markdown_code(table_name)); });
+        }
+
+        markdown
+    }
+
+    /// This function renders the diff as TSV, with one change per line.
+    ///
+    /// The columns are: change, table (or file path), key, field, old value and new value. Rows added or removed have their values in the
+    /// `old value` or `new value` column, joined by tabs escaped as `\t`.
+    pub fn to_tsv(&self) -> String {
+        let mut tsv = String::from("change\ttable\tkey\tfield\told_value\tnew_value\n");
+        let mut push = |change: &str, table: &str, key: &str, field: &str, old_value: &str, new_value: &str| {
+            let _ = writeln!(tsv, "{}\t{}\t{}\t{}\t{}\t{}", change, escape_tsv(table), escape_tsv(key), escape_tsv(field), escape_tsv(old_value), escape_tsv(new_value));
+        };
+
+        for path in &self.added_files {
+            push("added_file", path, "", "", "", "");
+        }
+
+        for path in &self.removed_files {
+            push("removed_file", path, "", "", "", "");
+        }
+
+        for (table_name, table_diff) in &self.tables {
+            for (field, (old_type, new_type)) in &table_diff.changed_field_types {
+                push("changed_field_type", table_name, "", field, old_type, new_type);
+            }
+
+            for (key, row) in &table_diff.added_rows {
+                push("added_row", table_name, key, "", "", &row.join("\t"));
+            }
+
+            for (key, row) in &table_diff.removed_rows {
+                push("removed_row", table_name, key, "", &row.join("\t"), "");
+            }
+
+            for (key, changes) in &table_diff.changed_rows {
+                for change in changes {
+                    push("changed_row", table_name, key, &change.field, &change.old_value, &change.new_value);
+                }
+            }
+
+            for (key, (old_count, new_count)) in &table_diff.duplicated_keys {
+                push("duplicated_key", table_name, key, "", &old_count.to_string(), &new_count.to_string());
+            }
+        }
+
+        for (key, text) in &self.locs.added {
+            push("added_loc", "", key, "text", "", text);
+        }
+
+        for (key, text) in &self.locs.removed {
+            push("removed_loc", "", key, "text", text, "");
+        }
+
+        for (key, (old_text, new_text)) in &self.locs.changed {
+            push("changed_loc", "", key, "text", old_text, new_text);
+        }
+
+        for table_name in &self.undecoded_tables {
+            push("undecoded_table", table_name, "", "", "", "");
+        }
+
+        tsv
+    }
+}
+
+impl TableDataDiff {
+
+    /// This function compares the rows of a table in two versions of a game. Missing tables are treated as empty.
+    fn new(old_rows: Option<TableRows>, new_rows: Option<TableRows>) -> Self {
+        let old_rows = old_rows.unwrap_or_default();
+        let new_rows = new_rows.unwrap_or_default();
+
+        // Keys of the new definition that are also in the old one. If there are none, rows are matched by all the fields in both definitions.
+        let common_fields = new_rows.fields.iter().filter(|field| old_rows.fields.contains(field)).cloned().collect::<Vec<_>>();
+        let mut key_fields = new_rows.key_fields.iter().filter(|field| common_fields.contains(field)).cloned().collect::<Vec<_>>();
+        if key_fields.is_empty() {
+            key_fields = common_fields.to_vec();
+        }
+
+        // Tables only in one version have no common fields, so they use their own keys and have all their rows added or removed.
+        let old_keyed = old_rows.keyed_rows(&key_fields);
+        let new_keyed = new_rows.keyed_rows(&key_fields);
+
+        let mut diff = Self {
+            old_version: old_rows.version,
+            new_version: new_rows.version,
+            old_fields: old_rows.fields.to_vec(),
+            new_fields: new_rows.fields.to_vec(),
+            ..Default::default()
+        };
+
+        for field in &common_fields {
+            if let (Some(old_type), Some(new_type)) = (old_rows.field_type(field), new_rows.field_type(field)) {
+                if old_type != new_type {
+                    diff.changed_field_types.insert(field.to_owned(), (old_type.to_string(), new_type.to_string()));
+                }
+            }
+        }
+
+        for (key, (new_row, new_count)) in &new_keyed {
+            let old_count = old_keyed.get(key).map(|(_, count)| *count).unwrap_or(0);
+            if (old_count > 1 || *new_count > 1) && old_count != *new_count {
+                diff.duplicated_keys.insert(key_to_string(key), (old_count, *new_count));
+            }
+
+            match old_keyed.get(key) {
+                Some((old_row, _)) => {
+                    let changes = common_fields.iter()
+                        .filter_map(|field| {
+                            let old_value = &old_row[old_rows.field_position(field)?];
+                            let new_value = &new_row[new_rows.field_position(field)?];
+                            if !values_equal(old_value, old_rows.field_type(field)?, new_value, new_rows.field_type(field)?) {
+                                Some(CellChange {
+                                    field: field.to_owned(),
+                                    old_value: old_value.to_owned(),
+                                    new_value: new_value.to_owned(),
+                                    ..Default::default()
+                                })
+                            } else {
+                                None
+                            }
+                        })
+                        .collect::<Vec<_>>();
+
+                    if !changes.is_empty() {
+                        diff.changed_rows.insert(key_to_string(key), changes);
+                    }
+                }
+                None => { diff.added_rows.insert(key_to_string(key), new_row.to_vec()); }
+            }
+        }
+
+        for (key, (old_row, old_count)) in &old_keyed {
+            if !new_keyed.contains_key(key) {
+                if *old_count > 1 {
+                    diff.duplicated_keys.insert(key_to_string(key), (*old_count, 0));
+                }
+
+                diff.removed_rows.insert(key_to_string(key), old_row.to_vec());
+            }
+        }
+
+        diff
+    }
+
+    /// This function adds to the changed values of reference columns their lookups, from the dependencies each value comes from.
+    fn add_lookups(&mut self, table_name: &str, old_dependencies: &Dependencies, new_dependencies: &Dependencies) {
+        if self.changed_rows.is_empty() {
+            return;
+        }
+
+        let old_lookups = field_lookups(old_dependencies, table_name);
+        let new_lookups = field_lookups(new_dependencies, table_name);
+        for change in self.changed_rows.values_mut().flatten() {
+            if let Some(lookup) = old_lookups.get(&change.field).and_then(|lookups| lookups.get(&change.old_value)) {
+                change.old_lookup = lookup.to_owned();
+            }
+
+            if let Some(lookup) = new_lookups.get(&change.field).and_then(|lookups| lookups.get(&change.new_value)) {
+                change.new_lookup = lookup.to_owned();
+            }
+        }
+    }
+
+    /// This function returns if the table had no differences.
+    pub fn is_empty(&self) -> bool {
+        self.old_version == self.new_version &&
+            self.changed_field_types.is_empty() &&
+            self.added_rows.is_empty() &&
+            self.removed_rows.is_empty() &&
+            self.changed_rows.is_empty() &&
+            self.duplicated_keys.is_empty()
+    }
+}
+
+impl LocDataDiff {
+
+    /// This function compares the Loc data of two versions of a game.
+    fn new(old_locs: &HashMap<String, String>, new_locs: &HashMap<String, String>) -> Self {
+        let mut diff = Self::default();
+        for (key, new_text) in new_locs {
+            match old_locs.get(key) {
+                Some(old_text) => if old_text != new_text {
+                    diff.changed.insert(key.to_owned(), (old_text.to_owned(), new_text.to_owned()));
+                },
+                None => { diff.added.insert(key.to_owned(), new_text.to_owned()); }
+            }
+        }
+
+        for (key, old_text) in old_locs {
+            if !new_locs.contains_key(key) {
+                diff.removed.insert(key.to_owned(), old_text.to_owned());
+            }
+        }
+
+        diff
+    }
+
+    /// This function returns if the Loc data had no differences.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl Default for TableRows {
+    fn default() -> Self {
+        Self {
+            version: -1,
+            fields: vec![],
+            field_types: vec![],
+            key_fields: vec![],
+            rows: vec![],
+        }
+    }
+}
+
+impl TableRows {
+
+    /// This function reads the rows of the vanilla table with the provided name, in the order the game loads them.
+    ///
+    /// It returns None if any of the files of the table is not decoded. If the dependencies do not have the table, it returns it empty.
+    fn from_dependencies(dependencies: &Dependencies, table_name: &str) -> Option<Option<Self>> {
+        let files = dependencies.db_data(table_name, true, false).ok()?;
+        if files.is_empty() {
+            return Some(None);
+        }
+
+        let tables = files.iter()
+            .map(|file| match file.decoded() {
+                Ok(RFileDecoded::DB(table)) => Some(table),
+                _ => None,
+            })
+            .collect::<Option<Vec<&DB>>>()?;
+
+        // All the files of a table usually have the same version. If not, the newest one is used for the field list.
+        let newest = tables.iter().max_by_key(|table| *table.definition().version())?;
+        let fields = newest.definition().fields_processed();
+        let mut table_rows = Self {
+            version: *newest.definition().version(),
+            fields: fields.iter().map(|field| field.name().to_owned()).collect(),
+            field_types: fields.iter().map(|field| field.field_type().clone()).collect(),
+            key_fields: fields.iter().filter(|field| field.is_key(Some(newest.patches()))).map(|field| field.name().to_owned()).collect(),
+            rows: vec![],
+        };
+
+        // Rows are stored in the field order of the newest definition. They're keyed later, once we know the keys in both versions.
+        for table in tables {
+            let table_fields = table.definition().fields_processed();
+            let positions = table_rows.fields.iter()
+                .map(|field| table_fields.iter().position(|table_field| table_field.name() == field))
+                .collect::<Vec<_>>();
+
+            for row in table.data(&None).ok()?.iter() {
+                table_rows.rows.push(positions.iter()
+                    .map(|position| position.map(|position| row[position].data_to_string().to_string()).unwrap_or_default())
+                    .collect());
+            }
+        }
+
+        Some(Some(table_rows))
+    }
+
+    /// This function returns the position of a field in the rows.
+    fn field_position(&self, field: &str) -> Option<usize> {
+        self.fields.iter().position(|own_field| own_field == field)
+    }
+
+    /// This function returns the type of a field.
+    fn field_type(&self, field: &str) -> Option<&FieldType> {
+        self.field_types.get(self.field_position(field)?)
+    }
+
+    /// This function returns the rows keyed by the values of the provided fields, keeping only the first row with each key,
+    /// along with the amount of rows with said key.
+    ///
+    /// If no fields are provided, the key fields of the table are used, or all its fields if it has no keys.
+    fn keyed_rows(&self, key_fields: &[String]) -> HashMap<Vec<String>, (&[String], usize)> {
+        let key_fields = if !key_fields.is_empty() {
+            key_fields
+        } else if !self.key_fields.is_empty() {
+            &self.key_fields
+        } else {
+            &self.fields
+        };
+
+        let key_positions = key_fields.iter().filter_map(|field| self.field_position(field)).collect::<Vec<_>>();
+        let mut keyed: HashMap<Vec<String>, (&[String], usize)> = HashMap::with_capacity(self.rows.len());
+        for row in &self.rows {
+            let key = key_positions.iter().map(|position| row[*position].to_owned()).collect();
+            keyed.entry(key).or_insert((&row[..], 0)).1 += 1;
+        }
+
+        keyed
+    }
+}
+
+/// This function returns the text of each Loc key of the vanilla data, keeping only the first one loaded for each key.
+fn loc_rows(dependencies: &Dependencies) -> Result<HashMap<String, String>> {
+    let mut locs = HashMap::new();
+    for file in dependencies.loc_data(true, false)? {
+        if let Ok(RFileDecoded::Loc(table)) = file.decoded() {
+            for row in table.data(&None)?.iter() {
+                locs.entry(row[0].data_to_string().to_string()).or_insert_with(|| row[1].data_to_string().to_string());
+            }
+        }
+    }
+
+    Ok(locs)
+}
+
+/// This function returns the lookups of the reference columns of the newest definition of a vanilla table, by field name.
+fn field_lookups(dependencies: &Dependencies, table_name: &str) -> HashMap<String, HashMap<String, String>> {
+    let definition = dependencies.db_data(table_name, true, false).unwrap_or_default()
+        .iter()
+        .filter_map(|file| match file.decoded() {
+            Ok(RFileDecoded::DB(table)) => Some(table.definition()),
+            _ => None,
+        })
+        .max_by_key(|definition| *definition.version())
+        .cloned();
+
+    match definition {
+        Some(definition) => {
+            let fields = definition.fields_processed();
+            dependencies.table_reference_lookups(None, &definition)
+                .into_iter()
+                .map(|(column, lookups)| (fields[column].name().to_owned(), lookups))
+                .collect()
+        }
+        None => HashMap::new(),
+    }
+}
+
+/// This function returns a human-readable string of a game version, or `unknown` if it's 0.
+fn version_to_string(game_version: u32) -> String {
+    if game_version == 0 {
+        "unknown".to_owned()
+    } else {
+        game_version_to_string(game_version)
+    }
+}
+
+/// This function returns a key of a row as text. Keys of more than one field are returned as a JSON array, so they cannot be confused.
+fn key_to_string(key: &[String]) -> String {
+    match key {
+        [key] => key.to_owned(),
+        _ => serde_json::to_string(key).unwrap_or_default(),
+    }
+}
+
+/// This function returns a row as a list of `field: value` pairs.
+fn row_to_string(fields: &[String], row: &[String]) -> String {
+    fields.iter().zip(row).map(|(field, value)| format!("{}: {}", markdown_code(field), markdown_code(value))).join(", ")
+}
+
+/// This function checks if two values of a field represent the same data, even if the type of the field changed.
+///
+/// Numeric and boolean values are compared by their numeric value. The rest are compared as text.
+fn values_equal(old_value: &str, old_type: &FieldType, new_value: &str, new_type: &FieldType) -> bool {
+    if old_type == new_type {
+        return old_value == new_value;
+    }
+
+    match (numeric_value(old_value, old_type), numeric_value(new_value, new_type)) {
+        (Some(old_value), Some(new_value)) => float_eq::float_eq!(old_value, new_value, abs <= 0.0001),
+        _ => old_value == new_value,
+    }
+}
+
+/// This function returns the numeric value of a value of a numeric or boolean field.
+fn numeric_value(value: &str, field_type: &FieldType) -> Option<f64> {
+    match field_type {
+        FieldType::Boolean => parse_str_as_bool(value).ok().map(|value| if value { 1.0 } else { 0.0 }),
+        FieldType::F32 |
+        FieldType::F64 |
+        FieldType::I16 |
+        FieldType::I32 |
+        FieldType::I64 |
+        FieldType::OptionalI16 |
+        FieldType::OptionalI32 |
+        FieldType::OptionalI64 => value.parse().ok(),
+        _ => None,
+    }
+}
+
+/// This function returns a value as a Markdown code span.
+///
+/// The span is delimited by more backticks than the longest run of backticks in the value, so these don't end it early.
+/// Backslashes are not escapes within code spans, so the rest of the value is kept as it is.
+fn markdown_code(value: &str) -> String {
+    let value = value.replace('\n', " ");
+    let longest_run = value.split(|character| character != '`').map(str::len).max().unwrap_or(0);
+    let delimiter = "`".repeat(longest_run + 1);
+    if value.starts_with('`') || value.ends_with('`') {
+        format!("{delimiter} {value} {delimiter}")
+    } else {
+        format!("{delimiter}{value}{delimiter}")
+    }
+}
+
+/// This function escapes the characters of a text that Markdown would render as formatting.
+fn markdown_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for character in value.chars() {
+        match character {
+            '\\' | '`' | '|' | '*' | '_' | '[' | ']' | '<' | '>' | '#' => {
+                escaped.push('\\');
+                escaped.push(character);
+            }
+            '\n' => escaped.push_str("<br>"),
+            _ => escaped.push(character),
+        }
+    }
+
+    escaped
+}
+
+/// This function escapes the characters that would break a TSV line.
+fn escape_tsv(value: &str) -> String {
+    value.replace('\t', "\\t").replace('\n', "\\n")
+}
@@ -0,0 +1,236 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! Module containing tests for the comparison of vanilla data.
+
+use std::env::temp_dir;
+use std::fs::{create_dir_all, remove_dir_all};
+
+use rpfm_lib::files::{Container, loc::Loc, pack::Pack, RFile, table::DecodedData};
+use rpfm_lib::games::{pfh_file_type::PFHFileType, pfh_version::PFHVersion, supported_games::{KEY_WARHAMMER_3, SupportedGames}};
+use rpfm_lib::schema::{Definition, Field};
+
+use super::*;
+
+fn definition(version: i32, fields: &[(&str, FieldType)]) -> Definition {
+    let mut definition = Definition::new(version, None);
+    definition.set_fields(fields.iter()
+        .map(|(name, field_type)| Field::new(name.to_string(), field_type.clone(), *name == "key", None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None))
+        .collect());
+    definition
+}
+
+fn schema() -> Schema {
+    let mut schema = Schema::default();
+    schema.add_definition("units_tables", &definition(1, &[("key", FieldType::StringU8), ("cost", FieldType::I32)]));
+    schema.add_definition("units_tables", &definition(2, &[("key", FieldType::StringU8), ("cost", FieldType::F32), ("name", FieldType::StringU8)]));
+    schema
+}
+
+fn db(schema: &Schema, version: i32, rows: Vec<Vec<DecodedData>>) -> RFileDecoded {
+    let definition = schema.definition_by_name_and_version("units_tables", version).unwrap();
+    let mut table = DB::new(definition, None, "units_tables", false);
+    table.set_data(None, &rows).unwrap();
+    RFileDecoded::DB(table)
+}
+
+fn loc(rows: &[(&str, &str)]) -> RFileDecoded {
+    let mut loc = Loc::new(false);
+    loc.set_data(&rows.iter().map(|(key, text)| vec![DecodedData::StringU16(key.to_string()), DecodedData::StringU16(text.to_string()), DecodedData::Boolean(false)]).collect::<Vec<_>>()).unwrap();
+    RFileDecoded::Loc(loc)
+}
+
+fn dependencies(folder: &Path, files: Vec<(&str, RFileDecoded)>, schema: &Schema) -> Dependencies {
+    let games = SupportedGames::default();
+    let game_info = games.game(KEY_WARHAMMER_3).unwrap();
+
+    let path = folder.join("data.pack");
+    let mut pack = Pack::new_with_name_and_version("data.pack", PFHVersion::PFH5);
+    pack.set_pfh_file_type(PFHFileType::Release);
+    for (file_path, decoded) in &files {
+        pack.insert(RFile::new_from_decoded(decoded, 0, file_path)).unwrap();
+    }
+    pack.save(Some(&path), game_info, &None).unwrap();
+
+    let mut dependencies = Dependencies::generate_dependencies_cache_from_packs(game_info, &[path], &None, 0, &None).unwrap();
+    dependencies.decode_tables(&Some(schema.clone()));
+    dependencies
+}
+
+fn string(value: &str) -> DecodedData {
+    DecodedData::StringU8(value.to_owned())
+}
+
+#[test]
+fn test_vanilla_diff() {
+    let schema = schema();
+    let folder = temp_dir().join("rpfm_test_vanilla_diff");
+    let _ = remove_dir_all(&folder);
+    create_dir_all(folder.join("old")).unwrap();
+    create_dir_all(folder.join("new")).unwrap();
+
+    let old = dependencies(&folder.join("old"), vec![
+        ("db/units_tables/data", db(&schema, 1, vec![
+            vec![string("a"), DecodedData::I32(1)],
+            vec![string("b"), DecodedData::I32(2)],
+            vec![string("c"), DecodedData::I32(3)],
+            vec![string("a"), DecodedData::I32(9)],
+        ])),
+        ("text/db/data.loc", loc(&[("changed", "Old"), ("removed", "Gone"), ("same", "Same")])),
+        ("script/removed.lua", RFileDecoded::Text(Default::default())),
+    ], &schema);
+
+    let new = dependencies(&folder.join("new"), vec![
+        ("db/units_tables/data", db(&schema, 2, vec![
+            vec![string("a"), DecodedData::F32(1.0), string("A")],
+            vec![string("b"), DecodedData::F32(2.5), string("B")],
+            vec![string("d"), DecodedData::F32(4.0), string("D")],
+        ])),
+        ("text/db/data.loc", loc(&[("changed", "New"), ("added", "Added"), ("same", "Same")])),
+        ("script/added.lua", RFileDecoded::Text(Default::default())),
+    ], &schema);
+
+    let diff = VanillaDiff::new(&old, &new).unwrap();
+    assert_eq!(diff.added_files(), &vec!["script/added.lua".to_owned()]);
+    assert_eq!(diff.removed_files(), &vec!["script/removed.lua".to_owned()]);
+    assert!(diff.undecoded_tables().is_empty());
+
+    // The type change is reported once, and values that didn't change with it, like `1` and `1.0000`, are not reported.
+    // Rows with duplicated keys only use the first one.
+    let table_diff = diff.tables().get("units_tables").unwrap();
+    assert_eq!((*table_diff.old_version(), *table_diff.new_version()), (1, 2));
+    assert_eq!(table_diff.changed_field_types().get("cost").unwrap(), &("I32".to_owned(), "F32".to_owned()));
+    assert_eq!(table_diff.changed_rows().keys().collect::<Vec<_>>(), vec!["b"]);
+    assert_eq!(table_diff.changed_rows().get("b").unwrap(), &vec![CellChange { field: "cost".to_owned(), old_value: "2".to_owned(), new_value: "2.5000".to_owned(), ..Default::default() }]);
+    assert_eq!(table_diff.added_rows().get("d").unwrap(), &vec!["d".to_owned(), "4.0000".to_owned(), "D".to_owned()]);
+    assert_eq!(table_diff.removed_rows().get("c").unwrap(), &vec!["c".to_owned(), "3".to_owned()]);
+    assert_eq!(table_diff.duplicated_keys(), &BTreeMap::from([("a".to_owned(), (2, 1))]));
+
+    assert_eq!(diff.locs().added().get("added").unwrap(), "Added");
+    assert_eq!(diff.locs().removed().get("removed").unwrap(), "Gone");
+    assert_eq!(diff.locs().changed().get("changed").unwrap(), &("Old".to_owned(), "New".to_owned()));
+    assert!(!diff.locs().changed().contains_key("same"));
+
+    let tsv = diff.to_tsv();
+    assert!(tsv.contains("changed_field_type\tunits_tables\t\tcost\tI32\tF32\n"));
+    assert!(tsv.contains("added_row\tunits_tables\td\t\t\td\\t4.0000\\tD\n"));
+
+    assert!(VanillaDiff::new(&old, &old).unwrap().is_empty());
+
+    remove_dir_all(&folder).unwrap();
+}
+
+#[test]
+fn test_vanilla_diff_values_equal() {
+    assert!(values_equal("1", &FieldType::I32, "1.0000", &FieldType::F32));
+    assert!(!values_equal("1", &FieldType::I32, "1.5000", &FieldType::F32));
+    assert!(values_equal("true", &FieldType::Boolean, "1", &FieldType::I16));
+    assert!(values_equal("5", &FieldType::I32, "5", &FieldType::StringU8));
+    assert!(!values_equal("5", &FieldType::I32, "five", &FieldType::StringU8));
+    assert!(!values_equal("1", &FieldType::StringU8, "1.0", &FieldType::StringU8));
+}
+
+#[test]
+fn test_vanilla_diff_markdown_escaping() {
+    let mut table_diff = TableDataDiff {
+        old_version: 1,
+        new_version: 1,
+        old_fields: vec!["key".to_owned()],
+        new_fields: vec!["key".to_owned()],
+        ..Default::default()
+    };
+    table_diff.added_rows.insert("a|b`c".to_owned(), vec!["a|b`c".to_owned()]);
+    table_diff.changed_field_types.insert("key".to_owned(), ("StringU8".to_owned(), "StringU16".to_owned()));
+
+    let mut diff = VanillaDiff::default();
+    diff.tables.insert("units_tables".to_owned(), table_diff);
+    diff.locs.added.insert("`key`".to_owned(), "*Bold* | [link]\nline".to_owned());
+
+    let markdown = diff.to_markdown();
+    assert!(markdown.contains("- Changed field types: `key` from StringU8 to StringU16.\n"));
+    assert!(!markdown.contains("Version changed"));
+    assert!(markdown.contains("- Added ``a|b`c``: `key`: ``a|b`c``\n"));
+    assert!(markdown.contains("- Added `` `key` ``: \"\\*Bold\\* \\| \\[link\\]<br>line\"\n"));
+}
+
+#[test]
+fn test_vanilla_diff_composite_keys() {
+    let table_rows = |fields: &[&str], key_fields: &[&str], rows: &[&[&str]]| TableRows {
+        version: 1,
+        fields: fields.iter().map(|field| field.to_string()).collect(),
+        field_types: vec![FieldType::StringU8; fields.len()],
+        key_fields: key_fields.iter().map(|field| field.to_string()).collect(),
+        rows: rows.iter().map(|row| row.iter().map(|value| value.to_string()).collect()).collect(),
+    };
+
+    // Keys with separators in their values are not mixed.
+    let rows: &[&[&str]] = &[&["x|y", "z", "1"], &["x", "y|z", "2"]];
+    let old_rows = table_rows(&["a", "b", "cost"], &["a", "b"], rows);
+    let new_rows = table_rows(&["a", "b", "cost"], &["a", "b"], rows);
+    assert!(TableDataDiff::new(Some(old_rows), Some(new_rows)).is_empty());
+
+    let old_rows = table_rows(&["a", "b", "cost"], &["a", "b"], rows);
+    let new_rows = table_rows(&["a", "b", "cost"], &["a", "b"], &[&["x|y", "z", "1"], &["x", "y|z", "3"]]);
+    let diff = TableDataDiff::new(Some(old_rows), Some(new_rows));
+    assert_eq!(diff.changed_rows().keys().collect::<Vec<_>>(), vec!["[\"x\",\"y|z\"]"]);
+
+    // If a key field is added, rows that only differ in the new key field are reported, as only the first one is compared.
+    let old_rows = table_rows(&["key", "cost"], &["key"], &[&["a", "1"], &["b", "2"]]);
+    let new_rows = table_rows(&["key", "level", "cost"], &["key", "level"], &[&["a", "1", "1"], &["a", "2", "5"], &["b", "1", "2"]]);
+    let diff = TableDataDiff::new(Some(old_rows), Some(new_rows));
+    assert!(diff.changed_rows().is_empty());
+    assert_eq!(diff.duplicated_keys(), &BTreeMap::from([("a".to_owned(), (1, 2))]));
+
+    let mut vanilla_diff = VanillaDiff::default();
+    vanilla_diff.tables.insert("units_tables".to_owned(), diff);
+    assert!(vanilla_diff.to_markdown().contains("- Key `a` has 1 rows in the old data and 2 in the new one. Only the first one is compared.\n"));
+    assert!(vanilla_diff.to_tsv().contains("duplicated_key\tunits_tables\ta\t\t1\t2\n"));
+}
+
+#[test]
+fn test_vanilla_diff_lookups() {
+    let unit = Field::new("unit".to_owned(), FieldType::StringU8, false, None, false, None, Some(("units".to_owned(), "key".to_owned())), Some(vec!["name".to_owned()]), String::new(), 0, 0, BTreeMap::new(), None);
+    let mut armies = definition(1, &[("key", FieldType::StringU8)]);
+    armies.set_fields(vec![armies.fields()[0].clone(), unit]);
+
+    let mut schema = Schema::default();
+    schema.add_definition("units_tables", &definition(2, &[("key", FieldType::StringU8), ("cost", FieldType::F32), ("name", FieldType::StringU8)]));
+    schema.add_definition("armies_tables", &armies);
+
+    let armies_table = |unit: &str| {
+        let mut table = DB::new(&armies, None, "armies_tables", false);
+        table.set_data(None, &[vec![string("army"), string(unit)]]).unwrap();
+        RFileDecoded::DB(table)
+    };
+
+    let folder = temp_dir().join("rpfm_test_vanilla_diff_lookups");
+    let _ = remove_dir_all(&folder);
+    create_dir_all(folder.join("old")).unwrap();
+    create_dir_all(folder.join("new")).unwrap();
+
+    let units = db(&schema, 2, vec![
+        vec![string("a"), DecodedData::F32(1.0), string("Swordsmen")],
+        vec![string("b"), DecodedData::F32(1.0), string("")],
+    ]);
+    let old = dependencies(&folder.join("old"), vec![("db/units_tables/data", units.clone()), ("db/armies_tables/data", armies_table("a"))], &schema);
+    let new = dependencies(&folder.join("new"), vec![("db/units_tables/data", units), ("db/armies_tables/data", armies_table("b"))], &schema);
+
+    // Changed values of reference columns get the lookups of their version. Values without lookup data are shown as they are.
+    let diff = VanillaDiff::new(&old, &new).unwrap();
+    let change = &diff.tables().get("armies_tables").unwrap().changed_rows().get("army").unwrap()[0];
+    assert_eq!((change.old_lookup().as_str(), change.new_lookup().as_str()), ("Swordsmen", ""));
+    assert!(diff.to_markdown().contains("    - `unit`: `a (Swordsmen)` to `b`\n"));
+    assert!(diff.to_tsv().contains("changed_row\tarmies_tables\tarmy\tunit\ta\tb\n"));
+
+    // Tables without reference columns have no lookups.
+    assert!(field_lookups(&old, "units_tables").is_empty());
+
+    remove_dir_all(&folder).unwrap();
+}
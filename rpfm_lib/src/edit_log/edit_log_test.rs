@@ -0,0 +1,174 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! Module containing tests for the `EditLog`.
+
+use crate::files::{db::DB, text::Text};
+use crate::games::pfh_version::PFHVersion;
+
+use super::*;
+
+const TABLE_PATH: &str = "db/test_tables/data";
+
+fn test_pack() -> Pack {
+    let definition = DB::test_definition();
+    let mut table = DB::new(&definition, None, "test_tables", false);
+    let rows = vec![table.new_row(), table.new_row(), table.new_row()];
+    table.set_data(None, &rows).unwrap();
+
+    let mut pack = Pack::new_with_name_and_version("test.pack", PFHVersion::PFH5);
+    pack.insert(RFile::new_from_decoded(&RFileDecoded::DB(table), 0, TABLE_PATH)).unwrap();
+    pack
+}
+
+fn table_data(pack: &Pack) -> Vec<Vec<DecodedData>> {
+    match pack.file(TABLE_PATH).unwrap().decoded().unwrap() {
+        RFileDecoded::DB(table) => table.data(&None).unwrap().to_vec(),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_edit_log_undo_redo() {
+    let mut pack = test_pack();
+    let mut log = EditLog::default();
+    let original = table_data(&pack);
+
+    let column = 7;
+    log.apply(&mut pack, EditOperation::EditCell {
+        path: TABLE_PATH.to_owned(),
+        row: 1,
+        column,
+        before: original[1][column].clone(),
+        after: DecodedData::StringU8("edited".to_owned()),
+    }).unwrap();
+
+    log.apply(&mut pack, EditOperation::RemoveRows {
+        path: TABLE_PATH.to_owned(),
+        rows: vec![(0, original[0].clone()), (2, original[2].clone())],
+    }).unwrap();
+
+    log.apply(&mut pack, EditOperation::RenameFile {
+        source: TABLE_PATH.to_owned(),
+        destination: "db/test_tables/renamed".to_owned(),
+    }).unwrap();
+
+    assert!(pack.has_file("db/test_tables/renamed"));
+    assert_eq!(log.undo(&mut pack).unwrap(), vec![TABLE_PATH.to_owned(), "db/test_tables/renamed".to_owned()]);
+
+    let data = table_data(&pack);
+    assert_eq!(data.len(), 1);
+    assert_eq!(data[0][column], DecodedData::StringU8("edited".to_owned()));
+
+    log.undo(&mut pack).unwrap();
+    log.undo(&mut pack).unwrap();
+    assert_eq!(table_data(&pack), original);
+    assert!(!log.can_undo());
+
+    log.redo(&mut pack).unwrap();
+    log.redo(&mut pack).unwrap();
+    assert_eq!(table_data(&pack).len(), 1);
+
+    // Operations that don't match the current state of the Pack must fail without changing it.
+    let result = log.apply(&mut pack, EditOperation::RemoveRows {
+        path: TABLE_PATH.to_owned(),
+        rows: vec![(0, original[0].clone())],
+    });
+
+    assert!(result.is_err());
+    assert_eq!(table_data(&pack).len(), 1);
+}
+
+#[test]
+fn test_edit_log_track_changes_and_squash() {
+    let mut pack = test_pack();
+    let mut log = EditLog::default();
+    let original = pack.clone();
+
+    let paths = [ContainerPath::File(TABLE_PATH.to_owned()), ContainerPath::Folder("script".to_owned())];
+    log.track_changes(&mut pack, &paths, |pack| {
+        if let Ok(RFileDecoded::DB(table)) = pack.file_mut(TABLE_PATH).unwrap().decoded_mut() {
+            table.data_mut()?.pop();
+        }
+
+        pack.insert(RFile::new_from_decoded(&RFileDecoded::Text(Text::default()), 0, "script/test.lua"))?;
+        pack.insert(RFile::new_from_decoded(&RFileDecoded::Text(Text::default()), 0, "scripts/untracked.lua"))
+    }).unwrap();
+
+    // Only the changes under the tracked paths must be recorded.
+    assert_eq!(log.undo_stack()[0].paths(), vec![TABLE_PATH.to_owned(), "script/test.lua".to_owned()]);
+    pack.remove(&ContainerPath::File("scripts/untracked.lua".to_owned()));
+
+    log.apply(&mut pack, EditOperation::RenameFile {
+        source: "script/test.lua".to_owned(),
+        destination: "script/renamed.lua".to_owned(),
+    }).unwrap();
+
+    log.squash_all();
+    assert_eq!(log.undo_stack().len(), 1);
+
+    log.undo(&mut pack).unwrap();
+    assert_eq!(pack.files(), original.files());
+
+    log.redo(&mut pack).unwrap();
+    assert!(pack.has_file("script/renamed.lua"));
+    assert_eq!(table_data(&pack).len(), 2);
+}
+
+#[test]
+fn test_edit_log_invalidate() {
+    let mut pack = test_pack();
+    let mut log = EditLog::default();
+
+    log.track_changes(&mut pack, &[ContainerPath::File(TABLE_PATH.to_owned())], |pack| {
+        if let Ok(RFileDecoded::DB(table)) = pack.file_mut(TABLE_PATH).unwrap().decoded_mut() {
+            table.data_mut()?.pop();
+        }
+        Ok(())
+    }).unwrap();
+
+    log.apply(&mut pack, EditOperation::AddFile(RFile::new_from_decoded(&RFileDecoded::Text(Text::default()), 0, "script/a.lua"))).unwrap();
+    log.apply(&mut pack, EditOperation::AddFile(RFile::new_from_decoded(&RFileDecoded::Text(Text::default()), 0, "script/b.lua"))).unwrap();
+    log.undo(&mut pack).unwrap();
+
+    // Editing the table outside the log drops its operation, but not the newer ones, nor the ones in the redo stack not touching it.
+    log.invalidate(&[ContainerPath::File(TABLE_PATH.to_owned())]);
+    assert_eq!(log.undo_stack().len(), 1);
+    assert_eq!(log.redo_stack().len(), 1);
+
+    log.invalidate(&[ContainerPath::Folder("script".to_owned())]);
+    assert!(!log.can_undo() && !log.can_redo());
+}
+
+#[test]
+fn test_edit_log_replace_file_state() {
+    let mut pack = test_pack();
+    let mut log = EditLog::default();
+
+    log.track_changes(&mut pack, &[ContainerPath::File(TABLE_PATH.to_owned())], |pack| {
+        if let Ok(RFileDecoded::DB(table)) = pack.file_mut(TABLE_PATH).unwrap().decoded_mut() {
+            table.data_mut()?.pop();
+        }
+        Ok(())
+    }).unwrap();
+
+    // Moving the file to the cache, like when saving, doesn't change its contents, so it can still be undone.
+    pack.file_mut(TABLE_PATH).unwrap().encode(&None, true, false, false).unwrap();
+    log.undo(&mut pack).unwrap();
+    assert_eq!(table_data(&pack).len(), 3);
+
+    // But edits done outside the log are detected.
+    if let Ok(RFileDecoded::DB(table)) = pack.file_mut(TABLE_PATH).unwrap().decoded_mut() {
+        table.data_mut().unwrap().clear();
+    }
+
+    assert!(log.redo(&mut pack).is_err());
+    assert!(table_data(&pack).is_empty());
+}
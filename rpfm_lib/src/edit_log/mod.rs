@@ -0,0 +1,438 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the edit log used to undo/redo changes done to a [Pack].
+
+The [EditLog] keeps two stacks of reversible [EditOperation], one for undoing and one for redoing. Operations can be:
+- Applied through the log with [EditLog::apply], which applies them to the Pack and records them.
+- Recorded after being applied by other means with [EditLog::record].
+- Tracked from any function that edits a Pack (like [DB::cascade_edition](crate::files::db::DB::cascade_edition)) with [EditLog::track_changes],
+  providing the paths it may edit.
+
+Consecutive operations can be squashed into a single one with [EditLog::squash], so they're undone/redone together.
+
+Edits done to a Pack without going through the log must be reported with [EditLog::invalidate], so the operations that
+no longer match the Pack are dropped instead of failing when undone.
+
+The log lives in memory alongside the Pack it edits, so it's meant for long-lived sessions like the UI. Each CLI command
+opens, edits and saves its Pack in a single run, so they don't keep a log.
+!*/
+
+use getset::Getters;
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::error::{RLibError, Result};
+use crate::files::{Container, ContainerPath, pack::Pack, RFile, RFileDecoded, table::DecodedData};
+
+#[cfg(test)] mod edit_log_test;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct represents the undo/redo history of a [Pack].
+#[derive(Clone, Debug, Default, PartialEq, Getters)]
+#[getset(get = "pub")]
+pub struct EditLog {
+
+    /// Operations that can be undone, from oldest to newest.
+    undo_stack: Vec<EditOperation>,
+
+    /// Operations that can be redone, from oldest to newest.
+    redo_stack: Vec<EditOperation>,
+}
+
+/// This enum represents a reversible operation over a [Pack].
+///
+/// Table operations work over the decoded data of DB and Loc files, and use the processed column positions.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EditOperation {
+
+    /// Edit of a single cell of a table.
+    EditCell {
+        path: String,
+        row: usize,
+        column: usize,
+        before: DecodedData,
+        after: DecodedData,
+    },
+
+    /// Rows added to a table. Each row is stored with its position after the addition.
+    AddRows {
+        path: String,
+        rows: Vec<(usize, Vec<DecodedData>)>,
+    },
+
+    /// Rows removed from a table. Each row is stored with its position before the removal.
+    RemoveRows {
+        path: String,
+        rows: Vec<(usize, Vec<DecodedData>)>,
+    },
+
+    /// Replacement of the entire data of a table, like when importing a TSV.
+    ReplaceTableData {
+        path: String,
+        before: Vec<Vec<DecodedData>>,
+        after: Vec<Vec<DecodedData>>,
+    },
+
+    /// File added to the Pack.
+    AddFile(RFile),
+
+    /// File removed from the Pack.
+    RemoveFile(RFile),
+
+    /// File replaced by another version of itself, with the same path.
+    ReplaceFile {
+        before: RFile,
+        after: RFile,
+    },
+
+    /// File moved from one path to another.
+    RenameFile {
+        source: String,
+        destination: String,
+    },
+
+    /// Group of operations, applied in order and undone in reverse order.
+    Batch(Vec<EditOperation>),
+}
+
+//---------------------------------------------------------------------------//
+//                       Enum & Structs Implementations
+//---------------------------------------------------------------------------//
+
+impl EditLog {
+
+    /// This function applies the provided operation to the Pack, and records it in the log.
+    ///
+    /// If the operation fails, the Pack is left as it was and nothing is recorded.
+    pub fn apply(&mut self, pack: &mut Pack, operation: EditOperation) -> Result<()> {
+        operation.apply(pack)?;
+        self.record(operation);
+        Ok(())
+    }
+
+    /// This function records an operation already applied to the Pack.
+    ///
+    /// Recording a new operation clears the redo stack.
+    pub fn record(&mut self, operation: EditOperation) {
+        if !operation.is_empty() {
+            self.undo_stack.push(operation);
+            self.redo_stack.clear();
+        }
+    }
+
+    /// This function executes the provided function over the Pack, and records the changes it made to the provided paths.
+    ///
+    /// Meant for operations that edit multiple files at once, like cascade editions, global replaces or optimizations.
+    /// Only the files under the provided paths are checked for changes, so they must cover all the files the function may add, edit or remove.
+    /// The changes are recorded as a single operation, so they're undone together.
+    pub fn track_changes<F, T>(&mut self, pack: &mut Pack, paths: &[ContainerPath], function: F) -> Result<T>
+        where F: FnOnce(&mut Pack) -> Result<T> {
+
+        let files_before = Self::files_under_paths(pack, paths)
+            .map(|path| (path.to_owned(), pack.files()[path].clone()))
+            .collect::<HashMap<_, _>>();
+
+        let result = function(pack)?;
+
+        // Check both the files before and after the edition, as files may have been added or removed.
+        let paths_after = Self::files_under_paths(pack, paths).cloned().collect::<Vec<_>>();
+        let paths = files_before.keys().cloned().chain(paths_after).collect::<BTreeSet<_>>();
+
+        let operations = paths.into_iter().filter_map(|path| {
+            match (files_before.get(&path), pack.files().get(&path)) {
+                (Some(before), Some(after)) if before != after => Some(EditOperation::ReplaceFile { before: before.clone(), after: after.clone() }),
+                (Some(before), None) => Some(EditOperation::RemoveFile(before.clone())),
+                (None, Some(after)) => Some(EditOperation::AddFile(after.clone())),
+                _ => None,
+            }
+        }).collect::<Vec<_>>();
+
+        self.record(EditOperation::Batch(operations));
+        Ok(result)
+    }
+
+    /// This function drops the operations that cannot be undone or redone anymore, due to the provided paths being edited without going through the log.
+    ///
+    /// The operations affecting these paths are dropped, along with the ones that depend on them: the older ones in the undo stack,
+    /// and the ones that would be redone after them in the redo stack.
+    pub fn invalidate(&mut self, paths: &[ContainerPath]) {
+        for stack in [&mut self.undo_stack, &mut self.redo_stack] {
+            let last_affected = stack.iter().rposition(|operation| operation.paths().iter().any(|path| Self::is_under_paths(path, paths)));
+            if let Some(index) = last_affected {
+                stack.drain(..=index);
+            }
+        }
+    }
+
+    /// This function undoes the last operation in the log, and returns the paths it affected.
+    ///
+    /// If there is nothing to undo, it does nothing.
+    pub fn undo(&mut self, pack: &mut Pack) -> Result<Vec<String>> {
+        match self.undo_stack.pop() {
+            Some(operation) => {
+                if let Err(error) = operation.revert(pack) {
+                    self.undo_stack.push(operation);
+                    return Err(error);
+                }
+
+                let paths = operation.paths();
+                self.redo_stack.push(operation);
+                Ok(paths)
+            }
+            None => Ok(vec![]),
+        }
+    }
+
+    /// This function redoes the last undone operation in the log, and returns the paths it affected.
+    ///
+    /// If there is nothing to redo, it does nothing.
+    pub fn redo(&mut self, pack: &mut Pack) -> Result<Vec<String>> {
+        match self.redo_stack.pop() {
+            Some(operation) => {
+                if let Err(error) = operation.apply(pack) {
+                    self.redo_stack.push(operation);
+                    return Err(error);
+                }
+
+                let paths = operation.paths();
+                self.undo_stack.push(operation);
+                Ok(paths)
+            }
+            None => Ok(vec![]),
+        }
+    }
+
+    /// This function squashes the last `count` operations of the log into a single one, so they're undone/redone together.
+    pub fn squash(&mut self, count: usize) {
+        if count > 1 {
+            let start = self.undo_stack.len().saturating_sub(count);
+            let operations = self.undo_stack.drain(start..).collect::<Vec<_>>();
+            if !operations.is_empty() {
+                self.undo_stack.push(EditOperation::Batch(operations));
+            }
+        }
+    }
+
+    /// This function squashes all the operations of the log into a single one.
+    pub fn squash_all(&mut self) {
+        self.squash(self.undo_stack.len());
+    }
+
+    /// This function returns if there are operations to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    /// This function returns if there are operations to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// This function clears the log.
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+
+    /// This function returns the paths of the files of the Pack under the provided paths.
+    fn files_under_paths<'a>(pack: &'a Pack, paths: &'a [ContainerPath]) -> impl Iterator<Item = &'a String> {
+        pack.files().keys().filter(move |file_path| Self::is_under_paths(file_path, paths))
+    }
+
+    /// This function returns if the provided file path is one of the provided paths, or is within one of them.
+    fn is_under_paths(file_path: &str, paths: &[ContainerPath]) -> bool {
+        paths.iter().any(|path| match path {
+            ContainerPath::File(path) => file_path == path,
+            ContainerPath::Folder(path) => path.is_empty() || (file_path.starts_with(path.as_str()) && (path.ends_with('/') || file_path[path.len()..].starts_with('/'))),
+        })
+    }
+}
+
+impl EditOperation {
+
+    /// This function applies the operation to the Pack.
+    ///
+    /// If the operation fails, the Pack is left as it was.
+    pub fn apply(&self, pack: &mut Pack) -> Result<()> {
+        match self {
+            Self::EditCell { path, row, column, before, after } => {
+                let data = Self::table_data_mut(pack, path)?;
+                let cell = data.get_mut(*row)
+                    .and_then(|row| row.get_mut(*column))
+                    .ok_or_else(|| RLibError::EditLogOutOfSync(path.to_owned()))?;
+
+                if cell != before {
+                    return Err(RLibError::EditLogOutOfSync(path.to_owned()));
+                }
+
+                *cell = after.clone();
+            }
+
+            Self::AddRows { path, rows } => {
+                let data = Self::table_data_mut(pack, path)?;
+                let mut rows = rows.iter().collect::<Vec<_>>();
+                rows.sort_by_key(|(row, _)| *row);
+
+                if rows.iter().enumerate().any(|(index, (row, _))| *row > data.len() + index) {
+                    return Err(RLibError::EditLogOutOfSync(path.to_owned()));
+                }
+
+                for (row, data_row) in rows {
+                    data.insert(*row, data_row.to_vec());
+                }
+            }
+
+            Self::RemoveRows { path, rows } => {
+                let data = Self::table_data_mut(pack, path)?;
+                let mut rows = rows.iter().collect::<Vec<_>>();
+                rows.sort_by_key(|(row, _)| *row);
+
+                if rows.iter().any(|(row, data_row)| data.get(*row) != Some(data_row)) {
+                    return Err(RLibError::EditLogOutOfSync(path.to_owned()));
+                }
+
+                for (row, _) in rows.iter().rev() {
+                    data.remove(*row);
+                }
+            }
+
+            Self::ReplaceTableData { path, before, after } => {
+                let data = Self::table_data_mut(pack, path)?;
+                if data != before {
+                    return Err(RLibError::EditLogOutOfSync(path.to_owned()));
+                }
+
+                *data = after.to_vec();
+            }
+
+            Self::AddFile(file) => {
+                if pack.has_file(file.path_in_container_raw()) {
+                    return Err(RLibError::EditLogOutOfSync(file.path_in_container_raw().to_owned()));
+                }
+
+                pack.insert(file.clone())?;
+            }
+
+            Self::RemoveFile(file) => {
+                if !pack.has_file(file.path_in_container_raw()) {
+                    return Err(RLibError::EditLogOutOfSync(file.path_in_container_raw().to_owned()));
+                }
+
+                pack.remove(&ContainerPath::File(file.path_in_container_raw().to_owned()));
+            }
+
+            Self::ReplaceFile { before, after } => {
+                match pack.file_mut(before.path_in_container_raw()) {
+                    Some(file) if file.has_same_data(before) => *file = after.clone(),
+                    _ => return Err(RLibError::EditLogOutOfSync(before.path_in_container_raw().to_owned())),
+                }
+            }
+
+            Self::RenameFile { source, destination } => {
+                if pack.has_file(destination) {
+                    return Err(RLibError::EditLogOutOfSync(destination.to_owned()));
+                }
+
+                pack.move_path(&ContainerPath::File(source.to_owned()), &ContainerPath::File(destination.to_owned()))?;
+            }
+
+            Self::Batch(operations) => {
+                for (index, operation) in operations.iter().enumerate() {
+                    if let Err(error) = operation.apply(pack) {
+
+                        // Revert the operations already applied, so the batch is applied atomically.
+                        for operation in operations[..index].iter().rev() {
+                            operation.revert(pack)?;
+                        }
+
+                        return Err(error);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// This function reverts the operation in the Pack.
+    ///
+    /// If the operation fails, the Pack is left as it was.
+    pub fn revert(&self, pack: &mut Pack) -> Result<()> {
+        self.inverse().apply(pack)
+    }
+
+    /// This function returns the operation that reverts this one.
+    pub fn inverse(&self) -> Self {
+        match self {
+            Self::EditCell { path, row, column, before, after } => Self::EditCell {
+                path: path.to_owned(),
+                row: *row,
+                column: *column,
+                before: after.clone(),
+                after: before.clone(),
+            },
+            Self::AddRows { path, rows } => Self::RemoveRows { path: path.to_owned(), rows: rows.to_vec() },
+            Self::RemoveRows { path, rows } => Self::AddRows { path: path.to_owned(), rows: rows.to_vec() },
+            Self::ReplaceTableData { path, before, after } => Self::ReplaceTableData {
+                path: path.to_owned(),
+                before: after.to_vec(),
+                after: before.to_vec(),
+            },
+            Self::AddFile(file) => Self::RemoveFile(file.clone()),
+            Self::RemoveFile(file) => Self::AddFile(file.clone()),
+            Self::ReplaceFile { before, after } => Self::ReplaceFile { before: after.clone(), after: before.clone() },
+            Self::RenameFile { source, destination } => Self::RenameFile { source: destination.to_owned(), destination: source.to_owned() },
+            Self::Batch(operations) => Self::Batch(operations.iter().rev().map(|operation| operation.inverse()).collect()),
+        }
+    }
+
+    /// This function returns the paths of the files affected by this operation.
+    pub fn paths(&self) -> Vec<String> {
+        match self {
+            Self::EditCell { path, .. } |
+            Self::AddRows { path, .. } |
+            Self::RemoveRows { path, .. } |
+            Self::ReplaceTableData { path, .. } => vec![path.to_owned()],
+            Self::AddFile(file) |
+            Self::RemoveFile(file) |
+            Self::ReplaceFile { before: file, .. } => vec![file.path_in_container_raw().to_owned()],
+            Self::RenameFile { source, destination } => vec![source.to_owned(), destination.to_owned()],
+            Self::Batch(operations) => operations.iter()
+                .flat_map(|operation| operation.paths())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    /// This function returns if the operation does nothing.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::AddRows { rows, .. } | Self::RemoveRows { rows, .. } => rows.is_empty(),
+            Self::Batch(operations) => operations.iter().all(|operation| operation.is_empty()),
+            _ => false,
+        }
+    }
+
+    /// This function returns a mutable reference to the data of the table at the provided path.
+    fn table_data_mut<'a>(pack: &'a mut Pack, path: &str) -> Result<&'a mut Vec<Vec<DecodedData>>> {
+        let file = pack.file_mut(path).ok_or_else(|| RLibError::FileNotFound(path.to_owned()))?;
+        match file.decoded_mut()? {
+            RFileDecoded::DB(table) => table.data_mut(),
+            RFileDecoded::Loc(table) => table.data_mut(),
+            _ => Err(RLibError::EditLogNotATable(path.to_owned())),
+        }
+    }
+}
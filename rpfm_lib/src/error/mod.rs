@@ -247,6 +247,9 @@ pub enum RLibError {
     #[error("The file with the path {0} hasn't been found in the dependencies cache.")]
     DependenciesCacheFileNotFound(String),
 
+    #[error("The dependencies cache is in an old or unsupported format, and needs regenerating.")]
+    DependenciesCacheUnsupportedFormat,
+
     #[error("This table already has the newer definition available.")]
     NoDefinitionUpdateAvailable,
 
@@ -265,9 +268,33 @@ pub enum RLibError {
     #[error("The `Localisable Fields` file hasn't been found.")]
     AssemblyKitLocalisableFieldsNotFound,
 
+    #[error("The Assembly Kit table \"{0}\" hasn't been found in the raw data folder.")]
+    AssemblyKitTableNotFound(String),
+
+    #[error("The Pack has no decodeable tables of type \"{0}\" to export to the Assembly Kit.")]
+    AssemblyKitExportTableNotFound(String),
+
     #[error("The raw table you tried to import is missing a definition.")]
     RawTableMissingDefinition,
 
+    #[error("The game with the key \"{0}\" is not supported.")]
+    GameNotSupported(String),
+
+    #[error("There are no definitions for the table \"{0}\" in the Schema, so no code can be generated for it.")]
+    CodegenNoDefinitionForTable(String),
+
+    #[error("The file {0} doesn't match the state the edit log expected. This means it has been edited without recording the changes in the log.")]
+    EditLogOutOfSync(String),
+
+    #[error("The file {0} is not a decoded DB or Loc table, so table operations cannot be applied to it.")]
+    EditLogNotATable(String),
+
+    #[error("The typed rows for the table \"{0}\", version {1}, cannot be used with the table \"{2}\", version {3}.")]
+    TypedRowsTableMismatch(String, i32, String, i32),
+
+    #[error("The validation rule \"{1}\" of the column \"{0}\" is invalid: {2}.")]
+    InvalidValidationRule(String, String, String),
+
     #[error("This TSV file has an error in the row {0}, field {1} (both starting at 0). Please, check it and make sure the value in that field is a valid value for that column.")]
     ImportTSVIncorrectRow(usize, usize),
 
@@ -15,6 +15,7 @@ use std::io::{BufReader, BufWriter, Write};
 
 use crate::binary::ReadBytes;
 use crate::files::*;
+use crate::files::table::DecodedData;
 
 use super::DB;
 /*
@@ -99,3 +100,287 @@ fn test_encode_db_sqlite() {
     assert_eq!(before, after);
 }
 
+
+#[test]
+fn test_db_index() {
+    let path = "../test_files/test_decode_db";
+    let mut reader = BufReader::new(File::open(path).unwrap());
+
+    let mut schema = Schema::default();
+    schema.add_definition("test_decode_db", &DB::test_definition());
+
+    let mut decodeable_extra_data = DecodeableExtraData::default();
+    decodeable_extra_data.file_name = Some("test_decode_db");
+    decodeable_extra_data.table_name = Some("test_decode_db");
+    decodeable_extra_data.schema = Some(&schema);
+
+    let mut data = DB::decode(&mut reader, &Some(decodeable_extra_data)).unwrap();
+    let column = data.column_position_by_name("stringu8").unwrap();
+    let row_count = data.data(&None).unwrap().len();
+
+    data.enable_index(&["stringu8".to_owned()]).unwrap();
+    assert_eq!(data.index().unwrap().rows(column, "AAAA").unwrap().len(), row_count);
+
+    // Editing the data must invalidate the index until it's rebuilt.
+    data.data_mut().unwrap()[0][column] = DecodedData::StringU8("CCCC".to_owned());
+    assert!(data.index().is_none());
+
+    data.update_index().unwrap();
+    assert_eq!(data.index().unwrap().rows(column, "CCCC").unwrap(), &[0]);
+    assert_eq!(data.index().unwrap().rows(column, "AAAA").unwrap().len(), row_count - 1);
+}
+
+#[test]
+fn test_db_decode_with_index() {
+    use std::collections::BTreeMap;
+    use std::io::Cursor;
+
+    use crate::schema::{Definition, Field, FieldType};
+
+    let mut definition = Definition::new(1, None);
+    definition.set_fields(vec![
+        Field::new("key".to_owned(), FieldType::StringU8, true, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None),
+        Field::new("value".to_owned(), FieldType::I32, false, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None),
+    ]);
+
+    let mut schema = Schema::default();
+    schema.add_definition("test_index_tables", &definition);
+
+    let mut table = DB::new(&definition, None, "test_index_tables", false);
+    table.set_data(None, &[
+        vec![DecodedData::StringU8("a".to_owned()), DecodedData::I32(1)],
+        vec![DecodedData::StringU8("b".to_owned()), DecodedData::I32(2)],
+        vec![DecodedData::StringU8("a".to_owned()), DecodedData::I32(3)],
+    ]).unwrap();
+
+    let mut encoded = vec![];
+    table.encode(&mut encoded, &None).unwrap();
+
+    let mut decodeable_extra_data = DecodeableExtraData::default();
+    decodeable_extra_data.table_name = Some("test_index_tables");
+    decodeable_extra_data.schema = Some(&schema);
+
+    // Without the flag, decoded tables have no index.
+    let data = DB::decode(&mut Cursor::new(encoded.to_vec()), &Some(decodeable_extra_data.clone())).unwrap();
+    assert!(data.index().is_none());
+    let unindexed = data.table().rows_containing_data("key", "a");
+
+    decodeable_extra_data.table_index = true;
+    let data = DB::decode(&mut Cursor::new(encoded), &Some(decodeable_extra_data)).unwrap();
+    let index = data.index().unwrap();
+    assert!(index.is_column_indexed(0));
+    assert!(!index.is_column_indexed(1));
+    assert_eq!(index.rows(0, "a").unwrap(), &[0, 2]);
+
+    // Lookups through the index must return the same rows as the linear search.
+    assert_eq!(data.table().rows_containing_data("key", "a"), unindexed);
+    assert_eq!(data.table().rows_containing_data("key", "b"), Some((0, vec![1])));
+    assert_eq!(data.table().rows_containing_data("key", "c"), None);
+    assert_eq!(data.table().rows_containing_data("value", "3"), Some((1, vec![2])));
+}
+
+#[test]
+fn test_db_cascade_edition_preview() {
+    use std::collections::BTreeMap;
+
+    use crate::edit_log::EditOperation;
+    use crate::files::{loc::Loc, pack::Pack, text::Text};
+    use crate::games::pfh_version::PFHVersion;
+    use crate::schema::{Definition, Field, FieldType};
+
+    let mut units = Definition::new(1, None);
+    units.set_fields(vec![
+        Field::new("key".to_owned(), FieldType::StringU8, true, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None),
+        Field::new("icon".to_owned(), FieldType::StringU8, false, None, true, None, None, None, String::new(), 0, 0, BTreeMap::new(), None),
+    ]);
+    units.set_localised_fields(vec![Field::new("onscreen_name".to_owned(), FieldType::StringU16, false, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None)]);
+    units.set_localised_key_order(vec![0]);
+
+    let mut land_units = Definition::new(1, None);
+    land_units.set_fields(vec![
+        Field::new("unit".to_owned(), FieldType::StringU8, true, None, false, None, Some(("units".to_owned(), "key".to_owned())), None, String::new(), 0, 0, BTreeMap::new(), None),
+    ]);
+
+    let mut schema = Schema::default();
+    schema.add_definition("units_tables", &units);
+    schema.add_definition("land_units_tables", &land_units);
+
+    let mut units_table = DB::new(&units, None, "units_tables", false);
+    units_table.set_data(None, &[
+        vec![DecodedData::StringU8("unit_a".to_owned()), DecodedData::StringU8("ui/icons/unit_a.png".to_owned())],
+        vec![DecodedData::StringU8("unit_ab".to_owned()), DecodedData::StringU8("ui/icons/unit_ab.png".to_owned())],
+    ]).unwrap();
+
+    let mut land_units_table = DB::new(&land_units, None, "land_units_tables", false);
+    land_units_table.set_data(None, &[
+        vec![DecodedData::StringU8("unit_ab".to_owned())],
+        vec![DecodedData::StringU8("unit_a".to_owned())],
+    ]).unwrap();
+
+    let mut loc = Loc::new(false);
+    let mut loc_row = loc.new_row();
+    loc_row[0] = DecodedData::StringU16("units_onscreen_name_unit_a".to_owned());
+    loc.set_data(&[loc_row]).unwrap();
+
+    let mut text = Text::default();
+    text.set_contents("spawn(\"unit_a\")\nspawn(\"unit_ab\")\n".to_owned());
+
+    let mut pack = Pack::new_with_name_and_version("test.pack", PFHVersion::PFH5);
+    pack.insert(RFile::new_from_decoded(&RFileDecoded::DB(units_table), 0, "db/units_tables/data")).unwrap();
+    pack.insert(RFile::new_from_decoded(&RFileDecoded::DB(land_units_table), 0, "db/land_units_tables/data")).unwrap();
+    pack.insert(RFile::new_from_decoded(&RFileDecoded::Loc(loc), 0, "text/units.loc")).unwrap();
+    pack.insert(RFile::new_from_decoded(&RFileDecoded::Text(text), 0, "script/spawn.lua")).unwrap();
+    pack.insert(RFile::new_from_decoded(&RFileDecoded::Text(Text::default()), 0, "script/unit_a/unit_a.txt")).unwrap();
+    pack.insert(RFile::new_from_vec(b"icon", FileType::Image, 0, "ui/icons/unit_a.png")).unwrap();
+    pack.insert(RFile::new_from_vec(b"icon", FileType::Image, 0, "ui/icons/unit_ab.png")).unwrap();
+    pack.insert(RFile::new_from_vec(b"local unit = \"unit_a\"", FileType::Text, 0, "script/encoded.lua")).unwrap();
+
+    // Start from the referencing column, so the source of the value has to be found.
+    let original = pack.clone();
+    let field = land_units.fields_processed()[0].clone();
+    let cascade = DB::cascade_edition_preview(&pack, &Some(schema), "land_units_tables", &field, &land_units, "unit_a", "unit_c").unwrap();
+
+    // The preview must not change the Pack, not even by decoding its files.
+    assert_eq!(pack.files(), original.files());
+    assert!(pack.file("script/encoded.lua").unwrap().decoded().is_err());
+    assert_eq!(cascade.operations().iter().filter(|operation| matches!(operation, EditOperation::EditCell { .. })).count(), 4);
+    assert_eq!(cascade.paths(), vec![
+        "db/land_units_tables/data".to_owned(),
+        "db/units_tables/data".to_owned(),
+        "script/encoded.lua".to_owned(),
+        "script/spawn.lua".to_owned(),
+        "text/units.loc".to_owned(),
+        "ui/icons/unit_a.png".to_owned(),
+        "ui/icons/unit_c.png".to_owned(),
+    ]);
+
+    cascade.apply(&mut pack).unwrap();
+
+    let table_data = |pack: &Pack, path: &str| match pack.file(path).unwrap().decoded().unwrap() {
+        RFileDecoded::DB(table) => table.data(&None).unwrap().to_vec(),
+        RFileDecoded::Loc(table) => table.data(&None).unwrap().to_vec(),
+        _ => unreachable!(),
+    };
+
+    assert_eq!(table_data(&pack, "db/units_tables/data")[0], vec![DecodedData::StringU8("unit_c".to_owned()), DecodedData::StringU8("ui/icons/unit_c.png".to_owned())]);
+    assert_eq!(table_data(&pack, "db/units_tables/data")[1][1], DecodedData::StringU8("ui/icons/unit_ab.png".to_owned()));
+    assert_eq!(table_data(&pack, "db/land_units_tables/data")[1][0], DecodedData::StringU8("unit_c".to_owned()));
+    assert_eq!(table_data(&pack, "text/units.loc")[0][0], DecodedData::StringU16("units_onscreen_name_unit_c".to_owned()));
+
+    // Only the files pointed by the edited filename columns get renamed.
+    assert!(pack.has_file("ui/icons/unit_c.png"));
+    assert!(pack.has_file("ui/icons/unit_ab.png"));
+    assert!(pack.has_file("script/unit_a/unit_a.txt"));
+
+    match pack.file("script/spawn.lua").unwrap().decoded().unwrap() {
+        RFileDecoded::Text(text) => assert_eq!(text.contents(), "spawn(\"unit_c\")\nspawn(\"unit_ab\")\n"),
+        _ => unreachable!(),
+    }
+
+    match pack.file("script/encoded.lua").unwrap().decoded().unwrap() {
+        RFileDecoded::Text(text) => assert_eq!(text.contents(), "local unit = \"unit_c\""),
+        _ => unreachable!(),
+    }
+
+    // Reverting the edition must leave the Pack as it was.
+    cascade.into_operation().revert(&mut pack).unwrap();
+    assert_eq!(table_data(&pack, "db/units_tables/data"), table_data(&original, "db/units_tables/data"));
+    assert!(pack.has_file("ui/icons/unit_a.png"));
+    assert!(!pack.has_file("ui/icons/unit_c.png"));
+}
+
+#[test]
+fn test_db_cascade_edition_preview_self_references() {
+    use std::collections::BTreeMap;
+
+    use crate::files::{pack::Pack, text::Text};
+    use crate::games::pfh_version::PFHVersion;
+    use crate::schema::{Definition, Field, FieldType};
+
+    // Columns referencing the key of their own table must be edited too.
+    let mut regions = Definition::new(1, None);
+    regions.set_fields(vec![
+        Field::new("key".to_owned(), FieldType::StringU8, true, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None),
+        Field::new("parent_key".to_owned(), FieldType::StringU8, false, None, false, None, Some(("regions".to_owned(), "key".to_owned())), None, String::new(), 0, 0, BTreeMap::new(), None),
+    ]);
+
+    let mut schema = Schema::default();
+    schema.add_definition("regions_tables", &regions);
+
+    let mut regions_table = DB::new(&regions, None, "regions_tables", false);
+    regions_table.set_data(None, &[
+        vec![DecodedData::StringU8("-north.".to_owned()), DecodedData::StringU8(String::new())],
+        vec![DecodedData::StringU8("south".to_owned()), DecodedData::StringU8("-north.".to_owned())],
+    ]).unwrap();
+
+    // Values starting or ending with non-word characters are still matched as whole words in text files.
+    let mut text = Text::default();
+    text.set_contents("spawn(-north.)\nspawn(a-north.)\nspawn(-north.b)\n".to_owned());
+
+    let mut pack = Pack::new_with_name_and_version("test.pack", PFHVersion::PFH5);
+    pack.insert(RFile::new_from_decoded(&RFileDecoded::DB(regions_table), 0, "db/regions_tables/data")).unwrap();
+    pack.insert(RFile::new_from_decoded(&RFileDecoded::Text(text), 0, "script/spawn.lua")).unwrap();
+
+    let field = regions.fields_processed()[0].clone();
+    let cascade = DB::cascade_edition_preview(&pack, &Some(schema), "regions_tables", &field, &regions, "-north.", "-east.").unwrap();
+    cascade.apply(&mut pack).unwrap();
+
+    match pack.file("db/regions_tables/data").unwrap().decoded().unwrap() {
+        RFileDecoded::DB(table) => assert_eq!(table.data(&None).unwrap().to_vec(), vec![
+            vec![DecodedData::StringU8("-east.".to_owned()), DecodedData::StringU8(String::new())],
+            vec![DecodedData::StringU8("south".to_owned()), DecodedData::StringU8("-east.".to_owned())],
+        ]),
+        _ => unreachable!(),
+    }
+
+    match pack.file("script/spawn.lua").unwrap().decoded().unwrap() {
+        RFileDecoded::Text(text) => assert_eq!(text.contents(), "spawn(-east.)\nspawn(a-north.)\nspawn(-north.b)\n"),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_db_decode_with_game_build() {
+    use std::collections::BTreeMap;
+    use std::io::Cursor;
+
+    use crate::schema::{Definition, Field, FieldType};
+
+    // Same version, different layout depending on the game build.
+    let mut old = Definition::new(1, None);
+    old.set_fields(vec![Field::new("key".to_owned(), FieldType::StringU8, true, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None)]);
+    old.set_game_builds(Some((0x01000000, 0x010100FF)));
+
+    let mut new = Definition::new(1, None);
+    new.set_fields(vec![
+        Field::new("key".to_owned(), FieldType::StringU8, true, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None),
+        Field::new("value".to_owned(), FieldType::I32, false, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None),
+    ]);
+    new.set_game_builds(Some((0x01020000, 0x01FFFFFF)));
+
+    let mut schema = Schema::default();
+    schema.add_definition("test_build_tables", &old);
+    schema.add_definition("test_build_tables", &new);
+    assert_eq!(schema.definitions_by_table_name("test_build_tables").unwrap().len(), 2);
+
+    let mut table = DB::new(&old, None, "test_build_tables", false);
+    table.set_data(None, &[vec![DecodedData::StringU8("a".to_owned())]]).unwrap();
+
+    let mut encoded = vec![];
+    table.encode(&mut encoded, &None).unwrap();
+
+    let mut decodeable_extra_data = DecodeableExtraData::default();
+    decodeable_extra_data.table_name = Some("test_build_tables");
+    decodeable_extra_data.schema = Some(&schema);
+
+    // Without a build, the definition for the latest one is used, which doesn't fit the data.
+    assert!(DB::decode(&mut Cursor::new(encoded.to_vec()), &Some(decodeable_extra_data.clone())).is_err());
+
+    decodeable_extra_data.game_build = Some(0x01010000);
+    let data = DB::decode(&mut Cursor::new(encoded.to_vec()), &Some(decodeable_extra_data.clone())).unwrap();
+    assert_eq!(data.definition(), &old);
+    assert_eq!(data.data(&None).unwrap().to_vec(), vec![vec![DecodedData::StringU8("a".to_owned())]]);
+
+    decodeable_extra_data.game_build = Some(0x01030000);
+    assert!(DB::decode(&mut Cursor::new(encoded), &Some(decodeable_extra_data)).is_err());
+}
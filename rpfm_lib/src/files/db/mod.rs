@@ -45,16 +45,16 @@ use serde_derive::{Serialize, Deserialize};
 use uuid::Uuid;
 
 use std::borrow::Cow;
-#[cfg(test)] use std::collections::BTreeMap;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs::File;
 use std::io::SeekFrom;
 
 use crate::binary::{ReadBytes, WriteBytes};
+use crate::edit_log::EditOperation;
 use crate::error::{RLibError, Result};
-use crate::files::{Container, ContainerPath, DecodeableExtraData, Decodeable, EncodeableExtraData, Encodeable, FileType, table::{DecodedData, Table}, pack::Pack, RFileDecoded};
+use crate::files::{Container, ContainerPath, DecodeableExtraData, Decodeable, EncodeableExtraData, Encodeable, FileType, table::{DecodedData, Table, TableIndex}, pack::Pack, RFileDecoded};
 #[cfg(test)] use crate::schema::FieldType;
-use crate::schema::{Definition, DefinitionPatch, Field, Schema};
+use crate::schema::{Definition, DefinitionPatch, Field, Schema, codegen::TableRow};
 use crate::utils::check_size_mismatch;
 
 /// If this sequence is found, the DB Table has a GUID after it.
@@ -85,6 +85,23 @@ pub struct DB {
     table: Table,
 }
 
+/// This holds the changes a cascade edition will do over a [Pack], so they can be previewed before applying them.
+///
+/// Operations are stored in the order they must be applied: table cell edits first, then text edits, then file renames.
+#[derive(PartialEq, Clone, Debug, Default, Getters)]
+#[getset(get = "pub")]
+pub struct CascadeEdition {
+
+    /// Value being replaced.
+    value_before: String,
+
+    /// Value replacing the old one.
+    value_after: String,
+
+    /// Operations that perform the edition.
+    operations: Vec<EditOperation>,
+}
+
 //---------------------------------------------------------------------------//
 //                           Implementation of DB
 //---------------------------------------------------------------------------//
@@ -97,6 +114,8 @@ impl Decodeable for DB {
         let table_name = extra_data.table_name.ok_or_else(|| RLibError::DecodingMissingExtraDataField("table_name".to_owned()))?;
         let return_incomplete = extra_data.return_incomplete;
         let pool = extra_data.pool;
+        let game_build = extra_data.game_build;
+        let table_index = extra_data.table_index;
 
         let (version, mysterious_byte, guid, entry_count) = Self::read_header(data)?;
 
@@ -111,12 +130,18 @@ impl Decodeable for DB {
 
         // Try to decode the table.
         let len = data.len()?;
-        let table = if version == 0 {
+        let mut table = if version == 0 {
             let index_reset = data.stream_position()?;
 
             // For version 0 tables, get all definitions between 0 and -99, and get the first one that works.
+            // If we know the game build, try first the ones for it.
+            let mut candidates = definitions.iter().filter(|definition| *definition.version() < 1).collect::<Vec<_>>();
+            if let Some(game_build) = game_build {
+                candidates.sort_by_key(|definition| !definition.is_for_game_build(game_build));
+            }
+
             let mut working_definition = Err(RLibError::DecodingDBNoDefinitionsFound);
-            for definition in definitions.iter().filter(|definition| *definition.version() < 1) {
+            for definition in candidates {
 
                 // First, reset the index in case it was changed in a previous iteration.
                 // Then, check if the definition works.
@@ -136,11 +161,10 @@ impl Decodeable for DB {
             Table::decode(&pool, data, definition, &definition_patch, Some(entry_count), return_incomplete, table_name)?
         }
 
-        // For +0 versions, we expect unique definitions.
+        // For +0 versions, we expect unique definitions, unless they're limited to specific game builds.
         else {
 
-            let definition = definitions.iter()
-                .find(|definition| *definition.version() == version)
+            let definition = schema.definition_by_name_version_and_build(table_name, version, game_build)
                 .ok_or(RLibError::DecodingDBNoDefinitionsFound)?;
 
             let definition_patch = schema.patches_for_table(table_name).cloned().unwrap_or_default();
@@ -154,6 +178,11 @@ impl Decodeable for DB {
             RLibError::DecodingTableIncomplete(error.to_string(), table.clone())
         })?;
 
+        // The index is only supported on the local backend, so tables on the SQL one are left without it.
+        if table_index {
+            let _ = table.enable_index(&[]);
+        }
+
         // If we've reached this, we've successfully decoded the table.
         Ok(Self {
             mysterious_byte,
@@ -290,6 +319,56 @@ impl DB {
         self.table.set_data(pool, data)
     }
 
+    /// This function enables the index of this table, and builds it.
+    ///
+    /// The index covers the key columns, the columns referencing other tables, and the extra columns provided.
+    pub fn enable_index(&mut self, extra_columns: &[String]) -> Result<()> {
+        self.table.enable_index(extra_columns)
+    }
+
+    /// This function disables the index of this table, freeing its memory.
+    pub fn disable_index(&mut self) {
+        self.table.disable_index()
+    }
+
+    /// This function returns the index of this table, if it's enabled and up-to-date.
+    pub fn index(&self) -> Option<&TableIndex> {
+        self.table.index()
+    }
+
+    /// This function rebuilds the index of this table if it's enabled and outdated.
+    pub fn update_index(&mut self) -> Result<()> {
+        self.table.update_index()
+    }
+
+    /// This function returns the entries of this DB table as typed rows, generated with [codegen](crate::schema::codegen).
+    ///
+    /// This fails if the table or the definition version of this table doesn't match the ones of the typed rows.
+    pub fn typed_rows<T: TableRow>(&self) -> Result<Vec<T>> {
+        self.check_typed_rows::<T>()?;
+        self.data(&None)?.iter()
+            .map(|row| T::try_from(row))
+            .collect()
+    }
+
+    /// This function replaces the data of this table with the typed rows provided.
+    ///
+    /// This fails if the table or the definition version of this table doesn't match the ones of the typed rows.
+    pub fn set_typed_rows<T: TableRow>(&mut self, rows: Vec<T>) -> Result<()> {
+        self.check_typed_rows::<T>()?;
+        let data = rows.into_iter().map(|row| row.into()).collect::<Vec<Vec<DecodedData>>>();
+        self.set_data(None, &data)
+    }
+
+    /// This function checks if the provided typed rows can be used with this table.
+    fn check_typed_rows<T: TableRow>(&self) -> Result<()> {
+        if self.table_name() != T::TABLE_NAME || *self.definition().version() != T::VERSION {
+            return Err(RLibError::TypedRowsTableMismatch(T::TABLE_NAME.to_owned(), T::VERSION, self.table_name().to_owned(), *self.definition().version()));
+        }
+
+        Ok(())
+    }
+
     /// This function returns a valid empty (with default values if any) row for this table.
     pub fn new_row(&self) -> Vec<DecodedData> {
         Table::new_row(self.definition(), Some(self.patches()))
@@ -383,23 +462,7 @@ impl DB {
         }
 
         // Just in case we're in a reference field, find the source, and trigger the edition from there.
-        let mut definition = definition.clone();
-        let mut field = field.clone();
-        let mut table_name = table_name.to_owned();
-        while let Some((ref_table, ref_column)) = field.is_reference() {
-            let ref_table_name = format!("{ref_table}_tables");
-            let table_folder = format!("db/{ref_table_name}");
-            let parent_files = pack.files_by_type_and_paths_mut(&[FileType::DB], &[ContainerPath::Folder(table_folder.to_owned())], true);
-            if !parent_files.is_empty() {
-                if let Ok(RFileDecoded::DB(table)) = parent_files[0].decoded() {
-                    if let Some(index) = table.definition().column_position_by_name(ref_column) {
-                        definition = table.definition().clone();
-                        field = definition.fields_processed()[index].clone();
-                        table_name = table.table_name().to_owned();
-                    }
-                }
-            }
-        }
+        let (table_name, field, definition) = Self::cascade_edition_source(pack, table_name, field, definition);
 
         // Get the tables/rows that need to be edited.
         let fields_processed = definition.fields_processed();
@@ -524,6 +587,302 @@ impl DB {
         edited_paths
     }
 
+    /// This function generates a preview of a cascade edition over the provided Pack, without changing the data of any of its files.
+    ///
+    /// Unlike [DB::cascade_edition], this covers:
+    /// - The source column of the value and all columns referencing it.
+    /// - The loc keys generated from the edited rows.
+    /// - Filename columns and text files containing the value as a whole word.
+    /// - Files and folders of the Pack pointed by the edited filename columns, which are moved to their new paths.
+    ///
+    /// The Pack is not changed in the process. To perform the edition, use [CascadeEdition::apply].
+    pub fn cascade_edition_preview(pack: &Pack, schema: &Option<Schema>, table_name: &str, field: &Field, definition: &Definition, value_before: &str, value_after: &str) -> Result<CascadeEdition> {
+        let mut cascade = CascadeEdition {
+            value_before: value_before.to_owned(),
+            value_after: value_after.to_owned(),
+            operations: vec![],
+        };
+
+        // If we're not changing anything, don't bother calculating the edition.
+        if value_before.is_empty() || value_before == value_after {
+            return Ok(cascade);
+        }
+
+        // Get the tables/columns that need to be edited, starting from the source of the value.
+        let (table_name, field, definition) = Self::cascade_edition_source(pack, table_name, field, definition);
+        let fields_processed = definition.fields_processed();
+        let mut ref_table_data = Table::tables_and_columns_referencing_our_own(schema, &table_name, field.name(), &fields_processed, definition.localised_fields())
+            .map(|(ref_table_data, _)| ref_table_data)
+            .unwrap_or_default();
+
+        // Self-referencing tables already have their referencing columns here, so the source column is added to them.
+        let source_columns = ref_table_data.entry(table_name).or_default();
+        if !source_columns.iter().any(|column| column == field.name()) {
+            source_columns.push(field.name().to_owned());
+        }
+
+        // First, the table cells. Referencing columns only get exact matches, while filename columns get partial ones.
+        let mut loc_keys = HashMap::new();
+        let mut renamed_paths = BTreeMap::new();
+        let mut db_files = pack.files_by_type(&[FileType::DB]);
+        db_files.sort_by(|a, b| a.path_in_container_raw().cmp(b.path_in_container_raw()));
+
+        for file in db_files {
+            if let Ok(RFileDecoded::DB(table)) = file.decoded() {
+                let path = file.path_in_container_raw();
+                let fields_processed = table.definition().fields_processed();
+                let ref_columns = ref_table_data.get(table.table_name());
+                let columns = fields_processed.iter()
+                    .enumerate()
+                    .filter_map(|(index, field)| {
+                        let is_reference = ref_columns.map(|columns| columns.iter().any(|name| name == field.name())).unwrap_or(false);
+                        if is_reference || field.is_filename() {
+                            Some((index, is_reference))
+                        } else {
+                            None
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                if columns.is_empty() {
+                    continue;
+                }
+
+                let localised_fields = table.definition().localised_fields();
+                let localised_order = table.definition().localised_key_order();
+                let table_name_no_tables = table.table_name_without_tables();
+
+                for (row_index, row) in table.data(&None)?.iter().enumerate() {
+                    let mut new_row = row.to_vec();
+                    for (column, is_reference) in &columns {
+                        match new_row.get_mut(*column) {
+                            Some(DecodedData::StringU8(data)) |
+                            Some(DecodedData::StringU16(data)) |
+                            Some(DecodedData::OptionalStringU8(data)) |
+                            Some(DecodedData::OptionalStringU16(data)) => {
+                                if *is_reference && data == value_before {
+                                    *data = value_after.to_owned();
+                                } else if fields_processed[*column].is_filename() {
+                                    if let Some(new_data) = Self::replace_whole_words(data, value_before, value_after) {
+                                        *data = new_data;
+                                    }
+                                }
+                            }
+                            _ => continue,
+                        }
+                    }
+
+                    if new_row == *row {
+                        continue;
+                    }
+
+                    for (column, (before, after)) in row.iter().zip(new_row.iter()).enumerate() {
+                        if before != after {
+                            cascade.operations.push(EditOperation::EditCell {
+                                path: path.to_owned(),
+                                row: row_index,
+                                column,
+                                before: before.clone(),
+                                after: after.clone(),
+                            });
+
+                            // If the cell points to files, they need to be moved to the new paths.
+                            if fields_processed[column].is_filename() {
+                                let paths_before = Self::filename_paths(&fields_processed[column], &before.data_to_string());
+                                let paths_after = Self::filename_paths(&fields_processed[column], &after.data_to_string());
+                                if paths_before.len() == paths_after.len() {
+                                    renamed_paths.extend(paths_before.into_iter().zip(paths_after).filter(|(before, after)| before != after));
+                                }
+                            }
+                        }
+                    }
+
+                    // If the edit changed the loc keys of the row, we need to update them too.
+                    let loc_key = |row: &[DecodedData]| localised_order.iter().map(|pos| row[*pos as usize].data_to_string()).collect::<Vec<_>>().join("");
+                    let (loc_key_before, loc_key_after) = (loc_key(row), loc_key(&new_row));
+                    if loc_key_before != loc_key_after {
+                        for loc_field in localised_fields {
+                            loc_keys.insert(
+                                format!("{}_{}_{}", table_name_no_tables, loc_field.name(), loc_key_before),
+                                format!("{}_{}_{}", table_name_no_tables, loc_field.name(), loc_key_after)
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        // Second, the loc keys.
+        if !loc_keys.is_empty() {
+            let mut loc_files = pack.files_by_type(&[FileType::Loc]);
+            loc_files.sort_by(|a, b| a.path_in_container_raw().cmp(b.path_in_container_raw()));
+
+            for file in loc_files {
+                if let Ok(RFileDecoded::Loc(loc)) = file.decoded() {
+                    for (row_index, row) in loc.data(&None)?.iter().enumerate() {
+                        let before = match row.first() {
+                            Some(before) => before,
+                            None => continue,
+                        };
+
+                        let mut after = before.clone();
+                        match &mut after {
+                            DecodedData::StringU8(key) |
+                            DecodedData::StringU16(key) |
+                            DecodedData::OptionalStringU8(key) |
+                            DecodedData::OptionalStringU16(key) => match loc_keys.get(key) {
+                                Some(key_new) => *key = key_new.to_owned(),
+                                None => continue,
+                            }
+                            _ => continue,
+                        }
+
+                        cascade.operations.push(EditOperation::EditCell {
+                            path: file.path_in_container_raw().to_owned(),
+                            row: row_index,
+                            column: 0,
+                            before: before.clone(),
+                            after,
+                        });
+                    }
+                }
+            }
+        }
+
+        // Third, text files. These are replaced as a whole. Files not yet decoded are decoded on a copy, so the Pack is left untouched.
+        let mut text_files = pack.files_by_type(&[FileType::Text]);
+        text_files.sort_by(|a, b| a.path_in_container_raw().cmp(b.path_in_container_raw()));
+
+        for file in text_files {
+            let mut after = file.clone();
+            if after.decoded().is_err() && after.decode(&None, true, false).is_err() {
+                continue;
+            }
+
+            if let Ok(RFileDecoded::Text(text)) = after.decoded_mut() {
+                if let Some(contents) = Self::replace_whole_words(text.contents(), value_before, value_after) {
+                    text.set_contents(contents);
+                    cascade.operations.push(EditOperation::ReplaceFile {
+                        before: file.clone(),
+                        after,
+                    });
+                }
+            }
+        }
+
+        // Last, the files pointed by the edited filename columns. These go at the end so the previous operations can still find their files.
+        let mut paths = pack.paths_raw();
+        paths.sort();
+
+        let mut destinations = HashSet::new();
+        for (path_before, path_after) in &renamed_paths {
+            for path in &paths {
+
+                // Paths in filename columns are case-insensitive, and may point to a file or to a folder.
+                let destination = if path.eq_ignore_ascii_case(path_before) {
+                    path_after.to_owned()
+                } else if path.len() > path_before.len() && path.is_char_boundary(path_before.len()) && path[..path_before.len()].eq_ignore_ascii_case(path_before) && path[path_before.len()..].starts_with('/') {
+                    format!("{}{}", path_after, &path[path_before.len()..])
+                } else {
+                    continue;
+                };
+
+                if !pack.has_file(&destination) && destinations.insert(destination.to_owned()) {
+                    cascade.operations.push(EditOperation::RenameFile {
+                        source: path.to_string(),
+                        destination,
+                    });
+                }
+            }
+        }
+
+        Ok(cascade)
+    }
+
+    /// This function replaces the occurrences of a value in a text where it's a whole word, returning the new text if any was replaced.
+    ///
+    /// Words are delimited by anything but ASCII letters, numbers and underscores, so values starting or ending with
+    /// other characters, like `foo.` or `-x`, are found too.
+    fn replace_whole_words(text: &str, value_before: &str, value_after: &str) -> Option<String> {
+        let is_word_char = |character: Option<char>| character.map(|character| character.is_ascii_alphanumeric() || character == '_').unwrap_or(false);
+        let mut new_text = String::with_capacity(text.len());
+        let mut last_end = 0;
+
+        for (start, _) in text.match_indices(value_before) {
+            let end = start + value_before.len();
+            if !is_word_char(text[..start].chars().next_back()) && !is_word_char(text[end..].chars().next()) {
+                new_text.push_str(&text[last_end..start]);
+                new_text.push_str(value_after);
+                last_end = end;
+            }
+        }
+
+        if last_end == 0 {
+            None
+        } else {
+            new_text.push_str(&text[last_end..]);
+            Some(new_text)
+        }
+    }
+
+    /// This function returns the paths of the files or folders a value of a filename column points to.
+    ///
+    /// Paths with wildcards are not supported, so no paths are returned for them.
+    fn filename_paths(field: &Field, value: &str) -> Vec<String> {
+        let path = match field.filename_relative_path() {
+            Some(relative_path) => relative_path.replace('%', value),
+            None => value.to_owned(),
+        };
+
+        if value.is_empty() || path.contains('*') {
+            return vec![];
+        }
+
+        path.replace('\\', "/")
+            .replace(';', ",")
+            .split(',')
+            .map(|path| path.trim_end_matches('/').to_owned())
+            .filter(|path| !path.is_empty())
+            .collect()
+    }
+
+    /// This function returns the table name, field and definition of the source of the value of the provided field.
+    ///
+    /// If the field is not a reference, or the referenced table is not in the Pack, the provided data is returned.
+    fn cascade_edition_source(pack: &Pack, table_name: &str, field: &Field, definition: &Definition) -> (String, Field, Definition) {
+        let mut definition = definition.clone();
+        let mut field = field.clone();
+        let mut table_name = table_name.to_owned();
+
+        // Keep track of the tables we went through, to avoid looping over circular references.
+        let mut tables_checked = HashSet::from([table_name.to_owned()]);
+        while let Some((ref_table, ref_column)) = field.is_reference().clone() {
+            let ref_table_name = format!("{ref_table}_tables");
+            if !tables_checked.insert(ref_table_name.to_owned()) {
+                break;
+            }
+
+            let table_folder = format!("db/{ref_table_name}");
+            let parent = pack.files_by_type_and_paths(&[FileType::DB], &[ContainerPath::Folder(table_folder)], true)
+                .into_iter()
+                .find_map(|file| match file.decoded() {
+                    Ok(RFileDecoded::DB(table)) => table.definition().column_position_by_name(&ref_column).map(|index| (table, index)),
+                    _ => None,
+                });
+
+            match parent {
+                Some((table, index)) => {
+                    definition = table.definition().clone();
+                    field = definition.fields_processed()[index].clone();
+                    table_name = table.table_name().to_owned();
+                }
+                None => break,
+            }
+        }
+
+        (table_name, field, definition)
+    }
+
     /// This function merges the data of a few DB tables into a new DB table.
     ///
     /// The metadata used (definition, patches) is taken from the first table on the list.
@@ -580,3 +939,37 @@ impl From<Table> for DB {
         }
     }
 }
+
+//---------------------------------------------------------------------------//
+//                     Implementation of CascadeEdition
+//---------------------------------------------------------------------------//
+
+impl CascadeEdition {
+
+    /// This function returns if the edition doesn't change anything.
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// This function returns the paths of the files affected by the edition, including the new paths of renamed files.
+    pub fn paths(&self) -> Vec<String> {
+        self.operations.iter()
+            .flat_map(|operation| operation.paths())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// This function returns the edition as a single operation, so it can be recorded in an [EditLog](crate::edit_log::EditLog).
+    pub fn into_operation(self) -> EditOperation {
+        EditOperation::Batch(self.operations)
+    }
+
+    /// This function applies the edition to the Pack, returning the paths of the files affected by it.
+    ///
+    /// The edition is applied atomically: if any of its operations fail, the Pack is left as it was.
+    pub fn apply(&self, pack: &mut Pack) -> Result<Vec<ContainerPath>> {
+        EditOperation::Batch(self.operations.to_vec()).apply(pack)?;
+        Ok(self.paths().into_iter().map(ContainerPath::File).collect())
+    }
+}
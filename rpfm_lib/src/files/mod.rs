@@ -318,6 +318,12 @@ pub struct DecodeableExtraData<'a> {
     #[cfg(feature = "integration_sqlite")]
     pool: Option<&'a Pool<SqliteConnectionManager>>,
 
+    /// Game build the table was made for, to pick the right definition when a table version has more than one. See [Pack::game_build](crate::files::pack::Pack::game_build).
+    game_build: Option<u32>,
+
+    /// If decoded tables should have the index of their key and reference columns enabled. See [Table::enable_index](crate::files::table::Table::enable_index).
+    table_index: bool,
+
     //------------------------------//
     // General-purpouse config data //
     //------------------------------//
@@ -1251,6 +1257,36 @@ impl RFile {
         Self::new_from_file(&path)
     }
 
+    /// This function creates a RFile from a lazy-loaded chunk of a file on disk, like a file stored within a cache.
+    ///
+    /// About the parameters:
+    /// - `disk_file_path`: Path on disk of the file containing the data.
+    /// - `disk_file_timestamp`: Last modified date of the file containing the data. Used to detect if it changed before reading from it.
+    /// - `data_pos`: Byte offset of the data from the beginning of the file on disk.
+    /// - `size`: Size in bytes of the data. The data must be uncompressed and unencrypted.
+    /// - `path`: Path of the RFile.
+    /// - `container_name`: Name of the container the RFile originally came from, if any.
+    ///
+    /// NOTE: Remember to call `guess_file_type` after this to properly set the FileType.
+    pub fn new_from_file_chunk(disk_file_path: &str, disk_file_timestamp: u64, data_pos: u64, size: u64, path: &str, container_name: Option<String>) -> Self {
+        let on_disk = OnDisk {
+            path: disk_file_path.to_owned(),
+            timestamp: disk_file_timestamp,
+            start: data_pos,
+            size,
+            is_compressed: false,
+            is_encrypted: None,
+        };
+
+        Self {
+            path: path.to_owned(),
+            timestamp: None,
+            file_type: FileType::Unknown,
+            container_name,
+            data: RFileInnerData::OnDisk(on_disk)
+        }
+    }
+
     /// This function creates a RFile from raw data on memory.
     ///
     /// NOTE: Remember to call `guess_file_type` after this to properly set the FileType.
@@ -1317,6 +1353,19 @@ impl RFile {
         }
     }
 
+    /// This function returns if both RFiles have the same path and data, ignoring their metadata.
+    ///
+    /// Data in different states (like a file decoded after a copy of it was made) cannot be compared without decoding it again,
+    /// so it's considered the same.
+    pub(crate) fn has_same_data(&self, other: &Self) -> bool {
+        self.path == other.path && match (&self.data, &other.data) {
+            (RFileInnerData::Decoded(data), RFileInnerData::Decoded(other_data)) => data == other_data,
+            (RFileInnerData::Cached(data), RFileInnerData::Cached(other_data)) => data == other_data,
+            (RFileInnerData::OnDisk(data), RFileInnerData::OnDisk(other_data)) => data == other_data,
+            _ => true,
+        }
+    }
+
     /// This function replace any data a RFile has with the provided raw data.
     pub fn set_cached(&mut self, data: &[u8]) {
         self.data = RFileInnerData::Cached(data.to_vec());
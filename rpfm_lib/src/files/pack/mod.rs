@@ -732,6 +732,17 @@ impl Pack {
         *self.header.build_number()
     }
 
+    /// This function returns the game build this Pack is intended for, to pick definitions limited to specific game builds.
+    ///
+    /// That's the game version in its header if it has one, or the build of the game installed in the provided path if it doesn't.
+    /// See [GameInfo::game_build].
+    pub fn game_build(&self, game_info: &GameInfo, game_path: &Path) -> Option<u32> {
+        match self.game_version() {
+            0 => game_info.game_build(game_path),
+            game_version => Some(game_version),
+        }
+    }
+
     /// This function returns the tool that created the Pack. Max 8 characters, 00-padded.
     pub fn authoring_tool(&self) -> &str {
         self.header.authoring_tool()
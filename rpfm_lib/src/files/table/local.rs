@@ -32,6 +32,7 @@ impl Table {
             definition: definition.clone(),
             table_name: table_name.to_owned(),
             table_data: TableData::Local(table_data),
+            index: None,
         };
 
         Ok(table)
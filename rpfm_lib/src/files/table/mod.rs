@@ -41,7 +41,7 @@ mod local;
 /// This struct contains the data of a Table-like PackedFile after being decoded.
 ///
 /// This is for internal use. If you need to interact with this in any way, do it through the PackedFile that contains it, not directly.
-#[derive(Clone, Debug, PartialEq, Getters, Setters, Serialize, Deserialize)]
+#[derive(Clone, Debug, Getters, Setters, Serialize, Deserialize)]
 #[getset(get = "pub", set = "pub")]
 pub struct Table {
 
@@ -55,7 +55,29 @@ pub struct Table {
     definition_patch: DefinitionPatch,
 
     #[getset(skip)]
-    table_data: TableData
+    table_data: TableData,
+
+    /// Optional index over the key and reference columns of the table. Only for runtime use.
+    #[getset(skip)]
+    #[serde(skip)]
+    index: Option<Box<TableIndex>>,
+}
+
+/// This struct contains a hash index over some columns of a table, to speed up searches by value.
+///
+/// The index gets outdated every time the data or the definition of its table changes. Use [Table::update_index] to rebuild it.
+#[derive(Clone, Debug, Default, Getters)]
+#[getset(get = "pub")]
+pub struct TableIndex {
+
+    /// Names of the columns indexed in addition to the key and reference columns.
+    extra_columns: Vec<String>,
+
+    /// The index itself, in a column -> (value -> rows) format.
+    data: HashMap<usize, HashMap<String, Vec<usize>>>,
+
+    /// If the index needs to be rebuilt before using it.
+    is_outdated: bool,
 }
 
 /// Internal enum to hold the table's data.
@@ -424,6 +446,42 @@ impl DecodedData {
 // Implementations for `Table`.
 //----------------------------------------------------------------//
 
+/// PartialEq implementation of `Table`. We need this implementation to ignore the index, as it's only runtime data.
+impl PartialEq for Table {
+    fn eq(&self, other: &Self) -> bool {
+        self.table_name == other.table_name &&
+            self.definition == other.definition &&
+            self.definition_patch == other.definition_patch &&
+            self.table_data == other.table_data
+    }
+}
+
+/// Implementation of `TableIndex`.
+impl TableIndex {
+
+    /// This function returns the rows containing the provided value in the provided column, if the column is indexed and the value is in it.
+    pub fn rows(&self, column: usize, value: &str) -> Option<&[usize]> {
+        self.data.get(&column)?.get(value).map(|rows| rows.as_slice())
+    }
+
+    /// This function returns if the provided value is in the provided column.
+    ///
+    /// If the column is not indexed, it returns false.
+    pub fn contains(&self, column: usize, value: &str) -> bool {
+        self.data.get(&column).map(|values| values.contains_key(value)).unwrap_or(false)
+    }
+
+    /// This function returns the values of a column, with the rows containing each of them, if the column is indexed.
+    pub fn column(&self, column: usize) -> Option<&HashMap<String, Vec<usize>>> {
+        self.data.get(&column)
+    }
+
+    /// This function returns if the provided column is indexed.
+    pub fn is_column_indexed(&self, column: usize) -> bool {
+        self.data.contains_key(&column)
+    }
+}
+
 /// Implementation of `Table`.
 impl Table {
 
@@ -443,7 +501,8 @@ impl Table {
             definition: definition.clone(),
             definition_patch,
             table_name: table_name.to_owned(),
-            table_data
+            table_data,
+            index: None,
         }
     }
 
@@ -461,6 +520,7 @@ impl Table {
     ///
     /// Note that using this makes you responsible of keeping the structure of the table "valid".
     pub fn data_mut(&mut self) -> Result<&mut Vec<Vec<DecodedData>>> {
+        self.invalidate_index();
         match &mut self.table_data {
             TableData::Local(data) => Ok(data),
             TableData::Sql(_) => unimplemented!(),
@@ -535,6 +595,7 @@ impl Table {
 
         // Then, we finally replace our definition and our data.
         self.definition = new_definition.clone();
+        self.invalidate_index();
     }
 
     /// This function replaces the data of this table with the one provided.
@@ -563,6 +624,7 @@ impl Table {
 
                 // If we passed all the checks, replace the data.
                 *table_data = data.to_vec();
+                self.invalidate_index();
                 Ok(())
             }
 
@@ -576,6 +638,76 @@ impl Table {
         }
     }
 
+    /// This function enables the index of this table, and builds it.
+    ///
+    /// The index covers the key columns, the columns referencing other tables, and the extra columns provided.
+    /// Only supported for tables using the local backend.
+    pub fn enable_index(&mut self, extra_columns: &[String]) -> Result<()> {
+        self.index = Some(Box::new(TableIndex {
+            extra_columns: extra_columns.to_vec(),
+            data: HashMap::new(),
+            is_outdated: true,
+        }));
+
+        self.update_index()
+    }
+
+    /// This function disables the index of this table, freeing its memory.
+    pub fn disable_index(&mut self) {
+        self.index = None;
+    }
+
+    /// This function returns the index of this table, if it's enabled and up-to-date.
+    pub fn index(&self) -> Option<&TableIndex> {
+        match self.index {
+            Some(ref index) if !index.is_outdated => Some(index.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// This function rebuilds the index of this table if it's enabled and outdated.
+    pub fn update_index(&mut self) -> Result<()> {
+        let is_outdated = match self.index {
+            Some(ref index) => index.is_outdated,
+            None => false,
+        };
+
+        if is_outdated {
+            let patches = Some(&self.definition_patch);
+            let extra_columns = self.index.as_ref().map(|index| index.extra_columns.to_vec()).unwrap_or_default();
+            let columns = self.definition.fields_processed()
+                .iter()
+                .enumerate()
+                .filter(|(_, field)| field.is_key(patches) || field.is_reference().is_some() || extra_columns.iter().any(|column| column == field.name()))
+                .map(|(column, _)| column)
+                .collect::<Vec<_>>();
+
+            let mut data: HashMap<usize, HashMap<String, Vec<usize>>> = columns.iter().map(|column| (*column, HashMap::new())).collect();
+            for (row, cells) in self.data(&None)?.iter().enumerate() {
+                for column in &columns {
+                    if let Some(values) = data.get_mut(column) {
+                        values.entry(cells[*column].data_to_string().to_string()).or_default().push(row);
+                    }
+                }
+            }
+
+            if let Some(ref mut index) = self.index {
+                index.data = data;
+                index.is_outdated = false;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// This function marks the index of this table as outdated, if the table has one.
+    fn invalidate_index(&mut self) {
+        if let Some(ref mut index) = self.index {
+            index.data.clear();
+            index.is_outdated = true;
+        }
+    }
+
     pub fn len(&self, pool: Option<&Pool<SqliteConnectionManager>>) -> Result<usize> {
         match &self.table_data {
             TableData::Local(data) => Ok(data.len()),
@@ -839,16 +971,7 @@ impl Table {
         let entries = self.data(pool)?;
         let fields = self.definition.fields();
         let fields_processed = self.definition.fields_processed();
-
-        // Get the colour positions of the tables, if any.
-        let combined_colour_positions = fields.iter().filter_map(|field| {
-            if let Some(colour_group) = field.is_part_of_colour() {
-                let colour_split = field.name().rsplitn(2, '_').collect::<Vec<&str>>();
-                let colour_field_name: String = if colour_split.len() == 2 { format!("{}{}", colour_split[1].to_lowercase(), MERGE_COLOUR_POST) } else { format!("{}_{}", MERGE_COLOUR_NO_NAME.to_lowercase(), colour_group) };
-
-                self.definition.column_position_by_name(&colour_field_name).map(|x| (colour_field_name, x))
-            } else { None }
-        }).collect::<HashMap<String, usize>>();
+        let combined_colour_positions = Self::combined_colour_positions(&self.definition);
 
         for row in entries.iter() {
 
@@ -857,179 +980,216 @@ impl Table {
                 return Err(RLibError::TableRowWrongFieldCount(fields_processed.len(), row.len()))
             }
 
-            // The way we process it is, we iterate over the definition fields (because it's what we need to write)
-            // and write the fields getting only what we need from the table data.
-            let mut data_column = 0;
-            for field in fields {
-
-                // First special situation: join back split colour columns, if the field is a split colour.
-                if let Some(colour_group) = field.is_part_of_colour() {
-                    let field_name = field.name().to_lowercase();
-                    let colour_split = field_name.rsplitn(2, '_').collect::<Vec<&str>>();
-                    let colour_channel = colour_split[0];
-                    let colour_field_name = if colour_split.len() == 2 {
-                        format!("{}{}", colour_split[1], MERGE_COLOUR_POST)
-                    } else {
-                        format!("{}_{}", MERGE_COLOUR_NO_NAME.to_lowercase(), colour_group)
-                    };
-
-                    if let Some(data_column) = combined_colour_positions.get(&colour_field_name) {
-                        match &row[*data_column] {
-                            DecodedData::ColourRGB(field_data) => {
-
-                                // Encode the full colour, then grab the byte of our field.
-                                let mut encoded = vec![];
-                                encoded.write_string_colour_rgb(field_data)?;
-
-                                let field_data =
-                                    if colour_channel == "r" || colour_channel == "red" { encoded[2] }
-                                    else if colour_channel == "g" || colour_channel == "green" { encoded[1] }
-                                    else if colour_channel == "b" || colour_channel == "blue" { encoded[0] }
-                                else { 0 };
-
-                                // Only these types can be split colours.
-                                match field.field_type() {
-                                    FieldType::I16 => data.write_i16(field_data as i16)?,
-                                    FieldType::I32 => data.write_i32(field_data as i32)?,
-                                    FieldType::I64 => data.write_i64(field_data as i64)?,
-                                    FieldType::F32 => data.write_f32(field_data as f32)?,
-                                    FieldType::F64 => data.write_f64(field_data as f64)?,
-                                    _ => return Err(RLibError::EncodingTableWrongFieldType(FieldType::from(&row[*data_column]).to_string(), field.field_type().to_string()))
-                                }
+            // Then we turn the row back into the layout of the definition fields, because it's what we need to write.
+            let raw_row = Self::row_to_raw(fields, &combined_colour_positions, row, schema_patches)?;
+            for (field, field_data) in fields.iter().zip(raw_row.iter()) {
+                match &**field_data {
+                    DecodedData::Boolean(field_data) => data.write_bool(*field_data)?,
+                    DecodedData::F32(field_data) => data.write_f32(*field_data)?,
+                    DecodedData::F64(field_data) => data.write_f64(*field_data)?,
+                    DecodedData::I16(field_data) => data.write_i16(*field_data)?,
+                    DecodedData::I32(field_data) => data.write_i32(*field_data)?,
+                    DecodedData::I64(field_data) => data.write_i64(*field_data)?,
+                    DecodedData::ColourRGB(field_data) => data.write_string_colour_rgb(field_data)?,
+                    DecodedData::OptionalI16(field_data) => {
+                        data.write_bool(true)?;
+                        data.write_i16(*field_data)?
+                    },
+                    DecodedData::OptionalI32(field_data) => {
+                        data.write_bool(true)?;
+                        data.write_i32(*field_data)?
+                    },
+                    DecodedData::OptionalI64(field_data) => {
+                        data.write_bool(true)?;
+                        data.write_i64(*field_data)?
+                    },
 
+                    // String fields may need preprocessing applied to them before encoding.
+                    DecodedData::StringU8(field_data) |
+                    DecodedData::StringU16(field_data) |
+                    DecodedData::OptionalStringU8(field_data) |
+                    DecodedData::OptionalStringU16(field_data) => {
+                        match field.field_type() {
+                            FieldType::StringU8 => data.write_sized_string_u8(&Self::unescape_special_chars(field_data))?,
+                            FieldType::StringU16 => data.write_sized_string_u16(&Self::unescape_special_chars(field_data))?,
+                            FieldType::OptionalStringU8 => data.write_optional_string_u8(&Self::unescape_special_chars(field_data))?,
+                            FieldType::OptionalStringU16 => data.write_optional_string_u16(&Self::unescape_special_chars(field_data))?,
+                            _ => return Err(RLibError::EncodingTableWrongFieldType(field_data.to_string(), field.field_type().to_string()))
+                        }
+                    }
 
-                            },
-                            _ => return Err(RLibError::EncodingTableWrongFieldType(FieldType::from(&row[*data_column]).to_string(), field.field_type().to_string()))
+                    // Make sure we at least have the counter before writing. We need at least that.
+                    DecodedData::SequenceU16(field_data) => {
+                        if field_data.len() < 2 {
+                            data.write_all(&[0, 0])?
+                        } else {
+                            data.write_all(field_data)?
+                        }
+                    },
+                    DecodedData::SequenceU32(field_data) => {
+                        if field_data.len() < 4 {
+                            data.write_all(&[0, 0, 0, 0])?
+                        } else {
+                            data.write_all(field_data)?
                         }
                     }
                 }
+            }
+        }
 
-                // Second special situation: bitwise columns.
-                else if field.is_bitwise() > 1 {
-                    let mut field_data: i64 = 0;
+        Ok(())
+    }
 
-                    // Bitwise columns are always consecutive booleans.
-                    for bitwise_column in 0..field.is_bitwise() {
-                        if let DecodedData::Boolean(boolean) = row[data_column] {
-                            if boolean {
-                                field_data |= 1 << bitwise_column;
-                            }
-                        }
+    /// This function returns the positions of the combined colour columns of the provided definition, by column name.
+    pub(crate) fn combined_colour_positions(definition: &Definition) -> HashMap<String, usize> {
+        definition.fields().iter().filter_map(|field| {
+            if let Some(colour_group) = field.is_part_of_colour() {
+                let colour_split = field.name().rsplitn(2, '_').collect::<Vec<&str>>();
+                let colour_field_name: String = if colour_split.len() == 2 { format!("{}{}", colour_split[1].to_lowercase(), MERGE_COLOUR_POST) } else { format!("{}_{}", MERGE_COLOUR_NO_NAME.to_lowercase(), colour_group) };
 
-                        else {
-                            return Err(RLibError::EncodingTableWrongFieldType(FieldType::from(&row[data_column]).to_string(), field.field_type().to_string()))
-                        }
+                definition.column_position_by_name(&colour_field_name).map(|x| (colour_field_name, x))
+            } else { None }
+        }).collect::<HashMap<String, usize>>()
+    }
 
-                        data_column += 1;
+    /// This function turns a row with the processed layout of a definition into one with the layout of its fields, the one used to encode it.
+    ///
+    /// This means joining back the bitwise columns into their integer, turning the enum values back into their integer,
+    /// and splitting the combined colour columns back into their channels. The rest of the values are returned as they are.
+    ///
+    /// - `fields`: fields of the definition, not the processed ones.
+    /// - `combined_colour_positions`: positions of the combined colour columns, as returned by `combined_colour_positions`.
+    pub(crate) fn row_to_raw<'a>(fields: &[Field], combined_colour_positions: &HashMap<String, usize>, row: &'a [DecodedData], schema_patches: &Option<&DefinitionPatch>) -> Result<Vec<Cow<'a, DecodedData>>> {
+        let mut raw_row = Vec::with_capacity(fields.len());
+        let mut data_column = 0;
+        for field in fields {
+
+            // First special situation: join back split colour columns, if the field is a split colour.
+            if let Some(colour_group) = field.is_part_of_colour() {
+                let field_name = field.name().to_lowercase();
+                let colour_split = field_name.rsplitn(2, '_').collect::<Vec<&str>>();
+                let colour_channel = colour_split[0];
+                let colour_field_name = if colour_split.len() == 2 {
+                    format!("{}{}", colour_split[1], MERGE_COLOUR_POST)
+                } else {
+                    format!("{}_{}", MERGE_COLOUR_NO_NAME.to_lowercase(), colour_group)
+                };
+
+                let field_data = match combined_colour_positions.get(&colour_field_name) {
+                    Some(data_column) => match &row[*data_column] {
+                        DecodedData::ColourRGB(field_data) => {
+
+                            // Encode the full colour, then grab the byte of our field.
+                            let mut encoded = vec![];
+                            encoded.write_string_colour_rgb(field_data)?;
+
+                            if colour_channel == "r" || colour_channel == "red" { encoded[2] }
+                            else if colour_channel == "g" || colour_channel == "green" { encoded[1] }
+                            else if colour_channel == "b" || colour_channel == "blue" { encoded[0] }
+                            else { 0 }
+                        },
+                        _ => return Err(RLibError::EncodingTableWrongFieldType(FieldType::from(&row[*data_column]).to_string(), field.field_type().to_string()))
+                    }
+                    None => 0,
+                };
+
+                // Only these types can be split colours.
+                raw_row.push(Cow::Owned(match field.field_type() {
+                    FieldType::I16 => DecodedData::I16(field_data as i16),
+                    FieldType::I32 => DecodedData::I32(field_data as i32),
+                    FieldType::I64 => DecodedData::I64(field_data as i64),
+                    FieldType::F32 => DecodedData::F32(field_data as f32),
+                    FieldType::F64 => DecodedData::F64(field_data as f64),
+                    _ => return Err(RLibError::EncodingTableWrongFieldType(FieldType::ColourRGB.to_string(), field.field_type().to_string()))
+                }));
+            }
+
+            // Second special situation: bitwise columns.
+            else if field.is_bitwise() > 1 {
+                let mut field_data: i64 = 0;
+
+                // Bitwise columns are always consecutive booleans.
+                for bitwise_column in 0..field.is_bitwise() {
+                    if let DecodedData::Boolean(boolean) = row[data_column] {
+                        if boolean {
+                            field_data |= 1 << bitwise_column;
+                        }
                     }
 
-                    // Only integer types can be bitwise.
-                    match field.field_type() {
-                        FieldType::I16 => data.write_i16(field_data as i16)?,
-                        FieldType::I32 => data.write_i32(field_data as i32)?,
-                        FieldType::I64 => data.write_i64(field_data)?,
-                        _ => return Err(RLibError::EncodingTableWrongFieldType(FieldType::from(&row[data_column]).to_string(), field.field_type().to_string()))
+                    else {
+                        return Err(RLibError::EncodingTableWrongFieldType(FieldType::from(&row[data_column]).to_string(), field.field_type().to_string()))
                     }
+
+                    data_column += 1;
                 }
 
-                // If no special behavior has been needed, encode the field as a normal field, except for strings.
-                else {
-
-                    match &row[data_column] {
-                        DecodedData::Boolean(field_data) => data.write_bool(*field_data)?,
-                        DecodedData::F32(field_data) => data.write_f32(*field_data)?,
-                        DecodedData::F64(field_data) => data.write_f64(*field_data)?,
-                        DecodedData::I16(field_data) => data.write_i16(*field_data)?,
-                        DecodedData::I32(field_data) => data.write_i32(*field_data)?,
-                        DecodedData::I64(field_data) => data.write_i64(*field_data)?,
-                        DecodedData::ColourRGB(field_data) => data.write_string_colour_rgb(field_data)?,
-                        DecodedData::OptionalI16(field_data) => {
-                            data.write_bool(true)?;
-                            data.write_i16(*field_data)?
-                        },
-                        DecodedData::OptionalI32(field_data) => {
-                            data.write_bool(true)?;
-                            data.write_i32(*field_data)?
-                        },
-                        DecodedData::OptionalI64(field_data) => {
-                            data.write_bool(true)?;
-                            data.write_i64(*field_data)?
-                        },
+                // Only integer types can be bitwise.
+                raw_row.push(Cow::Owned(match field.field_type() {
+                    FieldType::I16 => DecodedData::I16(field_data as i16),
+                    FieldType::I32 => DecodedData::I32(field_data as i32),
+                    FieldType::I64 => DecodedData::I64(field_data),
+                    _ => return Err(RLibError::EncodingTableWrongFieldType(FieldType::Boolean.to_string(), field.field_type().to_string()))
+                }));
+            }
 
-                        // String fields may need preprocessing applied to them before encoding.
-                        DecodedData::StringU8(field_data) |
-                        DecodedData::StringU16(field_data) |
-                        DecodedData::OptionalStringU8(field_data) |
-                        DecodedData::OptionalStringU16(field_data) => {
-
-                            // String files may be representations of enums (as integer => string) for ease of use.
-                            // If so, we need to find the underlying integer key of our string and encode that.
-                            if !field.enum_values().is_empty() {
-                                let field_data = match field.enum_values()
-                                    .iter()
-                                    .find_map(|(x, y)|
-                                        if y.to_lowercase() == field_data.to_lowercase() { Some(x) } else { None }) {
-                                    Some(value) => {
-                                        match field.field_type() {
-                                            FieldType::I16 => DecodedData::I16(*value as i16),
-                                            FieldType::I32 => DecodedData::I32(*value),
-                                            FieldType::I64 => DecodedData::I64(*value as i64),
-                                            _ => return Err(RLibError::EncodingTableWrongFieldType(field_data.to_string(), field.field_type().to_string()))
-                                        }
-                                    }
-                                    None => match row[data_column].convert_between_types(field.field_type()) {
-                                        Ok(data) => data,
-                                        Err(_) => {
-                                            let default_value = field.default_value(*schema_patches);
-                                            DecodedData::new_from_type_and_value(field.field_type(), &default_value)
-                                        }
-                                    }
-                                };
-
-                                // If there are no problems, encode the data.
-                                match field_data {
-                                    DecodedData::I16(field_data) => data.write_i16(field_data)?,
-                                    DecodedData::I32(field_data) => data.write_i32(field_data)?,
-                                    DecodedData::I64(field_data) => data.write_i64(field_data)?,
-                                    _ => return Err(RLibError::EncodingTableWrongFieldType(field_data.data_to_string().to_string(), field.field_type().to_string()))
-                                }
-                            }
-                            else {
-
-                                // If there are no problems, encode the data.
-                                match field.field_type() {
-                                    FieldType::StringU8 => data.write_sized_string_u8(&Self::unescape_special_chars(field_data))?,
-                                    FieldType::StringU16 => data.write_sized_string_u16(&Self::unescape_special_chars(field_data))?,
-                                    FieldType::OptionalStringU8 => data.write_optional_string_u8(&Self::unescape_special_chars(field_data))?,
-                                    FieldType::OptionalStringU16 => data.write_optional_string_u16(&Self::unescape_special_chars(field_data))?,
-                                    _ => return Err(RLibError::EncodingTableWrongFieldType(field_data.to_string(), field.field_type().to_string()))
-                                }
-                            }
+            // Third special situation: strings that are representations of enums (as integer => string) for ease of use.
+            // If so, we need to find the underlying integer key of our string.
+            else if let (false, DecodedData::StringU8(field_data) | DecodedData::StringU16(field_data) | DecodedData::OptionalStringU8(field_data) | DecodedData::OptionalStringU16(field_data)) = (field.enum_values().is_empty(), &row[data_column]) {
+                let field_data = match field.enum_values()
+                    .iter()
+                    .find_map(|(x, y)|
+                        if y.to_lowercase() == field_data.to_lowercase() { Some(x) } else { None }) {
+                    Some(value) => {
+                        match field.field_type() {
+                            FieldType::I16 => DecodedData::I16(*value as i16),
+                            FieldType::I32 => DecodedData::I32(*value),
+                            FieldType::I64 => DecodedData::I64(*value as i64),
+                            _ => return Err(RLibError::EncodingTableWrongFieldType(field_data.to_string(), field.field_type().to_string()))
                         }
-
-                        // Make sure we at least have the counter before writing. We need at least that.
-                        DecodedData::SequenceU16(field_data) => {
-                            if field_data.len() < 2 {
-                                data.write_all(&[0, 0])?
-                            } else {
-                                data.write_all(field_data)?
-                            }
-                        },
-                        DecodedData::SequenceU32(field_data) => {
-                            if field_data.len() < 4 {
-                                data.write_all(&[0, 0, 0, 0])?
-                            } else {
-                                data.write_all(field_data)?
-                            }
+                    }
+                    None => match row[data_column].convert_between_types(field.field_type()) {
+                        Ok(data) => data,
+                        Err(_) => {
+                            let default_value = field.default_value(*schema_patches);
+                            DecodedData::new_from_type_and_value(field.field_type(), &default_value)
                         }
                     }
+                };
 
-                    data_column += 1;
+                match field_data {
+                    DecodedData::I16(_) | DecodedData::I32(_) | DecodedData::I64(_) => raw_row.push(Cow::Owned(field_data)),
+                    _ => return Err(RLibError::EncodingTableWrongFieldType(field_data.data_to_string().to_string(), field.field_type().to_string()))
                 }
+
+                data_column += 1;
+            }
+
+            // If no special behavior has been needed, keep the field as it is.
+            else {
+                raw_row.push(Cow::Borrowed(&row[data_column]));
+                data_column += 1;
             }
         }
 
-        Ok(())
+        Ok(raw_row)
+    }
+
+    /// This function turns a row with the layout of the provided fields, the one used to decode it, into one with their processed layout.
+    ///
+    /// This is the same processing done when decoding a table: bitwise columns are split into boolean columns,
+    /// enum integers are turned into their values, and colour channels are combined into colour columns.
+    ///
+    /// - `fields`: fields of the definition, not the processed ones.
+    pub(crate) fn row_from_raw(fields: &[Field], raw_row: Vec<DecodedData>) -> Result<Vec<DecodedData>> {
+        let mut split_colours: BTreeMap<u8, HashMap<String, u8>> = BTreeMap::new();
+        let mut row_data = Vec::with_capacity(fields.len());
+        for (field, field_data) in fields.iter().zip(raw_row) {
+            Self::decode_field_postprocess(&mut row_data, field_data, field, &mut split_colours);
+        }
+
+        Self::decode_row_postprocess(&mut row_data, &mut split_colours)?;
+
+        Ok(row_data)
     }
 
     /// This function returns the data stored in the table.
@@ -1269,6 +1429,12 @@ impl Table {
         let mut row_indexes = vec![];
 
         let column_index = self.column_position_by_name(column_name)?;
+
+        // If the column is indexed, use the index instead of going through all the rows.
+        if let Some(index) = self.index().filter(|index| index.is_column_indexed(column_index)) {
+            return index.rows(column_index, data).map(|rows| (column_index, rows.to_vec()));
+        }
+
         for (row_index, row) in self.data(&None).ok()?.iter().enumerate() {
             if let Some(cell_data) = row.get(column_index) {
                 if cell_data.data_to_string() == data {
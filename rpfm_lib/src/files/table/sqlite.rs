@@ -62,6 +62,7 @@ impl Table {
             definition_patch: definition_patch.clone(),
             table_name: table_name.to_owned(),
             table_data,
+            index: None,
         };
 
         Ok(table)
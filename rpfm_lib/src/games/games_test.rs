@@ -0,0 +1,53 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! Module containing tests for the games' info.
+
+use super::*;
+use super::supported_games::{KEY_WARHAMMER_3, SupportedGames};
+
+#[test]
+fn test_dependencies_cache_file_name_by_game_version() {
+    let games = SupportedGames::default();
+    let game_info = games.game(KEY_WARHAMMER_3).unwrap();
+
+    assert_eq!(game_info.dependencies_cache_file_name_by_game_version(0), "wh3.pak2");
+    assert_eq!(game_info.dependencies_cache_file_name_by_game_version(0x0104000C), "wh3_17039372.pak2");
+}
+
+#[test]
+fn test_game_version_from_dependencies_cache_file_name() {
+    let games = SupportedGames::default();
+    let game_info = games.game(KEY_WARHAMMER_3).unwrap();
+
+    assert_eq!(game_info.game_version_from_dependencies_cache_file_name("wh3.pak2"), Some(0));
+    assert_eq!(game_info.game_version_from_dependencies_cache_file_name("wh3_17039372.pak2"), Some(0x0104000C));
+    assert_eq!(game_info.game_version_from_dependencies_cache_file_name(&game_info.dependencies_cache_file_name_by_game_version(0x01FF0203)), Some(0x01FF0203));
+
+    // Only versioned caches of this game are valid, and 0 is reserved for the unversioned one.
+    assert_eq!(game_info.game_version_from_dependencies_cache_file_name("wh3_0.pak2"), None);
+    assert_eq!(game_info.game_version_from_dependencies_cache_file_name("wh3_.pak2"), None);
+    assert_eq!(game_info.game_version_from_dependencies_cache_file_name("wh3_1.4.0.12.pak2"), None);
+    assert_eq!(game_info.game_version_from_dependencies_cache_file_name("wh3-17039372.pak2"), None);
+    assert_eq!(game_info.game_version_from_dependencies_cache_file_name("wh3_17039372.tmp"), None);
+    assert_eq!(game_info.game_version_from_dependencies_cache_file_name("wh3_17039372.pak2.tmp"), None);
+    assert_eq!(game_info.game_version_from_dependencies_cache_file_name("wh2_17039372.pak2"), None);
+    assert_eq!(game_info.game_version_from_dependencies_cache_file_name("wh3.pak1"), None);
+}
+
+#[test]
+fn test_game_build_without_exe() {
+    let games = SupportedGames::default();
+    let game_path = Path::new("/rpfm_test_game_build_without_exe");
+
+    // Without the exe there's no build to read, for any game.
+    assert_eq!(games.game(KEY_WARHAMMER_3).unwrap().game_build(game_path), None);
+    assert_eq!(games.game(KEY_TROY).unwrap().game_version_number(game_path), None);
+}
@@ -33,6 +33,8 @@ pub mod manifest;
 pub mod pfh_file_type;
 pub mod pfh_version;
 
+#[cfg(test)] mod games_test;
+
 const BRAZILIAN: &str = "br";
 const SIMPLIFIED_CHINESE: &str = "cn";
 const CZECH: &str = "cz";
@@ -229,6 +231,38 @@ impl GameInfo {
         &self.dependencies_cache_file_name
     }
 
+    /// This function returns this Game's dependencies cache file name for the provided game version, as returned by [GameInfo::game_build].
+    ///
+    /// A game version of 0 means the version is unknown, and returns the default dependencies cache file name.
+    pub fn dependencies_cache_file_name_by_game_version(&self, game_version: u32) -> String {
+        if game_version == 0 {
+            return self.dependencies_cache_file_name.to_owned()
+        }
+
+        match self.dependencies_cache_file_name.rsplit_once('.') {
+            Some((stem, extension)) => format!("{stem}_{game_version}.{extension}"),
+            None => format!("{}_{game_version}", self.dependencies_cache_file_name),
+        }
+    }
+
+    /// This function returns the game version a dependencies cache file name is for, if it's a dependencies cache file name of this Game.
+    ///
+    /// The default dependencies cache file name returns 0.
+    pub fn game_version_from_dependencies_cache_file_name(&self, file_name: &str) -> Option<u32> {
+        if file_name == self.dependencies_cache_file_name {
+            return Some(0);
+        }
+
+        let (stem, extension) = self.dependencies_cache_file_name.rsplit_once('.').unwrap_or((&self.dependencies_cache_file_name, ""));
+        let version = if extension.is_empty() {
+            file_name.strip_prefix(stem)?
+        } else {
+            file_name.strip_prefix(stem)?.strip_suffix(extension)?.strip_suffix('.')?
+        };
+
+        version.strip_prefix('_')?.parse().ok().filter(|version| *version != 0)
+    }
+
     /// This function returns this Game's raw_db_version, used to identify how to process AssKit table files for this game.
     pub fn raw_db_version(&self) -> i16 {
         self.raw_db_version
@@ -668,40 +702,46 @@ impl GameInfo {
     }
 
     /// This function gets the version number of the exe for the current GameSelected, if it exists.
+    ///
+    /// Only Troy stores this number in the header of its Packs, so it's only returned for Troy. For other uses, see [GameInfo::game_build].
     pub fn game_version_number(&self, game_path: &Path) -> Option<u32> {
         match self.game_key_name() {
-            KEY_TROY => {
-                let exe_path = self.executable_path(game_path)?;
-                if exe_path.is_file() {
-                    let mut data = vec![];
-                    let mut file = BufReader::new(File::open(exe_path).ok()?);
-                    file.read_to_end(&mut data).ok()?;
-
-                    let version_info = pe_version_info(&data).ok()?;
-                    let version_info = version_info.fixed()?;
-                    let mut version: u32 = 0;
-
-                    // The CA format is limited so these can only be u8 when encoded, so we can safetly convert them.
-                    let major = version_info.dwFileVersion.Major as u32;
-                    let minor = version_info.dwFileVersion.Minor as u32;
-                    let patch = version_info.dwFileVersion.Patch as u32;
-                    let build = version_info.dwFileVersion.Build as u32;
-
-                    version += major << 24;
-                    version += minor << 16;
-                    version += patch << 8;
-                    version += build;
-                    Some(version)
-                }
-
-                // If we have no exe, return a default value.
-                else {
-                    None
-                }
+            KEY_TROY => self.game_build(game_path),
+            _ => None,
+        }
+    }
 
-            }
+    /// This function gets the build of the game installed in the provided path, from the version of its exe, if it exists.
+    ///
+    /// The build is encoded as `major << 24 | minor << 16 | patch << 8 | build`, the same format Packs use for the game version in their header.
+    /// As each part only has 8 bits, parts bigger than 255 are saturated.
+    pub fn game_build(&self, game_path: &Path) -> Option<u32> {
+        let exe_path = self.executable_path(game_path)?;
+        if exe_path.is_file() {
+            let mut data = vec![];
+            let mut file = BufReader::new(File::open(exe_path).ok()?);
+            file.read_to_end(&mut data).ok()?;
+
+            let version_info = pe_version_info(&data).ok()?;
+            let version_info = version_info.fixed()?;
+            let mut version: u32 = 0;
+
+            // The CA format is limited so these can only be u8 when encoded. Only Troy guarantees that, so saturate them for other games.
+            let major = (version_info.dwFileVersion.Major as u32).min(0xFF);
+            let minor = (version_info.dwFileVersion.Minor as u32).min(0xFF);
+            let patch = (version_info.dwFileVersion.Patch as u32).min(0xFF);
+            let build = (version_info.dwFileVersion.Build as u32).min(0xFF);
+
+            version += major << 24;
+            version += minor << 16;
+            version += patch << 8;
+            version += build;
+            Some(version)
+        }
 
-            _ => None,
+        // If we have no exe, return a default value.
+        else {
+            None
         }
     }
 
@@ -0,0 +1,311 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! Module containing tests for the import and export of Assembly Kit raw tables.
+
+use std::env::temp_dir;
+use std::fs::remove_dir_all;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use crate::files::{Decodeable, Encodeable};
+use crate::files::loc::Loc;
+use crate::files::table::Table;
+use crate::games::pfh_version::PFHVersion;
+
+use super::*;
+use super::table_data::{RawTableField, RawTableRow};
+
+fn field(name: &str, field_type: FieldType, is_key: bool, default_value: Option<&str>) -> Field {
+    Field::new(name.to_owned(), field_type, is_key, default_value.map(|value| value.to_owned()), false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None)
+}
+
+/// This function returns an enum field, a bitwise field and the fields of a split colour, which are processed when decoding the table.
+fn processed_fields() -> Vec<Field> {
+    let enum_values = BTreeMap::from([(0, "melee".to_owned()), (1, "missile".to_owned()), (2, "cavalry".to_owned())]);
+    let colour = |name: &str| Field::new(name.to_owned(), FieldType::I32, false, Some("0".to_owned()), false, None, None, None, String::new(), 0, 0, BTreeMap::new(), Some(1));
+    vec![
+        Field::new("unit_type".to_owned(), FieldType::I32, false, Some("0".to_owned()), false, None, None, None, String::new(), 0, 0, enum_values, None),
+        Field::new("flags".to_owned(), FieldType::I32, false, Some("0".to_owned()), false, None, None, None, String::new(), 0, 3, BTreeMap::new(), None),
+        colour("banner_r"),
+        colour("banner_g"),
+        colour("banner_b"),
+    ]
+}
+
+fn raw_db_path(test_name: &str) -> PathBuf {
+    let path = temp_dir().join(format!("rpfm_{test_name}"));
+    let _ = remove_dir_all(&path);
+    path
+}
+
+/// This function returns a schema with a units table, and a Pack with a file of that table.
+fn units_pack(rows: &[(&str, &str)]) -> (Schema, Pack) {
+    let mut definition = Definition::new(1, None);
+    definition.set_fields(vec![
+        field("key", FieldType::StringU8, true, None),
+        field("cost", FieldType::StringU8, false, None),
+    ]);
+
+    let mut schema = Schema::default();
+    schema.add_definition("units_tables", &definition);
+
+    let mut table = DB::new(&definition, None, "units_tables", false);
+    table.set_data(None, &rows.iter()
+        .map(|(key, cost)| vec![DecodedData::StringU8(key.to_string()), DecodedData::StringU8(cost.to_string())])
+        .collect::<Vec<_>>()).unwrap();
+
+    let mut pack = Pack::new_with_name_and_version("test.pack", PFHVersion::PFH5);
+    pack.insert(RFile::new_from_decoded(&RFileDecoded::DB(table), 0, "db/units_tables/data")).unwrap();
+    (schema, pack)
+}
+
+#[test]
+fn test_import_raw_tables_report() {
+    let raw_db_path = raw_db_path("test_import_raw_tables_report");
+    let (schema, mut pack) = units_pack(&[("spearmen", "500"), ("halberdiers", "lots")]);
+    export_raw_tables(&mut pack, &schema, &raw_db_path, 2, &[]).unwrap();
+
+    // The newer definition changes the type of a column and adds another one.
+    let mut definition = Definition::new(2, None);
+    definition.set_fields(vec![
+        field("key", FieldType::StringU8, true, None),
+        field("cost", FieldType::I32, false, Some("100")),
+        field("is_naval", FieldType::Boolean, false, Some("true")),
+    ]);
+
+    let mut new_schema = schema.clone();
+    new_schema.add_definition("units_tables", &definition);
+
+    let mut new_pack = Pack::new_with_name_and_version("test.pack", PFHVersion::PFH5);
+    let report = import_raw_tables(&mut new_pack, &new_schema, &raw_db_path, 2, &["units_tables".to_owned()], "imported").unwrap();
+    assert_eq!(report.paths(), &vec![ContainerPath::File("db/units_tables/imported".to_owned())]);
+    assert!(report.has_problems());
+    assert_eq!(report.missing_columns().get("units_tables"), Some(&vec!["is_naval".to_owned()]));
+    assert_eq!(report.invalid_values().get("units_tables"), Some(&vec![(1, "cost".to_owned(), "lots".to_owned())]));
+    assert!(report.to_string().contains("Row 2, column cost: \"lots\"."));
+
+    // Missing and invalid values get the default value of their column.
+    let file = new_pack.file("db/units_tables/imported").unwrap();
+    let table = match file.decoded().unwrap() {
+        RFileDecoded::DB(table) => table,
+        _ => unreachable!(),
+    };
+
+    assert_eq!(table.data(&None).unwrap().to_vec(), vec![
+        vec![DecodedData::StringU8("spearmen".to_owned()), DecodedData::I32(500), DecodedData::Boolean(true)],
+        vec![DecodedData::StringU8("halberdiers".to_owned()), DecodedData::I32(100), DecodedData::Boolean(true)],
+    ]);
+
+    // Tables imported as they are report no problems.
+    let report = import_raw_tables(&mut new_pack, &schema, &raw_db_path, 2, &["units_tables".to_owned()], "imported").unwrap();
+    assert!(!report.has_problems());
+    assert!(report.to_string().is_empty());
+
+    // Numeric raw columns with values that cannot be parsed are reported too, and so are the raw columns the definition doesn't have.
+    let raw_db_path_numeric = self::raw_db_path("test_import_raw_tables_report_numeric");
+    let mut numeric_schema = Schema::default();
+    numeric_schema.add_definition("units_tables", &definition);
+
+    let mut numeric_table = DB::new(&definition, None, "units_tables", false);
+    numeric_table.set_data(None, &[
+        vec![DecodedData::StringU8("spearmen".to_owned()), DecodedData::I32(500), DecodedData::Boolean(false)],
+    ]).unwrap();
+
+    let mut numeric_pack = Pack::new_with_name_and_version("test.pack", PFHVersion::PFH5);
+    numeric_pack.insert(RFile::new_from_decoded(&RFileDecoded::DB(numeric_table), 0, "db/units_tables/data")).unwrap();
+    for path in export_raw_tables(&mut numeric_pack, &numeric_schema, &raw_db_path_numeric, 2, &[]).unwrap() {
+        let data = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, data.replace(">500<", ">5OO<")).unwrap();
+    }
+
+    let mut short_definition = Definition::new(3, None);
+    short_definition.set_fields(vec![
+        field("key", FieldType::StringU8, true, None),
+        field("cost", FieldType::I32, false, Some("100")),
+    ]);
+
+    let mut short_schema = Schema::default();
+    short_schema.add_definition("units_tables", &short_definition);
+
+    let report = import_raw_tables(&mut new_pack, &short_schema, &raw_db_path_numeric, 2, &["units_tables".to_owned()], "numeric").unwrap();
+    assert_eq!(report.invalid_values().get("units_tables"), Some(&vec![(0, "cost".to_owned(), "5OO".to_owned())]));
+    assert_eq!(report.dropped_columns().get("units_tables"), Some(&vec!["is_naval".to_owned()]));
+    assert!(report.to_string().contains("units_tables: columns of the raw table not found in the definition, not imported: is_naval."));
+
+    remove_dir_all(&raw_db_path_numeric).unwrap();
+
+    // Tables not in the Assembly Kit are errors.
+    assert!(matches!(import_raw_tables(&mut new_pack, &schema, &raw_db_path, 2, &["missing_tables".to_owned()], "imported"), Err(RLibError::AssemblyKitTableNotFound(_))));
+    assert!(matches!(import_raw_tables(&mut new_pack, &schema, &raw_db_path, 0, &["units_tables".to_owned()], "imported"), Err(RLibError::AssemblyKitUnsupportedVersion(0))));
+
+    remove_dir_all(&raw_db_path).unwrap();
+}
+
+#[test]
+fn test_import_raw_tables_processed_fields() {
+    let raw_db_path = raw_db_path("test_import_raw_tables_processed_fields");
+
+    let mut definition = Definition::new(1, None);
+    let mut fields = vec![field("key", FieldType::StringU8, true, None)];
+    fields.append(&mut processed_fields());
+    definition.set_fields(fields);
+
+    let mut schema = Schema::default();
+    schema.add_definition("units_tables", &definition);
+
+    // The raw table stores the integers of the enum and bitwise columns, and the channels of the colour in separate columns.
+    let raw_row = |values: &[(&str, &str)]| RawTableRow {
+        fields: values.iter()
+            .map(|(name, value)| RawTableField { field_name: name.to_string(), field_data: value.to_string(), state: None })
+            .collect(),
+    };
+
+    let raw_definition = RawDefinition::from_definition(&definition, "units_tables");
+    let raw_table = RawTable {
+        definition: Some(raw_definition.clone()),
+        rows: vec![
+            raw_row(&[("key", "spearmen"), ("unit_type", "2"), ("flags", "6"), ("banner_r", "255"), ("banner_g", "128"), ("banner_b", "0")]),
+            raw_row(&[("key", "archers"), ("unit_type", "1"), ("flags", "1"), ("banner_r", "10"), ("banner_g", "20"), ("banner_b", "30")]),
+        ],
+    };
+
+    DirBuilder::new().recursive(true).create(&raw_db_path).unwrap();
+    raw_definition.write(&raw_db_path, 2).unwrap();
+    raw_table.write(&raw_db_path, 2).unwrap();
+
+    // The raw columns match the fields of the definition, so nothing is reported, and the data gets the processed layout.
+    let mut pack = Pack::new_with_name_and_version("test.pack", PFHVersion::PFH5);
+    let report = import_raw_tables(&mut pack, &schema, &raw_db_path, 2, &["units_tables".to_owned()], "imported").unwrap();
+    assert!(!report.has_problems(), "{report}");
+
+    let mut table = match pack.file("db/units_tables/imported").unwrap().decoded().unwrap() {
+        RFileDecoded::DB(table) => table.clone(),
+        _ => unreachable!(),
+    };
+
+    let expected = vec![
+        vec![DecodedData::StringU8("spearmen".to_owned()), DecodedData::StringU8("cavalry".to_owned()), DecodedData::Boolean(false), DecodedData::Boolean(true), DecodedData::Boolean(true), DecodedData::ColourRGB("FF8000".to_owned())],
+        vec![DecodedData::StringU8("archers".to_owned()), DecodedData::StringU8("missile".to_owned()), DecodedData::Boolean(true), DecodedData::Boolean(false), DecodedData::Boolean(false), DecodedData::ColourRGB("0A141E".to_owned())],
+    ];
+    assert_eq!(table.data(&None).unwrap().to_vec(), expected);
+
+    // The imported table encodes back to the same values.
+    let mut encoded = vec![];
+    table.encode(&mut encoded, &None).unwrap();
+
+    let mut decodeable_extra_data = DecodeableExtraData::default();
+    decodeable_extra_data.set_table_name(Some("units_tables"));
+    decodeable_extra_data.set_schema(Some(&schema));
+    let decoded = DB::decode(&mut Cursor::new(encoded), &Some(decodeable_extra_data)).unwrap();
+    assert_eq!(decoded.data(&None).unwrap().to_vec(), expected);
+
+    remove_dir_all(&raw_db_path).unwrap();
+}
+
+#[test]
+fn test_export_raw_tables_round_trip() {
+    let raw_db_path = raw_db_path("test_export_raw_tables_round_trip");
+
+    let mut definition = Definition::new(1, None);
+    let mut fields = vec![
+        field("key", FieldType::StringU8, true, None),
+        field("category", FieldType::StringU8, true, None),
+        field("cost", FieldType::I32, false, None),
+        field("ratio", FieldType::F32, false, None),
+        field("is_naval", FieldType::Boolean, false, None),
+    ];
+    fields.append(&mut processed_fields());
+    definition.set_fields(fields);
+    definition.set_localised_fields(vec![field("onscreen_name", FieldType::StringU8, false, None)]);
+    definition.set_localised_key_order(vec![0, 1]);
+
+    let mut schema = Schema::default();
+    schema.add_definition("units_tables", &definition);
+
+    let row = |key: &str, category: &str, cost: i32, ratio: f32, is_naval: bool, unit_type: &str, flags: [bool; 3], banner: &str| {
+        let mut row = vec![
+            DecodedData::StringU8(key.to_owned()),
+            DecodedData::StringU8(category.to_owned()),
+            DecodedData::I32(cost),
+            DecodedData::F32(ratio),
+            DecodedData::Boolean(is_naval),
+            DecodedData::StringU8(unit_type.to_owned()),
+        ];
+        row.extend(flags.iter().map(|flag| DecodedData::Boolean(*flag)));
+        row.push(DecodedData::ColourRGB(banner.to_owned()));
+        row
+    };
+
+    // Keys that only match when joined must not be merged.
+    let mut table_a = DB::new(&definition, None, "units_tables", false);
+    table_a.set_data(None, &[
+        row("spear", "men", 500, 0.125, false, "melee", [true, false, true], "FF8000"),
+        row("galley", "", 900, 1.5, true, "missile", [false, false, false], "000000"),
+    ]).unwrap();
+
+    let mut table_b = DB::new(&definition, None, "units_tables", false);
+    table_b.set_data(None, &[
+        row("spear", "men", 999, 2.0, false, "cavalry", [false, false, false], "FFFFFF"),
+        row("spearm", "en", 300, 0.5, false, "cavalry", [false, true, false], "0A141E"),
+    ]).unwrap();
+
+    let mut loc = Loc::new(false);
+    let mut loc_row = loc.new_row();
+    loc_row[0] = DecodedData::StringU16("units_onscreen_name_spearmen".to_owned());
+    loc_row[1] = DecodedData::StringU16("Spearmen".to_owned());
+    loc.set_data(&[loc_row]).unwrap();
+
+    let mut pack = Pack::new_with_name_and_version("test.pack", PFHVersion::PFH5);
+    pack.insert(RFile::new_from_decoded(&RFileDecoded::DB(table_a), 0, "db/units_tables/a")).unwrap();
+    pack.insert(RFile::new_from_decoded(&RFileDecoded::DB(table_b), 0, "db/units_tables/b")).unwrap();
+    pack.insert(RFile::new_from_decoded(&RFileDecoded::Loc(loc), 0, "text/units.loc")).unwrap();
+
+    let paths = export_raw_tables(&mut pack, &schema, &raw_db_path, 2, &[]).unwrap();
+    assert!(paths.contains(&raw_db_path.join("TWaD_units.xml")));
+    assert!(paths.contains(&raw_db_path.join("units.xml")));
+    assert!(paths.iter().all(|path| path.is_file()));
+
+    // The definition keeps the keys, types and localised fields. Enum, bitwise and colour fields keep the layout of the binary tables.
+    let raw_definition = RawDefinition::read(&raw_db_path.join("TWaD_units.xml"), 2).unwrap();
+    let read_definition = Definition::from(&raw_definition);
+    let read_fields = read_definition.fields().iter().map(|field| (field.name().to_owned(), field.field_type().clone(), field.is_key(None))).collect::<Vec<_>>();
+    assert_eq!(read_fields, vec![
+        ("key".to_owned(), FieldType::StringU8, true),
+        ("category".to_owned(), FieldType::StringU8, true),
+        ("cost".to_owned(), FieldType::I32, false),
+        ("ratio".to_owned(), FieldType::F32, false),
+        ("is_naval".to_owned(), FieldType::Boolean, false),
+        ("unit_type".to_owned(), FieldType::I32, false),
+        ("flags".to_owned(), FieldType::I32, false),
+        ("banner_r".to_owned(), FieldType::I32, false),
+        ("banner_g".to_owned(), FieldType::I32, false),
+        ("banner_b".to_owned(), FieldType::I32, false),
+        ("onscreen_name".to_owned(), FieldType::StringU8, false),
+    ]);
+
+    let localisable_fields = RawLocalisableFields::read(&raw_db_path, 2).unwrap();
+    assert!(localisable_fields.fields.iter().any(|field| field.table_name == "units" && field.field == "onscreen_name"));
+
+    // The data keeps the first row of each key, in the order of the files, with full precision floats and the text from the Loc files.
+    // Enum and bitwise values are written as their integers, and colours are split in their channels.
+    let raw_table = RawTable::read(&raw_definition, &raw_db_path, 2).unwrap();
+    let table = Table::try_from(&raw_table).unwrap();
+    assert_eq!(table.data(&None).unwrap().to_vec(), vec![
+        vec![DecodedData::StringU8("spear".to_owned()), DecodedData::StringU8("men".to_owned()), DecodedData::I32(500), DecodedData::F32(0.125), DecodedData::Boolean(false), DecodedData::I32(0), DecodedData::I32(5), DecodedData::I32(255), DecodedData::I32(128), DecodedData::I32(0), DecodedData::StringU8("Spearmen".to_owned())],
+        vec![DecodedData::StringU8("galley".to_owned()), DecodedData::StringU8("".to_owned()), DecodedData::I32(900), DecodedData::F32(1.5), DecodedData::Boolean(true), DecodedData::I32(1), DecodedData::I32(0), DecodedData::I32(0), DecodedData::I32(0), DecodedData::I32(0), DecodedData::StringU8("".to_owned())],
+        vec![DecodedData::StringU8("spearm".to_owned()), DecodedData::StringU8("en".to_owned()), DecodedData::I32(300), DecodedData::F32(0.5), DecodedData::Boolean(false), DecodedData::I32(2), DecodedData::I32(2), DecodedData::I32(10), DecodedData::I32(20), DecodedData::I32(30), DecodedData::StringU8("Spearmen".to_owned())],
+    ]);
+
+    // Tables not in the Pack are errors.
+    assert!(matches!(export_raw_tables(&mut pack, &schema, &raw_db_path, 2, &["missing_tables".to_owned()]), Err(RLibError::AssemblyKitExportTableNotFound(_))));
+
+    remove_dir_all(&raw_db_path).unwrap();
+}
@@ -18,8 +18,8 @@ use serde_derive::Deserialize;
 use serde_xml_rs::from_reader;
 
 use std::fs::File;
-use std::io::BufReader;
-use std::path::Path;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
 
 use crate::error::{Result, RLibError};
 
@@ -32,7 +32,7 @@ use super::*;
 /// This is the raw equivalent to the `entries` field in a `DB` struct. In files, this is the equivalent to the `.xml` file with all the data in the table.
 ///
 /// It contains a vector with all the rows of data in the `.xml` table file.
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize)]
 #[serde(rename = "dataroot")]
 pub struct RawLocalisableFields {
 
@@ -66,4 +66,28 @@ impl RawLocalisableFields {
             _ => Err(RLibError::AssemblyKitUnsupportedVersion(version))
         }
     }
+
+    /// This function writes the localisable fields as a `TExc_LocalisableFields` file in the provided folder. It returns the path of the written file.
+    pub fn write(&self, raw_data_path: &Path, version: i16) -> Result<PathBuf> {
+        match version {
+            2 | 1 => {
+                let path = raw_data_path.join(format!("{LOCALISABLE_FILES_FILE_NAME_V2}.xml"));
+                let mut file = BufWriter::new(File::create(&path)?);
+
+                file.write_all(XML_HEADER.as_bytes())?;
+                file.write_all(b"<dataroot>\r\n")?;
+
+                for field in &self.fields {
+                    file.write_all(format!("<{LOCALISABLE_FILES_FILE_NAME_V2}>\r\n").as_bytes())?;
+                    write_xml_node(&mut file, "table_name", &field.table_name)?;
+                    write_xml_node(&mut file, "field", &field.field)?;
+                    file.write_all(format!("</{LOCALISABLE_FILES_FILE_NAME_V2}>\r\n").as_bytes())?;
+                }
+
+                file.write_all(b"</dataroot>\r\n")?;
+                Ok(path)
+            }
+            _ => Err(RLibError::AssemblyKitUnsupportedVersion(version))
+        }
+    }
 }
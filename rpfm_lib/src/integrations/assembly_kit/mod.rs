@@ -18,28 +18,36 @@ To differentiate between the different types of Assembly Kit, there are multiple
 - `2`: Anything since Rome 2.
 !*/
 
+use getset::Getters;
 use rayon::prelude::*;
 use serde_xml_rs::from_reader;
 
-use std::collections::HashMap;
-use std::fs::{File, read_dir};
-use std::io::BufReader;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::{self, Display};
+use std::fs::{DirBuilder, File, read_dir};
+use std::io::{BufReader, Write};
 use std::path::{Path, PathBuf};
 
 use crate::error::{Result, RLibError};
 use crate::games::GameInfo;
-use crate::files::db::DB;
+use crate::files::{Container, ContainerPath, db::DB, DecodeableExtraData, FileType, pack::Pack, RFile, RFileDecoded, table::{DecodedData, Table}};
 use crate::schema::*;
 
-use self::localisable_fields::RawLocalisableFields;
+use self::localisable_fields::{RawLocalisableField, RawLocalisableFields};
+use self::table_data::RawTable;
 use self::table_definition::RawDefinition;
 
 pub mod localisable_fields;
 pub mod table_data;
 pub mod table_definition;
 
+#[cfg(test)] mod assembly_kit_test;
+
 const LOCALISABLE_FILES_FILE_NAME_V2: &str = "TExc_LocalisableFields";
 
+const XML_HEADER: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\r\n";
+
 const RAW_DEFINITION_NAME_PREFIX_V2: &str = "TWaD_";
 const RAW_DEFINITION_IGNORED_FILES_V2: [&str; 5] = [
     "TWaD_schema_validation",
@@ -61,6 +69,24 @@ const RAW_DEFINITION_EXTENSION_V0: &str = ".xsd";
 /// - "TWaD_form_descriptions.xml": it's not a table.
 const BLACKLISTED_TABLES: [&str; 2] = ["translated_texts.xml", "TWaD_form_descriptions.xml"];
 
+/// This struct contains the result of an import of raw tables, with the data that could not be imported as it was.
+#[derive(Clone, Debug, Default, Getters)]
+#[getset(get = "pub")]
+pub struct ImportRawTablesReport {
+
+    /// Paths of the created files.
+    paths: Vec<ContainerPath>,
+
+    /// Columns of the definitions not found in the raw tables, by table name. They got their default value.
+    missing_columns: BTreeMap<String, Vec<String>>,
+
+    /// Columns of the raw tables not found in the definitions, by table name. Their data was not imported.
+    dropped_columns: BTreeMap<String, Vec<String>>,
+
+    /// Values that could not be converted to the type of their column, by table name, as (row, column, value). They got the default value of their column.
+    invalid_values: BTreeMap<String, Vec<(usize, String, String)>>,
+}
+
 //---------------------------------------------------------------------------//
 // Functions to process the Raw DB Tables from the Assembly Kit.
 //---------------------------------------------------------------------------//
@@ -121,10 +147,306 @@ pub fn update_schema_from_raw_files(
     }
 }
 
+/// This function returns the names of all the tables in the provided raw table folder, with the `_tables` suffix, sorted.
+///
+/// This includes tables only present in the Assembly Kit.
+pub fn raw_table_names(raw_db_path: &Path, version: i16) -> Result<Vec<String>> {
+    match version {
+        2 | 1 => {
+            let mut table_names = get_raw_definition_paths(raw_db_path, version)?.iter()
+                .filter(|path| !BLACKLISTED_TABLES.contains(&path.file_name().unwrap().to_str().unwrap()))
+                .filter_map(|path| path.file_stem()?.to_str()?.strip_prefix(RAW_DEFINITION_NAME_PREFIX_V2).map(|name| format!("{name}_tables")))
+                .collect::<Vec<_>>();
+
+            table_names.sort();
+            Ok(table_names)
+        }
+        _ => Err(RLibError::AssemblyKitUnsupportedVersion(version))
+    }
+}
+
+/// This function imports the provided tables from the raw table folder of an Assembly Kit into a Pack, as binary DB files.
+///
+/// - `raw_db_path`: folder with the raw tables, usually `assembly_kit/raw_data/db`.
+/// - `table_names`: names of the tables to import, with the `_tables` suffix.
+/// - `file_name`: name of the DB files to create, like `db/{table_name}/{file_name}`. Existing files are replaced.
+///
+/// Each table uses the newest definition for it in the schema, matching its columns by name against the fields of the definition,
+/// as the raw tables store bitwise, enum and colour columns like the binary tables do. Columns missing in the raw table,
+/// and values that cannot be converted to the type of their column, get the default value of their column, and are listed in the
+/// returned report, along with the columns of the raw table not in the definition. Empty values in non-text columns also get
+/// the default value of their column, but they're not reported, as that's how the Assembly Kit stores them. Tables without definitions in the schema (usually the ones only present in the Assembly Kit) use the definition
+/// from the Assembly Kit with version 0, so they may not be readable until a proper definition is added to the schema.
+///
+/// Localisable columns are not imported, as they're not part of the DB files.
+///
+/// It returns a report with the paths of the created files, and with the data that could not be imported as it was.
+pub fn import_raw_tables(pack: &mut Pack, schema: &Schema, raw_db_path: &Path, version: i16, table_names: &[String], file_name: &str) -> Result<ImportRawTablesReport> {
+    if version != 2 && version != 1 {
+        return Err(RLibError::AssemblyKitUnsupportedVersion(version));
+    }
+
+    let dbs = table_names.par_iter()
+        .map(|table_name| {
+            let raw_name = table_name.strip_suffix("_tables").unwrap_or(table_name);
+            let raw_definition_path = raw_db_path.join(format!("{RAW_DEFINITION_NAME_PREFIX_V2}{raw_name}.xml"));
+            if !raw_definition_path.is_file() {
+                return Err(RLibError::AssemblyKitTableNotFound(table_name.to_owned()));
+            }
+
+            let raw_definition = RawDefinition::read(&raw_definition_path, version)?;
+            let raw_table = RawTable::read(&raw_definition, raw_db_path, version)?;
+
+            let schema_patches = schema.patches_for_table(table_name);
+            let definition = match schema.definitions_by_table_name(table_name).and_then(|definitions| definitions.iter().max_by_key(|definition| *definition.version())) {
+                Some(definition) => definition.clone(),
+                None => {
+                    let mut definition = Definition::from(&raw_definition);
+                    definition.set_version(0);
+                    definition
+                }
+            };
+
+            // Match the columns by name. Localisable columns are not part of the table, so they're not reported as dropped.
+            let fields = definition.fields();
+            let missing_columns = fields.iter()
+                .filter(|field| !raw_definition.fields.iter().any(|raw_field| raw_field.name == *field.name()))
+                .map(|field| field.name().to_owned())
+                .collect::<Vec<_>>();
+
+            let dropped_columns = raw_definition.fields.iter()
+                .filter(|raw_field| !fields.iter().chain(definition.localised_fields().iter()).any(|field| field.name() == raw_field.name))
+                .map(|raw_field| raw_field.name.to_owned())
+                .collect::<Vec<_>>();
+
+            // Convert the raw values directly to the type of the definition, so values that cannot be parsed can be reported.
+            // Then process the rows like when decoding a table, so they get the processed layout of the definition.
+            let mut invalid_values = vec![];
+            let data = raw_table.rows.iter()
+                .enumerate()
+                .map(|(row_index, row)| Table::row_from_raw(fields, fields.iter()
+                    .map(|field| {
+                        let default = || DecodedData::new_from_type_and_value(field.field_type(), &field.default_value(schema_patches));
+                        let value = row.fields.iter()
+                            .find(|raw_field| raw_field.field_name == *field.name())
+                            .map(|raw_field| raw_field.field_data.as_str())
+                            .unwrap_or_default();
+
+                        match raw_value_to_decoded_data(field.field_type(), value) {
+                            Ok(Some(value)) => value,
+                            Ok(None) => default(),
+                            Err(_) => {
+                                invalid_values.push((row_index, field.name().to_owned(), value.to_owned()));
+                                default()
+                            }
+                        }
+                    })
+                    .collect::<Vec<_>>()))
+                .collect::<Result<Vec<_>>>()?;
+
+            let mut db = DB::new(&definition, schema_patches, table_name, false);
+            db.set_data(None, &data)?;
+            Ok((table_name.to_owned(), db, missing_columns, dropped_columns, invalid_values))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut report = ImportRawTablesReport::default();
+    for (table_name, db, missing_columns, dropped_columns, invalid_values) in dbs {
+        let path = format!("db/{table_name}/{file_name}");
+        let file = RFile::new_from_decoded(&RFileDecoded::DB(db), 0, &path);
+        if let Some(path) = pack.insert(file)? {
+            report.paths.push(path);
+        }
+
+        if !missing_columns.is_empty() {
+            report.missing_columns.insert(table_name.to_owned(), missing_columns);
+        }
+
+        if !dropped_columns.is_empty() {
+            report.dropped_columns.insert(table_name.to_owned(), dropped_columns);
+        }
+
+        if !invalid_values.is_empty() {
+            report.invalid_values.insert(table_name, invalid_values);
+        }
+    }
+
+    Ok(report)
+}
+
+impl ImportRawTablesReport {
+
+    /// This function returns if any data could not be imported as it was.
+    pub fn has_problems(&self) -> bool {
+        !self.missing_columns.is_empty() || !self.dropped_columns.is_empty() || !self.invalid_values.is_empty()
+    }
+}
+
+impl Display for ImportRawTablesReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (table_name, columns) in &self.missing_columns {
+            writeln!(f, "{table_name}: columns not found in the raw table, imported with their default value: {}.", columns.join(", "))?;
+        }
+
+        for (table_name, columns) in &self.dropped_columns {
+            writeln!(f, "{table_name}: columns of the raw table not found in the definition, not imported: {}.", columns.join(", "))?;
+        }
+
+        for (table_name, values) in &self.invalid_values {
+            writeln!(f, "{table_name}: values that cannot be converted to the type of their column, imported as its default value:")?;
+            for (row, column, value) in values {
+                writeln!(f, "  - Row {}, column {column}: \"{value}\".", row + 1)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// This function converts a raw value from the Assembly Kit to the provided type, failing if the value cannot be parsed as that type.
+///
+/// Empty values in non-text columns, and values of sequence columns, which are not used in raw tables, return `None`.
+fn raw_value_to_decoded_data(field_type: &FieldType, value: &str) -> Result<Option<DecodedData>> {
+    match field_type {
+        FieldType::ColourRGB |
+        FieldType::StringU8 |
+        FieldType::StringU16 |
+        FieldType::OptionalStringU8 |
+        FieldType::OptionalStringU16 => DecodedData::new_from_type_and_string(field_type, if value == "Frodo Best Waifu" { "" } else { value }).map(Some),
+        FieldType::SequenceU16(_) | FieldType::SequenceU32(_) => Ok(None),
+        _ if value.is_empty() => Ok(None),
+        _ => DecodedData::new_from_type_and_string(field_type, value).map(Some),
+    }
+}
+
 //---------------------------------------------------------------------------//
 // Utility functions to process raw files from the Assembly Kit.
 //---------------------------------------------------------------------------//
 
+/// This function exports the DB tables of a Pack to the raw table folder of an Assembly Kit, as raw tables and `TWaD_` definitions.
+///
+/// - `raw_db_path`: folder to write the raw tables to, usually `assembly_kit/raw_data/db`.
+/// - `table_names`: names of the tables to export, with the `_tables` suffix. If empty, all the tables in the Pack are exported.
+///
+/// All the files of the same table are merged into one raw table, using the newest definition between them. If multiple rows share
+/// the same key, the one in the file that comes first alphabetically is kept, as that's the one the game uses.
+///
+/// Localisable fields are exported as part of their table, getting their text from the Loc files in the Pack, and they're added
+/// to the `TExc_LocalisableFields` file of the folder, keeping the fields already in it.
+///
+/// It returns the paths of the written files.
+pub fn export_raw_tables(pack: &mut Pack, schema: &Schema, raw_db_path: &Path, version: i16, table_names: &[String]) -> Result<Vec<PathBuf>> {
+    if version != 2 && version != 1 {
+        return Err(RLibError::AssemblyKitUnsupportedVersion(version));
+    }
+
+    let mut extra_data = DecodeableExtraData::default();
+    extra_data.set_schema(Some(schema));
+    extra_data.set_game_build(Some(pack.game_version()).filter(|game_version| *game_version != 0));
+    let extra_data = Some(extra_data);
+
+    pack.files_by_type_mut(&[FileType::DB, FileType::Loc])
+        .par_iter_mut()
+        .for_each(|file| { let _ = file.decode(&extra_data, true, false); });
+
+    let mut files = pack.files_by_type(&[FileType::DB, FileType::Loc]);
+    files.sort_by(|a, b| a.path_in_container_raw().cmp(b.path_in_container_raw()));
+
+    let mut loc_data = HashMap::new();
+    let mut tables: BTreeMap<&str, Vec<&DB>> = BTreeMap::new();
+    for file in files {
+        match file.decoded() {
+            Ok(RFileDecoded::DB(table)) => {
+                if table_names.is_empty() || table_names.iter().any(|table_name| table_name == table.table_name()) {
+                    tables.entry(table.table_name()).or_default().push(table);
+                }
+            }
+            Ok(RFileDecoded::Loc(table)) => {
+                for row in table.data(&None)?.iter() {
+                    if let (DecodedData::StringU16(key), DecodedData::StringU16(text)) = (&row[0], &row[1]) {
+                        loc_data.entry(key.to_owned()).or_insert_with(|| text.to_owned());
+                    }
+                }
+            }
+            _ => continue,
+        }
+    }
+
+    if let Some(table_name) = table_names.iter().find(|table_name| !tables.contains_key(&***table_name)) {
+        return Err(RLibError::AssemblyKitExportTableNotFound(table_name.to_owned()));
+    }
+
+    DirBuilder::new().recursive(true).create(raw_db_path)?;
+
+    let raw_tables = tables.par_iter()
+        .map(|(table_name, tables)| {
+            let definition = tables.iter()
+                .map(|table| table.definition())
+                .max_by_key(|definition| *definition.version())
+                .unwrap();
+
+            let mut keys = HashSet::new();
+            let mut merged = DB::new(definition, Some(tables[0].patches()), table_name, false);
+            let mut data = vec![];
+
+            for table in tables {
+                let mut table = (*table).clone();
+                if table.definition() != definition {
+                    table.update(definition);
+                }
+
+                let key_positions = table.definition().fields_processed().iter()
+                    .enumerate()
+                    .filter_map(|(position, field)| if field.is_key(Some(table.patches())) { Some(position) } else { None })
+                    .collect::<Vec<_>>();
+
+                for row in table.data(&None)?.iter() {
+                    let key = key_positions.iter().map(|position| row[*position].data_to_string().to_string()).collect::<Vec<_>>();
+                    if key_positions.is_empty() || keys.insert(key) {
+                        data.push(row.to_vec());
+                    }
+                }
+            }
+
+            merged.set_data(None, &data)?;
+            RawTable::from_db(&merged, &loc_data)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut paths = vec![];
+    let mut localisable_fields = vec![];
+    for raw_table in &raw_tables {
+        let definition = raw_table.definition.as_ref().ok_or(RLibError::RawTableMissingDefinition)?;
+        paths.push(definition.write(raw_db_path, version)?);
+        paths.push(raw_table.write(raw_db_path, version)?);
+
+        let raw_table_name = definition.name.as_ref().and_then(|name| name.strip_suffix(".xml")).unwrap_or_default();
+        let table_name = format!("{raw_table_name}_tables");
+        if let Some(table) = tables.get(&*table_name) {
+            for field in table[0].definition().localised_fields() {
+                localisable_fields.push(RawLocalisableField {
+                    table_name: raw_table_name.to_owned(),
+                    field: field.name().to_owned(),
+                });
+            }
+        }
+    }
+
+    // Keep the localisable fields already in the folder, as that file is shared between all tables.
+    if !localisable_fields.is_empty() {
+        let mut raw_localisable_fields = RawLocalisableFields::read(raw_db_path, version).unwrap_or_default();
+        for field in localisable_fields {
+            if !raw_localisable_fields.fields.iter().any(|x| x.table_name == field.table_name && x.field == field.field) {
+                raw_localisable_fields.fields.push(field);
+            }
+        }
+
+        paths.push(raw_localisable_fields.write(raw_db_path, version)?);
+    }
+
+    Ok(paths)
+}
+
 /// This function returns all the raw Assembly Kit Table Definition files from the provided folder.
 ///
 /// Yoy must provide it the folder with the definitions inside, and the version of the game to process.
@@ -229,3 +551,21 @@ pub fn get_raw_localisable_fields_path(current_path: &Path, version: i16) -> Res
     // If we didn't find the file, return an error.
     Err(RLibError::AssemblyKitLocalisableFieldsNotFound)
 }
+
+/// This function writes a node with the provided value in its own line, escaping the value.
+fn write_xml_node<W: Write>(writer: &mut W, node: &str, value: &str) -> Result<()> {
+    writer.write_all(format!("<{node}>{}</{node}>\r\n", escape_xml(value)).as_bytes()).map_err(From::from)
+}
+
+/// This function escapes the characters not allowed in the text of a XML node.
+fn escape_xml(value: &str) -> Cow<'_, str> {
+    if value.contains(['&', '<', '>', '"', '\'']) {
+        Cow::from(value.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&apos;"))
+    } else {
+        Cow::from(value)
+    }
+}
@@ -14,19 +14,23 @@ Module with all the code to interact with the Assembly Kit's DB Files.
 This module contains all the code needed to parse Assembly Kit's DB files to a format we can understand.
 !*/
 
+use itertools::Itertools;
 use rayon::prelude::*;
 use regex::Regex;
 use serde_derive::Deserialize;
 use serde_xml_rs::from_reader;
+use uuid::Uuid;
 
+use std::collections::HashMap;
 use std::fs::File;
-use std::io::{BufReader, Read};
-use std::path::Path;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
 
 use crate::error::{Result, RLibError};
 use crate::files::{db::DB, table::{DecodedData, Table}};
 use crate::schema::FieldType;
 
+use super::{write_xml_node, XML_HEADER};
 use super::table_definition::RawDefinition;
 
 //---------------------------------------------------------------------------//
@@ -139,6 +143,89 @@ impl RawTable {
             _ => Err(RLibError::AssemblyKitUnsupportedVersion(version))
         }
     }
+
+    /// This function creates a `RawTable` from a `DB`, with its definition.
+    ///
+    /// The data is written with the layout of the definition fields, not the processed one, so bitwise, enum and colour fields
+    /// keep the integer values the Assembly Kit uses.
+    ///
+    /// The data of the localised fields is taken from the provided Loc data, by Loc key. Fields without Loc data are left empty.
+    pub fn from_db(db: &DB, loc_data: &HashMap<String, String>) -> Result<Self> {
+        let definition = RawDefinition::from_definition(db.definition(), db.table_name());
+        let fields = db.definition().fields();
+        let combined_colour_positions = Table::combined_colour_positions(db.definition());
+        let localised_fields = db.definition().localised_fields();
+        let localised_order = db.definition().localised_key_order();
+        let table_name_no_tables = db.table_name_without_tables();
+        let patches = Some(db.patches());
+
+        let rows = db.data(&None)?.iter()
+            .map(|row| {
+                let raw_row = Table::row_to_raw(fields, &combined_colour_positions, row, &patches)?;
+                let mut raw_fields = fields.iter()
+                    .zip(raw_row.iter())
+                    .filter(|(_, data)| !matches!(***data, DecodedData::SequenceU16(_) | DecodedData::SequenceU32(_)))
+                    .map(|(field, data)| RawTableField {
+                        field_name: field.name().to_owned(),
+                        field_data: match &**data {
+
+                            // Use the full precision, not the rounded one used for displaying floats.
+                            DecodedData::F32(data) => data.to_string(),
+                            DecodedData::F64(data) => data.to_string(),
+                            _ => data.data_to_string().to_string(),
+                        },
+                        state: None,
+                    })
+                    .collect::<Vec<_>>();
+
+                let key = localised_order.iter().filter_map(|position| row.get(*position as usize)).map(|data| data.data_to_string()).join("");
+                for field in localised_fields {
+                    let loc_key = format!("{}_{}_{}", table_name_no_tables, field.name(), key);
+                    raw_fields.push(RawTableField {
+                        field_name: field.name().to_owned(),
+                        field_data: loc_data.get(&loc_key).cloned().unwrap_or_default(),
+                        state: None,
+                    });
+                }
+
+                Ok(RawTableRow { fields: raw_fields })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            definition: Some(definition),
+            rows,
+        })
+    }
+
+    /// This function writes the table as a raw table in the provided folder. It returns the path of the written file.
+    ///
+    /// Every row gets a new `record_uuid`.
+    pub fn write(&self, raw_table_data_folder: &Path, version: i16) -> Result<PathBuf> {
+        match version {
+            2 | 1 => {
+                let name = self.definition.as_ref().and_then(|definition| definition.name.as_ref()).ok_or(RLibError::RawTableMissingDefinition)?;
+                let name_no_xml = name.strip_suffix(".xml").unwrap_or(name);
+                let path = raw_table_data_folder.join(name);
+                let mut file = BufWriter::new(File::create(&path)?);
+
+                file.write_all(XML_HEADER.as_bytes())?;
+                file.write_all(b"<dataroot>\r\n")?;
+
+                for row in &self.rows {
+                    file.write_all(format!("<{name_no_xml} record_uuid=\"{}\">\r\n", Uuid::new_v4().to_string().to_uppercase()).as_bytes())?;
+                    for field in &row.fields {
+                        write_xml_node(&mut file, &field.field_name, &field.field_data)?;
+                    }
+                    file.write_all(format!("</{name_no_xml}>\r\n").as_bytes())?;
+                }
+
+                file.write_all(b"</dataroot>\r\n")?;
+                Ok(path)
+            }
+            _ => Err(RLibError::AssemblyKitUnsupportedVersion(version))
+        }
+    }
 }
 
 impl TryFrom<&RawTable> for DB {
@@ -29,8 +29,8 @@ use serde_xml_rs::from_reader;
 
 use std::collections::BTreeMap;
 use std::fs::File;
-use std::io::BufReader;
-use std::path::Path;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
 
 use crate::error::{Result, RLibError};
 
@@ -140,6 +140,81 @@ impl RawDefinition {
         }
     }
 
+    /// This function creates a `RawDefinition` from a `Definition`, including its localised fields.
+    ///
+    /// - `table_name`: name of the table, with the `_tables` suffix.
+    ///
+    /// The fields are the ones of the definition, not the processed ones, as the Assembly Kit stores bitwise, enum and colour fields
+    /// as they're in the binary tables. Sequence fields are skipped, as the Assembly Kit has no equivalent for them.
+    pub fn from_definition(definition: &Definition, table_name: &str) -> Self {
+        let fields = definition.fields().iter()
+            .chain(definition.localised_fields().iter())
+            .filter(|field| !matches!(field.field_type(), FieldType::SequenceU16(_) | FieldType::SequenceU32(_)))
+            .map(|field| RawField::from_field(field, Some(definition.patches())))
+            .collect();
+
+        Self {
+            name: Some(format!("{}.xml", table_name.strip_suffix("_tables").unwrap_or(table_name))),
+            fields,
+        }
+    }
+
+    /// This function writes the definition as a `TWaD_` file in the provided folder. It returns the path of the written file.
+    pub fn write(&self, raw_definitions_folder: &Path, version: i16) -> Result<PathBuf> {
+        match version {
+            2 | 1 => {
+                let name = self.name.as_ref().ok_or(RLibError::RawTableMissingDefinition)?;
+                let path = raw_definitions_folder.join(format!("{RAW_DEFINITION_NAME_PREFIX_V2}{name}"));
+                let mut file = BufWriter::new(File::create(&path)?);
+
+                file.write_all(XML_HEADER.as_bytes())?;
+                file.write_all(b"<root>\r\n")?;
+
+                for field in &self.fields {
+                    file.write_all(b"<field>\r\n")?;
+                    write_xml_node(&mut file, "primary_key", &field.primary_key)?;
+                    write_xml_node(&mut file, "name", &field.name)?;
+                    write_xml_node(&mut file, "field_type", &field.field_type)?;
+                    write_xml_node(&mut file, "required", &field.required)?;
+
+                    for (node, value) in [
+                        ("default_value", &field.default_value),
+                        ("max_length", &field.max_length),
+                        ("is_filename", &field.is_filename),
+                        ("filename_relative_path", &field.filename_relative_path),
+                        ("fragment_path", &field.fragment_path),
+                    ] {
+                        if let Some(value) = value {
+                            write_xml_node(&mut file, node, value)?;
+                        }
+                    }
+
+                    if let Some(ref columns) = field.column_source_column {
+                        for column in columns {
+                            write_xml_node(&mut file, "column_source_column", column)?;
+                        }
+                    }
+
+                    for (node, value) in [
+                        ("column_source_table", &field.column_source_table),
+                        ("field_description", &field.field_description),
+                        ("encyclopaedia_export", &field.encyclopaedia_export),
+                    ] {
+                        if let Some(value) = value {
+                            write_xml_node(&mut file, node, value)?;
+                        }
+                    }
+
+                    file.write_all(b"</field>\r\n")?;
+                }
+
+                file.write_all(b"</root>\r\n")?;
+                Ok(path)
+            }
+            _ => Err(RLibError::AssemblyKitUnsupportedVersion(version))
+        }
+    }
+
     /// This function returns the fields without the localisable ones.
     pub fn get_non_localisable_fields(&self, raw_localisable_fields: &[RawLocalisableField], test_row: &RawTableRow) -> Vec<Field> {
         let raw_table_name = &self.name.as_ref().unwrap()[..self.name.as_ref().unwrap().len() - 4];
@@ -164,6 +239,54 @@ impl From<&RawDefinition> for Definition {
 }
 
 
+impl RawField {
+
+    /// This function creates a `RawField` from a `Field`.
+    pub fn from_field(field: &Field, schema_patches: Option<&DefinitionPatch>) -> Self {
+        let (field_type, required) = match field.field_type() {
+            FieldType::Boolean => ("yesno", "1"),
+            FieldType::F32 => ("single", "1"),
+            FieldType::F64 => ("double", "1"),
+            FieldType::I16 | FieldType::I32 => ("integer", "1"),
+            FieldType::OptionalI16 | FieldType::OptionalI32 => ("integer", "0"),
+            FieldType::I64 => ("card64", "1"),
+            FieldType::OptionalI64 => ("card64", "0"),
+            FieldType::ColourRGB => ("colour", "1"),
+            FieldType::StringU8 | FieldType::StringU16 => ("text", "1"),
+            FieldType::OptionalStringU8 | FieldType::OptionalStringU16 |
+            FieldType::SequenceU16(_) | FieldType::SequenceU32(_) => ("text", "0"),
+        };
+
+        let (column_source_table, column_source_column) = match field.is_reference() {
+            Some((table, column)) => {
+                let mut columns = vec![column.to_owned()];
+                if let Some(lookup) = field.lookup() {
+                    columns.extend_from_slice(lookup);
+                }
+
+                (Some(table.to_owned()), Some(columns))
+            }
+            None => (None, None),
+        };
+
+        Self {
+            primary_key: if field.is_key(schema_patches) { "1" } else { "0" }.to_owned(),
+            name: field.name().to_owned(),
+            field_type: field_type.to_owned(),
+            required: required.to_owned(),
+            default_value: field.default_value(schema_patches),
+            max_length: None,
+            is_filename: if field.is_filename() { Some("1".to_owned()) } else { None },
+            filename_relative_path: field.filename_relative_path().clone(),
+            fragment_path: None,
+            column_source_column,
+            column_source_table,
+            field_description: if field.description().is_empty() { None } else { Some(field.description().to_owned()) },
+            encyclopaedia_export: None,
+        }
+    }
+}
+
 impl From<&RawField> for Field {
     fn from(raw_field: &RawField) -> Self {
         let field_type = match &*raw_field.field_type {
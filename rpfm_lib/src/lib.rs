@@ -27,8 +27,12 @@
 use lazy_static::*;
 use regex::Regex;
 
+// Needed so the code generated by `schema::codegen`, which uses absolute `::rpfm_lib` paths, can be tested within this crate.
+#[cfg(test)] extern crate self as rpfm_lib;
+
 pub mod binary;
 pub mod compression;
+pub mod edit_log;
 pub mod encryption;
 pub mod error;
 pub mod files;
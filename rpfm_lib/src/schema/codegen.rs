@@ -0,0 +1,310 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the code generator for strongly typed table rows.
+
+This module turns the definitions of a [Schema] into Rust structs, one per table, so tools built on top
+of this lib don't need to access columns by their position in a `Vec<DecodedData>`. It's meant to be used
+from a build script:
+
+```ignore
+// build.rs
+use std::path::PathBuf;
+use rpfm_lib::schema::codegen::generate_table_rows_for_game;
+
+fn main() {
+    let dest_path = PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("tables.rs");
+    generate_table_rows_for_game(&PathBuf::from("schemas"), "warhammer_3", &["land_units_tables"], &dest_path).unwrap();
+}
+
+// lib.rs
+include!(concat!(env!("OUT_DIR"), "/tables.rs"));
+```
+
+Each generated struct implements [TableRow], `TryFrom<&[DecodedData]>` and `Into<Vec<DecodedData>>`, so it can be
+read from and written to a DB using [DB::typed_rows](crate::files::db::DB::typed_rows) and [DB::set_typed_rows](crate::files::db::DB::set_typed_rows).
+
+Generated structs follow the processed layout of the definition (bitwise columns split, enums as strings and colour columns
+merged), as that's the layout rows have once decoded.
+!*/
+
+use std::fmt::Write;
+use std::fs::{DirBuilder, File};
+use std::io::{BufWriter, Write as IOWrite};
+use std::path::Path;
+
+use crate::error::{RLibError, Result};
+use crate::files::table::DecodedData;
+use crate::games::supported_games::SupportedGames;
+
+use super::{Definition, Field, FieldType, Schema};
+
+/// List of Rust keywords, including the reserved ones, that need to be escaped when used as field names.
+const RUST_KEYWORDS: [&str; 49] = [
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false", "fn", "for",
+    "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "static", "struct", "super",
+    "trait", "true", "type", "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final", "macro",
+    "override", "priv", "typeof", "unsized", "virtual", "yield", "try"
+];
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This trait is implemented by all the row structs generated from a [Schema].
+///
+/// It ties the struct to the specific table and definition version it was generated from.
+pub trait TableRow: for<'a> TryFrom<&'a [DecodedData], Error = RLibError> + Into<Vec<DecodedData>> {
+
+    /// Name of the table this row belongs to, with the `_tables` suffix.
+    const TABLE_NAME: &'static str;
+
+    /// Version of the definition this row was generated from.
+    const VERSION: i32;
+}
+
+//---------------------------------------------------------------------------//
+//                             Public functions
+//---------------------------------------------------------------------------//
+
+/// This function generates the Rust code for the row structs of the provided tables, using their newest definition in the provided [Schema].
+///
+/// If no tables are provided, code for all the tables in the schema is generated.
+pub fn generate_table_rows(schema: &Schema, table_names: &[&str]) -> Result<String> {
+    let mut table_names = if table_names.is_empty() {
+        schema.definitions().keys().map(|table_name| table_name.as_str()).collect::<Vec<_>>()
+    } else {
+        table_names.to_vec()
+    };
+    table_names.sort_unstable();
+
+    let mut code = String::new();
+    let _ = writeln!(code, "// This file has been generated automatically by rpfm_lib from a schema. Do not edit it manually.");
+    let _ = writeln!(code);
+
+    for table_name in table_names {
+        let definition = schema.definitions_by_table_name(table_name)
+            .and_then(|definitions| definitions.iter().max_by_key(|definition| definition.version()))
+            .ok_or_else(|| RLibError::CodegenNoDefinitionForTable(table_name.to_owned()))?;
+
+        code.push_str(&generate_table_row(table_name, definition));
+    }
+
+    Ok(code)
+}
+
+/// This function loads the [Schema] at the provided path and writes the Rust code for the row structs of the provided tables to `dest_path`.
+///
+/// Meant to be used from build scripts.
+pub fn generate_table_rows_to_file(schema_path: &Path, table_names: &[&str], dest_path: &Path) -> Result<()> {
+    let schema = Schema::load(schema_path)?;
+    let code = generate_table_rows(&schema, table_names)?;
+
+    if let Some(parent_folder) = dest_path.parent() {
+        DirBuilder::new().recursive(true).create(parent_folder)?;
+    }
+
+    let mut file = BufWriter::new(File::create(dest_path)?);
+    file.write_all(code.as_bytes())?;
+    Ok(())
+}
+
+/// This function writes the Rust code for the row structs of the provided tables to `dest_path`, using the schema of the provided game.
+///
+/// Meant to be used from build scripts.
+pub fn generate_table_rows_for_game(schemas_path: &Path, game_key: &str, table_names: &[&str], dest_path: &Path) -> Result<()> {
+    let games = SupportedGames::default();
+    let game = games.game(game_key).ok_or_else(|| RLibError::GameNotSupported(game_key.to_owned()))?;
+    generate_table_rows_to_file(&schemas_path.join(game.schema_file_name()), table_names, dest_path)
+}
+
+//---------------------------------------------------------------------------//
+//                             Private functions
+//---------------------------------------------------------------------------//
+
+/// This function generates the struct and trait implementations for a specific table definition.
+fn generate_table_row(table_name: &str, definition: &Definition) -> String {
+    let struct_name = struct_name(table_name);
+    let fields = definition.fields_processed();
+    let field_names = field_names(&fields);
+    let patches = Some(definition.patches());
+
+    let mut code = String::new();
+
+    // Struct.
+    let _ = writeln!(code, "/// Row of the `{}` table, generated from the definition version {}.", table_name, definition.version());
+    let _ = writeln!(code, "#[derive(Clone, Debug, Default, PartialEq)]");
+    let _ = writeln!(code, "pub struct {struct_name} {{");
+    for (field, field_name) in fields.iter().zip(&field_names) {
+        write_field_doc(&mut code, field, field.is_key(patches));
+        let _ = writeln!(code, "    pub {}: {},", field_name, rust_type(field.field_type()));
+    }
+    let _ = writeln!(code, "}}");
+    let _ = writeln!(code);
+
+    // TableRow.
+    let _ = writeln!(code, "impl ::rpfm_lib::schema::codegen::TableRow for {struct_name} {{");
+    let _ = writeln!(code, "    const TABLE_NAME: &'static str = \"{table_name}\";");
+    let _ = writeln!(code, "    const VERSION: i32 = {};", definition.version());
+    let _ = writeln!(code, "}}");
+    let _ = writeln!(code);
+
+    // TryFrom<&[DecodedData]>.
+    let _ = writeln!(code, "impl TryFrom<&[::rpfm_lib::files::table::DecodedData]> for {struct_name} {{");
+    let _ = writeln!(code, "    type Error = ::rpfm_lib::error::RLibError;");
+    let _ = writeln!(code);
+    let _ = writeln!(code, "    fn try_from(row: &[::rpfm_lib::files::table::DecodedData]) -> Result<Self, Self::Error> {{");
+    let _ = writeln!(code, "        if row.len() != {} {{", fields.len());
+    let _ = writeln!(code, "            return Err(::rpfm_lib::error::RLibError::TableRowWrongFieldCount({}, row.len()));", fields.len());
+    let _ = writeln!(code, "        }}");
+    let _ = writeln!(code);
+    let _ = writeln!(code, "        Ok(Self {{");
+    for (index, (field, field_name)) in fields.iter().zip(&field_names).enumerate() {
+        let variant = decoded_data_variant(field.field_type());
+        let _ = writeln!(code, "            {field_name}: match &row[{index}] {{");
+        let value = if is_copy(field.field_type()) { "*value" } else { "value.clone()" };
+        let _ = writeln!(code, "                ::rpfm_lib::files::table::DecodedData::{variant}(value) => {value},");
+        let _ = writeln!(code, "                value => return Err(::rpfm_lib::error::RLibError::EncodingTableWrongFieldType(\"{variant}\".to_owned(), ::rpfm_lib::schema::FieldType::from(value).to_string())),");
+        let _ = writeln!(code, "            }},");
+    }
+    let _ = writeln!(code, "        }})");
+    let _ = writeln!(code, "    }}");
+    let _ = writeln!(code, "}}");
+    let _ = writeln!(code);
+
+    // Into<Vec<DecodedData>>.
+    let _ = writeln!(code, "impl From<{struct_name}> for Vec<::rpfm_lib::files::table::DecodedData> {{");
+    let _ = writeln!(code, "    fn from(row: {struct_name}) -> Self {{");
+    let _ = writeln!(code, "        vec![");
+    for (field, field_name) in fields.iter().zip(&field_names) {
+        let _ = writeln!(code, "            ::rpfm_lib::files::table::DecodedData::{}(row.{}),", decoded_data_variant(field.field_type()), field_name);
+    }
+    let _ = writeln!(code, "        ]");
+    let _ = writeln!(code, "    }}");
+    let _ = writeln!(code, "}}");
+    let _ = writeln!(code);
+
+    code
+}
+
+/// This function writes the doc comment of a field, using its description and its key/reference data.
+fn write_field_doc(code: &mut String, field: &Field, is_key: bool) {
+    let mut has_doc = false;
+    for line in field.description().lines().filter(|line| !line.trim().is_empty()) {
+        let _ = writeln!(code, "    /// {}", line.trim());
+        has_doc = true;
+    }
+
+    if is_key {
+        if has_doc {
+            let _ = writeln!(code, "    ///");
+        }
+        let _ = writeln!(code, "    /// Key column.");
+        has_doc = true;
+    }
+
+    if let Some((ref_table, ref_column)) = field.is_reference() {
+        if has_doc && !is_key {
+            let _ = writeln!(code, "    ///");
+        }
+        let _ = writeln!(code, "    /// References `{ref_table}_tables/{ref_column}`.");
+    }
+}
+
+/// This function returns the name of the struct for a table, in PascalCase and without the `_tables` suffix.
+pub(crate) fn struct_name(table_name: &str) -> String {
+    let table_name = table_name.strip_suffix("_tables").unwrap_or(table_name);
+    let mut name = table_name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect::<String>();
+
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+
+    name.push_str("Row");
+    name
+}
+
+/// This function returns valid and unique Rust identifiers for the provided fields.
+///
+/// Column names that end up with the same identifier, like `a-b` and `a_b`, get a numeric suffix in order of appearance.
+fn field_names(fields: &[Field]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(fields.len());
+    for field in fields {
+        let base_name = field_name(field.name());
+        let mut name = base_name.to_owned();
+        let mut index = 1;
+        while names.contains(&name) {
+            index += 1;
+            name = format!("{}_{}", base_name.trim_start_matches("r#"), index);
+        }
+
+        names.push(name);
+    }
+
+    names
+}
+
+/// This function returns a valid Rust identifier for a column name.
+fn field_name(column_name: &str) -> String {
+    let mut name = column_name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect::<String>();
+
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+
+    // `self`, `Self`, `super` and `crate` cannot be raw identifiers, so we suffix them instead.
+    if ["self", "super", "crate"].contains(&name.as_str()) {
+        name.push('_');
+    } else if RUST_KEYWORDS.contains(&name.as_str()) {
+        name.insert_str(0, "r#");
+    }
+
+    name
+}
+
+/// This function returns the Rust type used to store a value of the provided field type.
+fn rust_type(field_type: &FieldType) -> &'static str {
+    match field_type {
+        FieldType::Boolean => "bool",
+        FieldType::F32 => "f32",
+        FieldType::F64 => "f64",
+        FieldType::I16 | FieldType::OptionalI16 => "i16",
+        FieldType::I32 | FieldType::OptionalI32 => "i32",
+        FieldType::I64 | FieldType::OptionalI64 => "i64",
+        FieldType::ColourRGB |
+        FieldType::StringU8 |
+        FieldType::StringU16 |
+        FieldType::OptionalStringU8 |
+        FieldType::OptionalStringU16 => "String",
+        FieldType::SequenceU16(_) | FieldType::SequenceU32(_) => "Vec<u8>",
+    }
+}
+
+/// This function returns if the Rust type used to store a value of the provided field type is `Copy`.
+fn is_copy(field_type: &FieldType) -> bool {
+    matches!(rust_type(field_type), "bool" | "f32" | "f64" | "i16" | "i32" | "i64")
+}
+
+/// This function returns the name of the [DecodedData] variant used to store a value of the provided field type.
+fn decoded_data_variant(field_type: &FieldType) -> String {
+    field_type.to_string()
+}
@@ -0,0 +1,176 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! Module containing tests for the code generator of table rows.
+
+use std::collections::BTreeMap;
+
+use crate::files::{db::DB, table::DecodedData};
+
+use super::*;
+use super::codegen::generate_table_rows;
+
+// Code generated from `fixture_schema`. Regenerate it with `generate_table_rows` if the generator changes.
+mod fixture {
+    include!("../../../test_files/codegen_fixture_rows.rs");
+}
+
+fn field(name: &str, field_type: FieldType, is_key: bool) -> Field {
+    Field::new(name.to_owned(), field_type, is_key, None, false, None, None, None, String::new(), -1, 0, BTreeMap::new(), None)
+}
+
+fn schema(fields: Vec<Field>) -> Schema {
+    let mut schema = Schema::default();
+    let mut definition = Definition::new(3, None);
+    definition.set_fields(fields);
+    schema.add_definition("test_units_tables", &definition);
+    schema
+}
+
+fn fixture_schema() -> Schema {
+    schema(vec![
+        field("key", FieldType::StringU8, true),
+        field("is_naval", FieldType::Boolean, false),
+        field("f32", FieldType::F32, false),
+        field("f64", FieldType::F64, false),
+        field("i16", FieldType::I16, false),
+        field("i32", FieldType::I32, false),
+        field("i64", FieldType::I64, false),
+        field("colour", FieldType::ColourRGB, false),
+        field("name", FieldType::StringU16, false),
+        field("optional_i16", FieldType::OptionalI16, false),
+        field("optional_i32", FieldType::OptionalI32, false),
+        field("optional_i64", FieldType::OptionalI64, false),
+        field("optional_string_u8", FieldType::OptionalStringU8, false),
+        field("optional_string_u16", FieldType::OptionalStringU16, false),
+        field("sequence_u16", FieldType::SequenceU16(Box::new(Definition::new(-1, None))), false),
+        field("sequence_u32", FieldType::SequenceU32(Box::new(Definition::new(-1, None))), false),
+        field("type", FieldType::I32, false),
+        field("self", FieldType::I32, false),
+        field("a-b", FieldType::I32, false),
+        field("a_b", FieldType::I32, false),
+    ])
+}
+
+#[test]
+fn test_generate_table_rows() {
+    let schema = schema(vec![
+        field("key", FieldType::StringU8, true),
+        field("cost", FieldType::I32, false),
+        field("is_naval", FieldType::Boolean, false),
+    ]);
+
+    let code = generate_table_rows(&schema, &[]).unwrap();
+    assert!(code.contains("pub struct TestUnitsRow {"));
+    assert!(code.contains("    pub key: String,"));
+    assert!(code.contains("    pub cost: i32,"));
+    assert!(code.contains("    pub is_naval: bool,"));
+    assert!(code.contains("    const TABLE_NAME: &'static str = \"test_units_tables\";"));
+    assert!(code.contains("    const VERSION: i32 = 3;"));
+    assert!(code.contains("            cost: match &row[1] {"));
+    assert!(code.contains("            ::rpfm_lib::files::table::DecodedData::Boolean(row.is_naval),"));
+}
+
+#[test]
+fn test_generate_table_rows_keywords() {
+    let schema = schema(vec![
+        field("type", FieldType::StringU8, true),
+        field("yield", FieldType::I32, false),
+        field("become", FieldType::I32, false),
+        field("try", FieldType::I32, false),
+        field("self", FieldType::I32, false),
+        field("1st", FieldType::I32, false),
+    ]);
+
+    let code = generate_table_rows(&schema, &[]).unwrap();
+    assert!(code.contains("    pub r#type: String,"));
+    assert!(code.contains("    pub r#yield: i32,"));
+    assert!(code.contains("    pub r#become: i32,"));
+    assert!(code.contains("    pub r#try: i32,"));
+    assert!(code.contains("    pub self_: i32,"));
+    assert!(code.contains("    pub _1st: i32,"));
+}
+
+#[test]
+fn test_generate_table_rows_colliding_names() {
+    let schema = schema(vec![
+        field("a-b", FieldType::I32, false),
+        field("a_b", FieldType::I32, false),
+        field("A B", FieldType::I32, false),
+        field("type", FieldType::I32, false),
+        field("Type", FieldType::I32, false),
+    ]);
+
+    let code = generate_table_rows(&schema, &[]).unwrap();
+    assert!(code.contains("    pub a_b: i32,"));
+    assert!(code.contains("    pub a_b_2: i32,"));
+    assert!(code.contains("    pub a_b_3: i32,"));
+    assert!(code.contains("    pub r#type: i32,"));
+    assert!(code.contains("    pub type_2: i32,"));
+    assert!(code.contains("            ::rpfm_lib::files::table::DecodedData::I32(row.a_b_3),"));
+    assert!(code.contains("            type_2: match &row[4] {"));
+}
+
+#[test]
+fn test_generate_table_rows_missing_table() {
+    let schema = schema(vec![field("key", FieldType::StringU8, true)]);
+    assert!(generate_table_rows(&schema, &["missing_tables"]).is_err());
+}
+
+#[test]
+fn test_generate_table_rows_fixture() {
+    let schema = fixture_schema();
+    let code = generate_table_rows(&schema, &[]).unwrap();
+    assert_eq!(code, include_str!("../../../test_files/codegen_fixture_rows.rs"));
+
+    // Rows go through the generated code and back without changes.
+    let definition = schema.definitions_by_table_name("test_units_tables").unwrap().first().unwrap();
+    let row = vec![
+        DecodedData::StringU8("knight".to_owned()),
+        DecodedData::Boolean(true),
+        DecodedData::F32(1.5),
+        DecodedData::F64(2.5),
+        DecodedData::I16(-16),
+        DecodedData::I32(-32),
+        DecodedData::I64(-64),
+        DecodedData::ColourRGB("FF0000".to_owned()),
+        DecodedData::StringU16("Knight".to_owned()),
+        DecodedData::OptionalI16(16),
+        DecodedData::OptionalI32(32),
+        DecodedData::OptionalI64(64),
+        DecodedData::OptionalStringU8("optional".to_owned()),
+        DecodedData::OptionalStringU16("optional_u16".to_owned()),
+        DecodedData::SequenceU16(vec![0, 0]),
+        DecodedData::SequenceU32(vec![0, 0, 0, 0]),
+        DecodedData::I32(1),
+        DecodedData::I32(2),
+        DecodedData::I32(3),
+        DecodedData::I32(4),
+    ];
+
+    let mut table = DB::new(definition, None, "test_units_tables", false);
+    table.set_data(None, &[row.to_vec()]).unwrap();
+
+    let mut rows = table.typed_rows::<fixture::TestUnitsRow>().unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].key, "knight");
+    assert_eq!(rows[0].r#type, 1);
+    assert_eq!(rows[0].self_, 2);
+    assert_eq!((rows[0].a_b, rows[0].a_b_2), (3, 4));
+    assert_eq!(Vec::<DecodedData>::from(rows[0].clone()), row);
+
+    rows[0].r#type = 10;
+    table.set_typed_rows(rows).unwrap();
+    assert_eq!(table.data(&None).unwrap()[0][16], DecodedData::I32(10));
+
+    // Rows with the wrong layout are rejected.
+    assert!(fixture::TestUnitsRow::try_from(&row[1..]).is_err());
+    assert!(fixture::TestUnitsRow::try_from(&[&row[1..], &row[..1]].concat()[..]).is_err());
+}
@@ -0,0 +1,384 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the logic to compare two revisions of a [Schema].
+
+The comparison is done table by table and definition by definition, identifying each definition by its version and game builds:
+- Definitions present in both schemas are compared field by field.
+- Definitions whose game builds changed are compared against the definition with the same version in the old schema.
+- Versions only present in the new schema are compared against the newest older version of the old schema,
+  as that's usually the version they replace in a game patch.
+
+Fields are matched by name. Fields that are not matched by name but are in the same position and with the same
+type in both definitions are reported as renamed.
+
+The result can be rendered as a Markdown changelog with [SchemaDiff::to_markdown], or as JSON with [SchemaDiff::to_json].
+!*/
+
+use getset::Getters;
+use serde_derive::Serialize;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display, Write};
+
+use crate::error::Result;
+
+use super::{Definition, DefinitionPatch, Field, Schema};
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct contains the differences between two schemas.
+#[derive(Clone, Debug, Default, PartialEq, Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct SchemaDiff {
+
+    /// Tables only in the new schema.
+    added_tables: Vec<String>,
+
+    /// Tables only in the old schema.
+    removed_tables: Vec<String>,
+
+    /// Tables in both schemas with differences, by table name.
+    changed_tables: BTreeMap<String, TableDiff>,
+}
+
+/// This type identifies a definition within a table, by its version and game builds.
+pub type DefinitionId = (i32, Option<(u32, u32)>);
+
+/// This struct contains the differences between the definitions of a table in two schemas.
+#[derive(Clone, Debug, Default, PartialEq, Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct TableDiff {
+
+    /// Definitions only in the new schema.
+    added_versions: Vec<DefinitionId>,
+
+    /// Definitions only in the old schema.
+    removed_versions: Vec<DefinitionId>,
+
+    /// Field changes of each definition in the new schema with differences, from newest to oldest.
+    versions: Vec<VersionDiff>,
+}
+
+/// This struct contains the field changes of a version of a table.
+#[derive(Clone, Debug, Default, PartialEq, Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct VersionDiff {
+
+    /// Version in the new schema.
+    version: i32,
+
+    /// Game builds of the version in the new schema.
+    game_builds: Option<(u32, u32)>,
+
+    /// Version in the old schema it was compared against. If it's different from `version`, this version is new.
+    compared_to: i32,
+
+    /// Game builds of the version in the old schema it was compared against. If they're different from `game_builds`, the build range changed.
+    compared_to_game_builds: Option<(u32, u32)>,
+
+    /// Changes over the version in the old schema.
+    changes: Vec<FieldChange>,
+}
+
+/// This enum represents a change in a field between two definitions.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub enum FieldChange {
+    Added { name: String, field_type: String },
+    Removed { name: String },
+    Renamed { old_name: String, new_name: String },
+    TypeChanged { name: String, old_type: String, new_type: String },
+    KeyChanged { name: String, is_key: bool },
+    ReferenceChanged { name: String, old_reference: Option<(String, String)>, new_reference: Option<(String, String)> },
+    LookupChanged { name: String, old_lookup: Option<Vec<String>>, new_lookup: Option<Vec<String>> },
+    DescriptionChanged { name: String, old_description: String, new_description: String },
+}
+
+//---------------------------------------------------------------------------//
+//                       Enum & Structs Implementations
+//---------------------------------------------------------------------------//
+
+impl SchemaDiff {
+
+    /// This function compares two schemas, returning what changed from the old one to the new one.
+    pub fn new(old_schema: &Schema, new_schema: &Schema) -> Self {
+        let old_tables = old_schema.definitions().keys().collect::<BTreeSet<_>>();
+        let new_tables = new_schema.definitions().keys().collect::<BTreeSet<_>>();
+
+        let added_tables = new_tables.difference(&old_tables).map(|table_name| table_name.to_string()).collect();
+        let removed_tables = old_tables.difference(&new_tables).map(|table_name| table_name.to_string()).collect();
+        let changed_tables = old_tables.intersection(&new_tables)
+            .filter_map(|table_name| {
+                let diff = TableDiff::new(
+                    &old_schema.definitions()[*table_name],
+                    &new_schema.definitions()[*table_name],
+                    old_schema.patches_for_table(table_name),
+                    new_schema.patches_for_table(table_name)
+                );
+
+                if diff.is_empty() {
+                    None
+                } else {
+                    Some((table_name.to_string(), diff))
+                }
+            })
+            .collect();
+
+        Self {
+            added_tables,
+            removed_tables,
+            changed_tables,
+        }
+    }
+
+    /// This function returns if both schemas had the same definitions.
+    pub fn is_empty(&self) -> bool {
+        self.added_tables.is_empty() && self.removed_tables.is_empty() && self.changed_tables.is_empty()
+    }
+
+    /// This function renders the diff as a human-readable changelog in Markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("# Schema Changelog\n");
+        if self.is_empty() {
+            markdown.push_str("\nNo changes.\n");
+            return markdown;
+        }
+
+        if !self.added_tables.is_empty() {
+            markdown.push_str("\n## Added Tables\n\n");
+            self.added_tables.iter().for_each(|table_name| { let _ = writeln!(markdown, "- `{table_name}`"); });
+        }
+
+        if !self.removed_tables.is_empty() {
+            markdown.push_str("\n## Removed Tables\n\n");
+            self.removed_tables.iter().for_each(|table_name| { let _ = writeln!(markdown, "- `{table_name}`"); });
+        }
+
+        if !self.changed_tables.is_empty() {
+            markdown.push_str("\n## Changed Tables\n");
+            for (table_name, table_diff) in &self.changed_tables {
+                let _ = writeln!(markdown, "\n### `{table_name}`\n");
+
+                for (version, game_builds) in &table_diff.removed_versions {
+                    if !table_diff.versions.iter().any(|version_diff| version_diff.compared_to == *version && version_diff.compared_to_game_builds == *game_builds) {
+                        let _ = writeln!(markdown, "- Removed version {}.", version_name(*version, game_builds));
+                    }
+                }
+
+                for (version, game_builds) in &table_diff.added_versions {
+                    if !table_diff.versions.iter().any(|version_diff| version_diff.version == *version && version_diff.game_builds == *game_builds) {
+                        let _ = writeln!(markdown, "- Added version {}.", version_name(*version, game_builds));
+                    }
+                }
+
+                for version_diff in &table_diff.versions {
+                    if version_diff.version != version_diff.compared_to {
+                        let _ = writeln!(markdown, "- Added version {} (changes from version {}):", version_name(version_diff.version, &version_diff.game_builds), version_name(version_diff.compared_to, &version_diff.compared_to_game_builds));
+                    } else if version_diff.game_builds != version_diff.compared_to_game_builds {
+                        let _ = writeln!(markdown, "- Changed game builds of version {} from {} to {}:", version_diff.version, game_builds_name(&version_diff.compared_to_game_builds), game_builds_name(&version_diff.game_builds));
+                    } else {
+                        let _ = writeln!(markdown, "- Changed version {}:", version_name(version_diff.version, &version_diff.game_builds));
+                    }
+
+                    if version_diff.changes.is_empty() {
+                        markdown.push_str("    - No field changes.\n");
+                    }
+
+                    for change in &version_diff.changes {
+                        let _ = writeln!(markdown, "    - {change}");
+                    }
+                }
+            }
+        }
+
+        markdown
+    }
+
+    /// This function renders the diff as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(From::from)
+    }
+}
+
+impl TableDiff {
+
+    /// This function compares the definitions of a table in two schemas.
+    pub fn new(old_definitions: &[Definition], new_definitions: &[Definition], old_patches: Option<&DefinitionPatch>, new_patches: Option<&DefinitionPatch>) -> Self {
+        let id = |definition: &Definition| (*definition.version(), *definition.game_builds());
+        let old_ids = old_definitions.iter().map(id).collect::<BTreeSet<_>>();
+        let new_ids = new_definitions.iter().map(id).collect::<BTreeSet<_>>();
+
+        // Newest versions first, like in the schema files.
+        let added_versions = new_ids.iter().rev().filter(|id| !old_ids.contains(id)).copied().collect();
+        let removed_versions = old_ids.iter().rev().filter(|id| !new_ids.contains(id)).copied().collect::<Vec<_>>();
+
+        let mut new_definitions = new_definitions.iter().collect::<Vec<_>>();
+        new_definitions.sort_by_key(|definition| std::cmp::Reverse(id(definition)));
+
+        let versions = new_definitions.iter()
+            .filter_map(|new_definition| {
+                let version = *new_definition.version();
+                let game_builds = *new_definition.game_builds();
+
+                // Definitions are compared against the same definition, then against a removed one with the same version but other game builds,
+                // and new versions against the newest older version they replace.
+                let old_definition = old_definitions.iter()
+                    .find(|old_definition| id(old_definition) == (version, game_builds))
+                    .or_else(|| old_definitions.iter()
+                        .filter(|old_definition| *old_definition.version() == version && removed_versions.contains(&id(old_definition)))
+                        .max_by_key(|old_definition| old_definition.game_builds().map(|(_, last)| last)))
+                    .or_else(|| old_definitions.iter()
+                        .filter(|old_definition| *old_definition.version() < version)
+                        .max_by_key(|old_definition| id(old_definition)))?;
+
+                let changes = definition_changes(old_definition, new_definition, old_patches, new_patches);
+                if changes.is_empty() && id(old_definition) == (version, game_builds) {
+                    None
+                } else {
+                    Some(VersionDiff {
+                        version,
+                        game_builds,
+                        compared_to: *old_definition.version(),
+                        compared_to_game_builds: *old_definition.game_builds(),
+                        changes,
+                    })
+                }
+            })
+            .collect();
+
+        Self {
+            added_versions,
+            removed_versions,
+            versions,
+        }
+    }
+
+    /// This function returns if the table had no differences.
+    pub fn is_empty(&self) -> bool {
+        self.added_versions.is_empty() && self.removed_versions.is_empty() && self.versions.is_empty()
+    }
+}
+
+impl Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reference_to_string = |reference: &Option<(String, String)>| match reference {
+            Some((table, column)) => format!("`{table}/{column}`"),
+            None => "nothing".to_owned(),
+        };
+
+        let lookup_to_string = |lookup: &Option<Vec<String>>| match lookup {
+            Some(lookup) if !lookup.is_empty() => format!("`{}`", lookup.join(", ")),
+            _ => "nothing".to_owned(),
+        };
+
+        match self {
+            Self::Added { name, field_type } => write!(f, "Added field `{name}` ({field_type})."),
+            Self::Removed { name } => write!(f, "Removed field `{name}`."),
+            Self::Renamed { old_name, new_name } => write!(f, "Renamed field `{old_name}` to `{new_name}`."),
+            Self::TypeChanged { name, old_type, new_type } => write!(f, "Changed type of field `{name}` from {old_type} to {new_type}."),
+            Self::KeyChanged { name, is_key } => if *is_key {
+                write!(f, "Field `{name}` is now a key.")
+            } else {
+                write!(f, "Field `{name}` is no longer a key.")
+            },
+            Self::ReferenceChanged { name, old_reference, new_reference } => write!(f, "Changed reference of field `{name}` from {} to {}.", reference_to_string(old_reference), reference_to_string(new_reference)),
+            Self::LookupChanged { name, old_lookup, new_lookup } => write!(f, "Changed lookup of field `{name}` from {} to {}.", lookup_to_string(old_lookup), lookup_to_string(new_lookup)),
+            Self::DescriptionChanged { name, old_description, new_description } => write!(f, "Changed description of field `{name}` from \"{old_description}\" to \"{new_description}\"."),
+        }
+    }
+}
+
+/// This function returns the name of a version for the changelog, including its game builds if it has them.
+fn version_name(version: i32, game_builds: &Option<(u32, u32)>) -> String {
+    match game_builds {
+        Some(_) => format!("{version} ({})", game_builds_name(game_builds)),
+        None => version.to_string(),
+    }
+}
+
+/// This function returns the name of a game build range for the changelog.
+fn game_builds_name(game_builds: &Option<(u32, u32)>) -> String {
+    match game_builds {
+        Some((first, last)) => format!("builds {first}-{last}"),
+        None => "all builds".to_owned(),
+    }
+}
+
+/// This function returns the changes in the fields of two definitions, in the order of the fields of the new one.
+fn definition_changes(old_definition: &Definition, new_definition: &Definition, old_patches: Option<&DefinitionPatch>, new_patches: Option<&DefinitionPatch>) -> Vec<FieldChange> {
+    let old_fields = old_definition.fields();
+    let new_fields = new_definition.fields();
+
+    // Unmatched fields in the same position and with the same type are considered renamed.
+    let is_unmatched = |field: &Field, others: &[Field]| !others.iter().any(|other| other.name() == field.name());
+    let renamed_from = |new_index: usize, new_field: &Field| old_fields.get(new_index)
+        .filter(|old_field| is_unmatched(new_field, old_fields) && is_unmatched(old_field, new_fields) && old_field.field_type() == new_field.field_type());
+
+    let mut changes = vec![];
+    let mut renamed_old_names = BTreeSet::new();
+    for (new_index, new_field) in new_fields.iter().enumerate() {
+        let old_field = match old_fields.iter().find(|old_field| old_field.name() == new_field.name()) {
+            Some(old_field) => old_field,
+            None => match renamed_from(new_index, new_field) {
+                Some(old_field) => {
+                    renamed_old_names.insert(old_field.name());
+                    changes.push(FieldChange::Renamed { old_name: old_field.name().to_owned(), new_name: new_field.name().to_owned() });
+                    old_field
+                }
+                None => {
+                    changes.push(FieldChange::Added { name: new_field.name().to_owned(), field_type: new_field.field_type().to_string() });
+                    continue;
+                }
+            }
+        };
+
+        changes.append(&mut field_changes(old_field, new_field, old_patches, new_patches));
+    }
+
+    for old_field in old_fields {
+        if is_unmatched(old_field, new_fields) && !renamed_old_names.contains(old_field.name()) {
+            changes.push(FieldChange::Removed { name: old_field.name().to_owned() });
+        }
+    }
+
+    changes
+}
+
+/// This function returns the changes between two versions of the same field.
+fn field_changes(old_field: &Field, new_field: &Field, old_patches: Option<&DefinitionPatch>, new_patches: Option<&DefinitionPatch>) -> Vec<FieldChange> {
+    let name = new_field.name().to_owned();
+    let mut changes = vec![];
+
+    if old_field.field_type() != new_field.field_type() {
+        changes.push(FieldChange::TypeChanged { name: name.to_owned(), old_type: old_field.field_type().to_string(), new_type: new_field.field_type().to_string() });
+    }
+
+    let is_key = new_field.is_key(new_patches);
+    if old_field.is_key(old_patches) != is_key {
+        changes.push(FieldChange::KeyChanged { name: name.to_owned(), is_key });
+    }
+
+    if old_field.is_reference() != new_field.is_reference() {
+        changes.push(FieldChange::ReferenceChanged { name: name.to_owned(), old_reference: old_field.is_reference().clone(), new_reference: new_field.is_reference().clone() });
+    }
+
+    if old_field.lookup() != new_field.lookup() {
+        changes.push(FieldChange::LookupChanged { name: name.to_owned(), old_lookup: old_field.lookup().clone(), new_lookup: new_field.lookup().clone() });
+    }
+
+    if old_field.description() != new_field.description() {
+        changes.push(FieldChange::DescriptionChanged { name, old_description: old_field.description().to_owned(), new_description: new_field.description().to_owned() });
+    }
+
+    changes
+}
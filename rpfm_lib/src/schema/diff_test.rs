@@ -0,0 +1,174 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! Module containing tests for the comparison of schemas.
+
+use std::collections::{BTreeMap, HashMap};
+
+use super::*;
+use super::diff::{FieldChange, SchemaDiff};
+
+fn field(name: &str, field_type: FieldType, is_key: bool) -> Field {
+    Field::new(name.to_owned(), field_type, is_key, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None)
+}
+
+fn definition(version: i32, fields: Vec<Field>) -> Definition {
+    let mut definition = Definition::new(version, None);
+    definition.set_fields(fields);
+    definition
+}
+
+fn units_v1() -> Definition {
+    definition(1, vec![
+        field("key", FieldType::StringU8, true),
+        field("cost", FieldType::I32, false),
+        field("old_name", FieldType::StringU8, false),
+        field("removed", FieldType::Boolean, false),
+    ])
+}
+
+#[test]
+fn test_schema_diff_tables() {
+    let mut old_schema = Schema::default();
+    old_schema.add_definition("units_tables", &units_v1());
+    old_schema.add_definition("removed_tables", &definition(1, vec![field("key", FieldType::StringU8, true)]));
+
+    let mut new_schema = Schema::default();
+    new_schema.add_definition("units_tables", &units_v1());
+    new_schema.add_definition("added_tables", &definition(1, vec![field("key", FieldType::StringU8, true)]));
+
+    let diff = SchemaDiff::new(&old_schema, &new_schema);
+    assert_eq!(diff.added_tables(), &vec!["added_tables".to_owned()]);
+    assert_eq!(diff.removed_tables(), &vec!["removed_tables".to_owned()]);
+
+    // Identical tables are not reported.
+    assert!(diff.changed_tables().is_empty());
+    assert!(SchemaDiff::new(&old_schema, &old_schema).is_empty());
+    assert!(SchemaDiff::new(&old_schema, &old_schema).to_markdown().contains("No changes."));
+}
+
+#[test]
+fn test_schema_diff_fields() {
+    let mut old_schema = Schema::default();
+    old_schema.add_definition("units_tables", &units_v1());
+
+    // New versions are compared against the newest older version.
+    let mut new_field = field("new_name", FieldType::StringU8, false);
+    new_field.set_is_reference(Some(("names".to_owned(), "key".to_owned())));
+    let units_v2 = definition(2, vec![
+        field("key", FieldType::StringU8, true),
+        field("cost", FieldType::F32, true),
+        new_field,
+        field("added", FieldType::I16, false),
+    ]);
+
+    let mut new_schema = Schema::default();
+    new_schema.add_definition("units_tables", &units_v1());
+    new_schema.add_definition("units_tables", &units_v2);
+
+    let diff = SchemaDiff::new(&old_schema, &new_schema);
+    let table_diff = &diff.changed_tables()["units_tables"];
+    assert_eq!(table_diff.added_versions(), &vec![(2, None)]);
+    assert!(table_diff.removed_versions().is_empty());
+    assert_eq!(table_diff.versions().len(), 1);
+
+    let version_diff = &table_diff.versions()[0];
+    assert_eq!(*version_diff.version(), 2);
+    assert_eq!(*version_diff.compared_to(), 1);
+    assert_eq!(version_diff.changes(), &vec![
+        FieldChange::TypeChanged { name: "cost".to_owned(), old_type: "I32".to_owned(), new_type: "F32".to_owned() },
+        FieldChange::KeyChanged { name: "cost".to_owned(), is_key: true },
+        FieldChange::Renamed { old_name: "old_name".to_owned(), new_name: "new_name".to_owned() },
+        FieldChange::ReferenceChanged { name: "new_name".to_owned(), old_reference: None, new_reference: Some(("names".to_owned(), "key".to_owned())) },
+        FieldChange::Added { name: "added".to_owned(), field_type: "I16".to_owned() },
+        FieldChange::Removed { name: "removed".to_owned() },
+    ]);
+
+    let markdown = diff.to_markdown();
+    assert!(markdown.contains("### `units_tables`"));
+    assert!(markdown.contains("- Added version 2 (changes from version 1):"));
+    assert!(markdown.contains("    - Renamed field `old_name` to `new_name`."));
+    assert!(markdown.contains("    - Changed reference of field `new_name` from nothing to `names/key`."));
+
+    let json = diff.to_json().unwrap();
+    assert!(json.contains("\"units_tables\""));
+    assert!(json.contains("\"TypeChanged\""));
+}
+
+#[test]
+fn test_schema_diff_versions_and_patches() {
+    let mut old_schema = Schema::default();
+    old_schema.add_definition("units_tables", &definition(0, vec![field("key", FieldType::StringU8, true)]));
+    old_schema.add_definition("units_tables", &units_v1());
+
+    // Key changes through patches are reported too.
+    let mut new_schema = Schema::default();
+    new_schema.add_definition("units_tables", &units_v1());
+    new_schema.add_patch(HashMap::from([("units_tables".to_owned(), HashMap::from([("cost".to_owned(), HashMap::from([("is_key".to_owned(), "true".to_owned())]))]))]));
+
+    let diff = SchemaDiff::new(&old_schema, &new_schema);
+    let table_diff = &diff.changed_tables()["units_tables"];
+    assert_eq!(table_diff.removed_versions(), &vec![(0, None)]);
+    assert!(table_diff.added_versions().is_empty());
+    assert_eq!(table_diff.versions()[0].changes(), &vec![FieldChange::KeyChanged { name: "cost".to_owned(), is_key: true }]);
+
+    let markdown = diff.to_markdown();
+    assert!(markdown.contains("- Removed version 0."));
+    assert!(markdown.contains("- Changed version 1:"));
+    assert!(markdown.contains("    - Field `cost` is now a key."));
+}
+
+#[test]
+fn test_schema_diff_game_builds() {
+    let with_builds = |version: i32, game_builds: Option<(u32, u32)>, fields: Vec<Field>| {
+        let mut definition = definition(version, fields);
+        definition.set_game_builds(game_builds);
+        definition
+    };
+
+    let mut old_schema = Schema::default();
+    old_schema.add_definition("units_tables", &with_builds(1, Some((0, 99)), vec![field("key", FieldType::StringU8, true)]));
+    old_schema.add_definition("units_tables", &with_builds(1, Some((100, 199)), vec![field("key", FieldType::StringU8, true), field("cost", FieldType::I32, false)]));
+
+    // Definitions with the same version are matched by their game builds, and build range changes are reported.
+    let mut new_schema = Schema::default();
+    new_schema.add_definition("units_tables", &with_builds(1, Some((0, 99)), vec![field("key", FieldType::StringU8, true)]));
+    new_schema.add_definition("units_tables", &with_builds(1, Some((100, 299)), vec![field("key", FieldType::StringU8, true), field("cost", FieldType::I32, false)]));
+
+    let diff = SchemaDiff::new(&old_schema, &new_schema);
+    let table_diff = &diff.changed_tables()["units_tables"];
+    assert_eq!(table_diff.added_versions(), &vec![(1, Some((100, 299)))]);
+    assert_eq!(table_diff.removed_versions(), &vec![(1, Some((100, 199)))]);
+    assert_eq!(table_diff.versions().len(), 1);
+
+    let version_diff = &table_diff.versions()[0];
+    assert_eq!(*version_diff.game_builds(), Some((100, 299)));
+    assert_eq!(*version_diff.compared_to_game_builds(), Some((100, 199)));
+    assert!(version_diff.changes().is_empty());
+
+    let markdown = diff.to_markdown();
+    assert!(markdown.contains("- Changed game builds of version 1 from builds 100-199 to builds 100-299:"));
+    assert!(!markdown.contains("Removed version"));
+    assert!(!markdown.contains("Added version"));
+
+    // Changes in one of the definitions don't get mixed with the others with the same version.
+    let mut new_schema = Schema::default();
+    new_schema.add_definition("units_tables", &with_builds(1, Some((0, 99)), vec![field("key", FieldType::StringU8, true), field("added", FieldType::I32, false)]));
+    new_schema.add_definition("units_tables", &with_builds(1, Some((100, 199)), vec![field("key", FieldType::StringU8, true), field("cost", FieldType::I32, false)]));
+
+    let diff = SchemaDiff::new(&old_schema, &new_schema);
+    let table_diff = &diff.changed_tables()["units_tables"];
+    assert!(table_diff.added_versions().is_empty());
+    assert!(table_diff.removed_versions().is_empty());
+    assert_eq!(table_diff.versions().len(), 1);
+    assert_eq!(*table_diff.versions()[0].game_builds(), Some((0, 99)));
+    assert_eq!(table_diff.versions()[0].changes(), &vec![FieldChange::Added { name: "added".to_owned(), field_type: "I32".to_owned() }]);
+    assert!(diff.to_markdown().contains("- Changed version 1 (builds 0-99):"));
+}
@@ -0,0 +1,495 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with exporters of table definitions to standard formats, for external tools.
+
+Each table version can be exported as:
+- A [JSON Schema](https://json-schema.org/) document describing a row.
+- SQL DDL, for SQLite or PostgreSQL, with primary keys from the key columns and foreign keys from the references.
+  Only references to the only key column or to an `unique` column of the newest definition of a table in the schema
+  are exported as foreign keys, as the rest cannot be enforced by a SQL database.
+- A TypeScript interface describing a row.
+
+All exporters use the processed layout of the definition (bitwise columns split and colour columns merged), as that's the
+layout rows have once decoded. The exception are columns with enum values, which keep their integer type, so the exported
+data matches the one in the files. They're exported as enums of their known keys, with the names of the keys in their description.
+The validation rules from the schema patches (see [validation](super::validation)) are used where the format supports them.
+
+Optional columns are exported as regular ones, as decoded rows always have a value for them.
+Sequence columns are exported as opaque byte arrays, like in [codegen](super::codegen).
+!*/
+
+use serde_json::{json, Map, Value};
+
+use std::fmt::Write;
+use std::fs::{DirBuilder, File};
+use std::io::{BufWriter, Write as IOWrite};
+use std::path::Path;
+
+use crate::error::Result;
+
+use super::{Definition, DefinitionPatch, Field, FieldType, Schema};
+use super::codegen::struct_name;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This enum represents the SQL dialects the DDL exporter supports.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SqlDialect {
+    SQLite,
+    PostgreSQL,
+}
+
+/// This enum represents the formats a schema can be exported to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    JsonSchema,
+    Sql(SqlDialect),
+    TypeScript,
+}
+
+//---------------------------------------------------------------------------//
+//                       Enum & Structs Implementations
+//---------------------------------------------------------------------------//
+
+impl ExportFormat {
+
+    /// This function returns the extension of the files exported in this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::JsonSchema => "schema.json",
+            Self::Sql(_) => "sql",
+            Self::TypeScript => "ts",
+        }
+    }
+
+    /// This function exports a table definition of the provided schema in this format.
+    pub fn export_definition(&self, schema: &Schema, table_name: &str, definition: &Definition) -> Result<String> {
+        let schema_patches = schema.patches_for_table(table_name);
+        match self {
+            Self::JsonSchema => definition_to_json_schema(table_name, definition, schema_patches),
+            Self::Sql(dialect) => Ok(definition_to_sql(schema, table_name, definition, *dialect)),
+            Self::TypeScript => Ok(definition_to_typescript(table_name, definition, schema_patches)),
+        }
+    }
+}
+
+/// This function exports every version of every table in a schema to the provided folder, one file per table and version.
+///
+/// Files are named `{table_name}_v{version}.{extension}`, or `{table_name}_v{version}_b{first_build}-{last_build}.{extension}`
+/// for definitions with game builds. In PostgreSQL, the foreign keys of each definition go in an extra `.foreign_keys.sql` file,
+/// to be run once all the tables have been created.
+pub fn export_schema(schema: &Schema, format: ExportFormat, folder_path: &Path) -> Result<()> {
+    DirBuilder::new().recursive(true).create(folder_path)?;
+
+    for (table_name, definitions) in schema.definitions() {
+        for definition in definitions {
+            let file_name = definition_file_name(table_name, definition);
+            let data = format.export_definition(schema, table_name, definition)?;
+            let path = folder_path.join(format!("{}.{}", file_name, format.extension()));
+
+            let mut file = BufWriter::new(File::create(path)?);
+            file.write_all(data.as_bytes())?;
+
+            if let ExportFormat::Sql(SqlDialect::PostgreSQL) = format {
+                let data = definition_to_sql_foreign_keys(schema, table_name, definition);
+                if !data.is_empty() {
+                    let path = folder_path.join(format!("{file_name}.foreign_keys.sql"));
+                    let mut file = BufWriter::new(File::create(path)?);
+                    file.write_all(data.as_bytes())?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// This function returns the name of the exported files of a definition, without extension.
+fn definition_file_name(table_name: &str, definition: &Definition) -> String {
+    match definition.game_builds() {
+        Some((first, last)) => format!("{}_v{}_b{}-{}", table_name, definition.version(), first, last),
+        None => format!("{}_v{}", table_name, definition.version()),
+    }
+}
+
+/// This function exports a table definition as a JSON Schema document describing a row of the table.
+///
+/// Key columns are listed in the custom `x-primary-key` keyword, and references in the custom `x-reference` keyword of each column.
+pub fn definition_to_json_schema(table_name: &str, definition: &Definition, schema_patches: Option<&DefinitionPatch>) -> Result<String> {
+    let fields = export_fields(definition);
+    let mut properties = Map::new();
+
+    for field in &fields {
+        let mut property = json_schema_type(field.field_type());
+
+        if let Some(values) = enum_values(field, schema_patches) {
+            property.insert("enum".to_owned(), json!(values.iter().map(|value| json_value(field.field_type(), value)).collect::<Vec<_>>()));
+        }
+
+        if let Some(min_value) = field.min_value(schema_patches) {
+            property.insert("minimum".to_owned(), json!(min_value));
+        }
+
+        if let Some(max_value) = field.max_value(schema_patches) {
+            property.insert("maximum".to_owned(), json!(max_value));
+        }
+
+        if let Some(pattern) = field.value_pattern(schema_patches) {
+            property.insert("pattern".to_owned(), json!(format!("^(?:{pattern})$")));
+        }
+
+        if let Some(description) = description(field) {
+            property.insert("description".to_owned(), json!(description));
+        }
+
+        if let Some(default_value) = field.default_value(schema_patches) {
+            property.insert("default".to_owned(), json_value(field.field_type(), &default_value));
+        }
+
+        if let Some((ref_table, ref_column)) = field.is_reference() {
+            property.insert("x-reference".to_owned(), json!(format!("{ref_table}_tables/{ref_column}")));
+        }
+
+        properties.insert(field.name().to_owned(), Value::Object(property));
+    }
+
+    let document = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": format!("{table_name} v{}", definition.version()),
+        "type": "object",
+        "properties": properties,
+        "required": fields.iter().map(|field| field.name()).collect::<Vec<_>>(),
+        "additionalProperties": false,
+        "x-primary-key": fields.iter().filter(|field| field.is_key(schema_patches)).map(|field| field.name()).collect::<Vec<_>>(),
+    });
+
+    serde_json::to_string_pretty(&document).map_err(From::from)
+}
+
+/// This function exports a table definition of the provided schema as a `CREATE TABLE` statement in the provided SQL dialect.
+///
+/// The table is named after the table, without version, so each file can be loaded as the current version of the table.
+/// Foreign keys point to the referenced tables by name. In SQLite they're part of the table, as it doesn't check them on creation.
+/// In PostgreSQL they're not included, as they need the referenced tables to exist. Use [definition_to_sql_foreign_keys] for them.
+pub fn definition_to_sql(schema: &Schema, table_name: &str, definition: &Definition, dialect: SqlDialect) -> String {
+    let schema_patches = schema.patches_for_table(table_name);
+    let fields = export_fields(definition);
+    let keys = fields.iter()
+        .filter(|field| field.is_key(schema_patches))
+        .map(|field| sql_identifier(field.name()))
+        .collect::<Vec<_>>();
+
+    let mut columns = vec![];
+
+    for field in &fields {
+        let mut column = format!("    {} {} NOT NULL", sql_identifier(field.name()), sql_type(field.field_type(), dialect));
+
+        if let Some(default_value) = field.default_value(schema_patches).and_then(|value| sql_value(field.field_type(), &value, dialect)) {
+            column.push_str(&format!(" DEFAULT {default_value}"));
+        }
+
+        // Unique columns can be the target of foreign keys, so they need the constraint.
+        if field.is_unique(schema_patches) && (keys.len() != 1 || !field.is_key(schema_patches)) {
+            column.push_str(" UNIQUE");
+        }
+
+        if let Some(values) = enum_values(field, schema_patches) {
+            let values = values.iter().filter_map(|value| sql_value(field.field_type(), value, dialect)).collect::<Vec<_>>();
+            if !values.is_empty() {
+                column.push_str(&format!(" CHECK ({} IN ({}))", sql_identifier(field.name()), values.join(", ")));
+            }
+        }
+
+        // Comments go in their own line, so they don't swallow the comma after the column.
+        if let Some(description) = description(field) {
+            column.insert_str(0, &format!("    -- {}\n", description.replace('\n', " ")));
+        }
+
+        columns.push(column);
+    }
+
+    if !keys.is_empty() {
+        columns.push(format!("    PRIMARY KEY ({})", keys.join(", ")));
+    }
+
+    if dialect == SqlDialect::SQLite {
+        columns.extend(sql_foreign_keys(schema, definition).iter().map(|foreign_key| format!("    {foreign_key}")));
+    }
+
+    let mut sql = format!("-- {}.\n", sql_title(table_name, definition));
+    let _ = writeln!(sql, "CREATE TABLE {} (\n{}\n);", sql_identifier(table_name), columns.join(",\n"));
+    sql
+}
+
+/// This function exports the foreign keys of a table definition of the provided schema as PostgreSQL `ALTER TABLE` statements.
+///
+/// These statements must be run once all the referenced tables exist. If the definition has no foreign keys, this returns an empty string.
+pub fn definition_to_sql_foreign_keys(schema: &Schema, table_name: &str, definition: &Definition) -> String {
+    let foreign_keys = sql_foreign_keys(schema, definition);
+    if foreign_keys.is_empty() {
+        return String::new();
+    }
+
+    let mut sql = format!("-- Foreign keys of {}.\n", sql_title(table_name, definition));
+    for foreign_key in &foreign_keys {
+        let _ = writeln!(sql, "ALTER TABLE {} ADD {foreign_key};", sql_identifier(table_name));
+    }
+
+    sql
+}
+
+/// This function returns the title of the SQL of a definition, used in its comments.
+fn sql_title(table_name: &str, definition: &Definition) -> String {
+    match definition.game_builds() {
+        Some((first, last)) => format!("{table_name}, version {}, builds {first}-{last}", definition.version()),
+        None => format!("{table_name}, version {}", definition.version()),
+    }
+}
+
+/// This function returns the foreign key constraints of a definition.
+///
+/// Only references to the only key column or to an `unique` column of the newest definition of the referenced table are returned,
+/// as a SQL foreign key needs the referenced column to be unique by itself.
+fn sql_foreign_keys(schema: &Schema, definition: &Definition) -> Vec<String> {
+    export_fields(definition).iter()
+        .filter_map(|field| {
+            let (ref_table, ref_column) = field.is_reference().as_ref()?;
+            let ref_table_name = format!("{ref_table}_tables");
+            let ref_definition = schema.definitions_by_table_name(&ref_table_name)?.iter().max_by_key(|definition| *definition.version())?;
+            let ref_patches = schema.patches_for_table(&ref_table_name);
+            let ref_fields = export_fields(ref_definition);
+            let ref_keys = ref_fields.iter().filter(|field| field.is_key(ref_patches)).collect::<Vec<_>>();
+
+            let is_only_key = ref_keys.len() == 1 && ref_keys[0].name() == ref_column;
+            let is_unique = ref_fields.iter().any(|field| field.name() == ref_column && field.is_unique(ref_patches));
+            if is_only_key || is_unique {
+                Some(format!("FOREIGN KEY ({}) REFERENCES {}({})",
+                    sql_identifier(field.name()),
+                    sql_identifier(&ref_table_name),
+                    sql_identifier(ref_column)
+                ))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// This function exports a table definition as a TypeScript interface describing a row of the table.
+///
+/// The interface is named like the structs generated by [codegen](super::codegen), with the version as suffix.
+pub fn definition_to_typescript(table_name: &str, definition: &Definition, schema_patches: Option<&DefinitionPatch>) -> String {
+    let version = definition.version();
+    let mut code = String::new();
+    let _ = writeln!(code, "/** Row of `{table_name}`, version {version}. */");
+    let _ = writeln!(code, "export interface {}V{} {{", struct_name(table_name), if *version < 0 { format!("Minus{}", version.unsigned_abs()) } else { version.to_string() });
+
+    for field in export_fields(definition) {
+        let mut doc = vec![];
+        if let Some(description) = description(&field) {
+            doc.push(description);
+        }
+
+        if field.is_key(schema_patches) {
+            doc.push("Key column.".to_owned());
+        }
+
+        if let Some((ref_table, ref_column)) = field.is_reference() {
+            doc.push(format!("References `{ref_table}_tables/{ref_column}`."));
+        }
+
+        if !doc.is_empty() {
+            let _ = writeln!(code, "    /** {} */", doc.join(" ").replace("*/", "*\\/"));
+        }
+
+        let field_type = match enum_values(&field, schema_patches) {
+            Some(values) => values.iter().map(|value| json_value(field.field_type(), value).to_string()).collect::<Vec<_>>().join(" | "),
+            None => typescript_type(field.field_type()).to_owned(),
+        };
+
+        let _ = writeln!(code, "    {}: {field_type};", typescript_identifier(field.name()));
+    }
+
+    code.push_str("}\n");
+    code
+}
+
+/// This function returns the processed fields of a definition, with the columns with enum values keeping their original type.
+fn export_fields(definition: &Definition) -> Vec<Field> {
+    definition.fields_processed()
+        .into_iter()
+        .map(|mut field| {
+            if !field.enum_values().is_empty() {
+                if let Some(original) = definition.fields().iter().find(|original| original.name() == field.name()) {
+                    field.set_field_type(original.field_type().clone());
+                }
+            }
+
+            field
+        })
+        .collect()
+}
+
+/// This function returns the description of a column, with the names of its enum values if it has them.
+fn description(field: &Field) -> Option<String> {
+    let mut description = field.description().to_owned();
+    if !field.enum_values().is_empty() {
+        let values = field.enum_values().iter().map(|(key, name)| format!("{key} = {name}")).collect::<Vec<_>>().join(", ");
+        if !description.is_empty() {
+            description.push(' ');
+        }
+
+        let _ = write!(description, "Values: {values}.");
+    }
+
+    if description.is_empty() {
+        None
+    } else {
+        Some(description)
+    }
+}
+
+/// This function returns the known values of a column, from the keys of its enum values or from its `allowed_values` validation rule.
+fn enum_values(field: &Field, schema_patches: Option<&DefinitionPatch>) -> Option<Vec<String>> {
+    if !field.enum_values().is_empty() {
+        Some(field.enum_values().keys().map(|key| key.to_string()).collect())
+    } else {
+        field.allowed_values(schema_patches)
+    }
+}
+
+/// This function returns the JSON Schema type of the provided field type.
+fn json_schema_type(field_type: &FieldType) -> Map<String, Value> {
+    let value = match field_type {
+        FieldType::Boolean => json!({ "type": "boolean" }),
+        FieldType::F32 | FieldType::F64 => json!({ "type": "number" }),
+        FieldType::I16 | FieldType::OptionalI16 => json!({ "type": "integer", "minimum": i16::MIN, "maximum": i16::MAX }),
+        FieldType::I32 | FieldType::OptionalI32 => json!({ "type": "integer", "minimum": i32::MIN, "maximum": i32::MAX }),
+        FieldType::I64 | FieldType::OptionalI64 => json!({ "type": "integer" }),
+        FieldType::ColourRGB => json!({ "type": "string", "pattern": "^[0-9A-Fa-f]{6}$" }),
+        FieldType::StringU8 |
+        FieldType::StringU16 |
+        FieldType::OptionalStringU8 |
+        FieldType::OptionalStringU16 => json!({ "type": "string" }),
+        FieldType::SequenceU16(_) | FieldType::SequenceU32(_) => json!({ "type": "array", "items": { "type": "integer", "minimum": 0, "maximum": 255 } }),
+    };
+
+    match value {
+        Value::Object(map) => map,
+        _ => unreachable!(),
+    }
+}
+
+/// This function returns a value as a JSON value of the right type, falling back to a string if it cannot be parsed.
+fn json_value(field_type: &FieldType, value: &str) -> Value {
+    match field_type {
+        FieldType::Boolean => json!(parse_bool(value)),
+        FieldType::F32 | FieldType::F64 => value.parse::<f64>().map(|value| json!(value)).unwrap_or_else(|_| json!(value)),
+        FieldType::I16 | FieldType::I32 | FieldType::I64 |
+        FieldType::OptionalI16 | FieldType::OptionalI32 | FieldType::OptionalI64 => value.parse::<i64>().map(|value| json!(value)).unwrap_or_else(|_| json!(value)),
+        _ => json!(value),
+    }
+}
+
+/// This function returns the SQL type of the provided field type.
+fn sql_type(field_type: &FieldType, dialect: SqlDialect) -> &'static str {
+    match dialect {
+        SqlDialect::SQLite => match field_type {
+            FieldType::Boolean |
+            FieldType::I16 | FieldType::I32 | FieldType::I64 |
+            FieldType::OptionalI16 | FieldType::OptionalI32 | FieldType::OptionalI64 => "INTEGER",
+            FieldType::F32 | FieldType::F64 => "REAL",
+            FieldType::ColourRGB |
+            FieldType::StringU8 |
+            FieldType::StringU16 |
+            FieldType::OptionalStringU8 |
+            FieldType::OptionalStringU16 => "TEXT",
+            FieldType::SequenceU16(_) | FieldType::SequenceU32(_) => "BLOB",
+        },
+        SqlDialect::PostgreSQL => match field_type {
+            FieldType::Boolean => "BOOLEAN",
+            FieldType::F32 => "REAL",
+            FieldType::F64 => "DOUBLE PRECISION",
+            FieldType::I16 | FieldType::OptionalI16 => "SMALLINT",
+            FieldType::I32 | FieldType::OptionalI32 => "INTEGER",
+            FieldType::I64 | FieldType::OptionalI64 => "BIGINT",
+            FieldType::ColourRGB |
+            FieldType::StringU8 |
+            FieldType::StringU16 |
+            FieldType::OptionalStringU8 |
+            FieldType::OptionalStringU16 => "TEXT",
+            FieldType::SequenceU16(_) | FieldType::SequenceU32(_) => "BYTEA",
+        },
+    }
+}
+
+/// This function returns a value as a SQL literal of the provided type, or `None` if it's not valid for the type.
+fn sql_value(field_type: &FieldType, value: &str, dialect: SqlDialect) -> Option<String> {
+    match field_type {
+        FieldType::Boolean => Some(match (parse_bool(value), dialect) {
+            (true, SqlDialect::SQLite) => "1".to_owned(),
+            (false, SqlDialect::SQLite) => "0".to_owned(),
+            (true, SqlDialect::PostgreSQL) => "TRUE".to_owned(),
+            (false, SqlDialect::PostgreSQL) => "FALSE".to_owned(),
+        }),
+        FieldType::F32 | FieldType::F64 => value.parse::<f64>().ok().map(|value| value.to_string()),
+        FieldType::I16 | FieldType::I32 | FieldType::I64 |
+        FieldType::OptionalI16 | FieldType::OptionalI32 | FieldType::OptionalI64 => value.parse::<i64>().ok().map(|value| value.to_string()),
+        FieldType::SequenceU16(_) | FieldType::SequenceU32(_) => None,
+        _ => Some(sql_string(value)),
+    }
+}
+
+/// This function returns a string as a SQL string literal.
+fn sql_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// This function returns a name as a quoted SQL identifier.
+fn sql_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// This function returns the TypeScript type of the provided field type.
+fn typescript_type(field_type: &FieldType) -> &'static str {
+    match field_type {
+        FieldType::Boolean => "boolean",
+        FieldType::F32 | FieldType::F64 |
+        FieldType::I16 | FieldType::I32 | FieldType::I64 |
+        FieldType::OptionalI16 | FieldType::OptionalI32 | FieldType::OptionalI64 => "number",
+        FieldType::ColourRGB |
+        FieldType::StringU8 |
+        FieldType::StringU16 |
+        FieldType::OptionalStringU8 |
+        FieldType::OptionalStringU16 => "string",
+        FieldType::SequenceU16(_) | FieldType::SequenceU32(_) => "number[]",
+    }
+}
+
+/// This function returns a column name as a TypeScript property name, quoting it if it's not a valid identifier.
+fn typescript_identifier(name: &str) -> String {
+    let is_identifier = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '$') &&
+        name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+
+    if is_identifier {
+        name.to_owned()
+    } else {
+        serde_json::to_string(name).unwrap_or_default()
+    }
+}
+
+/// This function parses a boolean default value, as stored in the schema.
+fn parse_bool(value: &str) -> bool {
+    value.eq_ignore_ascii_case("true") || value == "1"
+}
@@ -0,0 +1,141 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! Module containing tests for the exporters of definitions.
+
+use serde_json::{json, Value};
+
+use std::collections::{BTreeMap, HashMap};
+use std::env::temp_dir;
+
+use super::*;
+use super::export::*;
+
+fn definition() -> Definition {
+    let enum_values = BTreeMap::from([(0, "none".to_owned()), (2, "melee".to_owned())]);
+
+    let mut definition = Definition::new(3, None);
+    definition.set_fields(vec![
+        Field::new("key".to_owned(), FieldType::StringU8, true, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None),
+        Field::new("class".to_owned(), FieldType::I32, false, Some("2".to_owned()), false, None, None, None, "Class of the unit.".to_owned(), 0, 0, enum_values, None),
+        Field::new("category".to_owned(), FieldType::StringU8, false, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None),
+    ]);
+    definition
+}
+
+fn patches() -> DefinitionPatch {
+    HashMap::from([("category".to_owned(), HashMap::from([("allowed_values".to_owned(), "infantry;cavalry".to_owned())]))])
+}
+
+#[test]
+fn test_export_json_schema_enums() {
+    let document = definition_to_json_schema("units_tables", &definition(), Some(&patches())).unwrap();
+    let document: Value = serde_json::from_str(&document).unwrap();
+
+    // Enum columns keep their integer type, with their keys as values and their names in the description.
+    let class = &document["properties"]["class"];
+    assert_eq!(class["type"], json!("integer"));
+    assert_eq!(class["enum"], json!([0, 2]));
+    assert_eq!(class["default"], json!(2));
+    assert_eq!(class["description"], json!("Class of the unit. Values: 0 = none, 2 = melee."));
+
+    let category = &document["properties"]["category"];
+    assert_eq!(category["type"], json!("string"));
+    assert_eq!(category["enum"], json!(["infantry", "cavalry"]));
+    assert_eq!(document["x-primary-key"], json!(["key"]));
+}
+
+fn field(name: &str, is_key: bool, is_reference: Option<(&str, &str)>) -> Field {
+    Field::new(name.to_owned(), FieldType::StringU8, is_key, None, false, None, is_reference.map(|(table, column)| (table.to_owned(), column.to_owned())), None, String::new(), 0, 0, BTreeMap::new(), None)
+}
+
+#[test]
+fn test_export_sql_enums() {
+    let mut schema = Schema::default();
+    schema.add_definition("units_tables", &definition());
+    schema.add_patch(HashMap::from([("units_tables".to_owned(), patches())]));
+
+    let sql = definition_to_sql(&schema, "units_tables", &definition(), SqlDialect::PostgreSQL);
+    assert!(sql.contains("    -- Class of the unit. Values: 0 = none, 2 = melee.\n    \"class\" INTEGER NOT NULL DEFAULT 2 CHECK (\"class\" IN (0, 2)),\n"));
+    assert!(sql.contains("\"category\" TEXT NOT NULL CHECK (\"category\" IN ('infantry', 'cavalry'))"));
+    assert!(sql.contains("PRIMARY KEY (\"key\")"));
+}
+
+#[test]
+fn test_export_typescript_enums() {
+    let code = definition_to_typescript("units_tables", &definition(), Some(&patches()));
+    assert!(code.contains("    /** Class of the unit. Values: 0 = none, 2 = melee. */\n    class: 0 | 2;\n"));
+    assert!(code.contains("    category: \"infantry\" | \"cavalry\";\n"));
+}
+
+#[test]
+fn test_export_sql_foreign_keys() {
+    let mut units = Definition::new(1, None);
+    units.set_fields(vec![field("key", true, None), field("category", false, None)]);
+
+    let mut abilities = Definition::new(1, None);
+    abilities.set_fields(vec![field("unit", true, None), field("ability", true, None), field("name", false, None)]);
+
+    let mut land_units = Definition::new(1, None);
+    land_units.set_fields(vec![
+        field("key", true, None),
+        field("unit", false, Some(("units", "key"))),
+        field("ability", false, Some(("abilities", "ability"))),
+        field("ability_name", false, Some(("abilities", "name"))),
+        field("missing", false, Some(("missing", "key"))),
+    ]);
+
+    let mut schema = Schema::default();
+    schema.add_definition("units_tables", &units);
+    schema.add_definition("abilities_tables", &abilities);
+    schema.add_definition("land_units_tables", &land_units);
+    schema.add_patch(HashMap::from([("abilities_tables".to_owned(), HashMap::from([("name".to_owned(), HashMap::from([("unique".to_owned(), "true".to_owned())]))]))]));
+
+    // Only references to the only key or to unique columns of known tables become foreign keys.
+    let sqlite = definition_to_sql(&schema, "land_units_tables", &land_units, SqlDialect::SQLite);
+    assert!(sqlite.contains("FOREIGN KEY (\"unit\") REFERENCES \"units_tables\"(\"key\")"));
+    assert!(sqlite.contains("FOREIGN KEY (\"ability_name\") REFERENCES \"abilities_tables\"(\"name\")"));
+    assert!(!sqlite.contains("FOREIGN KEY (\"ability\")"));
+    assert!(!sqlite.contains("FOREIGN KEY (\"missing\")"));
+    assert!(definition_to_sql(&schema, "abilities_tables", &abilities, SqlDialect::SQLite).contains("\"name\" TEXT NOT NULL UNIQUE"));
+
+    // In PostgreSQL, foreign keys go in their own statements.
+    let postgresql = definition_to_sql(&schema, "land_units_tables", &land_units, SqlDialect::PostgreSQL);
+    assert!(!postgresql.contains("FOREIGN KEY"));
+    assert_eq!(definition_to_sql_foreign_keys(&schema, "land_units_tables", &land_units), "-- Foreign keys of land_units_tables, version 1.\n\
+        ALTER TABLE \"land_units_tables\" ADD FOREIGN KEY (\"unit\") REFERENCES \"units_tables\"(\"key\");\n\
+        ALTER TABLE \"land_units_tables\" ADD FOREIGN KEY (\"ability_name\") REFERENCES \"abilities_tables\"(\"name\");\n");
+    assert!(definition_to_sql_foreign_keys(&schema, "units_tables", &units).is_empty());
+}
+
+#[test]
+fn test_export_schema_game_builds() {
+    let mut old = definition();
+    old.set_game_builds(Some((0, 99)));
+    let mut new = definition();
+    new.set_game_builds(Some((100, 199)));
+
+    let mut schema = Schema::default();
+    schema.add_definition("units_tables", &old);
+    schema.add_definition("units_tables", &new);
+    schema.add_definition("land_units_tables", &Definition::new(1, None));
+
+    // Definitions with the same version but different game builds must not overwrite each other.
+    let folder = temp_dir().join("rpfm_test_export_schema_game_builds");
+    let _ = std::fs::remove_dir_all(&folder);
+    export_schema(&schema, ExportFormat::Sql(SqlDialect::PostgreSQL), &folder).unwrap();
+
+    let mut files = std::fs::read_dir(&folder).unwrap().map(|entry| entry.unwrap().file_name().to_string_lossy().to_string()).collect::<Vec<_>>();
+    files.sort();
+    assert_eq!(files, vec!["land_units_tables_v1.sql", "units_tables_v3_b0-99.sql", "units_tables_v3_b100-199.sql"]);
+    assert!(std::fs::read_to_string(folder.join("units_tables_v3_b100-199.sql")).unwrap().starts_with("-- units_tables, version 3, builds 100-199.\n"));
+
+    std::fs::remove_dir_all(&folder).unwrap();
+}
@@ -0,0 +1,360 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the reference graph of a schema, built from the references between columns, for external tools.
+
+The graph can be built at two levels:
+- Table level, from a [Schema]: one node per table, one edge per column referencing another table.
+- Row level, from the tables of a [Pack]: one node per row, one edge per value referencing a row.
+
+Both can be filtered by table name prefixes. When filtered, every edge from or to a table matching the filter is kept,
+so the graph shows both what a table depends on and what depends on it.
+
+The graph can be written as [DOT](https://graphviz.org/doc/info/lang.html), [GraphML](http://graphml.graphdrawing.org/) or JSON.
+!*/
+
+use getset::Getters;
+use rayon::prelude::*;
+use serde_derive::Serialize;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::error::Result;
+use crate::files::{Container, DecodeableExtraData, FileType, pack::Pack, RFileDecoded};
+
+use super::Schema;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This enum represents the formats a reference graph can be written to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    GraphML,
+    Json,
+}
+
+/// This struct represents a reference graph, with its nodes and edges sorted.
+#[derive(Clone, Debug, Default, Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct ReferenceGraph {
+
+    /// Nodes of the graph. Tables for table-level graphs, rows for row-level graphs.
+    nodes: Vec<GraphNode>,
+
+    /// Edges of the graph, from the referencing node to the referenced one.
+    edges: Vec<GraphEdge>,
+}
+
+/// This struct represents a node of a reference graph.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct GraphNode {
+
+    /// Unique identifier of the node. The table name for tables, and `{table_name}:{key values}` for rows, with the key values separated by `|`.
+    ///
+    /// Rows of tables without keys use `{table_name}:#{position}` instead, starting at 1. The characters `\`, `|`, `=` and `#` of the values
+    /// in row ids are escaped with `\`, so ids are unique.
+    id: String,
+
+    /// Table the node belongs to.
+    table_name: String,
+
+    /// If the node is only known because something references it. These are tables without definitions in table-level graphs,
+    /// and referenced values not found in the Pack in row-level graphs. For the latter, the id is `{table_name}:{column}={value}`.
+    external: bool,
+}
+
+/// This struct represents an edge of a reference graph.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct GraphEdge {
+
+    /// Id of the referencing node.
+    source: String,
+
+    /// Id of the referenced node.
+    target: String,
+
+    /// Column of the referencing node holding the reference.
+    source_column: String,
+
+    /// Column of the referenced node the reference points to.
+    target_column: String,
+}
+
+//---------------------------------------------------------------------------//
+//                       Enum & Structs Implementations
+//---------------------------------------------------------------------------//
+
+impl GraphFormat {
+
+    /// This function returns the extension of the files written in this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Dot => "dot",
+            Self::GraphML => "graphml",
+            Self::Json => "json",
+        }
+    }
+}
+
+impl ReferenceGraph {
+
+    /// This function builds the table-level reference graph of a schema, using the newest definition of each table.
+    ///
+    /// If any table prefix is provided, only the edges from or to tables starting with one of them are kept.
+    pub fn from_schema(schema: &Schema, table_prefixes: &[String]) -> Self {
+        let mut nodes = BTreeMap::new();
+        let mut edges = BTreeSet::new();
+
+        for (table_name, definitions) in schema.definitions() {
+            let definition = match definitions.iter().max_by_key(|definition| definition.version()) {
+                Some(definition) => definition,
+                None => continue,
+            };
+
+            if matches_prefixes(table_name, table_prefixes) {
+                nodes.insert(table_name.to_owned(), false);
+            }
+
+            for field in definition.fields_processed() {
+                if let Some((ref_table, ref_column)) = field.is_reference() {
+                    let ref_table_name = format!("{ref_table}_tables");
+                    if matches_prefixes(table_name, table_prefixes) || matches_prefixes(&ref_table_name, table_prefixes) {
+                        nodes.insert(table_name.to_owned(), false);
+                        nodes.entry(ref_table_name.to_owned()).or_insert_with(|| schema.definitions_by_table_name(&ref_table_name).is_none());
+                        edges.insert(GraphEdge {
+                            source: table_name.to_owned(),
+                            target: ref_table_name,
+                            source_column: field.name().to_owned(),
+                            target_column: ref_column.to_owned(),
+                        });
+                    }
+                }
+            }
+        }
+
+        Self {
+            nodes: nodes.into_iter().map(|(id, external)| GraphNode { table_name: id.to_owned(), id, external }).collect(),
+            edges: edges.into_iter().collect(),
+        }
+    }
+
+    /// This function builds the row-level reference graph of the DB tables of a Pack, decoding them with the provided schema.
+    ///
+    /// Rows are identified by the values of their key columns, or by their position in the table if it has no keys. See [GraphNode::id] for the format.
+    /// If any table prefix is provided, only the edges from or to tables starting with one of them are kept.
+    pub fn from_pack(pack: &mut Pack, schema: &Schema, table_prefixes: &[String]) -> Result<Self> {
+        let mut extra_data = DecodeableExtraData::default();
+        extra_data.set_schema(Some(schema));
+        extra_data.set_game_build(Some(pack.game_version()).filter(|game_version| *game_version != 0));
+        let extra_data = Some(extra_data);
+
+        pack.files_by_type_mut(&[FileType::DB])
+            .par_iter_mut()
+            .for_each(|file| { let _ = file.decode(&extra_data, true, false); });
+
+        let mut files = pack.files_by_type(&[FileType::DB]);
+        files.sort_by(|a, b| a.path_in_container_raw().cmp(b.path_in_container_raw()));
+
+        let tables = files.iter()
+            .filter_map(|file| if let Ok(RFileDecoded::DB(table)) = file.decoded() { Some(table) } else { None })
+            .collect::<Vec<_>>();
+
+        // Columns referenced by any table, so we only index the values we need.
+        let referenced_columns = tables.iter()
+            .flat_map(|table| table.definition().fields_processed())
+            .filter_map(|field| field.is_reference().clone().map(|(ref_table, ref_column)| (format!("{ref_table}_tables"), ref_column)))
+            .collect::<HashSet<_>>();
+
+        // First pass: get the id of each row, and index them by their referenced values.
+        let mut row_ids: HashMap<&str, usize> = HashMap::new();
+        let mut table_rows = Vec::with_capacity(tables.len());
+        let mut index: HashMap<(String, String, String), String> = HashMap::new();
+        for table in &tables {
+            let definition = table.definition();
+            let fields = definition.fields_processed();
+            let patches = Some(definition.patches());
+            let key_indexes = fields.iter()
+                .enumerate()
+                .filter_map(|(column, field)| if field.is_key(patches) { Some(column) } else { None })
+                .collect::<Vec<_>>();
+
+            let referenced_indexes = fields.iter()
+                .enumerate()
+                .filter_map(|(column, field)| if referenced_columns.contains(&(table.table_name().to_owned(), field.name().to_owned())) { Some(column) } else { None })
+                .collect::<Vec<_>>();
+
+            let mut rows = vec![];
+            for row in table.data(&None)?.iter() {
+                let id = if key_indexes.is_empty() {
+                    let row_id = row_ids.entry(table.table_name()).or_default();
+                    *row_id += 1;
+                    format!("{}:#{}", table.table_name(), row_id)
+                } else {
+                    format!("{}:{}", table.table_name(), key_indexes.iter().map(|column| escape_id(&row[*column].data_to_string())).collect::<Vec<_>>().join("|"))
+                };
+
+                for column in &referenced_indexes {
+                    index.entry((table.table_name().to_owned(), fields[*column].name().to_owned(), row[*column].data_to_string().to_string()))
+                        .or_insert_with(|| id.to_owned());
+                }
+
+                rows.push((id, row.to_vec()));
+            }
+
+            table_rows.push((table.table_name(), fields, rows));
+        }
+
+        // Second pass: link the rows.
+        let mut nodes = BTreeMap::new();
+        let mut edges = BTreeSet::new();
+        for (table_name, fields, rows) in &table_rows {
+            for (id, row) in rows {
+                if matches_prefixes(table_name, table_prefixes) {
+                    nodes.insert(id.to_owned(), (table_name.to_string(), false));
+                }
+
+                for (column, field) in fields.iter().enumerate() {
+                    if let Some((ref_table, ref_column)) = field.is_reference() {
+                        let ref_table_name = format!("{ref_table}_tables");
+                        let value = row[column].data_to_string();
+                        if value.is_empty() || !(matches_prefixes(table_name, table_prefixes) || matches_prefixes(&ref_table_name, table_prefixes)) {
+                            continue;
+                        }
+
+                        let target = match index.get(&(ref_table_name.to_owned(), ref_column.to_owned(), value.to_string())) {
+                            Some(target) => {
+                                nodes.insert(target.to_owned(), (ref_table_name, false));
+                                target.to_owned()
+                            }
+                            None => {
+                                let target = format!("{ref_table_name}:{ref_column}={}", escape_id(&value));
+                                nodes.insert(target.to_owned(), (ref_table_name, true));
+                                target
+                            }
+                        };
+
+                        nodes.insert(id.to_owned(), (table_name.to_string(), false));
+                        edges.insert(GraphEdge {
+                            source: id.to_owned(),
+                            target,
+                            source_column: field.name().to_owned(),
+                            target_column: ref_column.to_owned(),
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            nodes: nodes.into_iter().map(|(id, (table_name, external))| GraphNode { id, table_name, external }).collect(),
+            edges: edges.into_iter().collect(),
+        })
+    }
+
+    /// This function writes the graph in the provided format.
+    pub fn to_format(&self, format: GraphFormat) -> Result<String> {
+        match format {
+            GraphFormat::Dot => Ok(self.to_dot()),
+            GraphFormat::GraphML => Ok(self.to_graphml()),
+            GraphFormat::Json => serde_json::to_string_pretty(&self).map_err(From::from),
+        }
+    }
+
+    /// This function writes the graph as a DOT digraph. External nodes are drawn dashed.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        let _ = writeln!(dot, "digraph references {{");
+        let _ = writeln!(dot, "    node [shape=box];");
+
+        for node in &self.nodes {
+            if node.external {
+                let _ = writeln!(dot, "    \"{}\" [style=dashed];", escape_dot(&node.id));
+            } else {
+                let _ = writeln!(dot, "    \"{}\";", escape_dot(&node.id));
+            }
+        }
+
+        for edge in &self.edges {
+            let _ = writeln!(dot, "    \"{}\" -> \"{}\" [label=\"{} -> {}\"];", escape_dot(&edge.source), escape_dot(&edge.target), escape_dot(&edge.source_column), escape_dot(&edge.target_column));
+        }
+
+        let _ = writeln!(dot, "}}");
+        dot
+    }
+
+    /// This function writes the graph as a GraphML document, with the data of nodes and edges as attributes.
+    pub fn to_graphml(&self) -> String {
+        let mut xml = String::new();
+        let _ = writeln!(xml, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+        let _ = writeln!(xml, "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">");
+        let _ = writeln!(xml, "  <key id=\"table_name\" for=\"node\" attr.name=\"table_name\" attr.type=\"string\"/>");
+        let _ = writeln!(xml, "  <key id=\"external\" for=\"node\" attr.name=\"external\" attr.type=\"boolean\"/>");
+        let _ = writeln!(xml, "  <key id=\"source_column\" for=\"edge\" attr.name=\"source_column\" attr.type=\"string\"/>");
+        let _ = writeln!(xml, "  <key id=\"target_column\" for=\"edge\" attr.name=\"target_column\" attr.type=\"string\"/>");
+        let _ = writeln!(xml, "  <graph id=\"references\" edgedefault=\"directed\">");
+
+        for node in &self.nodes {
+            let _ = writeln!(xml, "    <node id=\"{}\">", escape_xml(&node.id));
+            let _ = writeln!(xml, "      <data key=\"table_name\">{}</data>", escape_xml(&node.table_name));
+            let _ = writeln!(xml, "      <data key=\"external\">{}</data>", node.external);
+            let _ = writeln!(xml, "    </node>");
+        }
+
+        for edge in &self.edges {
+            let _ = writeln!(xml, "    <edge source=\"{}\" target=\"{}\">", escape_xml(&edge.source), escape_xml(&edge.target));
+            let _ = writeln!(xml, "      <data key=\"source_column\">{}</data>", escape_xml(&edge.source_column));
+            let _ = writeln!(xml, "      <data key=\"target_column\">{}</data>", escape_xml(&edge.target_column));
+            let _ = writeln!(xml, "    </edge>");
+        }
+
+        let _ = writeln!(xml, "  </graph>");
+        let _ = writeln!(xml, "</graphml>");
+        xml
+    }
+}
+
+/// This function returns if the table name starts with any of the provided prefixes. If there are none, everything matches.
+fn matches_prefixes(table_name: &str, table_prefixes: &[String]) -> bool {
+    table_prefixes.is_empty() || table_prefixes.iter().any(|prefix| table_name.starts_with(prefix))
+}
+
+/// This function escapes a value to be used as part of a row id, so the separators in it don't make different rows share an id.
+fn escape_id(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for character in value.chars() {
+        if matches!(character, '\\' | '|' | '=' | '#') {
+            escaped.push('\\');
+        }
+
+        escaped.push(character);
+    }
+
+    escaped
+}
+
+/// This function escapes a string to be used as a quoted DOT id.
+fn escape_dot(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// This function escapes a string to be used as XML text or attribute value.
+fn escape_xml(value: &str) -> String {
+    value.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
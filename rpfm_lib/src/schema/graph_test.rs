@@ -0,0 +1,153 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! Module containing tests for the reference graphs of schemas and Packs.
+
+use std::collections::BTreeMap;
+
+use crate::files::{Container, db::DB, pack::Pack, RFile, RFileDecoded, table::DecodedData};
+use crate::games::pfh_version::PFHVersion;
+
+use super::*;
+use super::graph::ReferenceGraph;
+
+fn field(name: &str, is_key: bool, is_reference: Option<(&str, &str)>) -> Field {
+    let is_reference = is_reference.map(|(table, column)| (table.to_owned(), column.to_owned()));
+    Field::new(name.to_owned(), FieldType::StringU8, is_key, None, false, None, is_reference, None, String::new(), 0, 0, BTreeMap::new(), None)
+}
+
+fn definition(fields: Vec<Field>) -> Definition {
+    let mut definition = Definition::new(1, None);
+    definition.set_fields(fields);
+    definition
+}
+
+fn schema() -> Schema {
+    let mut schema = Schema::default();
+    schema.add_definition("units_tables", &definition(vec![
+        field("key", true, None),
+        field("faction", true, None),
+        field("weapon", false, Some(("weapons", "key"))),
+    ]));
+    schema.add_definition("unit_sets_tables", &definition(vec![
+        field("unit", false, Some(("units", "key"))),
+        field("set", false, None),
+    ]));
+    schema.add_definition("weapons_tables", &definition(vec![
+        field("key", true, None),
+    ]));
+    schema
+}
+
+fn row(values: &[&str]) -> Vec<DecodedData> {
+    values.iter().map(|value| DecodedData::StringU8(value.to_string())).collect()
+}
+
+fn pack(schema: &Schema) -> Pack {
+    let mut pack = Pack::new_with_name_and_version("test.pack", PFHVersion::PFH5);
+    let tables: [(&str, Vec<Vec<DecodedData>>); 3] = [
+        ("units_tables", vec![row(&["a|b", "c", "sword"]), row(&["a", "b|c", "bow=long"])]),
+        ("unit_sets_tables", vec![row(&["a", "infantry"]), row(&["a", "infantry"])]),
+        ("weapons_tables", vec![row(&["sword"])]),
+    ];
+
+    for (table_name, rows) in tables {
+        let definition = schema.definitions_by_table_name(table_name).unwrap().first().unwrap();
+        let mut table = DB::new(definition, None, table_name, false);
+        table.set_data(None, &rows).unwrap();
+        pack.insert(RFile::new_from_decoded(&RFileDecoded::DB(table), 0, &format!("db/{table_name}/data"))).unwrap();
+    }
+
+    pack
+}
+
+fn ids(graph: &ReferenceGraph) -> Vec<(&str, bool)> {
+    graph.nodes().iter().map(|node| (node.id().as_ref(), *node.external())).collect()
+}
+
+fn edges(graph: &ReferenceGraph) -> Vec<(&str, &str)> {
+    graph.edges().iter().map(|edge| (edge.source().as_ref(), edge.target().as_ref())).collect()
+}
+
+#[test]
+fn test_graph_from_schema() {
+    let mut schema = schema();
+    schema.add_definition("recruitment_tables", &definition(vec![field("unit", true, Some(("units", "key"))), field("building", false, Some(("buildings", "key")))]));
+
+    let graph = ReferenceGraph::from_schema(&schema, &[]);
+    assert_eq!(ids(&graph), vec![
+        ("buildings_tables", true),
+        ("recruitment_tables", false),
+        ("unit_sets_tables", false),
+        ("units_tables", false),
+        ("weapons_tables", false),
+    ]);
+    assert_eq!(edges(&graph), vec![
+        ("recruitment_tables", "buildings_tables"),
+        ("recruitment_tables", "units_tables"),
+        ("unit_sets_tables", "units_tables"),
+        ("units_tables", "weapons_tables"),
+    ]);
+
+    // Filtering keeps the edges from and to the matching tables.
+    let graph = ReferenceGraph::from_schema(&schema, &["units_".to_owned()]);
+    assert_eq!(edges(&graph), vec![
+        ("recruitment_tables", "units_tables"),
+        ("unit_sets_tables", "units_tables"),
+        ("units_tables", "weapons_tables"),
+    ]);
+    assert!(!ids(&graph).contains(&("buildings_tables", true)));
+}
+
+#[test]
+fn test_graph_from_pack() {
+    let schema = schema();
+    let mut pack = pack(&schema);
+
+    let graph = ReferenceGraph::from_pack(&mut pack, &schema, &[]).unwrap();
+
+    // Keys containing separators must not make different rows share an id.
+    assert_eq!(ids(&graph), vec![
+        ("unit_sets_tables:#1", false),
+        ("unit_sets_tables:#2", false),
+        ("units_tables:a\\|b|c", false),
+        ("units_tables:a|b\\|c", false),
+        ("weapons_tables:key=bow\\=long", true),
+        ("weapons_tables:sword", false),
+    ]);
+    assert_eq!(edges(&graph), vec![
+        ("unit_sets_tables:#1", "units_tables:a|b\\|c"),
+        ("unit_sets_tables:#2", "units_tables:a|b\\|c"),
+        ("units_tables:a\\|b|c", "weapons_tables:sword"),
+        ("units_tables:a|b\\|c", "weapons_tables:key=bow\\=long"),
+    ]);
+
+    let graph = ReferenceGraph::from_pack(&mut pack, &schema, &["weapons_".to_owned()]).unwrap();
+    assert_eq!(edges(&graph), vec![
+        ("units_tables:a\\|b|c", "weapons_tables:sword"),
+        ("units_tables:a|b\\|c", "weapons_tables:key=bow\\=long"),
+    ]);
+}
+
+#[test]
+fn test_graph_formats() {
+    let schema = schema();
+    let mut pack = pack(&schema);
+    let graph = ReferenceGraph::from_pack(&mut pack, &schema, &["weapons_".to_owned()]).unwrap();
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph references {\n"));
+    assert!(dot.contains("    \"units_tables:a\\\\|b|c\" -> \"weapons_tables:sword\" [label=\"weapon -> key\"];\n"));
+    assert!(dot.contains("    \"weapons_tables:key=bow\\\\=long\" [style=dashed];\n"));
+
+    let graphml = graph.to_graphml();
+    assert!(graphml.contains("    <node id=\"weapons_tables:key=bow\\=long\">\n      <data key=\"table_name\">weapons_tables</data>\n      <data key=\"external\">true</data>\n"));
+    assert!(graphml.contains("    <edge source=\"units_tables:a\\|b|c\" target=\"weapons_tables:sword\">\n"));
+}
@@ -0,0 +1,512 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the inference engine for definitions of unknown DB table versions.
+
+When a patch bumps the version of a table, its new structure is usually the old one with a few columns added,
+removed or changed. This module takes advantage of that: it tries to align the fields of the previous definition
+(and of the Assembly Kit definition, if available) with the data of the first row, allowing a limited amount of edits.
+Every field list found this way is then checked against the entire table, and only the ones that decode every row
+exactly to the end of the data are returned.
+
+The candidates are ranked by plausibility, using:
+- The amount of edits over the definition used as template.
+- The sanity of the strings decoded.
+- The plausibility of the numbers decoded, to tell apart integers from floats.
+- The validity of the references of the columns kept from the template, if reference data is provided.
+
+Without a template, the search goes field by field over the first row, so it only finds simple definitions in a reasonable time.
+Candidates should always be reviewed before adding them to a [Schema](super::Schema) with [Schema::add_definition](super::Schema::add_definition).
+!*/
+
+use getset::Getters;
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Cursor;
+
+use crate::binary::ReadBytes;
+use crate::error::Result;
+use crate::files::{db::DB, table::{DecodedData, Table}};
+
+use super::{Definition, Field, FieldType};
+
+/// Maximum amount of edits allowed over a template definition.
+const MAX_EDITS: usize = 4;
+
+/// Maximum amount of nodes explored per search, to keep ambiguous tables from taking forever.
+const MAX_SEARCH_NODES: usize = 250_000;
+
+/// Maximum amount of fields tried in searches without template.
+const MAX_TEMPLATE_LESS_FIELDS: usize = 32;
+
+/// Types tried for new or changed fields. Less common types are only used if they're in the template.
+const FIELD_TYPES: [FieldType; 10] = [
+    FieldType::StringU8,
+    FieldType::OptionalStringU8,
+    FieldType::StringU16,
+    FieldType::OptionalStringU16,
+    FieldType::Boolean,
+    FieldType::I32,
+    FieldType::F32,
+    FieldType::I16,
+    FieldType::I64,
+    FieldType::F64,
+];
+
+/// Values referenced by other tables, by referenced table (without the `_tables` suffix) and column.
+pub type ReferenceValues = HashMap<(String, String), HashSet<String>>;
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct represents a definition proposed by the inference engine.
+#[derive(Clone, Debug, Getters)]
+#[getset(get = "pub")]
+pub struct InferredDefinition {
+
+    /// The proposed definition.
+    definition: Definition,
+
+    /// Plausibility of the definition. Higher is better.
+    score: f64,
+
+    /// Amount of fields added, removed or changed over the template definition. Changes of type that alter the size of a field count twice.
+    edits: usize,
+}
+
+/// Search state shared between all the nodes of a search.
+struct Search<'a> {
+    data: &'a [u8],
+    entry_count: u32,
+    template: &'a [Field],
+    max_fields: Option<usize>,
+    nodes: usize,
+    results: Vec<(Vec<Field>, usize)>,
+}
+
+/// Statistics of the values of a column, used for scoring.
+#[derive(Default)]
+struct ColumnStats {
+    values: usize,
+    implausible: usize,
+    references_found: usize,
+    first_value: Option<String>,
+    is_constant: bool,
+}
+
+//---------------------------------------------------------------------------//
+//                       Enum & Structs Implementations
+//---------------------------------------------------------------------------//
+
+/// This function infers the possible definitions for the provided DB table data, ranked from most to least plausible.
+///
+/// - `data`: the entire DB file, including its header.
+/// - `previous_definition`: the definition for the previous version of the table, if any.
+/// - `raw_definition`: the Assembly Kit definition of the table, converted with `Definition::from`, if any.
+/// - `reference_values`: data to check the references of the columns kept from the templates. Can be empty.
+/// - `max_candidates`: maximum amount of candidates to return.
+///
+/// If no candidate decodes the entire table, this returns an empty list.
+pub fn infer_definitions(data: &[u8], previous_definition: Option<&Definition>, raw_definition: Option<&Definition>, reference_values: &ReferenceValues, max_candidates: usize) -> Result<Vec<InferredDefinition>> {
+    let mut cursor = Cursor::new(data);
+    let (version, _, _, entry_count) = DB::read_header(&mut cursor)?;
+    let table_data = &data[cursor.position() as usize..];
+
+    let mut candidates: Vec<(Vec<Field>, usize)> = vec![];
+    let templates = [previous_definition, raw_definition].into_iter()
+        .flatten()
+        .map(|definition| definition.fields().to_vec())
+        .chain(previous_definition.or(raw_definition).is_none().then(Vec::new))
+        .collect::<Vec<_>>();
+
+    for template in &templates {
+        let mut search = Search {
+            data: table_data,
+            entry_count,
+            template,
+            max_fields: None,
+            nodes: 0,
+            results: vec![],
+        };
+
+        // Without template, go deeper one field at a time, so the simplest definitions are found first.
+        // Once something is found, we try one more field, as merged fields (like two I32 read as one I64) are common.
+        if template.is_empty() {
+            let mut found_at = None;
+            for max_fields in 1..=MAX_TEMPLATE_LESS_FIELDS {
+                search.max_fields = Some(max_fields);
+                search.search(0, 0, 0, &mut vec![]);
+
+                if found_at.is_none() && !search.results.is_empty() {
+                    found_at = Some(max_fields);
+                }
+
+                if found_at.map(|found_at| max_fields > found_at).unwrap_or(false) || search.nodes > MAX_SEARCH_NODES {
+                    break;
+                }
+            }
+        } else {
+            search.search(0, 0, 0, &mut vec![]);
+        }
+
+        candidates.append(&mut search.results);
+    }
+
+    // Dedup the candidates by their types, keeping the one with fewer edits.
+    let mut seen = HashSet::new();
+    candidates.sort_by_key(|(_, edits)| *edits);
+    candidates.retain(|(fields, _)| seen.insert(fields.iter().map(|field| format!("{:?}", field.field_type())).collect::<Vec<_>>()));
+
+    let mut inferred = candidates.into_iter()
+        .filter_map(|(fields, edits)| {
+            let stats = column_stats(table_data, entry_count, &fields, reference_values)?;
+            let fields = name_fields(fields, raw_definition);
+            let score = score(&fields, &stats, edits);
+
+            let mut definition = Definition::new_with_fields(version, &fields, &[], None);
+            if let Some(previous_definition) = previous_definition {
+                definition.set_localised_fields(previous_definition.localised_fields().to_vec());
+            }
+
+            Some(InferredDefinition {
+                definition,
+                score,
+                edits,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    inferred.sort_by(|a, b| b.score.total_cmp(&a.score));
+    inferred.truncate(max_candidates);
+    Ok(inferred)
+}
+
+impl<'a> Search<'a> {
+
+    /// This function explores all the field lists that can decode the first row from the provided state.
+    ///
+    /// - `index`: position of the next field of the template to align.
+    /// - `offset`: position in the data where the next field starts.
+    /// - `edits`: amount of edits over the template done so far.
+    fn search(&mut self, index: usize, offset: usize, edits: usize, fields: &mut Vec<Field>) {
+        self.nodes += 1;
+        if self.nodes > MAX_SEARCH_NODES {
+            return;
+        }
+
+        let min_row_size = min_row_size(fields);
+        let remaining_rows = self.entry_count.saturating_sub(1) as usize;
+        if offset + remaining_rows * min_row_size > self.data.len() {
+            return;
+        }
+
+        // Once the template is done (or we reached the field limit), check if what we have is a valid definition.
+        let is_complete = match self.max_fields {
+            Some(max_fields) => fields.len() == max_fields,
+            None => index == self.template.len(),
+        };
+
+        if is_complete && !fields.is_empty() && decodes_exactly(self.data, self.entry_count, fields) {
+            self.results.push((fields.to_vec(), edits));
+        }
+
+        if self.max_fields.map(|max_fields| fields.len() >= max_fields).unwrap_or(false) {
+            return;
+        }
+
+        if let Some(field) = self.template.get(index) {
+
+            // Keep the field as it is.
+            if let Some(new_offset) = read_field(self.data, offset, field.field_type()) {
+                fields.push(field.clone());
+                self.search(index + 1, new_offset, edits, fields);
+                fields.pop();
+            }
+
+            if edits < MAX_EDITS {
+
+                // Change the type of the field.
+                for (field_type, new_offset) in self.options(offset) {
+                    if field_type != *field.field_type() {
+                        let cost = type_change_cost(field.field_type(), &field_type);
+                        let mut field = field.clone();
+                        field.set_field_type(field_type);
+                        fields.push(field);
+                        self.search(index + 1, new_offset, edits + cost, fields);
+                        fields.pop();
+                    }
+                }
+
+                // Remove the field.
+                self.search(index + 1, offset, edits + 1, fields);
+            }
+        }
+
+        // Add a new field. Template-less searches are limited by the amount of fields instead.
+        if self.max_fields.is_some() || edits < MAX_EDITS {
+            for (field_type, new_offset) in self.options(offset) {
+                fields.push(Field::new(String::new(), field_type, false, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None));
+                self.search(index, new_offset, edits + 1, fields);
+                fields.pop();
+            }
+        }
+    }
+
+    /// This function returns the types that can be read at the provided offset, with the offset after them.
+    ///
+    /// They're sorted by how much the data supports them, so the most likely branches are explored first.
+    fn options(&self, offset: usize) -> Vec<(FieldType, usize)> {
+        let mut options = FIELD_TYPES.iter()
+            .filter_map(|field_type| {
+                let new_offset = read_field(self.data, offset, field_type)?;
+                let mut cursor = Cursor::new(self.data);
+                cursor.set_position(offset as u64);
+
+                let evidence = match read_value(&mut cursor, field_type)? {
+                    DecodedData::StringU8(value) |
+                    DecodedData::StringU16(value) |
+                    DecodedData::OptionalStringU8(value) |
+                    DecodedData::OptionalStringU16(value) => if value.is_empty() { 0 } else { 3 },
+                    DecodedData::Boolean(_) => 2,
+                    value if is_plausible_value(&value) => 1,
+                    _ => 0,
+                };
+
+                Some((field_type.clone(), new_offset, evidence))
+            })
+            .collect::<Vec<_>>();
+
+        options.sort_by_key(|(_, _, evidence)| std::cmp::Reverse(*evidence));
+        options.into_iter().map(|(field_type, new_offset, _)| (field_type, new_offset)).collect()
+    }
+}
+
+impl ColumnStats {
+
+    /// This function returns the ratio of plausible values in the column.
+    fn plausibility(&self) -> f64 {
+        if self.values == 0 { 1.0 } else { 1.0 - self.implausible as f64 / self.values as f64 }
+    }
+}
+
+/// This function tries to read a field of the provided type, returning the offset after it if it's a plausible value.
+fn read_field(data: &[u8], offset: usize, field_type: &FieldType) -> Option<usize> {
+    let mut cursor = Cursor::new(data);
+    cursor.set_position(offset as u64);
+
+    let is_valid = match field_type {
+        FieldType::Boolean => cursor.read_bool().is_ok(),
+        FieldType::F32 => cursor.read_f32().is_ok(),
+        FieldType::F64 => cursor.read_f64().is_ok(),
+        FieldType::I16 => cursor.read_i16().is_ok(),
+        FieldType::I32 => cursor.read_i32().is_ok(),
+        FieldType::I64 => cursor.read_i64().is_ok(),
+        FieldType::ColourRGB => cursor.read_string_colour_rgb().is_ok(),
+        FieldType::StringU8 => cursor.read_sized_string_u8().map(|string| is_sane_string(&string)).unwrap_or(false),
+        FieldType::StringU16 => cursor.read_sized_string_u16().map(|string| is_sane_string(&string)).unwrap_or(false),
+        FieldType::OptionalI16 => cursor.read_optional_i16().is_ok(),
+        FieldType::OptionalI32 => cursor.read_optional_i32().is_ok(),
+        FieldType::OptionalI64 => cursor.read_optional_i64().is_ok(),
+        FieldType::OptionalStringU8 => cursor.read_optional_string_u8().map(|string| is_sane_string(&string)).unwrap_or(false),
+        FieldType::OptionalStringU16 => cursor.read_optional_string_u16().map(|string| is_sane_string(&string)).unwrap_or(false),
+        FieldType::SequenceU16(definition) => cursor.read_u16().ok()
+            .and_then(|entry_count| Table::decode_table(&mut cursor, definition, Some(entry_count as u32), false).ok())
+            .is_some(),
+        FieldType::SequenceU32(definition) => cursor.read_u32().ok()
+            .and_then(|entry_count| Table::decode_table(&mut cursor, definition, Some(entry_count), false).ok())
+            .is_some(),
+    };
+
+    if is_valid {
+        Some(cursor.position() as usize)
+    } else {
+        None
+    }
+}
+
+/// This function checks if the provided fields decode all the rows of the table, ending exactly at the end of the data.
+fn decodes_exactly(data: &[u8], entry_count: u32, fields: &[Field]) -> bool {
+    let mut offset = 0;
+    for _ in 0..entry_count {
+        for field in fields {
+            match read_field(data, offset, field.field_type()) {
+                Some(new_offset) => offset = new_offset,
+                None => return false,
+            }
+        }
+    }
+
+    offset == data.len()
+}
+
+/// This function returns the minimum size a row can have with the provided fields.
+fn min_row_size(fields: &[Field]) -> usize {
+    fields.iter()
+        .map(|field| match field.field_type() {
+            FieldType::Boolean => 1,
+            FieldType::F32 | FieldType::I32 | FieldType::ColourRGB => 4,
+            FieldType::F64 | FieldType::I64 => 8,
+            FieldType::I16 | FieldType::StringU8 | FieldType::StringU16 | FieldType::SequenceU16(_) => 2,
+            FieldType::OptionalI16 => 3,
+            FieldType::OptionalI32 => 5,
+            FieldType::OptionalI64 => 9,
+            FieldType::OptionalStringU8 | FieldType::OptionalStringU16 => 1,
+            FieldType::SequenceU32(_) => 4,
+        })
+        .sum()
+}
+
+/// This function returns the amount of edits a change of type counts as.
+///
+/// Changes that keep the size of the field are cheaper, as changes that don't usually mean two fields merged
+/// into one (like an I32 and an F32 read as an F64), which is better explained by adding or removing a field.
+fn type_change_cost(old_type: &FieldType, new_type: &FieldType) -> usize {
+    let is_string = |field_type: &FieldType| matches!(field_type, FieldType::StringU8 | FieldType::StringU16 | FieldType::OptionalStringU8 | FieldType::OptionalStringU16);
+    let size = |field_type: &FieldType| min_row_size(&[Field::new(String::new(), field_type.clone(), false, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None)]);
+
+    if (is_string(old_type) && is_string(new_type)) || size(old_type) == size(new_type) {
+        1
+    } else {
+        2
+    }
+}
+
+/// This function decodes the entire table with the provided fields, and returns the stats of each column.
+fn column_stats(data: &[u8], entry_count: u32, fields: &[Field], reference_values: &ReferenceValues) -> Option<Vec<ColumnStats>> {
+    // Decode without postprocessing, so columns match the fields.
+    let mut stats = fields.iter().map(|_| ColumnStats::default()).collect::<Vec<_>>();
+    let mut cursor = Cursor::new(data);
+    for _ in 0..entry_count {
+        for (column, field) in fields.iter().enumerate() {
+            let value = read_value(&mut cursor, field.field_type())?;
+            let stats = &mut stats[column];
+            stats.values += 1;
+
+            if !is_plausible_value(&value) {
+                stats.implausible += 1;
+            }
+
+            let value_string = value.data_to_string();
+            match &stats.first_value {
+                Some(first_value) => stats.is_constant &= **first_value == *value_string,
+                None => {
+                    stats.first_value = Some(value_string.to_string());
+                    stats.is_constant = true;
+                }
+            }
+
+            if let Some((ref_table, ref_column)) = field.is_reference() {
+                if reference_values.get(&(ref_table.to_owned(), ref_column.to_owned())).map(|values| values.contains(&*value_string)).unwrap_or(false) {
+                    stats.references_found += 1;
+                }
+            }
+        }
+    }
+
+    if cursor.position() as usize == data.len() {
+        Some(stats)
+    } else {
+        None
+    }
+}
+
+/// This function reads a single value of the provided type.
+fn read_value(cursor: &mut Cursor<&[u8]>, field_type: &FieldType) -> Option<DecodedData> {
+    match field_type {
+        FieldType::Boolean => cursor.read_bool().ok().map(DecodedData::Boolean),
+        FieldType::F32 => cursor.read_f32().ok().map(DecodedData::F32),
+        FieldType::F64 => cursor.read_f64().ok().map(DecodedData::F64),
+        FieldType::I16 => cursor.read_i16().ok().map(DecodedData::I16),
+        FieldType::I32 => cursor.read_i32().ok().map(DecodedData::I32),
+        FieldType::I64 => cursor.read_i64().ok().map(DecodedData::I64),
+        FieldType::ColourRGB => cursor.read_string_colour_rgb().ok().map(DecodedData::ColourRGB),
+        FieldType::StringU8 => cursor.read_sized_string_u8().ok().map(DecodedData::StringU8),
+        FieldType::StringU16 => cursor.read_sized_string_u16().ok().map(DecodedData::StringU16),
+        FieldType::OptionalI16 => cursor.read_optional_i16().ok().map(DecodedData::OptionalI16),
+        FieldType::OptionalI32 => cursor.read_optional_i32().ok().map(DecodedData::OptionalI32),
+        FieldType::OptionalI64 => cursor.read_optional_i64().ok().map(DecodedData::OptionalI64),
+        FieldType::OptionalStringU8 => cursor.read_optional_string_u8().ok().map(DecodedData::OptionalStringU8),
+        FieldType::OptionalStringU16 => cursor.read_optional_string_u16().ok().map(DecodedData::OptionalStringU16),
+        FieldType::SequenceU16(_) | FieldType::SequenceU32(_) => {
+            let start = cursor.position() as usize;
+            let end = read_field(cursor.get_ref(), start, field_type)?;
+            cursor.set_position(end as u64);
+
+            let blob = cursor.get_ref()[start..end].to_vec();
+            Some(if let FieldType::SequenceU16(_) = field_type { DecodedData::SequenceU16(blob) } else { DecodedData::SequenceU32(blob) })
+        }
+    }
+}
+
+/// This function checks if a string looks like something a table would contain.
+fn is_sane_string(string: &str) -> bool {
+    !string.chars().any(|character| character.is_control() && character != '\n' && character != '\r' && character != '\t')
+}
+
+/// This function checks if a value is plausible for its type.
+///
+/// Integers and floats share sizes, so this is what tells them apart: floats read as integers are huge,
+/// and integers read as floats are either tiny denormals or NaNs.
+fn is_plausible_value(value: &DecodedData) -> bool {
+    match value {
+        DecodedData::F32(value) => *value == 0.0 || (value.is_finite() && value.abs() > 1e-6 && value.abs() < 1e9),
+        DecodedData::F64(value) => *value == 0.0 || (value.is_finite() && value.abs() > 1e-9 && value.abs() < 1e12),
+        DecodedData::I32(value) | DecodedData::OptionalI32(value) => value.unsigned_abs() < 1_000_000,
+        DecodedData::I16(value) | DecodedData::OptionalI16(value) => value.unsigned_abs() < 30_000,
+        DecodedData::I64(value) | DecodedData::OptionalI64(value) => value.unsigned_abs() < 1 << 48,
+        DecodedData::StringU8(value) |
+        DecodedData::StringU16(value) |
+        DecodedData::OptionalStringU8(value) |
+        DecodedData::OptionalStringU16(value) => is_sane_string(value),
+        _ => true,
+    }
+}
+
+/// This function gives names to the new fields, using the Assembly Kit definition if it has a field with the same type in the same position.
+fn name_fields(mut fields: Vec<Field>, raw_definition: Option<&Definition>) -> Vec<Field> {
+    let used_names = fields.iter().map(|field| field.name().to_owned()).collect::<HashSet<_>>();
+    for (index, field) in fields.iter_mut().enumerate() {
+        if field.name().is_empty() {
+            let raw_field = raw_definition
+                .and_then(|definition| definition.fields().get(index))
+                .filter(|raw_field| raw_field.field_type() == field.field_type() && !used_names.contains(raw_field.name()));
+
+            match raw_field {
+                Some(raw_field) => *field = raw_field.clone(),
+                None => {
+                    field.set_name(format!("unknown_{index}"));
+                }
+            }
+        }
+    }
+
+    fields
+}
+
+/// This function calculates the plausibility score of a candidate.
+fn score(fields: &[Field], stats: &[ColumnStats], edits: usize) -> f64 {
+    let mut score = -(edits as f64) * 10.0;
+    for (field, stats) in fields.iter().zip(stats.iter()) {
+        score -= (1.0 - stats.plausibility()) * 20.0;
+
+        if field.is_reference().is_some() && stats.values > 0 {
+            score += stats.references_found as f64 / stats.values as f64 * 5.0;
+        }
+
+        // Unknown fields are less plausible than known ones, specially if they never change.
+        if field.name().starts_with("unknown_") {
+            score -= if stats.is_constant { 6.0 } else { 1.0 };
+        }
+    }
+
+    score
+}
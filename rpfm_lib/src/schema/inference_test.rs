@@ -0,0 +1,120 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! Module containing tests for the inference of definitions.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::binary::WriteBytes;
+use crate::files::{db::DB, Encodeable, table::DecodedData};
+
+use super::*;
+use super::inference::infer_definitions;
+
+fn field(name: &str, field_type: FieldType) -> Field {
+    Field::new(name.to_owned(), field_type, false, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None)
+}
+
+fn definition(version: i32, fields: Vec<Field>) -> Definition {
+    let mut definition = Definition::new(version, None);
+    definition.set_fields(fields);
+    definition
+}
+
+fn encoded_table(definition: &Definition, rows: &[Vec<DecodedData>]) -> Vec<u8> {
+    let mut table = DB::new(definition, None, "test_tables", false);
+    table.set_data(None, rows).unwrap();
+
+    let mut encoded = vec![];
+    table.encode(&mut encoded, &None).unwrap();
+    encoded
+}
+
+fn field_types(definition: &Definition) -> Vec<FieldType> {
+    definition.fields().iter().map(|field| field.field_type().clone()).collect()
+}
+
+#[test]
+fn test_infer_added_field() {
+    let previous = definition(1, vec![field("key", FieldType::StringU8), field("value", FieldType::I32)]);
+    let current = definition(2, vec![field("key", FieldType::StringU8), field("value", FieldType::I32), field("multiplier", FieldType::F32)]);
+    let data = encoded_table(&current, &[
+        vec![DecodedData::StringU8("wh_main_emp_spearmen".to_owned()), DecodedData::I32(500), DecodedData::F32(1.5)],
+        vec![DecodedData::StringU8("wh_main_emp_halberdiers".to_owned()), DecodedData::I32(650), DecodedData::F32(0.75)],
+        vec![DecodedData::StringU8("wh_main_emp_greatswords".to_owned()), DecodedData::I32(900), DecodedData::F32(2.25)],
+    ]);
+
+    let inferred = infer_definitions(&data, Some(&previous), None, &HashMap::new(), 5).unwrap();
+    let best = &inferred[0];
+    assert_eq!(*best.definition().version(), 2);
+    assert_eq!(*best.edits(), 1);
+    assert_eq!(field_types(best.definition()), field_types(&current));
+
+    // Known fields keep their names, and new ones get a placeholder.
+    let names = best.definition().fields().iter().map(|field| field.name().to_owned()).collect::<Vec<_>>();
+    assert_eq!(names, vec!["key".to_owned(), "value".to_owned(), "unknown_2".to_owned()]);
+
+    // Candidates are sorted by score.
+    assert!(inferred.windows(2).all(|pair| pair[0].score() >= pair[1].score()));
+}
+
+#[test]
+fn test_infer_removed_field_with_raw_names() {
+    let previous = definition(1, vec![field("key", FieldType::StringU8), field("cost", FieldType::I32), field("is_naval", FieldType::Boolean)]);
+    let current = definition(2, vec![field("key", FieldType::StringU8), field("is_naval", FieldType::Boolean)]);
+    let data = encoded_table(&current, &[
+        vec![DecodedData::StringU8("galley".to_owned()), DecodedData::Boolean(true)],
+        vec![DecodedData::StringU8("spearmen".to_owned()), DecodedData::Boolean(false)],
+    ]);
+
+    let inferred = infer_definitions(&data, Some(&previous), None, &HashMap::new(), 5).unwrap();
+    let best = &inferred[0];
+    assert_eq!(*best.edits(), 1);
+    assert_eq!(best.definition().fields().iter().map(|field| field.name()).collect::<Vec<_>>(), vec!["key", "is_naval"]);
+
+    // The Assembly Kit definition is used to name new fields.
+    let raw = definition(0, vec![field("key", FieldType::StringU8), field("is_naval", FieldType::Boolean), field("onscreen_name", FieldType::StringU8)]);
+    let current = definition(3, raw.fields().to_vec());
+    let data = encoded_table(&current, &[
+        vec![DecodedData::StringU8("galley".to_owned()), DecodedData::Boolean(true), DecodedData::StringU8("Galley".to_owned())],
+        vec![DecodedData::StringU8("spearmen".to_owned()), DecodedData::Boolean(false), DecodedData::StringU8("Spearmen".to_owned())],
+    ]);
+
+    let inferred = infer_definitions(&data, Some(&previous), Some(&raw), &HashMap::new(), 5).unwrap();
+    assert!(inferred.iter().any(|candidate| candidate.definition().fields() == raw.fields()));
+}
+
+#[test]
+fn test_infer_without_template() {
+    let current = definition(1, vec![field("key", FieldType::StringU8), field("value", FieldType::I32)]);
+    let data = encoded_table(&current, &[
+        vec![DecodedData::StringU8("first_key".to_owned()), DecodedData::I32(10)],
+        vec![DecodedData::StringU8("second_key".to_owned()), DecodedData::I32(20)],
+    ]);
+
+    let inferred = infer_definitions(&data, None, None, &HashMap::new(), 10).unwrap();
+    assert!(inferred.len() <= 10);
+    assert!(inferred.iter().any(|candidate| field_types(candidate.definition()) == field_types(&current)));
+}
+
+#[test]
+fn test_infer_undecodeable_table() {
+
+    // Three rows can never fit in a single byte.
+    let mut data = vec![];
+    data.write_bool(false).unwrap();
+    data.write_u32(3).unwrap();
+    data.write_u8(1).unwrap();
+
+    assert!(infer_definitions(&data, None, None, &HashMap::new(), 5).unwrap().is_empty());
+
+    // Broken headers are errors, not empty lists.
+    assert!(infer_definitions(&[], None, None, &HashMap::new(), 5).is_err());
+}
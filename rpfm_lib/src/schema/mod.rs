@@ -67,7 +67,7 @@ use getset::*;
 use rayon::prelude::*;
 use ron::de::{from_bytes, from_str};
 use ron::ser::{to_string_pretty, PrettyConfig};
-use serde::{Serialize as SerdeSerialize, Serializer};
+use serde::{Serialize as SerdeSerialize, Serializer, ser::SerializeStruct};
 use serde_derive::{Serialize, Deserialize};
 #[cfg(feature = "integration_sqlite")] use rusqlite::types::Type;
 
@@ -86,9 +86,26 @@ use std::path::Path;
 use crate::error::Result;
 use crate::files::table::DecodedData;
 
+pub mod codegen;
+pub mod diff;
+pub mod export;
+pub mod graph;
+pub mod inference;
+pub mod overlay;
+pub mod validation;
+
 // Legacy Schemas, to keep backwards compatibility during updates.
 pub(crate) mod v4;
 
+#[cfg(test)] mod codegen_test;
+#[cfg(test)] mod diff_test;
+#[cfg(test)] mod export_test;
+#[cfg(test)] mod graph_test;
+#[cfg(test)] mod inference_test;
+#[cfg(test)] mod overlay_test;
+#[cfg(test)] mod schema_test;
+#[cfg(test)] mod validation_test;
+
 /// Name of the folder containing all the schemas.
 pub const SCHEMA_FOLDER: &str = "schemas";
 
@@ -134,7 +151,9 @@ pub struct Schema {
 }
 
 /// This struct contains all the data needed to decode a specific version of a versioned PackedFile.
-#[derive(Clone, PartialEq, Eq, Debug, Default, Getters, MutGetters, Setters, Serialize, Deserialize)]
+///
+/// Its [Serialize](SerdeSerialize) implementation is manual, to skip empty game builds only in human-readable formats.
+#[derive(Clone, PartialEq, Eq, Debug, Default, Getters, MutGetters, Setters, Deserialize)]
 #[getset(get = "pub", get_mut = "pub", set = "pub")]
 pub struct Definition {
 
@@ -155,6 +174,16 @@ pub struct Definition {
     /// Note: this order is only applicable to the processed fields, not the raw fields.
     localised_key_order: Vec<u32>,
 
+    /// Range of game builds this definition is for, as the first and last game version numbers, both inclusive.
+    ///
+    /// The game version numbers use the format of [GameInfo::game_build](crate::games::GameInfo::game_build),
+    /// which is also the one Packs store in their header. Only needed when a game reuses a table version with a different layout
+    /// across patches. If `None`, the definition is for any build.
+    ///
+    /// Only serialized in human-readable formats, like the schema files, if it's set.
+    #[serde(default)]
+    game_builds: Option<(u32, u32)>,
+
     /// Patches that apply to this definition.
     ///
     /// Not serialized. Populated when getting the definition.
@@ -268,11 +297,11 @@ impl Schema {
         });
     }
 
-    /// This function adds a definition for a table into the currently loaded schema.
+    /// This function adds a definition for a table into the currently loaded schema, replacing the one with the same version and game builds, if any.
     pub fn add_definition(&mut self, table_name: &str, definition: &Definition) {
         match self.definitions.get_mut(table_name) {
             Some(definitions) => {
-                match definitions.iter_mut().find(|def| def.version() == definition.version()) {
+                match definitions.iter_mut().find(|def| def.version() == definition.version() && def.game_builds() == definition.game_builds()) {
                     Some(def) => *def = definition.to_owned(),
                     None => definitions.push(definition.to_owned()),
                 }
@@ -314,27 +343,59 @@ impl Schema {
     ///
     /// As we may have versions from other games, we first need to check for the last definition in the dependency database.
     /// If that fails, we try to get it from the schema.
+    ///
+    /// If the schema has more than one definition for the chosen version, the one with the same game builds as the candidate is returned.
+    /// Without candidates, the one for the latest build of the game is returned.
     pub fn definition_newer(&self, table_name: &str, candidates: &[Definition]) -> Option<&Definition> {
 
         // Version is... complicated. We don't really want the last one, but the last one compatible with our game.
         // So we have to try to get it first from the Dependency Database first. If that fails, we fall back to the schema.
         if let Some(definition) = candidates.iter().max_by(|x, y| x.version().cmp(y.version())) {
-            self.definition_by_name_and_version(table_name, *definition.version())
+            self.definitions.get(table_name)?
+                .iter()
+                .find(|def| def.version() == definition.version() && def.game_builds() == definition.game_builds())
+                .or_else(|| self.definition_by_name_and_version(table_name, *definition.version()))
         }
 
         // If there was no coincidence in the dependency database... we risk ourselves getting the last definition we have for
         // that db from the schema.
         else{
-            self.definitions.get(table_name)?.get(0)
+            let version = *self.definitions.get(table_name)?.get(0)?.version();
+            self.definition_by_name_and_version(table_name, version)
         }
     }
 
+    /// This function returns the definition of a table for the provided version.
+    ///
+    /// If more than one definition has the provided version, it returns the one for the latest build of the game.
+    /// See [Schema::definition_by_name_version_and_build].
     pub fn definition_by_name_and_version(&self, table_name: &str, table_version: i32) -> Option<&Definition>  {
-        self.definitions.get(table_name)?.iter().find(|definition| *definition.version() == table_version)
+        self.definition_by_name_version_and_build(table_name, table_version, None)
     }
 
+    /// This function returns the definition of a table for the provided version and game build.
+    ///
+    /// If more than one definition has the provided version, it returns, in this order of preference:
+    /// - The one whose build range contains the provided game build.
+    /// - The one without a build range.
+    /// - The one with the newest build range.
+    ///
+    /// If no game build is provided, the latest build of the game is assumed.
+    pub fn definition_by_name_version_and_build(&self, table_name: &str, table_version: i32, game_build: Option<u32>) -> Option<&Definition> {
+        self.definitions.get(table_name)?
+            .iter()
+            .filter(|definition| *definition.version() == table_version)
+            .max_by_key(|definition| definition.game_build_preference(game_build))
+    }
+
+    /// This function returns a mutable reference to the definition of a table for the provided version.
+    ///
+    /// If more than one definition has the provided version, it returns the one for the latest build of the game.
     pub fn definition_by_name_and_version_mut(&mut self, table_name: &str, table_version: i32) -> Option<&mut Definition>  {
-        self.definitions.get_mut(table_name)?.iter_mut().find(|definition| *definition.version() == table_version)
+        self.definitions.get_mut(table_name)?
+            .iter_mut()
+            .filter(|definition| *definition.version() == table_version)
+            .max_by_key(|definition| definition.game_build_preference(None))
     }
 
     /// This function loads a [Schema] to memory from a provided `.ron` file.
@@ -349,6 +410,7 @@ impl Schema {
             if let Some(definitions) = schema.definitions_by_table_name_mut(&table_name) {
                 for definition in definitions {
                     definition.set_patches(patches.clone());
+                    definition.apply_localisation_patches();
                 }
             }
         }
@@ -368,6 +430,7 @@ impl Schema {
             if let Some(definitions) = schema.definitions_by_table_name_mut(&table_name) {
                 for definition in definitions {
                     definition.set_patches(patches.clone());
+                    definition.apply_localisation_patches();
                 }
             }
         }
@@ -576,6 +639,7 @@ impl Definition {
             localised_fields: vec![],
             fields: vec![],
             localised_key_order: vec![],
+            game_builds: None,
             patches: schema_patches.cloned().unwrap_or_default(),
         }
     }
@@ -587,10 +651,31 @@ impl Definition {
             localised_fields: loc_fields.to_vec(),
             fields: fields.to_vec(),
             localised_key_order: vec![],
+            game_builds: None,
             patches: schema_patches.cloned().unwrap_or_default(),
         }
     }
 
+    /// This function returns if the definition is for the provided game build. Definitions without a build range are for any build.
+    pub fn is_for_game_build(&self, game_build: u32) -> bool {
+        match self.game_builds {
+            Some((first, last)) => first <= game_build && game_build <= last,
+            None => true,
+        }
+    }
+
+    /// This function returns how preferable this definition is for the provided game build, compared to others with the same version.
+    ///
+    /// Definitions whose build range contains the build go first, then the ones without a build range, then the ones with newer build ranges.
+    fn game_build_preference(&self, game_build: Option<u32>) -> (bool, bool, Option<u32>) {
+        let contains_build = match (game_build, self.game_builds) {
+            (Some(game_build), Some(_)) => self.is_for_game_build(game_build),
+            _ => false,
+        };
+
+        (contains_build, self.game_builds.is_none(), self.game_builds.map(|(_, last)| last))
+    }
+
     /// This function returns the reference and lookup data of a definition.
     pub fn reference_data(&self) -> BTreeMap<i32, (String, String, Option<Vec<String>>)> {
         self.fields.iter()
@@ -847,6 +932,40 @@ impl Definition {
         }
     }
 
+    /// This function applies the table-wide localisation patches of the definition, if any.
+    ///
+    /// These patches use the following keys, with comma-separated values:
+    /// - `localised_fields`: names of the localised fields of the table. They replace the existing ones, keeping the data of the ones
+    ///   with the same name. New ones are text fields. Names of regular fields of the definition are ignored.
+    /// - `localised_key_order`: names of the key fields, in the order they're joined to form the loc keys. Ignored if any of them is not in the definition.
+    pub fn apply_localisation_patches(&mut self) {
+        if let Some(patch) = self.patches.get("-1") {
+            if let Some(localised_fields) = patch.get("localised_fields") {
+                let fields = self.fields_processed();
+                let localised_fields = localised_fields.split(',')
+                    .filter(|name| !name.is_empty() && !fields.iter().any(|field| field.name() == *name))
+                    .map(|name| match self.localised_fields.iter().find(|field| field.name() == name) {
+                        Some(field) => field.clone(),
+                        None => Field::new(name.to_owned(), FieldType::StringU8, false, None, false, None, None, None, String::new(), -1, 0, BTreeMap::new(), None),
+                    })
+                    .collect();
+
+                self.localised_fields = localised_fields;
+            }
+
+            if let Some(localised_key_order) = patch.get("localised_key_order") {
+                let fields = self.fields_processed();
+                let order = localised_key_order.split(',')
+                    .map(|name| fields.iter().position(|field| field.name() == name).map(|index| index as u32))
+                    .collect::<Option<Vec<_>>>();
+
+                if let Some(order) = order {
+                    self.localised_key_order = order;
+                }
+            }
+        }
+    }
+
     /// This function populates the `localised_fields` of a definition with data from the assembly kit.
     #[cfg(feature = "integration_assembly_kit")]
     pub fn update_from_raw_localisable_fields(&mut self, raw_definition: &RawDefinition, raw_localisable_fields: &[RawLocalisableField]) {
@@ -987,6 +1106,53 @@ impl Field {
         false
     }
 
+    /// Getter for the `min_value` validation rule for schema patches.
+    pub fn min_value(&self, schema_patches: Option<&DefinitionPatch>) -> Option<f64> {
+        self.schema_patch_value(schema_patches, "min_value").and_then(|value| value.parse().ok())
+    }
+
+    /// Getter for the `max_value` validation rule for schema patches.
+    pub fn max_value(&self, schema_patches: Option<&DefinitionPatch>) -> Option<f64> {
+        self.schema_patch_value(schema_patches, "max_value").and_then(|value| value.parse().ok())
+    }
+
+    /// Getter for the `pattern` validation rule for schema patches. This is a regex the entire value must match.
+    pub fn value_pattern(&self, schema_patches: Option<&DefinitionPatch>) -> Option<String> {
+        self.schema_patch_value(schema_patches, "pattern").map(|value| value.to_owned())
+    }
+
+    /// Getter for the `allowed_values` validation rule for schema patches, stored as a `;` separated list.
+    pub fn allowed_values(&self, schema_patches: Option<&DefinitionPatch>) -> Option<Vec<String>> {
+        self.schema_patch_value(schema_patches, "allowed_values").map(|value| value.split(';').map(|value| value.to_owned()).collect())
+    }
+
+    /// Getter for the `unique` validation rule for schema patches.
+    pub fn is_unique(&self, schema_patches: Option<&DefinitionPatch>) -> bool {
+        self.schema_patch_value(schema_patches, "unique").map(|value| value.parse::<bool>().unwrap_or(false)).unwrap_or(false)
+    }
+
+    /// Getter for the `empty_if_set` validation rule for schema patches.
+    ///
+    /// These are the columns that, if set, force this column to be empty.
+    pub fn empty_if_set(&self, schema_patches: Option<&DefinitionPatch>) -> Vec<String> {
+        self.schema_patch_value(schema_patches, "empty_if_set").map(|value| value.split(';').map(|value| value.to_owned()).collect()).unwrap_or_default()
+    }
+
+    /// Getter for the `required_if_set` validation rule for schema patches.
+    ///
+    /// These are the columns that, if set, force this column to not be empty.
+    pub fn required_if_set(&self, schema_patches: Option<&DefinitionPatch>) -> Vec<String> {
+        self.schema_patch_value(schema_patches, "required_if_set").map(|value| value.split(';').map(|value| value.to_owned()).collect()).unwrap_or_default()
+    }
+
+    /// This function returns the value of the provided key in the schema patches of this field, if any.
+    fn schema_patch_value<'a>(&self, schema_patches: Option<&'a DefinitionPatch>, key: &str) -> Option<&'a str> {
+        schema_patches
+            .and_then(|schema_patches| schema_patches.get(self.name()))
+            .and_then(|patch| patch.get(key))
+            .map(|value| value.as_str())
+    }
+
     /// Getter for the `explanation` field for schema patches.
     pub fn schema_patch_explanation(&self, schema_patches: Option<&DefinitionPatch>) -> String {
         if let Some(schema_patches) = schema_patches {
@@ -1054,6 +1220,29 @@ impl Default for Schema {
     }
 }
 
+/// Serialize implementation of `Definition`.
+///
+/// Definitions without game builds skip them in human-readable formats, so the schema files don't get a `game_builds: None` on every definition.
+/// Binary formats, like the bincode of the dependencies cache, are not self-describing, so they always get them.
+impl SerdeSerialize for Definition {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer, {
+        let skip_game_builds = serializer.is_human_readable() && self.game_builds.is_none();
+        let mut state = serializer.serialize_struct("Definition", if skip_game_builds { 4 } else { 5 })?;
+        state.serialize_field("version", &self.version)?;
+        state.serialize_field("fields", &self.fields)?;
+        state.serialize_field("localised_fields", &self.localised_fields)?;
+        state.serialize_field("localised_key_order", &self.localised_key_order)?;
+
+        if skip_game_builds {
+            state.skip_field("game_builds")?;
+        } else {
+            state.serialize_field("game_builds", &self.game_builds)?;
+        }
+
+        state.end()
+    }
+}
+
 /// Default implementation of `FieldType`.
 impl Default for Field {
     fn default() -> Self {
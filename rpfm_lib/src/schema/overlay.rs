@@ -0,0 +1,262 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the local overlays for a [Schema].
+
+An overlay holds the definitions and patches made locally for a game, in its own file outside the schema repo, so they're not
+lost when the schema is updated. It's merged on top of the upstream schema when loading it with [Schema::load_with_overlay].
+
+Once upstream covers an entry of the overlay, said entry can be dropped with [SchemaOverlay::prune].
+!*/
+
+use getset::*;
+use ron::de::from_bytes;
+use ron::ser::{to_string_pretty, PrettyConfig};
+use serde_derive::{Serialize, Deserialize};
+
+use std::collections::HashMap;
+use std::{fmt, fmt::Display};
+use std::fs::{DirBuilder, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+#[cfg(feature = "integration_log")] use crate::integrations::log::*;
+
+use crate::error::Result;
+
+use super::{Definition, DefinitionPatch, ordered_map_definitions, ordered_map_patches, Schema};
+
+/// Name of the folder containing all the schema overlays. It must not be the schemas folder, as that one is replaced on update.
+pub const SCHEMA_OVERLAY_FOLDER: &str = "schema_overlays";
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct represents the local overlay of a [Schema].
+#[derive(Clone, PartialEq, Eq, Debug, Default, Getters, MutGetters, Serialize, Deserialize)]
+#[getset(get = "pub", get_mut = "pub")]
+pub struct SchemaOverlay {
+
+    /// Local definitions, by table name.
+    #[serde(serialize_with = "ordered_map_definitions")]
+    definitions: HashMap<String, Vec<Definition>>,
+
+    /// Local patches, by table name.
+    #[serde(serialize_with = "ordered_map_patches")]
+    patches: HashMap<String, DefinitionPatch>,
+}
+
+//---------------------------------------------------------------------------//
+//                       Enum & Structs Implementations
+//---------------------------------------------------------------------------//
+
+impl SchemaOverlay {
+
+    /// This function loads a [SchemaOverlay] to memory from a provided `.ron` file.
+    ///
+    /// If the file doesn't exist, it returns an empty overlay.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+
+        let mut file = BufReader::new(File::open(path)?);
+        let mut data = Vec::with_capacity(file.get_ref().metadata()?.len() as usize);
+        file.read_to_end(&mut data)?;
+        from_bytes(&data).map_err(From::from)
+    }
+
+    /// This function saves a [SchemaOverlay] from memory to a `.ron` file with the provided path.
+    pub fn save(&mut self, path: &Path) -> Result<()> {
+        if let Some(parent_folder) = path.parent() {
+            DirBuilder::new().recursive(true).create(parent_folder)?;
+        }
+
+        self.definitions.values_mut().for_each(|definitions| definitions.sort_by(|a, b| b.version().cmp(a.version())));
+
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(self.export()?.as_bytes())?;
+        Ok(())
+    }
+
+    /// This function returns the overlay serialized to a `.ron` string, to share it.
+    pub fn export(&self) -> Result<String> {
+        to_string_pretty(&self, PrettyConfig::default()).map_err(From::from)
+    }
+
+    /// This function returns if the overlay has no definitions or patches.
+    pub fn is_empty(&self) -> bool {
+        self.definitions.is_empty() && self.patches.is_empty()
+    }
+
+    /// This function adds a definition for a table to the overlay, replacing the one with the same version and game builds, if any.
+    pub fn add_definition(&mut self, table_name: &str, definition: &Definition) {
+        let definitions = self.definitions.entry(table_name.to_owned()).or_default();
+        match definitions.iter_mut().find(|def| def.version() == definition.version() && def.game_builds() == definition.game_builds()) {
+            Some(def) => *def = definition.to_owned(),
+            None => definitions.push(definition.to_owned()),
+        }
+    }
+
+    /// This function removes the definition with the provided version and game builds for a table from the overlay.
+    ///
+    /// Definitions with the same version for other game builds are kept.
+    pub fn remove_definition(&mut self, table_name: &str, version: i32, game_builds: Option<(u32, u32)>) {
+        if let Some(definitions) = self.definitions.get_mut(table_name) {
+            definitions.retain(|definition| *definition.version() != version || *definition.game_builds() != game_builds);
+            if definitions.is_empty() {
+                self.definitions.remove(table_name);
+            }
+        }
+    }
+
+    /// This function adds a list of patches to the overlay, replacing the values already in it.
+    pub fn add_patch(&mut self, patches: HashMap<String, DefinitionPatch>) {
+        for (table_name, column_patches) in patches {
+            let table_patches = self.patches.entry(table_name).or_default();
+            for (column_name, patch) in column_patches {
+                table_patches.entry(column_name).or_default().extend(patch);
+            }
+        }
+    }
+
+    /// This function removes all the patches for a column of a table from the overlay.
+    pub fn remove_patch(&mut self, table_name: &str, column_name: &str) {
+        if let Some(table_patches) = self.patches.get_mut(table_name) {
+            table_patches.remove(column_name);
+            if table_patches.is_empty() {
+                self.patches.remove(table_name);
+            }
+        }
+    }
+
+    /// This function removes from the overlay the entries the provided upstream schema already covers:
+    /// - Definitions with the same version, game builds and fields as an upstream one.
+    /// - Patch values equal to the upstream ones.
+    ///
+    /// It returns the amount of entries removed.
+    pub fn prune(&mut self, upstream: &Schema) -> usize {
+        let mut removed = 0;
+
+        for (table_name, definitions) in self.definitions.iter_mut() {
+            let len = definitions.len();
+            definitions.retain(|definition| !upstream.definitions_by_table_name(table_name)
+                .map(|upstream| upstream.iter().any(|upstream| upstream.version() == definition.version() && upstream.game_builds() == definition.game_builds() && upstream.fields() == definition.fields()))
+                .unwrap_or(false));
+
+            removed += len - definitions.len();
+        }
+
+        for (table_name, table_patches) in self.patches.iter_mut() {
+            for (column_name, patch) in table_patches.iter_mut() {
+                let len = patch.len();
+                patch.retain(|key, value| upstream.patch_value(table_name, column_name, key) != Some(value));
+                removed += len - patch.len();
+            }
+
+            table_patches.retain(|_, patch| !patch.is_empty());
+        }
+
+        self.definitions.retain(|_, definitions| !definitions.is_empty());
+        self.patches.retain(|_, table_patches| !table_patches.is_empty());
+
+        removed
+    }
+
+    /// This function tries to upload the contents of the overlay to Sentry's service, so they can be added upstream.
+    ///
+    /// It requires the **integration_log** feature.
+    #[cfg(feature = "integration_log")]
+    pub fn upload(&self, sentry_guard: &ClientInitGuard, game_name: &str) -> Result<()> {
+        if !self.patches.is_empty() {
+            Schema::upload_patches(sentry_guard, game_name, self.patches.clone())?;
+        }
+
+        // Upstream expects one definition per table, so we need to upload each version separately.
+        let max_versions = self.definitions.values().map(|definitions| definitions.len()).max().unwrap_or(0);
+        for index in 0..max_versions {
+            let definitions = self.definitions.iter()
+                .filter_map(|(table_name, definitions)| definitions.get(index).map(|definition| (table_name.to_owned(), definition.clone())))
+                .collect();
+
+            Schema::upload_definitions(sentry_guard, game_name, definitions)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Schema {
+
+    /// This function loads a [Schema] to memory from a provided `.ron` file, and merges the [SchemaOverlay] in the provided path on top of it.
+    ///
+    /// If there is no overlay in the provided path, this is the same as [Schema::load].
+    pub fn load_with_overlay(path: &Path, overlay_path: &Path) -> Result<Self> {
+        let mut schema = Self::load(path)?;
+        let overlay = SchemaOverlay::load(overlay_path)?;
+        schema.apply_overlay(&overlay);
+        Ok(schema)
+    }
+
+    /// This function merges the provided [SchemaOverlay] on top of the schema.
+    ///
+    /// Definitions in the overlay replace the ones with the same version, and patches in the overlay replace the values of the same keys.
+    pub fn apply_overlay(&mut self, overlay: &SchemaOverlay) {
+        for (table_name, definitions) in overlay.definitions() {
+            for definition in definitions {
+                self.add_definition(table_name, definition);
+            }
+        }
+
+        self.add_patch(overlay.patches().clone());
+
+        // Reload the patches of the affected definitions.
+        for table_name in overlay.definitions().keys().chain(overlay.patches().keys()) {
+            if let Some(patches) = self.patches().get(table_name).cloned() {
+                if let Some(definitions) = self.definitions_by_table_name_mut(table_name) {
+                    for definition in definitions {
+                        definition.set_patches(patches.clone());
+                        definition.apply_localisation_patches();
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Display for SchemaOverlay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut definitions = self.definitions.iter().collect::<Vec<_>>();
+        definitions.sort_by(|a, b| a.0.cmp(b.0));
+
+        writeln!(f, "Definitions: {}.", self.definitions.values().map(|definitions| definitions.len()).sum::<usize>())?;
+        for (table_name, definitions) in definitions {
+            for definition in definitions {
+                writeln!(f, "- {}, version {}: {} fields.", table_name, definition.version(), definition.fields().len())?;
+            }
+        }
+
+        let mut patches = self.patches.iter()
+            .flat_map(|(table_name, table_patches)| table_patches.iter()
+                .flat_map(move |(column_name, patch)| patch.iter()
+                    .map(move |(key, value)| (table_name, column_name, key, value))))
+            .collect::<Vec<_>>();
+        patches.sort();
+
+        writeln!(f, "Patches: {}.", patches.len())?;
+        for (table_name, column_name, key, value) in patches {
+            writeln!(f, "- {table_name}/{column_name}: {key} = {value}")?;
+        }
+
+        Ok(())
+    }
+}
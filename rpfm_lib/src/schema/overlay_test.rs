@@ -0,0 +1,130 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! Module containing tests for the local schema overlays.
+
+use std::collections::{BTreeMap, HashMap};
+use std::env::temp_dir;
+use std::fs::remove_dir_all;
+
+use super::*;
+use super::overlay::SchemaOverlay;
+
+fn field(name: &str, field_type: FieldType) -> Field {
+    Field::new(name.to_owned(), field_type, false, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None)
+}
+
+fn definition(version: i32, fields: Vec<Field>) -> Definition {
+    let mut definition = Definition::new(version, None);
+    definition.set_fields(fields);
+    definition
+}
+
+fn patch(table_name: &str, column_name: &str, key: &str, value: &str) -> HashMap<String, DefinitionPatch> {
+    HashMap::from([(table_name.to_owned(), HashMap::from([(column_name.to_owned(), HashMap::from([(key.to_owned(), value.to_owned())]))]))])
+}
+
+fn upstream() -> Schema {
+    let mut schema = Schema::default();
+    schema.add_definition("units_tables", &definition(1, vec![field("key", FieldType::StringU8), field("cost", FieldType::I32)]));
+    schema.add_patch(patch("units_tables", "cost", "min_value", "0"));
+    schema
+}
+
+#[test]
+fn test_apply_overlay() {
+    let mut overlay = SchemaOverlay::default();
+    overlay.add_definition("units_tables", &definition(1, vec![field("key", FieldType::StringU8), field("cost", FieldType::I32), field("is_naval", FieldType::Boolean)]));
+    overlay.add_definition("units_tables", &definition(2, vec![field("key", FieldType::StringU8)]));
+    overlay.add_definition("ships_tables", &definition(1, vec![field("key", FieldType::StringU8)]));
+    overlay.add_patch(patch("units_tables", "cost", "min_value", "10"));
+    overlay.add_patch(patch("units_tables", "cost", "max_value", "1000"));
+
+    let mut schema = upstream();
+    schema.apply_overlay(&overlay);
+
+    // Definitions with the same version are replaced, and new ones are added.
+    let definitions = schema.definitions_by_table_name("units_tables").unwrap();
+    assert_eq!(definitions.len(), 2);
+    assert_eq!(definitions.iter().find(|definition| *definition.version() == 1).unwrap().fields().len(), 3);
+    assert!(definitions.iter().any(|definition| *definition.version() == 2));
+    assert!(schema.definitions_by_table_name("ships_tables").is_some());
+
+    // Patches replace the values of the same keys, and the definitions get them.
+    assert_eq!(schema.patch_value("units_tables", "cost", "min_value"), Some(&"10".to_owned()));
+    assert_eq!(schema.patch_value("units_tables", "cost", "max_value"), Some(&"1000".to_owned()));
+    for definition in definitions {
+        assert_eq!(definition.patches().get("cost").and_then(|patch| patch.get("min_value")), Some(&"10".to_owned()));
+    }
+
+    // An empty overlay changes nothing.
+    let mut schema = upstream();
+    schema.apply_overlay(&SchemaOverlay::default());
+    assert_eq!(schema, upstream());
+}
+
+#[test]
+fn test_prune_overlay() {
+    let mut overlay = SchemaOverlay::default();
+    overlay.add_definition("units_tables", &definition(1, vec![field("key", FieldType::StringU8), field("cost", FieldType::I32)]));
+    overlay.add_definition("units_tables", &definition(2, vec![field("key", FieldType::StringU8)]));
+    overlay.add_patch(patch("units_tables", "cost", "min_value", "0"));
+    overlay.add_patch(patch("units_tables", "cost", "max_value", "1000"));
+
+    // Only the entries equal to upstream ones are removed.
+    assert_eq!(overlay.prune(&upstream()), 2);
+    assert_eq!(overlay.definitions()["units_tables"].iter().map(|definition| *definition.version()).collect::<Vec<_>>(), vec![2]);
+    assert_eq!(overlay.patches()["units_tables"]["cost"], HashMap::from([("max_value".to_owned(), "1000".to_owned())]));
+    assert_eq!(overlay.prune(&upstream()), 0);
+
+    // Once upstream covers everything, the overlay is empty.
+    let mut new_upstream = upstream();
+    new_upstream.apply_overlay(&overlay);
+    assert_eq!(overlay.prune(&new_upstream), 2);
+    assert!(overlay.is_empty());
+    assert!(overlay.definitions().is_empty());
+    assert!(overlay.patches().is_empty());
+}
+
+#[test]
+fn test_overlay_edition_and_io() {
+    let mut overlay = SchemaOverlay::default();
+    overlay.add_definition("units_tables", &definition(1, vec![field("key", FieldType::StringU8)]));
+    overlay.add_patch(patch("units_tables", "cost", "min_value", "10"));
+
+    let path = temp_dir().join("rpfm_test_overlay_edition_and_io").join("overlay.ron");
+    let _ = remove_dir_all(path.parent().unwrap());
+
+    // Missing overlays load as empty ones.
+    assert!(SchemaOverlay::load(&path).unwrap().is_empty());
+
+    overlay.save(&path).unwrap();
+    assert_eq!(SchemaOverlay::load(&path).unwrap(), overlay);
+    assert!(overlay.to_string().contains("- units_tables/cost: min_value = 10"));
+
+    // Removing a definition only removes the one for its game builds.
+    let mut build_definition = definition(1, vec![field("key", FieldType::StringU8)]);
+    build_definition.set_game_builds(Some((1, 2)));
+    overlay.add_definition("units_tables", &build_definition);
+    assert_eq!(overlay.definitions().get("units_tables").unwrap().len(), 2);
+
+    overlay.remove_definition("units_tables", 1, Some((1, 2)));
+    assert_eq!(overlay.definitions().get("units_tables").unwrap(), &vec![definition(1, vec![field("key", FieldType::StringU8)])]);
+
+    overlay.remove_definition("units_tables", 1, Some((3, 4)));
+    assert_eq!(overlay.definitions().get("units_tables").unwrap().len(), 1);
+
+    // Removing the last entry of a table removes the table.
+    overlay.remove_definition("units_tables", 1, None);
+    overlay.remove_patch("units_tables", "cost");
+    assert!(overlay.is_empty());
+
+    remove_dir_all(path.parent().unwrap()).unwrap();
+}
@@ -0,0 +1,98 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! Module containing tests for getting definitions from a `Schema`.
+
+use std::collections::BTreeMap;
+
+use super::*;
+
+fn definition(version: i32, game_builds: Option<(u32, u32)>, fields: &[&str]) -> Definition {
+    let mut definition = Definition::new(version, None);
+    definition.set_fields(fields.iter().map(|name| Field::new(name.to_string(), FieldType::StringU8, false, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None)).collect());
+    definition.set_game_builds(game_builds);
+    definition
+}
+
+fn field_names(definition: Option<&Definition>) -> Vec<String> {
+    definition.unwrap().fields().iter().map(|field| field.name().to_owned()).collect()
+}
+
+fn schema() -> Schema {
+    let mut schema = Schema::default();
+    schema.add_definition("units_tables", &definition(2, Some((0x01000000, 0x010100FF)), &["old"]));
+    schema.add_definition("units_tables", &definition(2, Some((0x01020000, 0x01FFFFFF)), &["new"]));
+    schema.add_definition("units_tables", &definition(1, None, &["first"]));
+    schema
+}
+
+#[test]
+fn test_definition_is_for_game_build() {
+    let definition = definition(1, Some((0x01020000, 0x010300FF)), &[]);
+    assert!(!definition.is_for_game_build(0x010100FF));
+    assert!(definition.is_for_game_build(0x01020000));
+    assert!(definition.is_for_game_build(0x010300FF));
+    assert!(!definition.is_for_game_build(0x01040000));
+    assert!(Definition::new(1, None).is_for_game_build(0x01040000));
+}
+
+#[test]
+fn test_definition_by_name_version_and_build() {
+    let mut schema = schema();
+
+    assert_eq!(field_names(schema.definition_by_name_version_and_build("units_tables", 2, Some(0x01010005))), vec!["old"]);
+    assert_eq!(field_names(schema.definition_by_name_version_and_build("units_tables", 2, Some(0x01030000))), vec!["new"]);
+    assert_eq!(field_names(schema.definition_by_name_version_and_build("units_tables", 1, Some(0x01030000))), vec!["first"]);
+    assert!(schema.definition_by_name_version_and_build("units_tables", 3, None).is_none());
+
+    // Without a build, or with one outside every range, the newest range wins.
+    assert_eq!(field_names(schema.definition_by_name_version_and_build("units_tables", 2, None)), vec!["new"]);
+    assert_eq!(field_names(schema.definition_by_name_version_and_build("units_tables", 2, Some(0x02000000))), vec!["new"]);
+    assert_eq!(field_names(schema.definition_by_name_and_version("units_tables", 2)), vec!["new"]);
+    assert_eq!(field_names(schema.definition_by_name_and_version_mut("units_tables", 2).map(|definition| &*definition)), vec!["new"]);
+
+    // A definition without range is preferred over the ones whose range doesn't contain the build.
+    schema.add_definition("units_tables", &definition(2, None, &["any"]));
+    assert_eq!(field_names(schema.definition_by_name_version_and_build("units_tables", 2, Some(0x01010005))), vec!["old"]);
+    assert_eq!(field_names(schema.definition_by_name_version_and_build("units_tables", 2, Some(0x02000000))), vec!["any"]);
+}
+
+#[test]
+fn test_definition_newer() {
+    let schema = schema();
+
+    // The candidate's builds pick between definitions with the same version.
+    let candidates = vec![definition(1, None, &[]), definition(2, Some((0x01000000, 0x010100FF)), &[])];
+    assert_eq!(field_names(schema.definition_newer("units_tables", &candidates)), vec!["old"]);
+
+    let candidates = vec![definition(2, Some((0x01020000, 0x01FFFFFF)), &[])];
+    assert_eq!(field_names(schema.definition_newer("units_tables", &candidates)), vec!["new"]);
+
+    // Candidates with unknown builds get the one for the latest build.
+    let candidates = vec![definition(2, Some((0, 1)), &[])];
+    assert_eq!(field_names(schema.definition_newer("units_tables", &candidates)), vec!["new"]);
+    assert_eq!(field_names(schema.definition_newer("units_tables", &[])), vec!["new"]);
+}
+
+#[test]
+fn test_definition_game_builds_serialization() {
+    let without_builds = definition(1, None, &["key"]);
+    let serialized = ron::ser::to_string(&without_builds).unwrap();
+
+    // Definitions without game builds, like the ones from before game builds existed, are for any build.
+    assert!(!serialized.contains("game_builds"));
+    assert_eq!(from_str::<Definition>(&serialized).unwrap(), without_builds);
+    assert!(!serde_json::to_string(&without_builds).unwrap().contains("game_builds"));
+
+    let with_builds = definition(1, Some((0x01000000, 0x010100FF)), &["key"]);
+    let serialized = ron::ser::to_string(&with_builds).unwrap();
+    assert!(serialized.contains("game_builds"));
+    assert_eq!(from_str::<Definition>(&serialized).unwrap(), with_builds);
+}
@@ -0,0 +1,320 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+/*!
+Module with the validation rules schema patches can declare for table columns.
+
+These rules are meant to encode constraints of the engine that are not part of the table definitions,
+like values that crash the game if they're out of a specific range. They're declared per column in a [DefinitionPatch],
+using the following keys:
+
+| Key               | Value                          | Rule                                                      |
+| ----------------- | ------------------------------ | --------------------------------------------------------- |
+| `min_value`       | Number                         | The value cannot be lower than this.                      |
+| `max_value`       | Number                         | The value cannot be higher than this.                     |
+| `pattern`         | Regex                          | The entire value must match this regex.                   |
+| `allowed_values`  | `;` separated list of values   | The value must be one of these.                           |
+| `unique`          | `true` or `false`              | The value cannot be repeated in other rows of the table.  |
+| `empty_if_set`    | `;` separated list of columns  | If any of these columns is set, the value must be empty.  |
+| `required_if_set` | `;` separated list of columns  | If any of these columns is set, the value cannot be empty. |
+
+Only the cross-column rules check empty values. To forbid empty values, use the `not_empty` patch. The exception is
+the `unique` rule, which only ignores empty strings and sequences, so repeated `0` or `false` values are reported.
+
+A value is considered set if it's not empty, `false` or `0`.
+!*/
+
+use regex::Regex;
+
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+
+use crate::error::{RLibError, Result};
+use crate::files::table::DecodedData;
+
+use super::{Definition, DefinitionPatch};
+
+//---------------------------------------------------------------------------//
+//                              Enum & Structs
+//---------------------------------------------------------------------------//
+
+/// This struct contains the validation rules of all the columns of a table, ready to check its data.
+#[derive(Clone, Debug, Default)]
+pub struct TableValidator {
+
+    /// Rules for each column with at least one rule, by column position in the processed fields.
+    columns: Vec<(usize, ColumnRules)>,
+}
+
+/// This struct contains the validation rules of a single column.
+#[derive(Clone, Debug, Default)]
+struct ColumnRules {
+    min_value: Option<f64>,
+    max_value: Option<f64>,
+    pattern: Option<(String, Regex)>,
+    allowed_values: Option<Vec<String>>,
+    unique: bool,
+
+    /// Columns that force this one to be empty, as position and name.
+    empty_if_set: Vec<(usize, String)>,
+
+    /// Columns that force this one to not be empty, as position and name.
+    required_if_set: Vec<(usize, String)>,
+}
+
+/// This enum represents the ways a value can break the validation rules of its column.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValidationError {
+    OutOfRange(String, Option<f64>, Option<f64>),
+    PatternMismatch(String, String),
+    NotAllowed(String, Vec<String>),
+    NotUnique(String),
+    MustBeEmpty(String),
+    Required(String),
+}
+
+//---------------------------------------------------------------------------//
+//                       Enum & Structs Implementations
+//---------------------------------------------------------------------------//
+
+impl TableValidator {
+
+    /// This function builds the validator for a table, using the rules in the provided patches.
+    ///
+    /// It fails if any of the rules cannot be used, like invalid regexes or cross-column rules referring to unknown columns.
+    pub fn new(definition: &Definition, schema_patches: Option<&DefinitionPatch>) -> Result<Self> {
+        let fields_processed = definition.fields_processed();
+        let column_position = |field_name: &str, rule: &str, column_name: &str| fields_processed.iter()
+            .position(|field| field.name() == column_name)
+            .map(|column| (column, column_name.to_owned()))
+            .ok_or_else(|| RLibError::InvalidValidationRule(field_name.to_owned(), rule.to_owned(), format!("there is no column named \"{column_name}\"")));
+
+        let mut columns = vec![];
+        for (column, field) in fields_processed.iter().enumerate() {
+            let pattern = match field.value_pattern(schema_patches) {
+                Some(pattern) => {
+                    let regex = Regex::new(&format!("^(?:{pattern})$"))
+                        .map_err(|error| RLibError::InvalidValidationRule(field.name().to_owned(), "pattern".to_owned(), error.to_string()))?;
+                    Some((pattern, regex))
+                },
+                None => None,
+            };
+
+            let rules = ColumnRules {
+                min_value: field.min_value(schema_patches),
+                max_value: field.max_value(schema_patches),
+                pattern,
+                allowed_values: field.allowed_values(schema_patches),
+                unique: field.is_unique(schema_patches),
+                empty_if_set: field.empty_if_set(schema_patches).iter()
+                    .map(|column_name| column_position(field.name(), "empty_if_set", column_name))
+                    .collect::<Result<Vec<_>>>()?,
+                required_if_set: field.required_if_set(schema_patches).iter()
+                    .map(|column_name| column_position(field.name(), "required_if_set", column_name))
+                    .collect::<Result<Vec<_>>>()?,
+            };
+
+            if !rules.is_empty() {
+                columns.push((column, rules));
+            }
+        }
+
+        Ok(Self {
+            columns
+        })
+    }
+
+    /// This function returns if the table has no validation rules.
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    /// This function returns the positions of the columns with validation rules.
+    pub fn columns(&self) -> Vec<usize> {
+        self.columns.iter().map(|(column, _)| *column).collect()
+    }
+
+    /// This function returns if the values of the provided column must be unique in the table.
+    pub fn is_column_unique(&self, column: usize) -> bool {
+        self.columns.iter().any(|(rules_column, rules)| *rules_column == column && rules.unique)
+    }
+
+    /// This function checks a single cell of a row against the rules of its column.
+    ///
+    /// The `unique` rule needs the entire table, so it's only checked by [TableValidator::validate].
+    pub fn validate_cell(&self, row: &[DecodedData], column: usize) -> Vec<ValidationError> {
+        match self.columns.iter().find(|(rules_column, _)| *rules_column == column) {
+            Some((_, rules)) => rules.validate(row, column),
+            None => vec![],
+        }
+    }
+
+    /// This function checks the entire data of a table, returning the row, column and error of each broken rule.
+    pub fn validate(&self, data: &[Vec<DecodedData>]) -> Vec<(usize, usize, ValidationError)> {
+        let mut errors = self.columns.iter()
+            .flat_map(|(column, rules)| rules.validate_column(data, *column).into_iter().map(|(row, error)| (row, *column, error)))
+            .collect::<Vec<_>>();
+
+        errors.sort_by_key(|(row, column, _)| (*row, *column));
+        errors
+    }
+
+    /// This function checks a single column of the data of a table, returning the row and error of each broken rule.
+    pub fn validate_column(&self, data: &[Vec<DecodedData>], column: usize) -> Vec<(usize, ValidationError)> {
+        match self.columns.iter().find(|(rules_column, _)| *rules_column == column) {
+            Some((_, rules)) => rules.validate_column(data, column),
+            None => vec![],
+        }
+    }
+}
+
+impl ColumnRules {
+
+    /// This function returns if there are no rules for the column.
+    fn is_empty(&self) -> bool {
+        self.min_value.is_none() &&
+            self.max_value.is_none() &&
+            self.pattern.is_none() &&
+            self.allowed_values.is_none() &&
+            !self.unique &&
+            self.empty_if_set.is_empty() &&
+            self.required_if_set.is_empty()
+    }
+
+    /// This function checks an entire column against these rules, returning the row and error of each broken rule.
+    fn validate_column(&self, data: &[Vec<DecodedData>], column: usize) -> Vec<(usize, ValidationError)> {
+        let mut errors = data.iter()
+            .enumerate()
+            .flat_map(|(row_index, row)| self.validate(row, column).into_iter().map(move |error| (row_index, error)))
+            .collect::<Vec<_>>();
+
+        if self.unique {
+            let mut values: HashMap<String, Vec<usize>> = HashMap::new();
+            for (row_index, row) in data.iter().enumerate() {
+                if let Some(cell) = row.get(column) {
+                    if !is_empty(cell) {
+                        values.entry(cell.data_to_string().to_string()).or_default().push(row_index);
+                    }
+                }
+            }
+
+            for (value, rows) in values {
+                if rows.len() > 1 {
+                    errors.extend(rows.into_iter().map(|row_index| (row_index, ValidationError::NotUnique(value.to_owned()))));
+                }
+            }
+        }
+
+        errors.sort_by_key(|(row, _)| *row);
+        errors
+    }
+
+    /// This function checks a cell against these rules, except the `unique` one.
+    fn validate(&self, row: &[DecodedData], column: usize) -> Vec<ValidationError> {
+        let mut errors = vec![];
+        let cell = match row.get(column) {
+            Some(cell) => cell,
+            None => return errors,
+        };
+
+        let value = cell.data_to_string();
+        let cell_is_set = is_set(cell);
+
+        for (other_column, other_name) in &self.empty_if_set {
+            if cell_is_set && row.get(*other_column).map(is_set).unwrap_or(false) {
+                errors.push(ValidationError::MustBeEmpty(other_name.to_owned()));
+            }
+        }
+
+        for (other_column, other_name) in &self.required_if_set {
+            if !cell_is_set && row.get(*other_column).map(is_set).unwrap_or(false) {
+                errors.push(ValidationError::Required(other_name.to_owned()));
+            }
+        }
+
+        // Empty values are not checked by the rest of the rules.
+        if value.is_empty() {
+            return errors;
+        }
+
+        if self.min_value.is_some() || self.max_value.is_some() {
+            let in_range = match value.parse::<f64>() {
+                Ok(number) => self.min_value.map(|min| number >= min).unwrap_or(true) && self.max_value.map(|max| number <= max).unwrap_or(true),
+                Err(_) => false,
+            };
+
+            if !in_range {
+                errors.push(ValidationError::OutOfRange(value.to_string(), self.min_value, self.max_value));
+            }
+        }
+
+        if let Some((pattern, regex)) = &self.pattern {
+            if !regex.is_match(&value) {
+                errors.push(ValidationError::PatternMismatch(value.to_string(), pattern.to_owned()));
+            }
+        }
+
+        if let Some(allowed_values) = &self.allowed_values {
+            if !allowed_values.iter().any(|allowed_value| *allowed_value == value) {
+                errors.push(ValidationError::NotAllowed(value.to_string(), allowed_values.to_vec()));
+            }
+        }
+
+        errors
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::OutOfRange(value, min, max) => {
+                let min = min.map(|min| min.to_string()).unwrap_or_else(|| "-∞".to_owned());
+                let max = max.map(|max| max.to_string()).unwrap_or_else(|| "∞".to_owned());
+                write!(f, "Value \"{value}\" is out of the range [{min}, {max}].")
+            }
+            Self::PatternMismatch(value, pattern) => write!(f, "Value \"{value}\" doesn't match the pattern \"{pattern}\"."),
+            Self::NotAllowed(value, allowed_values) => write!(f, "Value \"{value}\" is not one of the allowed values: {}.", allowed_values.join(", ")),
+            Self::NotUnique(value) => write!(f, "Value \"{value}\" is repeated in other rows."),
+            Self::MustBeEmpty(column) => write!(f, "Value must be empty if column \"{column}\" is set."),
+            Self::Required(column) => write!(f, "Value cannot be empty if column \"{column}\" is set."),
+        }
+    }
+}
+
+/// This function returns if a cell contains an empty string or sequence.
+fn is_empty(cell: &DecodedData) -> bool {
+    match cell {
+        DecodedData::ColourRGB(value) |
+        DecodedData::StringU8(value) |
+        DecodedData::StringU16(value) |
+        DecodedData::OptionalStringU8(value) |
+        DecodedData::OptionalStringU16(value) => value.is_empty(),
+        DecodedData::SequenceU16(value) | DecodedData::SequenceU32(value) => value.is_empty(),
+        _ => false,
+    }
+}
+
+/// This function returns if a cell is considered to have a value set.
+fn is_set(cell: &DecodedData) -> bool {
+    match cell {
+        DecodedData::Boolean(value) => *value,
+        DecodedData::F32(value) => *value != 0.0,
+        DecodedData::F64(value) => *value != 0.0,
+        DecodedData::I16(value) | DecodedData::OptionalI16(value) => *value != 0,
+        DecodedData::I32(value) | DecodedData::OptionalI32(value) => *value != 0,
+        DecodedData::I64(value) | DecodedData::OptionalI64(value) => *value != 0,
+        DecodedData::ColourRGB(value) |
+        DecodedData::StringU8(value) |
+        DecodedData::StringU16(value) |
+        DecodedData::OptionalStringU8(value) |
+        DecodedData::OptionalStringU16(value) => !value.is_empty(),
+        DecodedData::SequenceU16(value) | DecodedData::SequenceU32(value) => !value.is_empty(),
+    }
+}
@@ -0,0 +1,149 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! Module containing tests for the validation rules of table columns.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::error::RLibError;
+use crate::files::table::DecodedData;
+
+use super::*;
+use super::validation::{TableValidator, ValidationError};
+
+fn definition() -> Definition {
+    let mut definition = Definition::new(1, None);
+    definition.set_fields(vec![
+        Field::new("key".to_owned(), FieldType::StringU8, true, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None),
+        Field::new("cost".to_owned(), FieldType::I32, false, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None),
+        Field::new("category".to_owned(), FieldType::StringU8, false, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None),
+        Field::new("is_naval".to_owned(), FieldType::Boolean, false, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None),
+        Field::new("ship".to_owned(), FieldType::StringU8, false, None, false, None, None, None, String::new(), 0, 0, BTreeMap::new(), None),
+    ]);
+    definition
+}
+
+fn patches(rules: &[(&str, &str, &str)]) -> DefinitionPatch {
+    let mut patches: DefinitionPatch = HashMap::new();
+    for (field, key, value) in rules {
+        patches.entry(field.to_string()).or_default().insert(key.to_string(), value.to_string());
+    }
+    patches
+}
+
+fn row(key: &str, cost: i32, category: &str, is_naval: bool, ship: &str) -> Vec<DecodedData> {
+    vec![
+        DecodedData::StringU8(key.to_owned()),
+        DecodedData::I32(cost),
+        DecodedData::StringU8(category.to_owned()),
+        DecodedData::Boolean(is_naval),
+        DecodedData::StringU8(ship.to_owned()),
+    ]
+}
+
+#[test]
+fn test_validation_value_rules() {
+    let patches = patches(&[
+        ("key", "pattern", "[a-z_]+"),
+        ("cost", "min_value", "0"),
+        ("cost", "max_value", "1000"),
+        ("category", "allowed_values", "infantry;cavalry"),
+    ]);
+
+    let validator = TableValidator::new(&definition(), Some(&patches)).unwrap();
+    assert_eq!(validator.columns(), vec![0, 1, 2]);
+
+    assert!(validator.validate_cell(&row("spearmen", 500, "infantry", false, ""), 1).is_empty());
+    assert_eq!(validator.validate_cell(&row("spearmen", 1001, "infantry", false, ""), 1), vec![ValidationError::OutOfRange("1001".to_owned(), Some(0.0), Some(1000.0))]);
+    assert_eq!(validator.validate_cell(&row("spearmen", -1, "infantry", false, ""), 1), vec![ValidationError::OutOfRange("-1".to_owned(), Some(0.0), Some(1000.0))]);
+
+    // The pattern must match the entire value.
+    assert_eq!(validator.validate_cell(&row("Spearmen", 500, "infantry", false, ""), 0), vec![ValidationError::PatternMismatch("Spearmen".to_owned(), "[a-z_]+".to_owned())]);
+    assert_eq!(validator.validate_cell(&row("spearmen_2", 500, "infantry", false, ""), 0).len(), 1);
+
+    assert_eq!(validator.validate_cell(&row("spearmen", 500, "artillery", false, ""), 2), vec![ValidationError::NotAllowed("artillery".to_owned(), vec!["infantry".to_owned(), "cavalry".to_owned()])]);
+
+    // Empty values are only checked by cross-column rules.
+    assert!(validator.validate_cell(&row("", 500, "", false, ""), 0).is_empty());
+    assert!(validator.validate_cell(&row("", 500, "", false, ""), 2).is_empty());
+
+    // Columns without rules are never reported.
+    assert!(validator.validate_cell(&row("spearmen", 500, "infantry", true, "???"), 4).is_empty());
+}
+
+#[test]
+fn test_validation_cross_column_rules() {
+    let patches = patches(&[
+        ("ship", "required_if_set", "is_naval"),
+        ("category", "empty_if_set", "is_naval"),
+    ]);
+
+    let validator = TableValidator::new(&definition(), Some(&patches)).unwrap();
+    assert!(validator.validate_cell(&row("galley", 100, "", true, "galley_ship"), 4).is_empty());
+    assert!(validator.validate_cell(&row("spearmen", 100, "infantry", false, ""), 4).is_empty());
+    assert_eq!(validator.validate_cell(&row("galley", 100, "", true, ""), 4), vec![ValidationError::Required("is_naval".to_owned())]);
+    assert_eq!(validator.validate_cell(&row("galley", 100, "infantry", true, "galley_ship"), 2), vec![ValidationError::MustBeEmpty("is_naval".to_owned())]);
+}
+
+#[test]
+fn test_validation_unique() {
+    let patches = patches(&[("category", "unique", "true"), ("cost", "max_value", "10")]);
+    let validator = TableValidator::new(&definition(), Some(&patches)).unwrap();
+    assert!(validator.is_column_unique(2));
+    assert!(!validator.is_column_unique(1));
+
+    let data = vec![
+        row("a", 1, "infantry", false, ""),
+        row("b", 20, "cavalry", false, ""),
+        row("c", 1, "infantry", false, ""),
+        row("d", 1, "", false, ""),
+        row("e", 1, "", false, ""),
+    ];
+
+    // Unique is not checked per cell, and empty values can be repeated.
+    assert!(validator.validate_cell(&data[0], 2).is_empty());
+    assert_eq!(validator.validate_column(&data, 2), vec![
+        (0, ValidationError::NotUnique("infantry".to_owned())),
+        (2, ValidationError::NotUnique("infantry".to_owned())),
+    ]);
+
+    assert_eq!(validator.validate(&data), vec![
+        (0, 2, ValidationError::NotUnique("infantry".to_owned())),
+        (1, 1, ValidationError::OutOfRange("20".to_owned(), None, Some(10.0))),
+        (2, 2, ValidationError::NotUnique("infantry".to_owned())),
+    ]);
+
+    // Zeros and false are values, so they cannot be repeated.
+    let zero_patches = self::patches(&[("cost", "unique", "true"), ("is_naval", "unique", "true")]);
+    let validator = TableValidator::new(&definition(), Some(&zero_patches)).unwrap();
+    let data = vec![
+        row("a", 0, "", false, ""),
+        row("b", 0, "", true, ""),
+        row("c", 1, "", false, ""),
+    ];
+
+    assert_eq!(validator.validate(&data), vec![
+        (0, 1, ValidationError::NotUnique("0".to_owned())),
+        (0, 3, ValidationError::NotUnique("false".to_owned())),
+        (1, 1, ValidationError::NotUnique("0".to_owned())),
+        (2, 3, ValidationError::NotUnique("false".to_owned())),
+    ]);
+}
+
+#[test]
+fn test_validation_invalid_rules() {
+    let invalid_pattern = patches(&[("key", "pattern", "[a-z")]);
+    assert!(matches!(TableValidator::new(&definition(), Some(&invalid_pattern)), Err(RLibError::InvalidValidationRule(field, rule, _)) if field == "key" && rule == "pattern"));
+
+    let unknown_column = patches(&[("ship", "required_if_set", "is_flying")]);
+    assert!(matches!(TableValidator::new(&definition(), Some(&unknown_column)), Err(RLibError::InvalidValidationRule(field, rule, _)) if field == "ship" && rule == "required_if_set"));
+
+    assert!(TableValidator::new(&definition(), None).unwrap().is_empty());
+}
@@ -22,6 +22,8 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::error::{RLibError, Result};
 
+#[cfg(test)] mod utils_test;
+
 //--------------------------------------------------------//
 // Generic utils.
 //--------------------------------------------------------//
@@ -176,6 +178,30 @@ pub(crate) fn pe_resources(bytes: &[u8]) -> std::result::Result<Resources, pelit
     }
 }
 
+//--------------------------------------------------------//
+// Game version utils.
+//--------------------------------------------------------//
+
+/// This function turns a game version number into a human-readable `major.minor.patch.build` string.
+pub fn game_version_to_string(game_version: u32) -> String {
+    format!("{}.{}.{}.{}", game_version >> 24, (game_version >> 16) & 0xFF, (game_version >> 8) & 0xFF, game_version & 0xFF)
+}
+
+/// This function parses a game version number from either a `major.minor.patch.build` string, or the raw number.
+pub fn game_version_from_string(game_version: &str) -> Option<u32> {
+    let game_version = game_version.trim();
+    if !game_version.contains('.') {
+        return game_version.parse().ok();
+    }
+
+    let parts = game_version.split('.').map(|part| part.parse::<u8>().ok()).collect::<Option<Vec<_>>>()?;
+    if parts.len() != 4 {
+        return None;
+    }
+
+    Some(parts.iter().fold(0, |version, part| (version << 8) + *part as u32))
+}
+
 //--------------------------------------------------------//
 // VWise utils.
 //--------------------------------------------------------//
@@ -0,0 +1,35 @@
+//---------------------------------------------------------------------------//
+// Copyright (c) 2017-2023 Ismael Gutiérrez González. All rights reserved.
+//
+// This file is part of the Rusted PackFile Manager (RPFM) project,
+// which can be found here: https://github.com/Frodo45127/rpfm.
+//
+// This file is licensed under the MIT license, which can be found here:
+// https://github.com/Frodo45127/rpfm/blob/master/LICENSE.
+//---------------------------------------------------------------------------//
+
+//! Module containing tests for the generic utils.
+
+use super::*;
+
+#[test]
+fn test_game_version_from_string() {
+    assert_eq!(game_version_from_string("1.4.0.12"), Some(0x0104000C));
+    assert_eq!(game_version_from_string(" 255.255.255.255 "), Some(u32::MAX));
+    assert_eq!(game_version_from_string("16908300"), Some(0x0102000C));
+    assert_eq!(game_version_from_string("0"), Some(0));
+
+    // Each part of the dotted format must fit in a byte, and all four must be there.
+    assert_eq!(game_version_from_string("1.256.0.0"), None);
+    assert_eq!(game_version_from_string("1.4.0"), None);
+    assert_eq!(game_version_from_string("1.4.0.12.1"), None);
+    assert_eq!(game_version_from_string("1.4.a.12"), None);
+    assert_eq!(game_version_from_string("-1"), None);
+    assert_eq!(game_version_from_string(""), None);
+}
+
+#[test]
+fn test_game_version_to_string() {
+    assert_eq!(game_version_to_string(0x0104000C), "1.4.0.12");
+    assert_eq!(game_version_from_string(&game_version_to_string(0x01FF0203)), Some(0x01FF0203));
+}
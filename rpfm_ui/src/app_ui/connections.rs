@@ -36,6 +36,8 @@ pub unsafe fn set_connections(app_ui: &Rc<AppUI>, slots: &AppUISlots) {
     app_ui.packfile_save_packfile_as.triggered().connect(&slots.packfile_save_packfile_as);
     app_ui.packfile_install.triggered().connect(&slots.packfile_install);
     app_ui.packfile_uninstall.triggered().connect(&slots.packfile_uninstall);
+    app_ui.packfile_undo_edit.triggered().connect(&slots.packfile_undo_edit);
+    app_ui.packfile_redo_edit.triggered().connect(&slots.packfile_redo_edit);
     app_ui.packfile_load_all_ca_packfiles.triggered().connect(&slots.packfile_load_all_ca_packfiles);
 
     app_ui.change_packfile_type_boot.triggered().connect(&slots.packfile_change_packfile_type);
@@ -132,6 +134,7 @@ pub unsafe fn set_connections(app_ui: &Rc<AppUI>, slots: &AppUISlots) {
     //-----------------------------------------------//
     app_ui.tools_faction_painter.triggered().connect(&slots.tools_faction_painter);
     app_ui.tools_unit_editor.triggered().connect(&slots.tools_unit_editor);
+    app_ui.tools_mod_conflicts.triggered().connect(&slots.tools_mod_conflicts);
 
     //-----------------------------------------------//
     // `About` menu connections.
@@ -147,6 +150,7 @@ pub unsafe fn set_connections(app_ui: &Rc<AppUI>, slots: &AppUISlots) {
     //-----------------------------------------------//
     app_ui.debug_update_current_schema_from_asskit.triggered().connect(&slots.debug_update_current_schema_from_asskit);
     app_ui.debug_import_schema_patch.triggered().connect(&slots.debug_import_schema_patch);
+    app_ui.debug_upload_schema_overlay.triggered().connect(&slots.debug_upload_schema_overlay);
     app_ui.debug_reload_style_sheet.triggered().connect(&slots.debug_reload_style_sheet);
 
     //-----------------------------------------------//
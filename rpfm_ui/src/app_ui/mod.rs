@@ -158,6 +158,8 @@ pub struct AppUI {
     packfile_save_packfile_as: QPtr<QAction>,
     packfile_install: QPtr<QAction>,
     packfile_uninstall: QPtr<QAction>,
+    packfile_undo_edit: QPtr<QAction>,
+    packfile_redo_edit: QPtr<QAction>,
     packfile_open_recent: QBox<QMenu>,
     packfile_open_from_content: QBox<QMenu>,
     packfile_open_from_data: QBox<QMenu>,
@@ -298,6 +300,7 @@ pub struct AppUI {
     //-------------------------------------------------------------------------------//
     tools_faction_painter: QPtr<QAction>,
     tools_unit_editor: QPtr<QAction>,
+    tools_mod_conflicts: QPtr<QAction>,
 
     //-------------------------------------------------------------------------------//
     // `About` menu.
@@ -313,6 +316,7 @@ pub struct AppUI {
     //-------------------------------------------------------------------------------//
     debug_update_current_schema_from_asskit: QPtr<QAction>,
     debug_import_schema_patch: QPtr<QAction>,
+    debug_upload_schema_overlay: QPtr<QAction>,
     debug_reload_style_sheet: QPtr<QAction>,
 
     //-------------------------------------------------------------------------------//
@@ -469,6 +473,8 @@ impl AppUI {
         let packfile_save_packfile_as = add_action_to_menu(&menu_bar_packfile, shortcuts.as_ref(), "pack_menu", "save_pack_as", "save_packfile_as", Some(main_window.static_upcast::<qt_widgets::QWidget>()));
         let packfile_install = add_action_to_menu(&menu_bar_packfile, shortcuts.as_ref(), "pack_menu", "install_pack", "packfile_install", Some(main_window.static_upcast::<qt_widgets::QWidget>()));
         let packfile_uninstall = add_action_to_menu(&menu_bar_packfile, shortcuts.as_ref(), "pack_menu", "uninstall_pack", "packfile_uninstall", Some(main_window.static_upcast::<qt_widgets::QWidget>()));
+        let packfile_undo_edit = add_action_to_menu(&menu_bar_packfile, shortcuts.as_ref(), "pack_menu", "undo_pack_edit", "packfile_undo_edit", Some(main_window.static_upcast::<qt_widgets::QWidget>()));
+        let packfile_redo_edit = add_action_to_menu(&menu_bar_packfile, shortcuts.as_ref(), "pack_menu", "redo_pack_edit", "packfile_redo_edit", Some(main_window.static_upcast::<qt_widgets::QWidget>()));
 
         let packfile_open_recent = QMenu::from_q_string_q_widget(&qtr("open_recent"), &menu_bar_packfile);
         let packfile_open_from_content = QMenu::from_q_string_q_widget(&qtr("open_from_content"), &menu_bar_packfile);
@@ -716,6 +722,8 @@ impl AppUI {
             tools_unit_editor.set_enabled(false);
         }
 
+        let tools_mod_conflicts = menu_bar_tools.add_action_q_string(&qtr("tools_mod_conflicts"));
+
         //-----------------------------------------------//
         // `About` Menu.
         //-----------------------------------------------//
@@ -732,6 +740,7 @@ impl AppUI {
         // Populate the `Debug` menu.
         let debug_update_current_schema_from_asskit = menu_bar_debug.add_action_q_string(&qtr("update_current_schema_from_asskit"));
         let debug_import_schema_patch = menu_bar_debug.add_action_q_string(&qtr("import_schema_patch"));
+        let debug_upload_schema_overlay = menu_bar_debug.add_action_q_string(&qtr("upload_schema_overlay"));
         let debug_reload_style_sheet = menu_bar_debug.add_action_q_string(&qtr("reload_style_sheet"));
 
         //-------------------------------------------------------------------------------//
@@ -777,6 +786,8 @@ impl AppUI {
             packfile_save_packfile_as,
             packfile_install,
             packfile_uninstall,
+            packfile_undo_edit,
+            packfile_redo_edit,
             packfile_open_recent,
             packfile_open_from_content,
             packfile_open_from_data,
@@ -917,6 +928,7 @@ impl AppUI {
             //-------------------------------------------------------------------------------//
             tools_faction_painter,
             tools_unit_editor,
+            tools_mod_conflicts,
 
             //-------------------------------------------------------------------------------//
             // "About" menu.
@@ -932,6 +944,7 @@ impl AppUI {
             //-------------------------------------------------------------------------------//
             debug_update_current_schema_from_asskit,
             debug_import_schema_patch,
+            debug_upload_schema_overlay,
             debug_reload_style_sheet,
 
             //-------------------------------------------------------------------------------//
@@ -1049,6 +1062,60 @@ impl AppUI {
         Ok(())
     }
 
+    /// This function undoes or redoes the last edition recorded in the edit log of the open Pack, and updates the UI to reflect it.
+    pub unsafe fn undo_redo_pack_edit(
+        app_ui: &Rc<Self>,
+        pack_file_contents_ui: &Rc<PackFileContentsUI>,
+        undo: bool,
+    ) {
+
+        // Make sure the backend has the data of all open views before touching it.
+        if let Err(error) = Self::back_to_back_end_all(app_ui, pack_file_contents_ui) {
+            return show_dialog(&app_ui.main_window, error, false);
+        }
+
+        let command = if undo { Command::UndoPackEdit } else { Command::RedoPackEdit };
+        let receiver = CENTRAL_COMMAND.send_background(command);
+        let response = CentralCommand::recv(&receiver);
+        match response {
+            Response::VecContainerPathVecContainerPathVecContainerPath(added, modified, removed) => Self::update_views_after_pack_edit(app_ui, pack_file_contents_ui, added, modified, removed),
+            Response::Error(error) => show_dialog(&app_ui.main_window, error, false),
+            _ => panic!("{THREADS_COMMUNICATION_ERROR}{response:?}"),
+        }
+    }
+
+    /// This function updates the TreeView and the open views after an edition affecting multiple files of the Pack.
+    pub unsafe fn update_views_after_pack_edit(
+        app_ui: &Rc<Self>,
+        pack_file_contents_ui: &Rc<PackFileContentsUI>,
+        added: Vec<ContainerPath>,
+        modified: Vec<ContainerPath>,
+        removed: Vec<ContainerPath>,
+    ) {
+        for path in &removed {
+            let _ = Self::purge_that_one_specifically(app_ui, pack_file_contents_ui, path.path_raw(), DataSource::PackFile, false);
+        }
+
+        pack_file_contents_ui.packfile_contents_tree_view().update_treeview(true, TreeViewOperation::Delete(removed), DataSource::PackFile);
+        pack_file_contents_ui.packfile_contents_tree_view().update_treeview(true, TreeViewOperation::Add(added), DataSource::PackFile);
+        pack_file_contents_ui.packfile_contents_tree_view().update_treeview(true, TreeViewOperation::Modify(modified.to_vec()), DataSource::PackFile);
+
+        // Reload all the open views of the edited files, closing the ones that fail to reload.
+        let failed_paths = {
+            let mut open_packedfiles = UI_STATE.set_open_packedfiles();
+            modified.iter()
+                .filter(|path| match open_packedfiles.iter_mut().find(|x| *x.path_read() == path.path_raw() && x.data_source() == DataSource::PackFile) {
+                    Some(file_view) => file_view.reload(path.path_raw(), pack_file_contents_ui).is_err(),
+                    None => false,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        for path in failed_paths {
+            let _ = Self::purge_that_one_specifically(app_ui, pack_file_contents_ui, path.path_raw(), DataSource::PackFile, false);
+        }
+    }
+
     /// This function deletes all the widgets corresponding to opened PackedFiles.
     #[must_use = "If one of those mysterious save errors happen here and we don't use the result, we may be losing the new changes to a file."]
     pub unsafe fn purge_them_all(
@@ -1491,6 +1558,8 @@ impl AppUI {
             app_ui.packfile_new_packfile.set_enabled(true);
             app_ui.packfile_save_packfile.set_enabled(enable);
             app_ui.packfile_save_packfile_as.set_enabled(enable);
+            app_ui.packfile_undo_edit.set_enabled(enable);
+            app_ui.packfile_redo_edit.set_enabled(enable);
 
             // Ensure it's a file and it's not in data before proceeding.
             let enable_install = if !pack_path.is_file() { false }
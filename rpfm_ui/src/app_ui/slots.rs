@@ -97,6 +97,8 @@ pub struct AppUISlots {
     pub packfile_save_packfile_as: QBox<SlotOfBool>,
     pub packfile_install: QBox<SlotOfBool>,
     pub packfile_uninstall: QBox<SlotOfBool>,
+    pub packfile_undo_edit: QBox<SlotOfBool>,
+    pub packfile_redo_edit: QBox<SlotOfBool>,
     pub packfile_load_all_ca_packfiles: QBox<SlotOfBool>,
     pub packfile_change_packfile_type: QBox<SlotOfBool>,
     pub packfile_index_includes_timestamp: QBox<SlotOfBool>,
@@ -147,6 +149,7 @@ pub struct AppUISlots {
     //-----------------------------------------------//
     pub tools_faction_painter: QBox<SlotNoArgs>,
     pub tools_unit_editor: QBox<SlotNoArgs>,
+    pub tools_mod_conflicts: QBox<SlotNoArgs>,
 
     //-----------------------------------------------//
     // `About` menu slots.
@@ -162,6 +165,7 @@ pub struct AppUISlots {
     //-----------------------------------------------//
     pub debug_update_current_schema_from_asskit: QBox<SlotOfBool>,
     pub debug_import_schema_patch: QBox<SlotNoArgs>,
+    pub debug_upload_schema_overlay: QBox<SlotNoArgs>,
     pub debug_reload_style_sheet: QBox<SlotNoArgs>,
 
     //-----------------------------------------------//
@@ -433,6 +437,24 @@ impl AppUISlots {
             }
         ));
 
+        // What happens when we trigger the "Undo Pack Edit" action.
+        let packfile_undo_edit = SlotOfBool::new(&app_ui.main_window, clone!(
+            app_ui,
+            pack_file_contents_ui => move |_| {
+                info!("Triggering `Undo Pack Edit` By Slot");
+                AppUI::undo_redo_pack_edit(&app_ui, &pack_file_contents_ui, true);
+            }
+        ));
+
+        // What happens when we trigger the "Redo Pack Edit" action.
+        let packfile_redo_edit = SlotOfBool::new(&app_ui.main_window, clone!(
+            app_ui,
+            pack_file_contents_ui => move |_| {
+                info!("Triggering `Redo Pack Edit` By Slot");
+                AppUI::undo_redo_pack_edit(&app_ui, &pack_file_contents_ui, false);
+            }
+        ));
+
         // What happens when we trigger the "Load All CA PackFiles" action.
         let packfile_load_all_ca_packfiles = SlotOfBool::new(&app_ui.main_window, clone!(
             app_ui,
@@ -1227,6 +1249,56 @@ impl AppUISlots {
             show_dialog(&app_ui.main_window, TOOLS_NOT_ENABLED_ERROR, false);
         }));
 
+        // What happens when we trigger the "Mod Conflicts" action.
+        #[cfg(feature = "enable_tools")]let tools_mod_conflicts = SlotNoArgs::new(&app_ui.main_window, clone!(
+            app_ui => move || {
+                info!("Triggering `Mod Conflicts` By Slot");
+
+                let file_dialog = QFileDialog::from_q_widget_q_string(
+                    &app_ui.main_window,
+                    &qtr("mod_conflicts_select_packs"),
+                );
+                file_dialog.set_name_filter(&QString::from_std_str("PackFiles (*.pack)"));
+                file_dialog.set_file_mode(FileMode::ExistingFiles);
+
+                if file_dialog.exec() == 1 {
+                    let mut paths = vec![];
+                    for index in 0..file_dialog.selected_files().count_0a() {
+                        paths.push(PathBuf::from(file_dialog.selected_files().at(index).to_std_string()));
+                    }
+
+                    app_ui.toggle_main_window(false);
+
+                    let receiver = CENTRAL_COMMAND.send_background(Command::AnalyseModConflicts(paths));
+                    let response = CENTRAL_COMMAND.recv_try(&receiver);
+                    match response {
+                        Response::ConflictReport(report) => {
+                            let dialog = QDialog::new_1a(&app_ui.main_window);
+                            dialog.set_window_title(&qtr("mod_conflicts_title"));
+                            dialog.set_modal(true);
+
+                            let main_grid = create_grid_layout(dialog.static_upcast());
+                            let report_text_edit = QTextEdit::from_q_widget(&dialog);
+                            report_text_edit.set_read_only(true);
+                            report_text_edit.set_plain_text(&QString::from_std_str(report.to_string()));
+                            main_grid.add_widget_5a(&report_text_edit, 0, 0, 1, 1);
+
+                            dialog.resize_2a(1000, 600);
+                            dialog.exec();
+                        }
+                        Response::Error(error) => show_dialog(&app_ui.main_window, error, false),
+                        _ => panic!("{THREADS_COMMUNICATION_ERROR}{response:?}"),
+                    }
+
+                    app_ui.toggle_main_window(true);
+                }
+            }
+        ));
+
+        #[cfg(not(feature = "enable_tools"))]let tools_mod_conflicts = SlotNoArgs::new(&app_ui.main_window, clone!(app_ui => move || {
+            show_dialog(&app_ui.main_window, TOOLS_NOT_ENABLED_ERROR, false);
+        }));
+
 		//-----------------------------------------------//
         // `About` menu logic.
         //-----------------------------------------------//
@@ -1392,6 +1464,25 @@ impl AppUISlots {
             }
         ));
 
+        // What happens when we trigger the "Upload Schema Overlay" action.
+        let debug_upload_schema_overlay = SlotNoArgs::new(&app_ui.main_window, clone!(
+            app_ui => move || {
+                info!("Triggering `Upload Schema Overlay` By Slot");
+
+                app_ui.toggle_main_window(false);
+
+                let receiver = CENTRAL_COMMAND.send_background(Command::UploadSchemaOverlay);
+                let response = CENTRAL_COMMAND.recv_try(&receiver);
+                match response {
+                    Response::Success => show_dialog(&app_ui.main_window, tr("upload_schema_overlay_success"), true),
+                    Response::Error(error) => show_dialog(&app_ui.main_window, error, false),
+                    _ => panic!("{THREADS_COMMUNICATION_ERROR}{response:?}"),
+                }
+
+                app_ui.toggle_main_window(true);
+            }
+        ));
+
         let debug_reload_style_sheet = SlotNoArgs::new(&app_ui.main_window, clone!(
             app_ui => move || {
                 info!("Triggering `Reload StyleSheets` By Slot");
@@ -1736,6 +1827,8 @@ impl AppUISlots {
             packfile_save_packfile_as,
             packfile_install,
             packfile_uninstall,
+            packfile_undo_edit,
+            packfile_redo_edit,
             packfile_load_all_ca_packfiles,
             packfile_change_packfile_type,
             packfile_index_includes_timestamp,
@@ -1786,6 +1879,7 @@ impl AppUISlots {
             //-----------------------------------------------//
             tools_faction_painter,
             tools_unit_editor,
+            tools_mod_conflicts,
 
     		//-----------------------------------------------//
 	        // `About` menu slots.
@@ -1801,6 +1895,7 @@ impl AppUISlots {
             //-----------------------------------------------//
             debug_update_current_schema_from_asskit,
             debug_import_schema_patch,
+            debug_upload_schema_overlay,
             debug_reload_style_sheet,
 
             //-----------------------------------------------//
@@ -30,6 +30,8 @@ pub unsafe fn set_tips(app_ui: &Rc<AppUI>) {
     app_ui.packfile_save_packfile_as.set_status_tip(&qtr("tt_packfile_save_packfile_as"));
     app_ui.packfile_install.set_status_tip(&qtr("tt_packfile_install"));
     app_ui.packfile_uninstall.set_status_tip(&qtr("tt_packfile_uninstall"));
+    app_ui.packfile_undo_edit.set_status_tip(&qtr("tt_packfile_undo_edit"));
+    app_ui.packfile_redo_edit.set_status_tip(&qtr("tt_packfile_redo_edit"));
     app_ui.packfile_load_all_ca_packfiles.set_status_tip(&qtr("tt_packfile_load_all_ca_packfiles"));
     app_ui.packfile_settings.set_status_tip(&qtr("tt_packfile_settings"));
     app_ui.packfile_quit.set_status_tip(&qtr("tt_packfile_quit"));
@@ -21,7 +21,7 @@ use open::that;
 use rayon::prelude::*;
 use time::OffsetDateTime;
 
-use std::collections::{BTreeMap, HashMap, hash_map::DefaultHasher};
+use std::collections::{BTreeMap, BTreeSet, HashMap, hash_map::DefaultHasher};
 #[cfg(feature = "enable_tools")] use std::collections::HashSet;
 use std::env::temp_dir;
 use std::fs::{DirBuilder, File};
@@ -32,10 +32,14 @@ use std::sync::{Arc, atomic::Ordering, RwLock};
 use std::time::SystemTime;
 use std::thread;
 
+#[cfg(feature = "enable_tools")] use rpfm_extensions::conflicts::ConflictReport;
 use rpfm_extensions::dependencies::Dependencies;
 use rpfm_extensions::diagnostics::Diagnostics;
+#[cfg(feature = "enable_tools")] use rpfm_extensions::load_order::LoadOrder;
 use rpfm_extensions::optimizer::OptimizableContainer;
+use rpfm_extensions::search::SearchSource;
 
+use rpfm_lib::edit_log::{EditLog, EditOperation};
 use rpfm_lib::files::{animpack::AnimPack, Container, ContainerPath, db::DB, DecodeableExtraData, FileType, loc::Loc, pack::*, portrait_settings::PortraitSettings, RFile, RFileDecoded, text::*};
 use rpfm_lib::games::{GameInfo, LUA_REPO, LUA_BRANCH, LUA_REMOTE, pfh_file_type::PFHFileType};
 use rpfm_lib::integrations::{assembly_kit::*, git::*, log::*};
@@ -75,6 +79,9 @@ pub fn background_loop() {
     let mut pack_file_decoded = Pack::default();
     let mut pack_files_decoded_extra = BTreeMap::new();
 
+    // Log with the editions done to `pack_file_decoded` that affect multiple files, so they can be undone.
+    let mut edit_log = EditLog::default();
+
     // Preload the default game's dependencies.
     let mut dependencies = Arc::new(RwLock::new(Dependencies::default()));
 
@@ -99,7 +106,10 @@ pub fn background_loop() {
             Command::Exit => return,
 
             // In case we want to reset the PackFile to his original state (dummy)...
-            Command::ResetPackFile => pack_file_decoded = Pack::default(),
+            Command::ResetPackFile => {
+                pack_file_decoded = Pack::default();
+                edit_log.clear();
+            }
 
             // In case we want to remove a Secondary Packfile from memory...
             Command::RemovePackFileExtra(path) => { pack_files_decoded_extra.remove(&path); },
@@ -110,6 +120,7 @@ pub fn background_loop() {
                 let pack_version = game_selected.pfh_version_by_file_type(PFHFileType::Mod);
                 pack_file_decoded = Pack::new_with_name_and_version("unknown.pack", pack_version);
                 pack_file_decoded.set_settings(initialize_pack_settings());
+                edit_log.clear();
 
                 if let Some(version_number) = game_selected.game_version_number(&setting_path(game_selected.game_key_name())) {
                     pack_file_decoded.set_game_version(version_number);
@@ -121,11 +132,15 @@ pub fn background_loop() {
                 match Pack::read_and_merge(&paths, setting_bool("use_lazy_loading"), false) {
                     Ok(pack) => {
                         pack_file_decoded = pack;
+                        edit_log.clear();
 
                         // Force decoding of table/locs, so they're in memory for the diagnostics to work.
                         if let Some(ref schema) = *SCHEMA.read().unwrap() {
                             let mut decode_extra_data = DecodeableExtraData::default();
                             decode_extra_data.set_schema(Some(schema));
+                            decode_extra_data.set_table_index(true);
+                            let game_selected = GAME_SELECTED.read().unwrap();
+                            decode_extra_data.set_game_build(pack_file_decoded.game_build(&game_selected, &setting_path(game_selected.game_key_name())));
                             let extra_data = Some(decode_extra_data);
 
                             let mut files = pack_file_decoded.files_by_type_mut(&[FileType::DB, FileType::Loc]);
@@ -160,11 +175,14 @@ pub fn background_loop() {
                 match Pack::read_and_merge_ca_packs(&game_selected, &setting_path(game_selected.game_key_name())) {
                     Ok(pack) => {
                         pack_file_decoded = pack;
+                        edit_log.clear();
 
                         // Force decoding of table/locs, so they're in memory for the diagnostics to work.
                         if let Some(ref schema) = *SCHEMA.read().unwrap() {
                             let mut decode_extra_data = DecodeableExtraData::default();
                             decode_extra_data.set_schema(Some(schema));
+                            decode_extra_data.set_table_index(true);
+                            decode_extra_data.set_game_build(pack_file_decoded.game_build(&game_selected, &setting_path(game_selected.game_key_name())));
                             let extra_data = Some(decode_extra_data);
 
                             let mut files = pack_file_decoded.files_by_type_mut(&[FileType::DB, FileType::Loc]);
@@ -293,11 +311,14 @@ pub fn background_loop() {
                 if rebuild_dependencies {
                 info!("Branch 1.");
                     let pack_dependencies = pack_file_decoded.dependencies().to_vec();
+                    let pack_name = pack_file_decoded.disk_file_name();
+                    let pack_game_version = pack_file_decoded.game_version();
                     let handle = thread::spawn(move || {
                         let game_selected = GAME_SELECTED.read().unwrap();
                         let game_path = setting_path(game_selected.game_key_name());
-                        let file_path = dependencies_cache_path().unwrap().join(game_selected.dependencies_cache_file_name());
+                        let (_, file_path) = Dependencies::cache_for_game_version(&dependencies_cache_path().unwrap(), &game_selected, &game_path, pack_game_version);
                         let file_path = if game_changed { Some(&*file_path) } else { None };
+                        dependencies.write().unwrap().set_installed_mods(&installed_mods_sources(), &[pack_name]);
                         let _ = dependencies.write().unwrap().rebuild(&None, &pack_dependencies, file_path, &game_selected, &game_path);
                         dependencies
                     });
@@ -344,9 +365,13 @@ pub fn background_loop() {
                 if game_path.is_dir() {
                     match Dependencies::generate_dependencies_cache(&game_selected, &game_path, &asskit_path) {
                         Ok(mut cache) => {
-                            let dependencies_path = dependencies_cache_path().unwrap().join(game_selected.dependencies_cache_file_name());
+                            let dependencies_path = dependencies_cache_path().unwrap().join(game_selected.dependencies_cache_file_name_by_game_version(*cache.game_version()));
                             match cache.save(&dependencies_path) {
                                 Ok(_) => {
+
+                                    // Caches of the old split format are replaced by the one we just generated.
+                                    let _ = Dependencies::remove_legacy_caches(&dependencies_cache_path().unwrap(), &game_selected);
+                                    dependencies.write().unwrap().set_installed_mods(&installed_mods_sources(), &[pack_file_decoded.disk_file_name()]);
                                     let _ = dependencies.write().unwrap().rebuild(&SCHEMA.read().unwrap(), pack_file_decoded.dependencies(), Some(&dependencies_path), &game_selected, &game_path);
                                     let dependencies_info = DependenciesInfo::from(&*dependencies.read().unwrap());
                                     CentralCommand::send_back(&sender, Response::DependenciesInfo(dependencies_info));
@@ -410,7 +435,8 @@ pub fn background_loop() {
             // In case we want to optimize our PackFile...
             Command::OptimizePackFile => {
                 if let Some(ref schema) = *SCHEMA.read().unwrap() {
-                    match pack_file_decoded.optimize(&mut dependencies.write().unwrap(), schema, setting_bool("optimize_not_renamed_packedfiles")) {
+                    let optimize_not_renamed_packedfiles = setting_bool("optimize_not_renamed_packedfiles");
+                    match edit_log.track_changes(&mut pack_file_decoded, &[ContainerPath::Folder(String::new())], |pack| pack.optimize(&mut dependencies.write().unwrap(), schema, optimize_not_renamed_packedfiles)) {
                         Ok(paths_to_delete) => CentralCommand::send_back(&sender, Response::HashSetString(paths_to_delete)),
                         Err(error) => CentralCommand::send_back(&sender, Response::Error(From::from(error))),
                     }
@@ -421,7 +447,7 @@ pub fn background_loop() {
 
             // In case we want to Patch the SiegeAI of a PackFile...
             Command::PatchSiegeAI => {
-                match pack_file_decoded.patch_siege_ai() {
+                match edit_log.track_changes(&mut pack_file_decoded, &[ContainerPath::Folder(String::new())], |pack| pack.patch_siege_ai()) {
                     Ok(result) => CentralCommand::send_back(&sender, Response::StringVecContainerPath(result.0, result.1)),
                     Err(error) => CentralCommand::send_back(&sender, Response::Error(From::from(error)))
                 }
@@ -520,7 +546,7 @@ pub fn background_loop() {
                     },
                 };
                 let file = RFile::new_from_decoded(&decoded, 0, &path);
-                match pack_file_decoded.insert(file) {
+                match edit_log.track_changes(&mut pack_file_decoded, &[ContainerPath::File(path.to_owned())], |pack| pack.insert(file)) {
                     Ok(_) => CentralCommand::send_back(&sender, Response::Success),
                     Err(error) => CentralCommand::send_back(&sender, Response::Error(From::from(error))),
                 }
@@ -533,34 +559,39 @@ pub fn background_loop() {
 
                 let paths = source_paths.iter().zip(destination_paths.iter()).collect::<Vec<(&PathBuf, &ContainerPath)>>();
                 let schema = SCHEMA.read().unwrap();
-                for (source_path, destination_path) in paths {
+                let include_base_folder = setting_bool("include_base_folder_on_add_from_folder");
+                let _ = edit_log.track_changes(&mut pack_file_decoded, &destination_paths, |pack| {
+                    for (source_path, destination_path) in paths {
 
-                    // Skip ignored paths.
-                    if let Some(ref paths_to_ignore) = paths_to_ignore {
-                        if paths_to_ignore.iter().any(|x| source_path.starts_with(x)) {
-                            continue;
+                        // Skip ignored paths.
+                        if let Some(ref paths_to_ignore) = paths_to_ignore {
+                            if paths_to_ignore.iter().any(|x| source_path.starts_with(x)) {
+                                continue;
+                            }
                         }
-                    }
 
-                    match destination_path {
-                        ContainerPath::File(destination_path) => {
-                            match pack_file_decoded.insert_file(source_path, destination_path, &schema) {
-                                Ok(path) => if let Some(path) = path {
-                                    added_paths.push(path);
-                                },
-                                Err(error) => it_broke = Some(error),
-                            }
-                        },
+                        match destination_path {
+                            ContainerPath::File(destination_path) => {
+                                match pack.insert_file(source_path, destination_path, &schema) {
+                                    Ok(path) => if let Some(path) = path {
+                                        added_paths.push(path);
+                                    },
+                                    Err(error) => it_broke = Some(error),
+                                }
+                            },
 
-                        // TODO: See what should we do with the ignored paths.
-                        ContainerPath::Folder(destination_path) => {
-                            match pack_file_decoded.insert_folder(source_path, destination_path, &None, &schema, setting_bool("include_base_folder_on_add_from_folder")) {
-                                Ok(mut paths) => added_paths.append(&mut paths),
-                                Err(error) => it_broke = Some(error),
-                            }
-                        },
+                            // TODO: See what should we do with the ignored paths.
+                            ContainerPath::Folder(destination_path) => {
+                                match pack.insert_folder(source_path, destination_path, &None, &schema, include_base_folder) {
+                                    Ok(mut paths) => added_paths.append(&mut paths),
+                                    Err(error) => it_broke = Some(error),
+                                }
+                            },
+                        }
                     }
-                }
+
+                    Ok(())
+                });
 
                 if let Some(error) = it_broke {
                     CentralCommand::send_back(&sender, Response::VecContainerPath(added_paths.to_vec()));
@@ -574,6 +605,9 @@ pub fn background_loop() {
                 if let Some(ref schema) = *SCHEMA.read().unwrap() {
                     let mut decode_extra_data = DecodeableExtraData::default();
                     decode_extra_data.set_schema(Some(schema));
+                    decode_extra_data.set_table_index(true);
+                    let game_selected = GAME_SELECTED.read().unwrap();
+                    decode_extra_data.set_game_build(pack_file_decoded.game_build(&game_selected, &setting_path(game_selected.game_key_name())));
                     let extra_data = Some(decode_extra_data);
 
                     let mut files = pack_file_decoded.files_by_paths_mut(&added_paths, false);
@@ -590,9 +624,12 @@ pub fn background_loop() {
                     // Try to add the PackedFile to the main PackFile.
                     Some(pack) => {
                         let files = pack.files_by_paths(&paths, false);
-                        for file in files {
-                            let _ = pack_file_decoded.insert(file.clone());
-                        }
+                        let _ = edit_log.track_changes(&mut pack_file_decoded, &paths, |open_pack| {
+                            for file in files {
+                                let _ = open_pack.insert(file.clone());
+                            }
+                            Ok(())
+                        });
 
                         CentralCommand::send_back(&sender, Response::VecContainerPath(paths.to_vec()));
 
@@ -600,6 +637,9 @@ pub fn background_loop() {
                         if let Some(ref schema) = *SCHEMA.read().unwrap() {
                             let mut decode_extra_data = DecodeableExtraData::default();
                             decode_extra_data.set_schema(Some(schema));
+                            decode_extra_data.set_table_index(true);
+                            let game_selected = GAME_SELECTED.read().unwrap();
+                            decode_extra_data.set_game_build(pack_file_decoded.game_build(&game_selected, &setting_path(game_selected.game_key_name())));
                             let extra_data = Some(decode_extra_data);
 
                             let mut files = pack_file_decoded.files_by_type_mut(&[FileType::DB, FileType::Loc]);
@@ -614,6 +654,7 @@ pub fn background_loop() {
 
             // In case we want to move stuff from our PackFile to an Animpack...
             Command::AddPackedFilesFromPackFileToAnimpack(anim_pack_path, paths) => {
+                edit_log.invalidate(&[ContainerPath::File(anim_pack_path.to_owned())]);
                 let files = pack_file_decoded.files_by_paths(&paths, false).into_iter().cloned().collect::<Vec<RFile>>();
                 match pack_file_decoded.files_mut().get_mut(&anim_pack_path) {
                     Some(file) => {
@@ -681,16 +722,20 @@ pub fn background_loop() {
                 };
 
                 let paths = files.iter().map(|file| file.path_in_container()).collect::<Vec<_>>();
-                for mut file in files {
-                    let _ = file.guess_file_type();
-                    let _ = pack_file_decoded.insert(file);
-                }
+                let _ = edit_log.track_changes(&mut pack_file_decoded, &paths, |pack| {
+                    for mut file in files {
+                        let _ = file.guess_file_type();
+                        let _ = pack.insert(file);
+                    }
+                    Ok(())
+                });
 
                 CentralCommand::send_back(&sender, Response::VecContainerPath(paths));
             }
 
             // In case we want to delete files from an Animpack...
             Command::DeleteFromAnimpack((anim_pack_path, paths)) => {
+                edit_log.invalidate(&[ContainerPath::File(anim_pack_path.to_owned())]);
                 match pack_file_decoded.files_mut().get_mut(&anim_pack_path) {
                     Some(file) => {
 
@@ -873,6 +918,12 @@ pub fn background_loop() {
                     }
                 }
                 else if let Some(file) = pack_file_decoded.files_mut().get_mut(&path) {
+
+                    // Views are saved back before any pack-wide edition, so only invalidate the edit log if the view actually changed the file.
+                    if file.decoded().ok() != Some(&file_decoded) {
+                        edit_log.invalidate(&[ContainerPath::File(path.to_owned())]);
+                    }
+
                     if let Err(error) = file.set_decoded(file_decoded) {
                         CentralCommand::send_back(&sender, Response::Error(From::from(error)));
                     }
@@ -881,7 +932,10 @@ pub fn background_loop() {
             }
 
             // In case we want to delete PackedFiles from a PackFile...
-            Command::DeletePackedFiles(paths) => CentralCommand::send_back(&sender, Response::VecContainerPath(paths.iter().flat_map(|path| pack_file_decoded.remove(path)).collect())),
+            Command::DeletePackedFiles(paths) => {
+                let removed = edit_log.track_changes(&mut pack_file_decoded, &paths, |pack| Ok(paths.iter().flat_map(|path| pack.remove(path)).collect::<Vec<_>>())).unwrap_or_default();
+                CentralCommand::send_back(&sender, Response::VecContainerPath(removed));
+            }
 
             // In case we want to extract PackedFiles from a PackFile...
             Command::ExtractPackedFiles(container_paths, path, extract_tables_to_tsv) => {
@@ -949,7 +1003,8 @@ pub fn background_loop() {
 
             // In case we want to rename one or more files/folders...
             Command::RenamePackedFiles(renaming_data) => {
-                match pack_file_decoded.move_paths(&renaming_data) {
+                let paths = renaming_data.iter().flat_map(|(source, destination)| [source.clone(), destination.clone()]).collect::<Vec<_>>();
+                match edit_log.track_changes(&mut pack_file_decoded, &paths, |pack| pack.move_paths(&renaming_data)) {
                     Ok(data) => CentralCommand::send_back(&sender, Response::VecContainerPathContainerPath(data)),
                     Err(error) => CentralCommand::send_back(&sender, Response::Error(From::from(error))),
                 }
@@ -997,23 +1052,29 @@ pub fn background_loop() {
 
             // In case we want to merge DB or Loc Tables from a PackFile...
             Command::MergeFiles(paths, merged_path, delete_source_files) => {
-                let files_to_merge = pack_file_decoded.files_by_paths(&paths, false);
-                match RFile::merge(&files_to_merge, &merged_path) {
-                    Ok(file) => {
-                        let _ = pack_file_decoded.insert(file);
+                let mut tracked_paths = paths.to_vec();
+                tracked_paths.push(ContainerPath::File(merged_path.to_owned()));
 
-                        if delete_source_files {
-                            paths.iter().for_each(|path| { pack_file_decoded.remove(path); });
-                        }
+                let result = edit_log.track_changes(&mut pack_file_decoded, &tracked_paths, |pack| {
+                    let file = RFile::merge(&pack.files_by_paths(&paths, false), &merged_path)?;
+                    let _ = pack.insert(file);
 
-                        CentralCommand::send_back(&sender, Response::String(merged_path.to_string()));
-                    },
+                    if delete_source_files {
+                        paths.iter().for_each(|path| { pack.remove(path); });
+                    }
+
+                    Ok(())
+                });
+
+                match result {
+                    Ok(_) => CentralCommand::send_back(&sender, Response::String(merged_path.to_string())),
                     Err(error) => CentralCommand::send_back(&sender, Response::Error(From::from(error))),
                 }
             }
 
             // In case we want to update a table...
             Command::UpdateTable(path) => {
+                edit_log.invalidate(&[path.clone()]);
                 let path = path.path_raw();
                 if let Some(rfile) = pack_file_decoded.file_mut(path) {
                     if let Ok(decoded) = rfile.decoded_mut() {
@@ -1029,7 +1090,7 @@ pub fn background_loop() {
             Command::GlobalSearchReplaceMatches(mut global_search, matches) => {
                 let game_info = GAME_SELECTED.read().unwrap();
                 if let Some(ref schema) = *SCHEMA.read().unwrap() {
-                    let paths = global_search.replace(&game_info, schema, &mut pack_file_decoded, &mut dependencies.write().unwrap(), &matches);
+                    let paths = edit_log.track_changes(&mut pack_file_decoded, &[ContainerPath::Folder(String::new())], |pack| Ok(global_search.replace(&game_info, schema, pack, &mut dependencies.write().unwrap(), &matches))).unwrap_or_default();
                     let files_info = paths.iter().flat_map(|path| pack_file_decoded.files_by_path(path, false).iter().map(|file| RFileInfo::from(*file)).collect::<Vec<RFileInfo>>()).collect();
 
                     CentralCommand::send_back(&sender, Response::GlobalSearchVecRFileInfo(global_search, files_info));
@@ -1042,7 +1103,7 @@ pub fn background_loop() {
             Command::GlobalSearchReplaceAll(mut global_search) => {
                 let game_info = GAME_SELECTED.read().unwrap();
                 if let Some(ref schema) = *SCHEMA.read().unwrap() {
-                    let paths = global_search.replace_all(&game_info, schema, &mut pack_file_decoded, &mut dependencies.write().unwrap());
+                    let paths = edit_log.track_changes(&mut pack_file_decoded, &[ContainerPath::Folder(String::new())], |pack| Ok(global_search.replace_all(&game_info, schema, pack, &mut dependencies.write().unwrap()))).unwrap_or_default();
                     let files_info = paths.iter().flat_map(|path| pack_file_decoded.files_by_path(path, false).iter().map(|file| RFileInfo::from(*file)).collect::<Vec<RFileInfo>>()).collect();
 
                     CentralCommand::send_back(&sender, Response::GlobalSearchVecRFileInfo(global_search, files_info));
@@ -1062,6 +1123,7 @@ pub fn background_loop() {
 
             // In case we want to change the format of a ca_vp8 video...
             Command::SetVideoFormat(path, format) => {
+                edit_log.invalidate(&[ContainerPath::File(path.to_owned())]);
                 match pack_file_decoded.files_mut().get_mut(&path) {
                     Some(ref mut rfile) => {
                         match rfile.decoded_mut() {
@@ -1078,13 +1140,22 @@ pub fn background_loop() {
                 }
             },
 
-            // In case we want to save an schema to disk...
-            Command::SaveSchema(mut schema) => {
-                match schema.save(&schemas_path().unwrap().join(GAME_SELECTED.read().unwrap().schema_file_name())) {
-                    Ok(_) => {
-                        *SCHEMA.write().unwrap() = Some(schema);
-                        CentralCommand::send_back(&sender, Response::Success);
-                    },
+            // In case we want to save a definition to the local overlay of the schema...
+            Command::SaveLocalDefinition(table_name, definition) => {
+                let overlay_path = schema_overlays_path().unwrap().join(GAME_SELECTED.read().unwrap().schema_file_name());
+                match SchemaOverlay::load(&overlay_path) {
+                    Ok(mut overlay) => {
+                        overlay.add_definition(&table_name, &definition);
+                        match overlay.save(&overlay_path) {
+                            Ok(_) => {
+                                if let Some(ref mut schema) = *SCHEMA.write().unwrap() {
+                                    schema.add_definition(&table_name, &definition);
+                                }
+                                CentralCommand::send_back(&sender, Response::Success);
+                            },
+                            Err(error) => CentralCommand::send_back(&sender, Response::Error(From::from(error))),
+                        }
+                    }
                     Err(error) => CentralCommand::send_back(&sender, Response::Error(From::from(error))),
                 }
             }
@@ -1126,6 +1197,7 @@ pub fn background_loop() {
                             Some(file) => {
                                 match RFile::tsv_import_from_path(&external_path, schema) {
                                     Ok(imported) => {
+                                        edit_log.invalidate(&[ContainerPath::File(internal_path.to_owned())]);
                                         let decoded = imported.decoded().unwrap();
                                         file.set_decoded(decoded.clone()).unwrap();
                                         CentralCommand::send_back(&sender, Response::RFileDecoded(decoded.clone()))
@@ -1176,6 +1248,7 @@ pub fn background_loop() {
 
             // When we want to save a PackedFile from the external view....
             Command::SavePackedFileFromExternalView(path, external_path) => {
+                edit_log.invalidate(&[ContainerPath::File(path.to_owned())]);
                 match save_files_from_external_path(&mut pack_file_decoded, &path, &external_path) {
                     Ok(_) => CentralCommand::send_back(&sender, Response::Success),
                     Err(error) => CentralCommand::send_back(&sender, Response::Error(From::from(error))),
@@ -1191,6 +1264,7 @@ pub fn background_loop() {
                             Ok(_) => {
                                 let game = GAME_SELECTED.read().unwrap();
                                 let schema_path = schemas_path().unwrap().join(game.schema_file_name());
+                                let overlay_path = schema_overlays_path().unwrap().join(game.schema_file_name());
 
                                 // Encode the decoded tables with the old schema, then re-decode them with the new one.
                                 let mut tables = pack_file_decoded.files_by_type_mut(&[FileType::DB]);
@@ -1198,7 +1272,14 @@ pub fn background_loop() {
 
                                 tables.par_iter_mut().for_each(|x| { let _ = x.encode(&extra_data, true, true, false); });
 
-                                *SCHEMA.write().unwrap() = Schema::load(&schema_path).ok();
+                                // Drop from the local overlay everything the updated schema already covers.
+                                if let (Ok(schema), Ok(mut overlay)) = (Schema::load(&schema_path), SchemaOverlay::load(&overlay_path)) {
+                                    if overlay.prune(&schema) > 0 {
+                                        let _ = overlay.save(&overlay_path);
+                                    }
+                                }
+
+                                *SCHEMA.write().unwrap() = Schema::load_with_overlay(&schema_path, &overlay_path).ok();
 
                                 if let Some(ref schema) = *SCHEMA.read().unwrap() {
                                     let mut extra_data = DecodeableExtraData::default();
@@ -1212,8 +1293,9 @@ pub fn background_loop() {
                                     // Then rebuild the dependencies stuff.
                                     if dependencies.read().unwrap().is_vanilla_data_loaded(false) {
                                         let game_path = setting_path(game.game_key_name());
-                                        let dependencies_file_path = dependencies_cache_path().unwrap().join(game.dependencies_cache_file_name());
+                                        let (_, dependencies_file_path) = Dependencies::cache_for_game_version(&dependencies_cache_path().unwrap(), &game, &game_path, pack_file_decoded.game_version());
 
+                                        dependencies.write().unwrap().set_installed_mods(&installed_mods_sources(), &[pack_file_decoded.disk_file_name()]);
                                         match dependencies.write().unwrap().rebuild(&SCHEMA.read().unwrap(), pack_file_decoded.dependencies(), Some(&*dependencies_file_path), &game, &game_path) {
                                             Ok(_) => CentralCommand::send_back(&sender, Response::Success),
                                             Err(_) => CentralCommand::send_back(&sender, Response::Error(anyhow!("Schema updated, but dependencies cache rebuilding failed. You may need to regenerate it."))),
@@ -1385,9 +1467,12 @@ pub fn background_loop() {
                 if SCHEMA.read().unwrap().is_some() {
                     let game_selected = GAME_SELECTED.read().unwrap();
                     let game_path = setting_path(game_selected.game_key_name());
-                    let dependencies_file_path = dependencies_cache_path().unwrap().join(game_selected.dependencies_cache_file_name());
-                    let file_path = if !rebuild_only_current_mod_dependencies { Some(&*dependencies_file_path) } else { None };
+                    // Reload the vanilla data too if the Pack is for a game version other than the one of the loaded cache, so it's checked against the right one.
+                    let (game_version, dependencies_file_path) = Dependencies::cache_for_game_version(&dependencies_cache_path().unwrap(), &game_selected, &game_path, pack_file_decoded.game_version());
+                    let game_version_changed = game_version != *dependencies.read().unwrap().game_version() && dependencies_file_path.is_file();
+                    let file_path = if !rebuild_only_current_mod_dependencies || game_version_changed { Some(&*dependencies_file_path) } else { None };
 
+                    dependencies.write().unwrap().set_installed_mods(&installed_mods_sources(), &[pack_file_decoded.disk_file_name()]);
                     let _ = dependencies.write().unwrap().rebuild(&SCHEMA.read().unwrap(), pack_file_decoded.dependencies(), file_path, &game_selected, &game_path);
                     let dependencies_info = DependenciesInfo::from(&*dependencies.read().unwrap());
                     CentralCommand::send_back(&sender, Response::DependenciesInfo(dependencies_info));
@@ -1396,13 +1481,56 @@ pub fn background_loop() {
                 }
             },
 
-            Command::CascadeEdition(table_name, definition, changes) => {
-                let edited_paths = changes.iter().flat_map(|(field, value_before, value_after)| {
-                    DB::cascade_edition(&mut pack_file_decoded, &SCHEMA.read().unwrap(), &table_name, field, &definition, value_before, value_after)
-                }).collect::<Vec<_>>();
+            Command::CascadeEditionPreview(table_name, definition, changes) => {
+                let schema = SCHEMA.read().unwrap();
+                let previews = changes.iter()
+                    .map(|(field, value_before, value_after)| DB::cascade_edition_preview(&pack_file_decoded, &schema, &table_name, field, &definition, value_before, value_after))
+                    .filter(|preview| preview.as_ref().map(|preview| !preview.is_empty()).unwrap_or(true))
+                    .collect::<Result<Vec<_>, _>>();
+
+                // Files outside the Pack cannot be edited, so we report the ones using the old values for the user to review them.
+                let mut external_references = BTreeSet::new();
+                if let Some(schema) = &*schema {
+                    let dependencies = dependencies.read().unwrap();
+                    for (field, value_before, _) in &changes {
+                        let key_references = dependencies.references_to_key(&pack_file_decoded, schema, &table_name, field.name(), value_before);
+                        external_references.extend(key_references.referencing_rows().iter().map(|row| (row.source(), row.path()))
+                            .chain(key_references.loc_rows().iter().map(|row| (row.source(), row.path())))
+                            .chain(key_references.text_files().iter().map(|text| (text.source(), text.path())))
+                            .filter(|(source, _)| **source != SearchSource::Pack)
+                            .map(|(source, path)| (DataSource::from(source), path.to_owned())));
+                    }
+                }
 
-                let packed_files_info = pack_file_decoded.files_by_paths(&edited_paths, false).into_par_iter().map(From::from).collect();
-                CentralCommand::send_back(&sender, Response::VecContainerPathVecRFileInfo(edited_paths, packed_files_info));
+                match previews {
+                    Ok(previews) => CentralCommand::send_back(&sender, Response::VecCascadeEditionVecDataSourceString(previews, external_references.into_iter().collect())),
+                    Err(error) => CentralCommand::send_back(&sender, Response::Error(From::from(error))),
+                }
+            }
+
+            Command::CascadeEdition(editions) => {
+                let operation = EditOperation::Batch(editions.into_iter().map(|edition| edition.into_operation()).collect());
+                let paths = operation.paths();
+                match edited_paths(&mut pack_file_decoded, paths, |pack| Ok(edit_log.apply(pack, operation)?)) {
+                    Ok((added, modified, removed)) => CentralCommand::send_back(&sender, Response::VecContainerPathVecContainerPathVecContainerPath(added, modified, removed)),
+                    Err(error) => CentralCommand::send_back(&sender, Response::Error(error)),
+                }
+            }
+
+            Command::UndoPackEdit => {
+                let paths = edit_log.undo_stack().last().map(|operation| operation.paths()).unwrap_or_default();
+                match edited_paths(&mut pack_file_decoded, paths, |pack| Ok(edit_log.undo(pack).map(|_| ())?)) {
+                    Ok((added, modified, removed)) => CentralCommand::send_back(&sender, Response::VecContainerPathVecContainerPathVecContainerPath(added, modified, removed)),
+                    Err(error) => CentralCommand::send_back(&sender, Response::Error(error)),
+                }
+            }
+
+            Command::RedoPackEdit => {
+                let paths = edit_log.redo_stack().last().map(|operation| operation.paths()).unwrap_or_default();
+                match edited_paths(&mut pack_file_decoded, paths, |pack| Ok(edit_log.redo(pack).map(|_| ())?)) {
+                    Ok((added, modified, removed)) => CentralCommand::send_back(&sender, Response::VecContainerPathVecContainerPathVecContainerPath(added, modified, removed)),
+                    Err(error) => CentralCommand::send_back(&sender, Response::Error(error)),
+                }
             }
 
             Command::GoToDefinition(ref_table, ref_column, ref_data) => {
@@ -1463,70 +1591,26 @@ pub fn background_loop() {
                 }
             },
 
-            Command::SearchReferences(reference_map, value) => {
-                let paths = reference_map.keys().map(|x| ContainerPath::Folder(format!("db/{x}"))).collect::<Vec<ContainerPath>>();
-                let files = pack_file_decoded.files_by_paths(&paths, true);
-
-                let mut references: Vec<(DataSource, String, String, usize, usize)> = vec![];
-
-                // Pass for local tables.
-                for (table_name, columns) in &reference_map {
-                    for file in &files {
-                        if file.db_table_name_from_path().unwrap() == table_name {
-                            if let Ok(RFileDecoded::DB(data)) = file.decoded() {
-                                for column_name in columns {
-                                    if let Some((column_index, row_indexes)) = data.table().rows_containing_data(column_name, &value) {
-                                        for row_index in &row_indexes {
-                                            references.push((DataSource::PackFile, file.path_in_container_raw().to_owned(), column_name.to_owned(), column_index, *row_index));
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+            Command::SearchReferences(table_name, column_name, value) => {
+                match &*SCHEMA.read().unwrap() {
+                    Some(schema) => {
+                        let key_references = dependencies.read().unwrap().references_to_key(&pack_file_decoded, schema, &table_name, &column_name, &value);
 
-                // Pass for parent tables.
-                for (table_name, columns) in &reference_map {
-                        if let Ok(tables) = dependencies.read().unwrap().db_data(table_name, false, true) {
-                        references.append(&mut tables.par_iter().map(|table| {
-                            let mut references = vec![];
-                            if let Ok(RFileDecoded::DB(data)) = table.decoded() {
-                                for column_name in columns {
-                                    if let Some((column_index, row_indexes)) = data.table().rows_containing_data(column_name, &value) {
-                                        for row_index in &row_indexes {
-                                            references.push((DataSource::ParentFiles, table.path_in_container_raw().to_owned(), column_name.to_owned(), column_index, *row_index));
-                                        }
-                                    }
-                                }
-                            }
+                        let mut references = key_references.referencing_rows().iter()
+                            .map(|row| (DataSource::from(row.source()), row.path().to_owned(), row.column_name().to_owned(), *row.column_index(), *row.row_index()))
+                            .collect::<Vec<(DataSource, String, String, usize, usize)>>();
 
-                            references
-                        }).flatten().collect());
-                    }
-                }
+                        references.extend(key_references.loc_rows().iter()
+                            .map(|row| (DataSource::from(row.source()), row.path().to_owned(), "key".to_owned(), 0, *row.row_index())));
 
-                // Pass for vanilla tables.
-                for (table_name, columns) in &reference_map {
-                    if let Ok(tables) = dependencies.read().unwrap().db_data(table_name, true, false) {
-                        references.append(&mut tables.par_iter().map(|table| {
-                            let mut references = vec![];
-                            if let Ok(RFileDecoded::DB(data)) = table.decoded() {
-                                for column_name in columns {
-                                    if let Some((column_index, row_indexes)) = data.table().rows_containing_data(column_name, &value) {
-                                        for row_index in &row_indexes {
-                                            references.push((DataSource::GameFiles, table.path_in_container_raw().to_owned(), column_name.to_owned(), column_index, *row_index));
-                                        }
-                                    }
-                                }
-                            }
+                        // Text files have no columns, so we report their lines as rows.
+                        references.extend(key_references.text_files().iter()
+                            .flat_map(|text| text.lines().iter().map(|line| (DataSource::from(text.source()), text.path().to_owned(), String::new(), 0, *line))));
 
-                            references
-                        }).flatten().collect());
+                        CentralCommand::send_back(&sender, Response::VecDataSourceStringStringUsizeUsize(references));
                     }
+                    None => CentralCommand::send_back(&sender, Response::Error(anyhow!("There is no Schema for the Game Selected."))),
                 }
-
-                CentralCommand::send_back(&sender, Response::VecDataSourceStringStringUsizeUsize(references));
             },
 
             Command::GoToLoc(loc_key) => {
@@ -1606,26 +1690,31 @@ pub fn background_loop() {
                 let mut added_paths = vec![];
 
                 let dependencies = dependencies.read().unwrap();
+                let mut files = vec![];
                 for (data_source, paths) in &paths_by_data_source {
-                    let files = match data_source {
-                        DataSource::GameFiles => dependencies.files_by_path(paths, true, false, false),
-                        DataSource::ParentFiles => dependencies.files_by_path(paths, false, true, false),
+                    match data_source {
+                        DataSource::GameFiles => files.extend(dependencies.files_by_path(paths, true, false, false).into_values()),
+                        DataSource::ParentFiles => files.extend(dependencies.files_by_path(paths, false, true, false).into_values()),
 
                         _ => {
                             CentralCommand::send_back(&sender, Response::Error(anyhow!("You can't import files from this source.")));
                             CentralCommand::send_back(&sender, Response::Success);
                             continue 'background_loop;
                         },
-                    };
+                    }
+                }
 
-                    for file in files.into_values() {
+                let paths = files.iter().map(|file| file.path_in_container()).collect::<Vec<_>>();
+                let _ = edit_log.track_changes(&mut pack_file_decoded, &paths, |pack| {
+                    for file in files {
                         let mut file = file.clone();
                         let _ = file.guess_file_type();
-                        if let Ok(Some(path)) = pack_file_decoded.insert(file) {
+                        if let Ok(Some(path)) = pack.insert(file) {
                             added_paths.push(path);
                         }
                     }
-                }
+                    Ok(())
+                });
 
                 CentralCommand::send_back(&sender, Response::VecContainerPath(added_paths));
                 CentralCommand::send_back(&sender, Response::Success);
@@ -1739,9 +1828,20 @@ pub fn background_loop() {
             Command::ImportSchemaPatch(patch) => {
                 match *SCHEMA.write().unwrap() {
                     Some(ref mut schema) => {
-                        schema.add_patch(patch);
-                        match schema.save(&schemas_path().unwrap().join(GAME_SELECTED.read().unwrap().schema_file_name())) {
-                            Ok(_) => CentralCommand::send_back(&sender, Response::Success),
+                        let overlay_path = schema_overlays_path().unwrap().join(GAME_SELECTED.read().unwrap().schema_file_name());
+                        match SchemaOverlay::load(&overlay_path) {
+                            Ok(mut overlay) => {
+                                overlay.add_patch(patch.clone());
+                                match overlay.save(&overlay_path) {
+                                    Ok(_) => {
+                                        let mut patch_overlay = SchemaOverlay::default();
+                                        patch_overlay.add_patch(patch);
+                                        schema.apply_overlay(&patch_overlay);
+                                        CentralCommand::send_back(&sender, Response::Success)
+                                    },
+                                    Err(error) => CentralCommand::send_back(&sender, Response::Error(From::from(error))),
+                                }
+                            }
                             Err(error) => CentralCommand::send_back(&sender, Response::Error(From::from(error))),
                         }
                     }
@@ -1749,13 +1849,71 @@ pub fn background_loop() {
                 }
             }
 
+            Command::UploadSchemaOverlay => {
+                let game = GAME_SELECTED.read().unwrap();
+                let overlay_path = schema_overlays_path().unwrap().join(game.schema_file_name());
+                match SchemaOverlay::load(&overlay_path) {
+                    Ok(overlay) => {
+                        if overlay.is_empty() {
+                            CentralCommand::send_back(&sender, Response::Error(anyhow!("There are no local definitions or patches to upload.")));
+                        } else {
+                            match overlay.upload(&SENTRY_GUARD.read().unwrap(), game.display_name()) {
+                                Ok(_) => CentralCommand::send_back(&sender, Response::Success),
+                                Err(error) => CentralCommand::send_back(&sender, Response::Error(From::from(error))),
+                            }
+                        }
+                    }
+                    Err(error) => CentralCommand::send_back(&sender, Response::Error(From::from(error))),
+                }
+            }
+
             Command::GenerateMissingLocData => {
-                match pack_file_decoded.generate_missing_loc_data() {
+                match edit_log.track_changes(&mut pack_file_decoded, &[ContainerPath::File("text/missing_locs.loc".to_owned())], |pack| pack.generate_missing_loc_data()) {
                     Ok(path) => CentralCommand::send_back(&sender, Response::OptionContainerPath(path)),
                     Err(error) => CentralCommand::send_back(&sender, Response::Error(From::from(error))),
                 }
             }
 
+            Command::GetAssemblyKitTableNames => {
+                let raw_db_version = GAME_SELECTED.read().unwrap().raw_db_version();
+                match assembly_kit_path() {
+                    Ok(raw_db_path) => match raw_table_names(&raw_db_path, raw_db_version) {
+                        Ok(table_names) => CentralCommand::send_back(&sender, Response::VecString(table_names)),
+                        Err(error) => CentralCommand::send_back(&sender, Response::Error(From::from(error))),
+                    },
+                    Err(error) => CentralCommand::send_back(&sender, Response::Error(error)),
+                }
+            }
+
+            Command::ImportAssemblyKitTables(table_names, file_name) => {
+                let raw_db_version = GAME_SELECTED.read().unwrap().raw_db_version();
+                if let Some(ref schema) = *SCHEMA.read().unwrap() {
+                    match assembly_kit_path() {
+                        Ok(raw_db_path) => match edit_log.track_changes(&mut pack_file_decoded, &[ContainerPath::Folder("db/".to_owned())], |pack| import_raw_tables(pack, schema, &raw_db_path, raw_db_version, &table_names, &file_name)) {
+                            Ok(report) => {
+                                let problems = if report.has_problems() { report.to_string() } else { String::new() };
+                                CentralCommand::send_back(&sender, Response::StringVecContainerPath(problems, report.paths().to_vec()))
+                            },
+                            Err(error) => CentralCommand::send_back(&sender, Response::Error(From::from(error))),
+                        },
+                        Err(error) => CentralCommand::send_back(&sender, Response::Error(error)),
+                    }
+                } else {
+                    CentralCommand::send_back(&sender, Response::Error(anyhow!("Schema not found. Maybe you need to download it?")));
+                }
+            }
+
+            #[cfg(feature = "enable_tools")] Command::AnalyseModConflicts(paths) => {
+                if let Some(ref schema) = *SCHEMA.read().unwrap() {
+                    match LoadOrder::from_paths(&paths, None).and_then(|load_order| ConflictReport::from_load_order(&load_order, schema)) {
+                        Ok(report) => CentralCommand::send_back(&sender, Response::ConflictReport(report)),
+                        Err(error) => CentralCommand::send_back(&sender, Response::Error(From::from(error))),
+                    }
+                } else {
+                    CentralCommand::send_back(&sender, Response::Error(anyhow!("Schema not found. Maybe you need to download it?")));
+                }
+            }
+
             // Initialize the folder for a MyMod, including the folder structure it needs.
             Command::InitializeMyModFolder(mod_name, mod_game, sublime_support, vscode_support, git_support)  => {
                 let mut mymod_path = setting_path(MYMOD_BASE_PATH);
@@ -1955,8 +2113,9 @@ fn load_schemas(sender: &Sender<Response>, pack: &mut Pack, game: &GameInfo) {
 
     // Load the new schema.
     let schema_path = schemas_path().unwrap().join(game.schema_file_name());
+    let overlay_path = schema_overlays_path().unwrap().join(game.schema_file_name());
     let _ = Schema::update(&schema_path, &PathBuf::from("schemas/patches.ron"), game.game_key_name());         // Quick fix so we can load old schemas. To be removed once 4.0 lands.
-    *SCHEMA.write().unwrap() = Schema::load(&schema_path).ok();
+    *SCHEMA.write().unwrap() = Schema::load_with_overlay(&schema_path, &overlay_path).ok();
 
     // Redecode all the tables in the open file.
     if let Some(ref schema) = *SCHEMA.read().unwrap() {
@@ -2020,3 +2179,27 @@ fn save_files_from_external_path(pack: &mut Pack, internal_path: &str, external_
         None => Err(anyhow!("Failed to find file with path in pack: {}", internal_path)),
     }
 }
+
+/// This function performs an edition over the provided paths of the Pack.
+///
+/// It returns the paths of the files added, modified and removed by it.
+fn edited_paths<F>(pack: &mut Pack, paths: Vec<String>, edition: F) -> Result<(Vec<ContainerPath>, Vec<ContainerPath>, Vec<ContainerPath>)>
+    where F: FnOnce(&mut Pack) -> Result<()> {
+    let existed = paths.iter().map(|path| pack.has_file(path)).collect::<Vec<_>>();
+
+    edition(pack)?;
+
+    let mut added = vec![];
+    let mut modified = vec![];
+    let mut removed = vec![];
+    for (path, existed) in paths.into_iter().zip(existed) {
+        match (existed, pack.has_file(&path)) {
+            (false, true) => added.push(ContainerPath::File(path)),
+            (true, true) => modified.push(ContainerPath::File(path)),
+            (true, false) => removed.push(ContainerPath::File(path)),
+            (false, false) => {},
+        }
+    }
+
+    Ok((added, modified, removed))
+}
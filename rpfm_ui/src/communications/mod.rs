@@ -22,16 +22,17 @@ use std::fmt::Debug;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 
+#[cfg(feature = "enable_tools")] use rpfm_extensions::conflicts::ConflictReport;
 use rpfm_extensions::dependencies::TableReferences;
 use rpfm_extensions::diagnostics::Diagnostics;
 use rpfm_extensions::search::{GlobalSearch, MatchHolder};
 
-use rpfm_lib::files::{anim_fragment::AnimFragment, anims_table::AnimsTable, audio::Audio, ContainerPath, video::SupportedFormats, db::DB, esf::ESF, image::Image, loc::Loc, matched_combat::MatchedCombat, pack::PackSettings, RFile, RFileDecoded, portrait_settings::PortraitSettings, text::Text, uic::UIC};
+use rpfm_lib::files::{anim_fragment::AnimFragment, anims_table::AnimsTable, audio::Audio, ContainerPath, video::SupportedFormats, db::{CascadeEdition, DB}, esf::ESF, image::Image, loc::Loc, matched_combat::MatchedCombat, pack::PackSettings, RFile, RFileDecoded, portrait_settings::PortraitSettings, text::Text, uic::UIC};
 #[cfg(feature = "support_rigidmodel")] use rpfm_lib::files::rigidmodel::RigidModel;
 use rpfm_lib::games::pfh_file_type::PFHFileType;
 use rpfm_lib::integrations::{git::GitResponse, log::info};
 use rpfm_lib::notes::Note;
-use rpfm_lib::schema::{Definition, DefinitionPatch, Field, Schema};
+use rpfm_lib::schema::{Definition, DefinitionPatch, Field};
 
 use crate::app_ui::NewFile;
 use crate::backend::*;
@@ -239,8 +240,8 @@ pub enum Command {
     // This command is used to change the format of a ca_vp8 video packedfile. Requires the path of the PackedFile and the new format.
     SetVideoFormat(String, SupportedFormats),
 
-    // This command is used to save the provided schema to disk.
-    SaveSchema(Schema),
+    /// This command is used to save a definition to the local overlay of the schema, and to add it to the schema in memory.
+    SaveLocalDefinition(String, Definition),
 
     /// This command is used to save to encoded data the cache of the provided paths, and then clean up the cache.
     CleanCache(Vec<ContainerPath>),
@@ -284,8 +285,17 @@ pub enum Command {
     /// This command is used to rebuild the dependencies of a PackFile. The bool is for rebuilding the whole dependencies, or just the mod-specific ones.
     RebuildDependencies(bool),
 
-    /// This command is used to trigger a cascade edition on all referenced data.
-    CascadeEdition(String, Definition, Vec<(Field, String, String)>),
+    /// This command is used to get a preview of a cascade edition on all referenced data. Contains the table, its definition, and the field, old value and new value of each edition.
+    CascadeEditionPreview(String, Definition, Vec<(Field, String, String)>),
+
+    /// This command is used to apply cascade editions previously previewed.
+    CascadeEdition(Vec<CascadeEdition>),
+
+    /// This command is used to undo the last edition recorded in the edit log of the open Pack.
+    UndoPackEdit,
+
+    /// This command is used to redo the last edition undone from the edit log of the open Pack.
+    RedoPackEdit,
 
     /// This command is used for the Go To Definition feature. Contains table, column, and value to search.
     GoToDefinition(String, String, String),
@@ -296,8 +306,8 @@ pub enum Command {
     /// This command is used to get the loc file/column/row of a key. Contains the loc key to search.
     GoToLoc(String),
 
-    /// This command is used for the Find References feature. Contains list of table/columns to search, and value to search.
-    SearchReferences(HashMap<String, Vec<String>>, String),
+    /// This command is used for the Find References feature. Contains the table, column, and value to search.
+    SearchReferences(String, String, String),
 
     /// This command is used to get the type of a File.
     //GetFileType(String),
@@ -323,12 +333,24 @@ pub enum Command {
     /// This command is used to upload a schema patch.
     UploadSchemaPatch(String, HashMap<String, DefinitionPatch>),
 
-    /// This command is used to import a schema patch in the local schema patches.
+    /// This command is used to import a schema patch in the local overlay of the schema.
     ImportSchemaPatch(HashMap<String, DefinitionPatch>),
 
+    /// This command is used to upload the local overlay of the schema.
+    UploadSchemaOverlay,
+
     /// This command is used to generate all missing loc entries for the currently open PackFile.
     GenerateMissingLocData,
 
+    /// This command is used to get the names of the tables available in the Assembly Kit of the game selected.
+    GetAssemblyKitTableNames,
+
+    /// This command is used to import tables from the Assembly Kit of the game selected into the currently open PackFile, with the provided file name.
+    ImportAssemblyKitTables(Vec<String>, String),
+
+    /// This command is used to check the provided Packs for conflicts between them.
+    #[cfg(feature = "enable_tools")] AnalyseModConflicts(Vec<PathBuf>),
+
     /// This command is used to check for updates on the tw_autogen thing.
     CheckLuaAutogenUpdates,
 
@@ -482,6 +504,12 @@ pub enum Response {
     HashMapDataSourceHashMapStringRFile(HashMap<DataSource, HashMap<String, RFile>>),
     Diagnostics(Diagnostics),
 
+    /// Response to return `ConflictReport`.
+    #[cfg(feature = "enable_tools")] ConflictReport(ConflictReport),
+
+    /// Response to return `(Vec<CascadeEdition>, Vec<(DataSource, String)>)`.
+    VecCascadeEditionVecDataSourceString(Vec<CascadeEdition>, Vec<(DataSource, String)>),
+
     #[cfg(feature = "enable_tools")] Definition(Definition),
     #[cfg(feature = "enable_tools")] HashMapDataSourceHashSetContainerPath(HashMap<DataSource, HashSet<ContainerPath>>),
     #[cfg(feature = "enable_tools")] VecContainerPathVecContainerPath(Vec<ContainerPath>, Vec<ContainerPath>),
@@ -491,6 +519,7 @@ pub enum Response {
     HashSetString(HashSet<String>),
     StringVecContainerPath(String, Vec<ContainerPath>),
     VecContainerPathVecRFileInfo(Vec<ContainerPath>, Vec<RFileInfo>),
+    VecContainerPathVecContainerPathVecContainerPath(Vec<ContainerPath>, Vec<ContainerPath>, Vec<ContainerPath>),
     StringVecPathBuf(String, Vec<PathBuf>),
 }
 
@@ -58,6 +58,10 @@ pub unsafe fn set_connections(ui: &DiagnosticsUI, slots: &DiagnosticsUISlots) {
     ui.checkbox_incorrect_game_path.toggled().connect(&slots.toggle_filters);
     ui.checkbox_banned_table.toggled().connect(&slots.toggle_filters);
     ui.checkbox_value_cannot_be_empty.toggled().connect(&slots.toggle_filters);
+    ui.checkbox_validation_rule_broken.toggled().connect(&slots.toggle_filters);
+    ui.checkbox_invalid_validation_rule.toggled().connect(&slots.toggle_filters);
+    ui.checkbox_outdated_game_build.toggled().connect(&slots.toggle_filters);
+    ui.checkbox_dependencies_cache_baseline.toggled().connect(&slots.toggle_filters);
     ui.checkbox_invalid_art_set_id.toggled().connect(&slots.toggle_filters);
     ui.checkbox_invalid_variant_filename.toggled().connect(&slots.toggle_filters);
     ui.checkbox_file_diffuse_not_found_for_variant.toggled().connect(&slots.toggle_filters);
@@ -133,6 +133,10 @@ pub struct DiagnosticsUI {
     checkbox_incorrect_game_path: QBox<QCheckBox>,
     checkbox_banned_table: QBox<QCheckBox>,
     checkbox_value_cannot_be_empty: QBox<QCheckBox>,
+    checkbox_validation_rule_broken: QBox<QCheckBox>,
+    checkbox_invalid_validation_rule: QBox<QCheckBox>,
+    checkbox_outdated_game_build: QBox<QCheckBox>,
+    checkbox_dependencies_cache_baseline: QBox<QCheckBox>,
     checkbox_invalid_art_set_id: QBox<QCheckBox>,
     checkbox_invalid_variant_filename: QBox<QCheckBox>,
     checkbox_file_diffuse_not_found_for_variant: QBox<QCheckBox>,
@@ -254,6 +258,10 @@ impl DiagnosticsUI {
         let checkbox_incorrect_game_path = QCheckBox::from_q_string_q_widget(&qtr("label_incorrect_game_path"), &sidebar_scroll_area);
         let checkbox_banned_table = QCheckBox::from_q_string_q_widget(&qtr("label_banned_table"), &sidebar_scroll_area);
         let checkbox_value_cannot_be_empty = QCheckBox::from_q_string_q_widget(&qtr("label_value_cannot_be_empty"), &sidebar_scroll_area);
+        let checkbox_validation_rule_broken = QCheckBox::from_q_string_q_widget(&qtr("label_validation_rule_broken"), &sidebar_scroll_area);
+        let checkbox_invalid_validation_rule = QCheckBox::from_q_string_q_widget(&qtr("label_invalid_validation_rule"), &sidebar_scroll_area);
+        let checkbox_outdated_game_build = QCheckBox::from_q_string_q_widget(&qtr("label_outdated_game_build"), &sidebar_scroll_area);
+        let checkbox_dependencies_cache_baseline = QCheckBox::from_q_string_q_widget(&qtr("label_dependencies_cache_baseline"), &sidebar_scroll_area);
         let checkbox_invalid_art_set_id = QCheckBox::from_q_string_q_widget(&qtr("label_invalid_art_set_id"), &sidebar_scroll_area);
         let checkbox_invalid_variant_filename = QCheckBox::from_q_string_q_widget(&qtr("label_invalid_variant_filename"), &sidebar_scroll_area);
         let checkbox_file_diffuse_not_found_for_variant = QCheckBox::from_q_string_q_widget(&qtr("label_file_diffuse_not_found_for_variant"), &sidebar_scroll_area);
@@ -284,6 +292,10 @@ impl DiagnosticsUI {
         checkbox_incorrect_game_path.set_checked(true);
         checkbox_banned_table.set_checked(true);
         checkbox_value_cannot_be_empty.set_checked(true);
+        checkbox_validation_rule_broken.set_checked(true);
+        checkbox_invalid_validation_rule.set_checked(true);
+        checkbox_outdated_game_build.set_checked(true);
+        checkbox_dependencies_cache_baseline.set_checked(true);
         checkbox_invalid_art_set_id.set_checked(true);
         checkbox_invalid_variant_filename.set_checked(true);
         checkbox_file_diffuse_not_found_for_variant.set_checked(false);
@@ -314,6 +326,10 @@ impl DiagnosticsUI {
         sidebar_grid.add_widget_1a(&checkbox_incorrect_game_path);
         sidebar_grid.add_widget_1a(&checkbox_banned_table);
         sidebar_grid.add_widget_1a(&checkbox_value_cannot_be_empty);
+        sidebar_grid.add_widget_1a(&checkbox_validation_rule_broken);
+        sidebar_grid.add_widget_1a(&checkbox_invalid_validation_rule);
+        sidebar_grid.add_widget_1a(&checkbox_outdated_game_build);
+        sidebar_grid.add_widget_1a(&checkbox_dependencies_cache_baseline);
         sidebar_grid.add_widget_1a(&checkbox_invalid_art_set_id);
         sidebar_grid.add_widget_1a(&checkbox_invalid_variant_filename);
         sidebar_grid.add_widget_1a(&checkbox_file_diffuse_not_found_for_variant);
@@ -366,6 +382,10 @@ impl DiagnosticsUI {
             checkbox_incorrect_game_path,
             checkbox_banned_table,
             checkbox_value_cannot_be_empty,
+            checkbox_validation_rule_broken,
+            checkbox_invalid_validation_rule,
+            checkbox_outdated_game_build,
+            checkbox_dependencies_cache_baseline,
             checkbox_invalid_art_set_id,
             checkbox_invalid_variant_filename,
             checkbox_file_diffuse_not_found_for_variant,
@@ -1342,6 +1362,12 @@ impl DiagnosticsUI {
         if diagnostics_ui.checkbox_value_cannot_be_empty.is_checked() {
             diagnostic_type_pattern.push_str(&format!("{}|", TableDiagnosticReportType::ValueCannotBeEmpty(String::new())));
         }
+        if diagnostics_ui.checkbox_validation_rule_broken.is_checked() {
+            diagnostic_type_pattern.push_str(&format!("{}|", TableDiagnosticReportType::ValidationRuleBroken(String::new(), String::new())));
+        }
+        if diagnostics_ui.checkbox_invalid_validation_rule.is_checked() {
+            diagnostic_type_pattern.push_str(&format!("{}|", TableDiagnosticReportType::InvalidValidationRule(String::new())));
+        }
 
 
         if diagnostics_ui.checkbox_invalid_dependency_packfile.is_checked() {
@@ -1365,6 +1391,14 @@ impl DiagnosticsUI {
             diagnostic_type_pattern.push_str(&format!("{}|", PackDiagnosticReportType::InvalidPackName(String::new())));
         }
 
+        if diagnostics_ui.checkbox_outdated_game_build.is_checked() {
+            diagnostic_type_pattern.push_str(&format!("{}|", PackDiagnosticReportType::OutdatedGameBuild(0, 0)));
+        }
+
+        if diagnostics_ui.checkbox_dependencies_cache_baseline.is_checked() {
+            diagnostic_type_pattern.push_str(&format!("{}|", ConfigDiagnosticReportType::DependenciesCacheBaseline(0, 0)));
+        }
+
         if diagnostics_ui.checkbox_datacored_portrait_settings.is_checked() {
             diagnostic_type_pattern.push_str(&format!("{}|", PortraitSettingsDiagnosticReportType::DatacoredPortraitSettings));
         }
@@ -1517,6 +1551,8 @@ impl DiagnosticsUI {
             TableDiagnosticReportType::FieldWithPathNotFound(_) => qtr("field_with_path_not_found_explanation"),
             TableDiagnosticReportType::BannedTable => qtr("banned_table_explanation"),
             TableDiagnosticReportType::ValueCannotBeEmpty(_) => qtr("value_cannot_be_empty_explanation"),
+            TableDiagnosticReportType::ValidationRuleBroken(_,_) => qtr("validation_rule_broken_explanation"),
+            TableDiagnosticReportType::InvalidValidationRule(_) => qtr("invalid_validation_rule_explanation"),
         };
 
         for item in items {
@@ -1539,6 +1575,7 @@ impl DiagnosticsUI {
             ConfigDiagnosticReportType::DependenciesCacheNotGenerated => qtr("dependencies_cache_not_generated_explanation"),
             ConfigDiagnosticReportType::DependenciesCacheOutdated => qtr("dependencies_cache_outdated_explanation"),
             ConfigDiagnosticReportType::DependenciesCacheCouldNotBeLoaded(error) => qtre("dependencies_cache_could_not_be_loaded_explanation", &[error]),
+            ConfigDiagnosticReportType::DependenciesCacheBaseline(_, _) => qtr("dependencies_cache_baseline_explanation"),
             ConfigDiagnosticReportType::IncorrectGamePath => qtr("incorrect_game_path_explanation"),
         };
 
@@ -1550,6 +1587,7 @@ impl DiagnosticsUI {
     pub unsafe fn set_tooltips_packfile(items: &[&CppBox<QStandardItem>], report_type: &PackDiagnosticReportType) {
         let tool_tip = match report_type {
             PackDiagnosticReportType::InvalidPackName(_) => qtr("invalid_packfile_name_explanation"),
+            PackDiagnosticReportType::OutdatedGameBuild(_, _) => qtr("outdated_game_build_explanation"),
         };
 
         for item in items {
@@ -1627,6 +1665,12 @@ impl DiagnosticsUI {
         if !self.checkbox_value_cannot_be_empty.is_checked() {
             diagnostics_ignored.push(TableDiagnosticReportType::ValueCannotBeEmpty(String::new()).to_string());
         }
+        if !self.checkbox_validation_rule_broken.is_checked() {
+            diagnostics_ignored.push(TableDiagnosticReportType::ValidationRuleBroken(String::new(), String::new()).to_string());
+        }
+        if !self.checkbox_invalid_validation_rule.is_checked() {
+            diagnostics_ignored.push(TableDiagnosticReportType::InvalidValidationRule(String::new()).to_string());
+        }
 
         if !self.checkbox_invalid_dependency_packfile.is_checked() {
             diagnostics_ignored.push(DependencyDiagnosticReportType::InvalidDependencyPackName(String::new()).to_string());
@@ -1649,6 +1693,14 @@ impl DiagnosticsUI {
             diagnostics_ignored.push(PackDiagnosticReportType::InvalidPackName(String::new()).to_string());
         }
 
+        if !self.checkbox_outdated_game_build.is_checked() {
+            diagnostics_ignored.push(PackDiagnosticReportType::OutdatedGameBuild(0, 0).to_string());
+        }
+
+        if !self.checkbox_dependencies_cache_baseline.is_checked() {
+            diagnostics_ignored.push(ConfigDiagnosticReportType::DependenciesCacheBaseline(0, 0).to_string());
+        }
+
         if !self.checkbox_datacored_portrait_settings.is_checked() {
             diagnostics_ignored.push(PortraitSettingsDiagnosticReportType::DatacoredPortraitSettings.to_string());
         }
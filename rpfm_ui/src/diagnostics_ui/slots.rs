@@ -150,6 +150,10 @@ impl DiagnosticsUISlots {
                 let _blocker_25 = QSignalBlocker::from_q_object(diagnostics_ui.checkbox_invalid_variant_filename.static_upcast::<QObject>());
                 let _blocker_26 = QSignalBlocker::from_q_object(diagnostics_ui.checkbox_file_diffuse_not_found_for_variant.static_upcast::<QObject>());
                 let _blocker_27 = QSignalBlocker::from_q_object(diagnostics_ui.checkbox_datacored_portrait_settings.static_upcast::<QObject>());
+                let _blocker_28 = QSignalBlocker::from_q_object(diagnostics_ui.checkbox_validation_rule_broken.static_upcast::<QObject>());
+                let _blocker_29 = QSignalBlocker::from_q_object(diagnostics_ui.checkbox_outdated_game_build.static_upcast::<QObject>());
+                let _blocker_30 = QSignalBlocker::from_q_object(diagnostics_ui.checkbox_dependencies_cache_baseline.static_upcast::<QObject>());
+                let _blocker_31 = QSignalBlocker::from_q_object(diagnostics_ui.checkbox_invalid_validation_rule.static_upcast::<QObject>());
 
                 diagnostics_ui.checkbox_outdated_table.toggle();
                 diagnostics_ui.checkbox_invalid_reference.toggle();
@@ -179,6 +183,10 @@ impl DiagnosticsUISlots {
                 diagnostics_ui.checkbox_invalid_variant_filename.toggle();
                 diagnostics_ui.checkbox_file_diffuse_not_found_for_variant.toggle();
                 diagnostics_ui.checkbox_datacored_portrait_settings.toggle();
+                diagnostics_ui.checkbox_validation_rule_broken.toggle();
+                diagnostics_ui.checkbox_outdated_game_build.toggle();
+                diagnostics_ui.checkbox_dependencies_cache_baseline.toggle();
+                diagnostics_ui.checkbox_invalid_validation_rule.toggle();
 
                 DiagnosticsUI::filter(&app_ui, &diagnostics_ui);
             }
@@ -102,6 +102,7 @@ pub struct GlobalSearchUI {
     search_source_parent: QPtr<QRadioButton>,
     search_source_game: QPtr<QRadioButton>,
     search_source_asskit: QPtr<QRadioButton>,
+    search_source_installed_mods: QPtr<QRadioButton>,
 
     search_on_all_checkbox: QPtr<QCheckBox>,
     search_on_dbs_checkbox: QPtr<QCheckBox>,
@@ -176,10 +177,12 @@ impl GlobalSearchUI {
         let search_source_parent: QPtr<QRadioButton> = find_widget(&main_widget.static_upcast(), "source_parent")?;
         let search_source_game: QPtr<QRadioButton> = find_widget(&main_widget.static_upcast(), "source_game")?;
         let search_source_asskit: QPtr<QRadioButton> = find_widget(&main_widget.static_upcast(), "source_asskit")?;
+        let search_source_installed_mods: QPtr<QRadioButton> = find_widget(&main_widget.static_upcast(), "source_installed_mods")?;
         search_source_packfile.set_text(&qtr("global_search_source_packfile"));
         search_source_parent.set_text(&qtr("global_search_source_parent"));
         search_source_game.set_text(&qtr("global_search_source_game"));
         search_source_asskit.set_text(&qtr("global_search_source_asskit"));
+        search_source_installed_mods.set_text(&qtr("global_search_source_installed_mods"));
 
         let search_source_group_box: QPtr<QGroupBox> = find_widget(&main_widget.static_upcast(), "search_source_groupbox")?;
         search_source_group_box.set_title(&qtr("global_search_search_source"));
@@ -263,6 +266,7 @@ impl GlobalSearchUI {
             search_source_parent,
             search_source_game,
             search_source_asskit,
+            search_source_installed_mods,
 
             search_on_all_checkbox,
             search_on_dbs_checkbox,
@@ -311,6 +315,8 @@ impl GlobalSearchUI {
             global_search.source = SearchSource::GameFiles;
         } else if self.search_source_asskit.is_checked() {
             global_search.source = SearchSource::AssKitFiles;
+        } else if self.search_source_installed_mods.is_checked() {
+            global_search.source = SearchSource::InstalledMods;
         }
 
         if self.search_on_all_checkbox.is_checked() {
@@ -579,7 +585,8 @@ impl GlobalSearchUI {
                 DataSource::PackFile
             },
 
-            SearchSource::ParentFiles => {
+            // Installed mods are loaded as parent files.
+            SearchSource::ParentFiles | SearchSource::InstalledMods => {
                 let tree_index = dependencies_ui.dependencies_tree_view().expand_treeview_to_item(&path, DataSource::ParentFiles);
                 if let Some(ref tree_index) = tree_index {
                     if tree_index.is_valid() {
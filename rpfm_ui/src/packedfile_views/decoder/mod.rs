@@ -1316,13 +1316,18 @@ impl PackedFileDecoderView {
     /// This function adds the definition currently in the view to a temporal schema, and returns it.
     unsafe fn add_definition_to_schema(&self) -> Schema {
         let mut schema = SCHEMA.read().unwrap().clone().unwrap();
-        let mut definition = Definition::new(self.version, None);
-        *definition.fields_mut() = self.get_fields_from_view(None);
-        schema.add_definition(&self.table_name, &definition);
+        schema.add_definition(&self.table_name, &self.definition_from_view());
 
         schema
     }
 
+    /// This function returns the definition currently in the decoder.
+    unsafe fn definition_from_view(&self) -> Definition {
+        let mut definition = Definition::new(self.version, None);
+        *definition.fields_mut() = self.get_fields_from_view(None);
+        definition
+    }
+
     /// This function generates a valid definition using the assembly kit as reference. To stop decoding manually.
     ///
     /// Known issues:
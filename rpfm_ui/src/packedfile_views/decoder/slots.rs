@@ -564,7 +564,7 @@ impl PackedFileDecoderViewSlots {
             app_ui,
             pack_file_contents_ui,
             view => move || {
-                let schema = view.add_definition_to_schema();
+                let definition = view.definition_from_view();
 
                 // Save and close all PackedFiles that use our definition.
                 let mut packed_files_to_save = vec![];
@@ -588,7 +588,7 @@ impl PackedFileDecoderViewSlots {
                 }
 
                 let _ = CENTRAL_COMMAND.send_background(Command::CleanCache(packed_files_to_save));
-                let receiver = CENTRAL_COMMAND.send_background(Command::SaveSchema(schema));
+                let receiver = CENTRAL_COMMAND.send_background(Command::SaveLocalDefinition(view.table_name().to_owned(), definition));
                 let response = CentralCommand::recv(&receiver);
                 match response {
                     Response::Success => show_dialog(&view.table_view, "Schema successfully saved.", true),
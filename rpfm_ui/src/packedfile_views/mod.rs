@@ -29,6 +29,8 @@ use std::sync::{Arc, RwLock, RwLockReadGuard};
 use rpfm_lib::integrations::log::*;
 use rpfm_lib::files::{ContainerPath, db::DB, loc::Loc, FileType, RFileDecoded, text::Text};
 
+use rpfm_extensions::search::SearchSource;
+
 use crate::app_ui::AppUI;
 use crate::CENTRAL_COMMAND;
 use crate::communications::{CentralCommand, Command, Response, THREADS_COMMUNICATION_ERROR};
@@ -709,3 +711,14 @@ impl From<&str> for DataSource {
         }
     }
 }
+
+impl From<&SearchSource> for DataSource {
+    fn from(value: &SearchSource) -> Self {
+        match value {
+            SearchSource::Pack => Self::PackFile,
+            SearchSource::ParentFiles | SearchSource::InstalledMods => Self::ParentFiles,
+            SearchSource::GameFiles => Self::GameFiles,
+            SearchSource::AssKitFiles => Self::AssKitFiles,
+        }
+    }
+}
@@ -46,6 +46,7 @@ pub unsafe fn set_connections(ui: &PackFileContentsUI, slots: &PackFileContentsS
     ui.context_menu_add_file.triggered().connect(&slots.contextual_menu_add_file);
     ui.context_menu_add_folder.triggered().connect(&slots.contextual_menu_add_folder);
     ui.context_menu_add_from_packfile.triggered().connect(&slots.contextual_menu_add_from_packfile);
+    ui.context_menu_add_from_assembly_kit.triggered().connect(&slots.contextual_menu_add_from_assembly_kit);
     ui.context_menu_delete.triggered().connect(&slots.contextual_menu_delete);
     ui.context_menu_extract.triggered().connect(&slots.contextual_menu_extract);
     ui.context_menu_rename.triggered().connect(&slots.contextual_menu_rename);
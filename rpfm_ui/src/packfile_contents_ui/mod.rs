@@ -21,15 +21,19 @@ use qt_widgets::QFileDialog;
 use qt_widgets::QGridLayout;
 use qt_widgets::QLabel;
 use qt_widgets::QLineEdit;
+use qt_widgets::QListView;
 use qt_widgets::QMenu;
+use qt_widgets::QPushButton;
 use qt_widgets::QToolButton;
 use qt_widgets::QTreeView;
 use qt_widgets::QWidget;
 
+use qt_gui::QStandardItem;
 use qt_gui::QStandardItemModel;
 
 use qt_core::QBox;
 use qt_core::CaseSensitivity;
+use qt_core::CheckState;
 use qt_core::DockWidgetArea;
 use qt_core::QObject;
 use qt_core::QPtr;
@@ -100,6 +104,7 @@ pub struct PackFileContentsUI {
     context_menu_add_file: QPtr<QAction>,
     context_menu_add_folder: QPtr<QAction>,
     context_menu_add_from_packfile: QPtr<QAction>,
+    context_menu_add_from_assembly_kit: QPtr<QAction>,
     context_menu_new_folder: QPtr<QAction>,
     context_menu_new_packed_file_anim_pack: QPtr<QAction>,
     context_menu_new_packed_file_db: QPtr<QAction>,
@@ -201,6 +206,7 @@ impl PackFileContentsUI {
         let context_menu_add_file = add_action_to_menu(&menu_add.static_upcast(), app_ui.shortcuts().as_ref(), "pack_tree_context_menu", "add_file", "context_menu_add_file", Some(packfile_contents_tree_view.static_upcast::<qt_widgets::QWidget>()));
         let context_menu_add_folder = add_action_to_menu(&menu_add.static_upcast(), app_ui.shortcuts().as_ref(), "pack_tree_context_menu", "add_folder", "context_menu_add_folder", Some(packfile_contents_tree_view.static_upcast::<qt_widgets::QWidget>()));
         let context_menu_add_from_packfile = add_action_to_menu(&menu_add.static_upcast(), app_ui.shortcuts().as_ref(), "pack_tree_context_menu", "add_from_pack", "context_menu_add_from_packfile", Some(packfile_contents_tree_view.static_upcast::<qt_widgets::QWidget>()));
+        let context_menu_add_from_assembly_kit = add_action_to_menu(&menu_add.static_upcast(), app_ui.shortcuts().as_ref(), "pack_tree_context_menu", "add_from_assembly_kit", "context_menu_add_from_assembly_kit", Some(packfile_contents_tree_view.static_upcast::<qt_widgets::QWidget>()));
         let context_menu_new_folder = add_action_to_menu(&menu_create.static_upcast(), app_ui.shortcuts().as_ref(), "pack_tree_context_menu", "new_folder", "context_menu_new_folder", Some(packfile_contents_tree_view.static_upcast::<qt_widgets::QWidget>()));
         let context_menu_new_packed_file_anim_pack = add_action_to_menu(&menu_create.static_upcast(), app_ui.shortcuts().as_ref(), "pack_tree_context_menu", "new_animpack", "context_menu_new_packed_file_anim_pack", Some(packfile_contents_tree_view.static_upcast::<qt_widgets::QWidget>()));
         let context_menu_new_packed_file_db = add_action_to_menu(&menu_create.static_upcast(), app_ui.shortcuts().as_ref(), "pack_tree_context_menu", "new_db", "context_menu_new_packed_file_db", Some(packfile_contents_tree_view.static_upcast::<qt_widgets::QWidget>()));
@@ -234,6 +240,7 @@ impl PackFileContentsUI {
         context_menu_add_file.set_enabled(false);
         context_menu_add_folder.set_enabled(false);
         context_menu_add_from_packfile.set_enabled(false);
+        context_menu_add_from_assembly_kit.set_enabled(false);
         context_menu_new_folder.set_enabled(false);
         context_menu_new_packed_file_anim_pack.set_enabled(false);
         context_menu_new_packed_file_db.set_enabled(false);
@@ -276,6 +283,7 @@ impl PackFileContentsUI {
             context_menu_add_file,
             context_menu_add_folder,
             context_menu_add_from_packfile,
+            context_menu_add_from_assembly_kit,
 
             context_menu_new_folder,
             context_menu_new_packed_file_anim_pack,
@@ -446,6 +454,58 @@ impl PackFileContentsUI {
         )
     }
 
+    /// This function creates the "Add from Assembly Kit" dialog.
+    ///
+    /// It returns the tables to import and the name of the files to create, or `None` if the dialog is canceled or closed.
+    pub unsafe fn create_assembly_kit_import_dialog(app_ui: &Rc<AppUI>, table_names: &[String]) -> Option<(Vec<String>, String)> {
+
+        // Create and configure the dialog.
+        let dialog = QDialog::new_1a(app_ui.main_window());
+        dialog.set_window_title(&qtr("assembly_kit_import_title"));
+        dialog.set_modal(true);
+        dialog.resize_2a(400, 600);
+        let main_grid = create_grid_layout(dialog.static_upcast());
+
+        let instructions_label = QLabel::from_q_string_q_widget(&qtr("assembly_kit_import_instructions"), &dialog);
+        let tables_list_view = QListView::new_1a(&dialog);
+        let tables_model = QStandardItemModel::new_1a(&tables_list_view);
+        tables_list_view.set_model(&tables_model);
+
+        for table_name in table_names {
+            let item = QStandardItem::from_q_string(&QString::from_std_str(table_name));
+            item.set_checkable(true);
+            item.set_editable(false);
+            item.set_check_state(CheckState::Unchecked);
+            tables_model.append_row_q_standard_item(item.into_ptr());
+        }
+
+        let file_name_line_edit = QLineEdit::from_q_string_q_widget(&QString::from_std_str("assembly_kit"), &dialog);
+        file_name_line_edit.set_placeholder_text(&qtr("assembly_kit_import_file_name"));
+        let accept_button = QPushButton::from_q_string_q_widget(&qtr("assembly_kit_import_accept"), &dialog);
+
+        main_grid.add_widget_5a(&instructions_label, 0, 0, 1, 2);
+        main_grid.add_widget_5a(&tables_list_view, 1, 0, 1, 2);
+        main_grid.add_widget_5a(&file_name_line_edit, 2, 0, 1, 1);
+        main_grid.add_widget_5a(&accept_button, 2, 1, 1, 1);
+
+        accept_button.released().connect(dialog.slot_accept());
+
+        if dialog.exec() == 1 {
+            let file_name = file_name_line_edit.text().to_std_string();
+            let selected_tables = (0..tables_model.row_count_0a())
+                .map(|row| tables_model.item_1a(row))
+                .filter(|item| item.check_state() == CheckState::Checked)
+                .map(|item| item.text().to_std_string())
+                .collect::<Vec<_>>();
+
+            if file_name.is_empty() || selected_tables.is_empty() {
+                None
+            } else {
+                Some((selected_tables, file_name))
+            }
+        } else { None }
+    }
+
     pub unsafe fn extract_packed_files(
         app_ui: &Rc<AppUI>,
         pack_file_contents_ui: &Rc<Self>,
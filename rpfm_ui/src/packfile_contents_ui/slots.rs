@@ -75,6 +75,7 @@ pub struct PackFileContentsSlots {
     pub contextual_menu_add_file: QBox<SlotOfBool>,
     pub contextual_menu_add_folder: QBox<SlotOfBool>,
     pub contextual_menu_add_from_packfile: QBox<SlotOfBool>,
+    pub contextual_menu_add_from_assembly_kit: QBox<SlotOfBool>,
     pub contextual_menu_delete: QBox<SlotOfBool>,
     pub contextual_menu_extract: QBox<SlotOfBool>,
     pub contextual_menu_rename: QBox<SlotOfBool>,
@@ -311,6 +312,7 @@ impl PackFileContentsSlots {
                         pack_file_contents_ui.context_menu_add_file.set_enabled(false);
                         pack_file_contents_ui.context_menu_add_folder.set_enabled(false);
                         pack_file_contents_ui.context_menu_add_from_packfile.set_enabled(true);
+                        pack_file_contents_ui.context_menu_add_from_assembly_kit.set_enabled(true);
                         pack_file_contents_ui.context_menu_new_folder.set_enabled(false);
                         pack_file_contents_ui.context_menu_new_packed_file_anim_pack.set_enabled(false);
                         pack_file_contents_ui.context_menu_new_packed_file_db.set_enabled(true);
@@ -344,6 +346,7 @@ impl PackFileContentsSlots {
 
                         // These options are valid for 1 or more folders.
                         pack_file_contents_ui.context_menu_add_from_packfile.set_enabled(true);
+                        pack_file_contents_ui.context_menu_add_from_assembly_kit.set_enabled(true);
                         pack_file_contents_ui.context_menu_new_packed_file_db.set_enabled(true);
                         pack_file_contents_ui.context_menu_merge_tables.set_enabled(false);
                         pack_file_contents_ui.context_menu_delete.set_enabled(true);
@@ -375,6 +378,7 @@ impl PackFileContentsSlots {
                         pack_file_contents_ui.context_menu_add_file.set_enabled(false);
                         pack_file_contents_ui.context_menu_add_folder.set_enabled(false);
                         pack_file_contents_ui.context_menu_add_from_packfile.set_enabled(true);
+                        pack_file_contents_ui.context_menu_add_from_assembly_kit.set_enabled(true);
                         pack_file_contents_ui.context_menu_new_folder.set_enabled(false);
                         pack_file_contents_ui.context_menu_new_packed_file_anim_pack.set_enabled(false);
                         pack_file_contents_ui.context_menu_new_packed_file_db.set_enabled(true);
@@ -401,6 +405,7 @@ impl PackFileContentsSlots {
                         pack_file_contents_ui.context_menu_add_file.set_enabled(true);
                         pack_file_contents_ui.context_menu_add_folder.set_enabled(true);
                         pack_file_contents_ui.context_menu_add_from_packfile.set_enabled(true);
+                        pack_file_contents_ui.context_menu_add_from_assembly_kit.set_enabled(true);
                         pack_file_contents_ui.context_menu_new_folder.set_enabled(true);
                         pack_file_contents_ui.context_menu_new_packed_file_anim_pack.set_enabled(true);
                         pack_file_contents_ui.context_menu_new_packed_file_db.set_enabled(true);
@@ -427,6 +432,7 @@ impl PackFileContentsSlots {
                         pack_file_contents_ui.context_menu_add_file.set_enabled(false);
                         pack_file_contents_ui.context_menu_add_folder.set_enabled(false);
                         pack_file_contents_ui.context_menu_add_from_packfile.set_enabled(true);
+                        pack_file_contents_ui.context_menu_add_from_assembly_kit.set_enabled(true);
                         pack_file_contents_ui.context_menu_new_folder.set_enabled(false);
                         pack_file_contents_ui.context_menu_new_packed_file_anim_pack.set_enabled(false);
                         pack_file_contents_ui.context_menu_new_packed_file_db.set_enabled(true);
@@ -452,6 +458,7 @@ impl PackFileContentsSlots {
                         pack_file_contents_ui.context_menu_add_file.set_enabled(false);
                         pack_file_contents_ui.context_menu_add_folder.set_enabled(false);
                         pack_file_contents_ui.context_menu_add_from_packfile.set_enabled(true);
+                        pack_file_contents_ui.context_menu_add_from_assembly_kit.set_enabled(true);
                         pack_file_contents_ui.context_menu_new_folder.set_enabled(false);
                         pack_file_contents_ui.context_menu_new_packed_file_anim_pack.set_enabled(false);
                         pack_file_contents_ui.context_menu_new_packed_file_db.set_enabled(true);
@@ -477,6 +484,7 @@ impl PackFileContentsSlots {
                         pack_file_contents_ui.context_menu_add_file.set_enabled(false);
                         pack_file_contents_ui.context_menu_add_folder.set_enabled(false);
                         pack_file_contents_ui.context_menu_add_from_packfile.set_enabled(true);
+                        pack_file_contents_ui.context_menu_add_from_assembly_kit.set_enabled(true);
                         pack_file_contents_ui.context_menu_new_folder.set_enabled(false);
                         pack_file_contents_ui.context_menu_new_packed_file_anim_pack.set_enabled(false);
                         pack_file_contents_ui.context_menu_new_packed_file_db.set_enabled(true);
@@ -503,6 +511,7 @@ impl PackFileContentsSlots {
                         pack_file_contents_ui.context_menu_add_file.set_enabled(false);
                         pack_file_contents_ui.context_menu_add_folder.set_enabled(false);
                         pack_file_contents_ui.context_menu_add_from_packfile.set_enabled(false);
+                        pack_file_contents_ui.context_menu_add_from_assembly_kit.set_enabled(false);
                         pack_file_contents_ui.context_menu_new_folder.set_enabled(false);
                         pack_file_contents_ui.context_menu_new_packed_file_anim_pack.set_enabled(false);
                         pack_file_contents_ui.context_menu_new_packed_file_db.set_enabled(false);
@@ -793,6 +802,43 @@ impl PackFileContentsSlots {
             }
         ));
 
+        // What happens when we trigger the "Add from Assembly Kit" action in the Contextual Menu.
+        let contextual_menu_add_from_assembly_kit = SlotOfBool::new(&pack_file_contents_ui.packfile_contents_dock_widget, clone!(
+            app_ui,
+            pack_file_contents_ui => move |_| {
+                info!("Triggering `Add From Assembly Kit` By Slot");
+
+                let receiver = CENTRAL_COMMAND.send_background(Command::GetAssemblyKitTableNames);
+                let response = CentralCommand::recv(&receiver);
+                let table_names = match response {
+                    Response::VecString(table_names) => table_names,
+                    Response::Error(error) => return show_dialog(app_ui.main_window(), error, false),
+                    _ => panic!("{THREADS_COMMUNICATION_ERROR}{response:?}"),
+                };
+
+                if let Some((table_names, file_name)) = PackFileContentsUI::create_assembly_kit_import_dialog(&app_ui, &table_names) {
+                    app_ui.toggle_main_window(false);
+
+                    let receiver = CENTRAL_COMMAND.send_background(Command::ImportAssemblyKitTables(table_names, file_name));
+                    let response = CENTRAL_COMMAND.recv_try(&receiver);
+                    match response {
+                        Response::StringVecContainerPath(problems, paths) => {
+                            pack_file_contents_ui.packfile_contents_tree_view.update_treeview(true, TreeViewOperation::Add(paths), DataSource::PackFile);
+                            UI_STATE.set_is_modified(true, &app_ui, &pack_file_contents_ui);
+
+                            if !problems.is_empty() {
+                                show_dialog(app_ui.main_window(), tre("assembly_kit_import_problems", &[&problems]), false);
+                            }
+                        }
+                        Response::Error(error) => show_dialog(app_ui.main_window(), error, false),
+                        _ => panic!("{THREADS_COMMUNICATION_ERROR}{response:?}"),
+                    }
+
+                    app_ui.toggle_main_window(true);
+                }
+            }
+        ));
+
         // What happens when we trigger the "Delete" action in the Contextual Menu.
         let contextual_menu_delete = SlotOfBool::new(&pack_file_contents_ui.packfile_contents_dock_widget, clone!(
             app_ui,
@@ -1335,6 +1381,7 @@ impl PackFileContentsSlots {
             contextual_menu_add_file,
             contextual_menu_add_folder,
             contextual_menu_add_from_packfile,
+            contextual_menu_add_from_assembly_kit,
             contextual_menu_delete,
             contextual_menu_extract,
             contextual_menu_rename,
@@ -26,9 +26,11 @@ use anyhow::{anyhow, Result};
 use std::fs::DirBuilder;
 use std::path::PathBuf;
 
+use rpfm_extensions::dependencies::InstalledModsSource;
+
 use rpfm_lib::error::RLibError;
 use rpfm_lib::games::{*, supported_games::*};
-use rpfm_lib::schema::SCHEMA_FOLDER;
+use rpfm_lib::schema::{SCHEMA_FOLDER, overlay::SCHEMA_OVERLAY_FOLDER};
 
 use rpfm_ui_common::*;
 pub use rpfm_ui_common::settings::*;
@@ -43,6 +45,9 @@ pub const ZIP_PATH: &str = "7zip_path";
 /// Key of the MyMod path in the settings";
 pub const MYMOD_BASE_PATH: &str = "mymods_base_path";
 
+/// Key of the sources of installed mods to use as dependencies in the settings, separated by `;`.
+pub const INSTALLED_MODS_SOURCES: &str = "installed_mods_sources";
+
 const DEPENDENCIES_FOLDER: &str = "dependencies";
 
 //-------------------------------------------------------------------------------//
@@ -57,6 +62,7 @@ pub unsafe fn init_settings(main_window: &QPtr<QMainWindow>) {
 
     set_setting_if_new_string(&q_settings, MYMOD_BASE_PATH, "");
     set_setting_if_new_string(&q_settings, ZIP_PATH, "");
+    set_setting_if_new_string(&q_settings, INSTALLED_MODS_SOURCES, "");
 
     for game in &SUPPORTED_GAMES.games() {
         let game_key = game.game_key_name();
@@ -175,6 +181,11 @@ pub fn schemas_path() -> Result<PathBuf> {
     Ok(config_path()?.join(SCHEMA_FOLDER))
 }
 
+/// This function returns the schema overlays path.
+pub fn schema_overlays_path() -> Result<PathBuf> {
+    Ok(config_path()?.join(SCHEMA_OVERLAY_FOLDER))
+}
+
 /// This function returns the lua autogen path.
 pub fn lua_autogen_base_path() -> Result<PathBuf> {
     Ok(config_path()?.join(LUA_AUTOGEN_FOLDER))
@@ -198,6 +209,15 @@ pub fn dependencies_cache_path() -> Result<PathBuf> {
     Ok(config_path()?.join(DEPENDENCIES_FOLDER))
 }
 
+/// This function returns the sources of installed mods to use as dependencies, in order of priority.
+pub fn installed_mods_sources() -> Vec<InstalledModsSource> {
+    setting_string(INSTALLED_MODS_SOURCES)
+        .split(';')
+        .filter(|source| !source.trim().is_empty())
+        .map(InstalledModsSource::from)
+        .collect()
+}
+
 /// This function returns the dependencies path.
 pub fn assembly_kit_path() -> Result<PathBuf> {
     let game_selected = GAME_SELECTED.read().unwrap();
@@ -80,6 +80,7 @@ pub struct SettingsUI {
     // `Path` section of the `Settings` dialog.
     //-------------------------------------------------------------------------------//
     paths_zip_line_edit: QBox<QLineEdit>,
+    paths_installed_mods_line_edit: QBox<QLineEdit>,
     paths_zip_button: QBox<QPushButton>,
     paths_mymod_line_edit: QBox<QLineEdit>,
     paths_mymod_button: QBox<QPushButton>,
@@ -340,6 +341,14 @@ impl SettingsUI {
         extra_paths_grid.add_widget_5a(&paths_zip_line_edit, 1, 1, 1, 1);
         extra_paths_grid.add_widget_5a(&paths_zip_button, 1, 2, 1, 1);
 
+        // Create the installed mods stuff.
+        let paths_installed_mods_label = QLabel::from_q_string_q_widget(&qtr("settings_paths_installed_mods"), &extra_paths_frame);
+        let paths_installed_mods_line_edit = QLineEdit::from_q_widget(&extra_paths_frame);
+        paths_installed_mods_line_edit.set_placeholder_text(&qtr("settings_paths_installed_mods_ph"));
+
+        extra_paths_grid.add_widget_5a(&paths_installed_mods_label, 2, 0, 1, 1);
+        extra_paths_grid.add_widget_5a(&paths_installed_mods_line_edit, 2, 1, 1, 2);
+
         paths_grid.add_widget_5a(&extra_paths_frame, 1, 0, 1, 3);
 
         //-----------------------------------------------//
@@ -705,6 +714,7 @@ impl SettingsUI {
             // `Path` section of the `Settings` dialog.
             //-------------------------------------------------------------------------------//
             paths_zip_line_edit,
+            paths_installed_mods_line_edit,
             paths_zip_button,
             paths_mymod_line_edit,
             paths_mymod_button,
@@ -826,6 +836,7 @@ impl SettingsUI {
         // Load the MyMod and 7Zip paths, if exists.
         self.paths_mymod_line_edit.set_text(&QString::from_std_str(setting_string_from_q_setting(&q_settings, MYMOD_BASE_PATH)));
         self.paths_zip_line_edit.set_text(&QString::from_std_str(setting_string_from_q_setting(&q_settings, ZIP_PATH)));
+        self.paths_installed_mods_line_edit.set_text(&QString::from_std_str(setting_string_from_q_setting(&q_settings, INSTALLED_MODS_SOURCES)));
 
         // Load the Game Paths, if they exists.
         for (key, path) in self.paths_games_line_edits.iter() {
@@ -948,6 +959,7 @@ impl SettingsUI {
 
         set_setting_string_to_q_setting(&q_settings, MYMOD_BASE_PATH, &self.paths_mymod_line_edit.text().to_std_string());
         set_setting_string_to_q_setting(&q_settings, ZIP_PATH, &self.paths_zip_line_edit.text().to_std_string());
+        set_setting_string_to_q_setting(&q_settings, INSTALLED_MODS_SOURCES, &self.paths_installed_mods_line_edit.text().to_std_string());
 
         // For each entry, we check if it's a valid directory and save it into Settings.
         for (key, line_edit) in self.paths_games_line_edits.iter() {
@@ -75,11 +75,12 @@ use std::rc::Rc;
 
 use rpfm_extensions::dependencies::TableReferences;
 
-use rpfm_lib::files::{FileType, db::DB, loc::Loc, table::*};
-use rpfm_lib::schema::{Definition, Field, FieldType, Schema};
+use rpfm_lib::edit_log::EditOperation;
+use rpfm_lib::files::{FileType, db::{CascadeEdition, DB}, loc::Loc, table::*};
+use rpfm_lib::schema::{Definition, Field, FieldType, Schema, validation::{TableValidator, ValidationError}};
 
 use rpfm_ui_common::ASSETS_PATH;
-use rpfm_ui_common::locale::{qtr, qtre, tr};
+use rpfm_ui_common::locale::{qtr, qtre, tr, tre};
 
 use crate::app_ui::AppUI;
 use crate::CENTRAL_COMMAND;
@@ -237,9 +238,6 @@ pub struct TableView {
 
     banned_table: bool,
 
-    #[getset(skip)]
-    reference_map: Arc<HashMap<String, HashMap<String, Vec<String>>>>,
-
     save_lock: Arc<AtomicBool>,
     undo_lock: Arc<AtomicBool>,
 
@@ -520,18 +518,10 @@ impl TableView {
         let timer_delayed_updates = QTimer::new_1a(parent);
         timer_delayed_updates.set_single_shot(true);
 
-        // Get the reference data for this table, to speedup reference searching.
-        let reference_map = if let TableType::NormalTable(_) = table_data {
-            HashMap::new()
-        } else if let Some(schema) = &*SCHEMA.read().unwrap() {
-            if let Some(table_name) = table_name {
-                schema.referencing_columns_for_table(table_name, &table_definition)
-            } else {
-                HashMap::new()
-            }
-        } else {
+        // Schema-based tables need the schema for reference searching.
+        if !matches!(table_data, TableType::NormalTable(_)) && SCHEMA.read().unwrap().is_none() {
             return Err(anyhow!("There is no Schema for the Game Selected."));
-        };
+        }
 
         // Create the raw Struct and begin
         let packed_file_table_view = Arc::new(TableView {
@@ -594,7 +584,6 @@ impl TableView {
             packed_file_path: packed_file_path.clone(),
             packed_file_type: Arc::new(packed_file_type),
             banned_table,
-            reference_map: Arc::new(reference_map),
 
             undo_lock,
             save_lock,
@@ -784,6 +773,58 @@ impl TableView {
         self.data_source.read().unwrap().clone()
     }
 
+    /// This function re-validates the row of an edited item against the validation rules of the table, updating the error marks of its cells.
+    ///
+    /// Cross-column rules depend on other cells of the row, so the entire row is checked. If the edited column must be unique,
+    /// the entire column is checked too, as the edit may have created or removed a duplicate in other rows.
+    pub unsafe fn validate_item(&self, item: Ptr<QStandardItem>) {
+        let definition = self.table_definition();
+        let validator = match TableValidator::new(&definition, Some(definition.patches())) {
+            Ok(validator) => validator,
+            Err(error) => return log_to_status_bar(&error.to_string()),
+        };
+
+        if validator.is_empty() {
+            return;
+        }
+
+        let model = self.table_model_ptr();
+        let row = item.row();
+        let column = item.column() as usize;
+        let row_data = get_row_from_view(&model, &definition, row);
+
+        let blocker = QSignalBlocker::from_q_object(&self.table_model);
+        let mut messages = vec![];
+        for rule_column in validator.columns() {
+            let errors = validator.validate_cell(&row_data, rule_column);
+            let has_error = !errors.is_empty();
+            model.item_2a(row, rule_column as i32).set_data_2a(&QVariant::from_bool(has_error), ITEM_HAS_ERROR);
+
+            if rule_column == column {
+                messages.extend(errors.iter().map(|error| error.to_string()));
+            }
+        }
+
+        if validator.is_column_unique(column) {
+            let data = (0..model.row_count_0a()).map(|row| get_row_from_view(&model, &definition, row)).collect::<Vec<_>>();
+            let errors = validator.validate_column(&data, column);
+            for row_to_check in 0..model.row_count_0a() {
+                let has_error = errors.iter().any(|(row_with_error, _)| *row_with_error == row_to_check as usize);
+                model.item_2a(row_to_check, column as i32).set_data_2a(&QVariant::from_bool(has_error), ITEM_HAS_ERROR);
+            }
+
+            // The per-cell errors of the edited cell are already in the list, so only add the unique ones.
+            messages.extend(errors.iter()
+                .filter(|(row_with_error, error)| *row_with_error == row as usize && matches!(error, ValidationError::NotUnique(_)))
+                .map(|(_, error)| error.to_string()));
+        }
+        blocker.unblock();
+
+        if !messages.is_empty() {
+            log_to_status_bar(&messages.join(" "));
+        }
+    }
+
     pub unsafe fn start_delayed_updates_timer(&self) {
         self.timer_delayed_updates.set_interval(1500);
         self.timer_delayed_updates.start_0a();
@@ -2319,54 +2360,46 @@ impl TableView {
         if let Some(editions) = self.cascade_edition_dialog(&indexes) {
             app_ui.toggle_main_window(false);
 
-            // Trigger editions in our own table.
-            let real_cells = editions.iter()
-                .map(|(_, new_value, row, column)| (self.table_model.index_2a(*row, *column), &**new_value))
-                .collect::<Vec<(CppBox<QModelIndex>, &str)>>();
-
-            let fields_processed = self.table_definition().fields_processed();
-            self.set_data_on_cells(&real_cells, 0, &[], &fields_processed, app_ui, pack_file_contents_ui);
-
-            // Stop the timer again.
-            self.timer_delayed_updates.stop();
-
-            // Now that we know what to edit, save all views of referencing files, so we only have to deal with them in the background.
+            // Save all views first, so the preview is calculated over the latest data.
             let _ = AppUI::back_to_back_end_all(app_ui, pack_file_contents_ui);
 
-            // Then ask the backend to do the heavy work.
+            // Then ask the backend to calculate what needs to be changed.
             let definition = self.table_definition().clone();
             let fields_processed = definition.fields_processed();
             let changes = editions.iter().map(|(value_before, value_after, _, column)|
                 (fields_processed[*column as usize].clone(), value_before.to_string(), value_after.to_string()))
                 .collect::<Vec<_>>();
 
-            let receiver = CENTRAL_COMMAND.send_background(Command::CascadeEdition(table_name, definition, changes));
+            let receiver = CENTRAL_COMMAND.send_background(Command::CascadeEditionPreview(table_name, definition, changes));
             let response = CentralCommand::recv(&receiver);
-            match response {
-                Response::VecContainerPathVecRFileInfo(edited_paths, packed_files_info) => {
-
-                    // If it worked, get the list of edited PackedFiles and update the TreeView to reflect the change.
-                    pack_file_contents_ui.packfile_contents_tree_view().update_treeview(true, TreeViewOperation::Modify(edited_paths.to_vec()), DataSource::PackFile);
-                    pack_file_contents_ui.packfile_contents_tree_view().update_treeview(true, TreeViewOperation::MarkAlwaysModified(edited_paths.to_vec()), DataSource::PackFile);
-                    pack_file_contents_ui.packfile_contents_tree_view().update_treeview(true, TreeViewOperation::UpdateTooltip(packed_files_info), DataSource::PackFile);
-
-                    // Before finishing, reload all edited views.
-                    let mut open_packedfiles = UI_STATE.set_open_packedfiles();
-                    edited_paths.iter().for_each(|path| {
-                        if let Some(file_view) = open_packedfiles.iter_mut().find(|x| *x.path_read() == path.path_raw() && x.data_source() == DataSource::PackFile) {
-                            if file_view.reload(path.path_raw(), pack_file_contents_ui).is_err() {
-                                let _ = AppUI::purge_that_one_specifically(app_ui, pack_file_contents_ui, path.path_raw(), DataSource::PackFile, false);
-                            }
-                        }
-                    });
-
-                    app_ui.toggle_main_window(true);
+            app_ui.toggle_main_window(true);
 
-                    // Now it's safe to trigger the timer.
-                    self.start_delayed_updates_timer();
+            let (previews, external_references) = match response {
+                Response::VecCascadeEditionVecDataSourceString(previews, external_references) => (previews, external_references),
+                Response::Error(error) => {
+                    show_dialog(&self.table_view, error, false);
+                    return self.start_delayed_updates_timer();
                 }
                 _ => panic!("{THREADS_COMMUNICATION_ERROR}{response:?}"),
+            };
+
+            // Let the user review the changes before applying them.
+            if !previews.is_empty() && self.cascade_edition_preview_dialog(&previews, &external_references) {
+                app_ui.toggle_main_window(false);
+
+                let receiver = CENTRAL_COMMAND.send_background(Command::CascadeEdition(previews));
+                let response = CentralCommand::recv(&receiver);
+                match response {
+                    Response::VecContainerPathVecContainerPathVecContainerPath(added, modified, removed) => AppUI::update_views_after_pack_edit(app_ui, pack_file_contents_ui, added, modified, removed),
+                    Response::Error(error) => show_dialog(&self.table_view, error, false),
+                    _ => panic!("{THREADS_COMMUNICATION_ERROR}{response:?}"),
+                }
+
+                app_ui.toggle_main_window(true);
             }
+
+            // Now it's safe to trigger the timer.
+            self.start_delayed_updates_timer();
         }
 
         // If we didn't do anything, but we cut a timer, continue it.
@@ -2416,6 +2449,68 @@ impl TableView {
         } else { None }
     }
 
+    /// This function creates the dialog to review the changes of a cascade edition.
+    ///
+    /// References to the edited values in files outside the Pack are listed too, as they will not be updated.
+    ///
+    /// It returns if the changes have been accepted.
+    pub unsafe fn cascade_edition_preview_dialog(&self, previews: &[CascadeEdition], external_references: &[(DataSource, String)]) -> bool {
+
+        // Create and configure the dialog.
+        let dialog = QDialog::new_1a(&self.table_view);
+        dialog.set_window_title(&qtr("cascade_edition_preview_dialog"));
+        dialog.set_modal(true);
+        dialog.resize_2a(800, 600);
+
+        let main_grid = create_grid_layout(dialog.static_upcast());
+        let instructions_label = QLabel::from_q_string_q_widget(&qtr("cascade_edition_preview_instructions"), &dialog);
+        let changes_text_edit = QTextEdit::from_q_widget(&dialog);
+        changes_text_edit.set_read_only(true);
+
+        let changes = previews.iter()
+            .flat_map(|preview| preview.operations())
+            .map(|operation| match operation {
+                EditOperation::EditCell { path, row, column, before, after } => tre("cascade_edition_preview_cell", &[
+                    path,
+                    &(row + 1).to_string(),
+                    &(column + 1).to_string(),
+                    &before.data_to_string(),
+                    &after.data_to_string(),
+                ]),
+                EditOperation::ReplaceFile { before, .. } => tre("cascade_edition_preview_file", &[before.path_in_container_raw()]),
+                EditOperation::RenameFile { source, destination } => tre("cascade_edition_preview_rename", &[source, destination]),
+                _ => operation.paths().join(", "),
+            })
+            .join("\n");
+        changes_text_edit.set_plain_text(&QString::from_std_str(changes));
+
+        let external_label = QLabel::from_q_string_q_widget(&qtr("cascade_edition_preview_external"), &dialog);
+        let external_text_edit = QTextEdit::from_q_widget(&dialog);
+        external_text_edit.set_read_only(true);
+
+        let external = external_references.iter()
+            .map(|(data_source, path)| format!("{data_source}: {path}"))
+            .join("\n");
+        external_text_edit.set_plain_text(&QString::from_std_str(external));
+        external_label.set_visible(!external_references.is_empty());
+        external_text_edit.set_visible(!external_references.is_empty());
+
+        let accept_button = QPushButton::from_q_string(&qtr("gen_loc_accept"));
+        let cancel_button = QPushButton::from_q_string(&qtr("cascade_edition_preview_cancel"));
+
+        main_grid.add_widget_5a(&instructions_label, 0, 0, 1, 2);
+        main_grid.add_widget_5a(&changes_text_edit, 1, 0, 1, 2);
+        main_grid.add_widget_5a(&external_label, 2, 0, 1, 2);
+        main_grid.add_widget_5a(&external_text_edit, 3, 0, 1, 2);
+        main_grid.add_widget_5a(&accept_button, 4, 0, 1, 1);
+        main_grid.add_widget_5a(&cancel_button, 4, 1, 1, 1);
+
+        accept_button.released().connect(dialog.slot_accept());
+        cancel_button.released().connect(dialog.slot_reject());
+
+        dialog.exec() == 1
+    }
+
     /// This function creates the "Patch Column" dialog and submits a patch of accepted.
     pub unsafe fn patch_column(&self) -> Result<()> {
 
@@ -197,6 +197,9 @@ impl TableViewSlots {
                             blocker.unblock();
                         }
 
+                        // Check the edited item against the validation rules from the schema patches.
+                        view.validate_item(item);
+
                         // For pasting, or really any heavy operation, only do these tasks the last iteration of the operation.
                         if !view.save_lock.load(Ordering::SeqCst) {
                             update_undo_model(&view.table_model_ptr(), &view.undo_model_ptr());
@@ -608,7 +611,7 @@ impl TableViewSlots {
                 let index = view.table_filter.map_to_source(filter_index.as_ref());
                 if index.is_valid() && !view.table_model.item_from_index(&index).is_checkable() {
                     if let Some(field) = view.table_definition.read().unwrap().fields_processed().get(index.column() as usize) {
-                        if let Some(reference_data) = view.reference_map.get(field.name()) {
+                        if let Some(table_name) = view.table_name() {
 
                             // Stop if we have another find already running.
                             if references_ui.references_table_view().is_enabled() {
@@ -616,17 +619,16 @@ impl TableViewSlots {
                                 references_ui.references_table_view().set_enabled(false);
 
                                 let selected_value = index.data_0a().to_string().to_std_string();
-                                let receiver = CENTRAL_COMMAND.send_background(Command::SearchReferences(reference_data.clone(), selected_value));
+                                let receiver = CENTRAL_COMMAND.send_background(Command::SearchReferences(table_name.to_lowercase(), field.name().to_owned(), selected_value));
                                 let response = CENTRAL_COMMAND.recv_try(&receiver);
                                 match response {
-                                    Response::VecDataSourceStringStringUsizeUsize(data) => {
-                                        references_ui.load_references_to_ui(data);
-
-                                        // Reenable the table.
-                                        references_ui.references_table_view().set_enabled(true);
-                                    }
+                                    Response::VecDataSourceStringStringUsizeUsize(data) => references_ui.load_references_to_ui(data),
+                                    Response::Error(error) => show_dialog(&view.table_view, error, false),
                                     _ => panic!("{THREADS_COMMUNICATION_ERROR}{response:?}"),
                                 }
+
+                                // Reenable the table.
+                                references_ui.references_table_view().set_enabled(true);
                             }
                         }
                     }
@@ -965,49 +965,8 @@ pub unsafe fn get_table_from_view(
     definition: &Definition
 ) -> Result<Table> {
     let mut entries = vec![];
-
     for row in 0..model.row_count_0a() {
-        let mut new_row: Vec<DecodedData> = vec![];
-
-        // Bitwise columns can span across multiple columns. That means we have to keep track of the column ourselves.
-        for (column, field) in definition.fields_processed().iter().enumerate() {
-
-            // Create a new Item.
-            let item = match field.field_type() {
-
-                // This one needs a couple of changes before turning it into an item in the table.
-                FieldType::Boolean => DecodedData::Boolean(model.item_2a(row, column as i32).check_state() == CheckState::Checked),
-
-                // Numbers need parsing, and this can fail.
-                FieldType::F32 => DecodedData::F32(model.item_2a(row, column as i32).data_1a(2).to_float_0a()),
-                FieldType::F64 => DecodedData::F64(model.item_2a(row, column as i32).data_1a(2).to_double_0a()),
-                FieldType::I16 => DecodedData::I16(model.item_2a(row, column as i32).data_1a(2).to_int_0a() as i16),
-                FieldType::I32 => DecodedData::I32(model.item_2a(row, column as i32).data_1a(2).to_int_0a()),
-                FieldType::I64 => DecodedData::I64(model.item_2a(row, column as i32).data_1a(2).to_long_long_0a()),
-                FieldType::OptionalI16 => DecodedData::OptionalI16(model.item_2a(row, column as i32).data_1a(2).to_int_0a() as i16),
-                FieldType::OptionalI32 => DecodedData::OptionalI32(model.item_2a(row, column as i32).data_1a(2).to_int_0a()),
-                FieldType::OptionalI64 => DecodedData::OptionalI64(model.item_2a(row, column as i32).data_1a(2).to_long_long_0a()),
-
-                // Colours need parsing to turn them into integers.
-                FieldType::ColourRGB => DecodedData::ColourRGB(QString::to_std_string(&model.item_2a(row, column as i32).text())),
-
-                // All these are just normal Strings.
-                FieldType::StringU8 => DecodedData::StringU8(QString::to_std_string(&model.item_2a(row, column as i32).text())),
-                FieldType::StringU16 => DecodedData::StringU16(QString::to_std_string(&model.item_2a(row, column as i32).text())),
-                FieldType::OptionalStringU8 => DecodedData::OptionalStringU8(QString::to_std_string(&model.item_2a(row, column as i32).text())),
-                FieldType::OptionalStringU16 => DecodedData::OptionalStringU16(QString::to_std_string(&model.item_2a(row, column as i32).text())),
-
-                // Sequences in the UI are not yet supported.
-                FieldType::SequenceU16(_) => DecodedData::SequenceU16(serde_json::from_str(&model.item_2a(row, column as i32).data_1a(ITEM_SEQUENCE_DATA).to_string().to_std_string()).unwrap()),
-                FieldType::SequenceU32(_) => {
-                    let data = model.item_2a(row, column as i32).data_1a(ITEM_SEQUENCE_DATA).to_string().to_std_string();
-                    info!("Sequence data: {}", data);
-                    DecodedData::SequenceU32(serde_json::from_str(&data).unwrap())
-                }
-            };
-            new_row.push(item);
-        }
-        entries.push(new_row);
+        entries.push(get_row_from_view(model, definition, row));
     }
 
     let mut table = Table::new(definition, None, "", false);
@@ -1015,6 +974,56 @@ pub unsafe fn get_table_from_view(
     Ok(table)
 }
 
+/// This function is used to get the data of a specific row of a TableView.
+pub unsafe fn get_row_from_view(
+    model: &QPtr<QStandardItemModel>,
+    definition: &Definition,
+    row: i32
+) -> Vec<DecodedData> {
+    let mut new_row: Vec<DecodedData> = vec![];
+
+    // Bitwise columns can span across multiple columns. That means we have to keep track of the column ourselves.
+    for (column, field) in definition.fields_processed().iter().enumerate() {
+
+        // Create a new Item.
+        let item = match field.field_type() {
+
+            // This one needs a couple of changes before turning it into an item in the table.
+            FieldType::Boolean => DecodedData::Boolean(model.item_2a(row, column as i32).check_state() == CheckState::Checked),
+
+            // Numbers need parsing, and this can fail.
+            FieldType::F32 => DecodedData::F32(model.item_2a(row, column as i32).data_1a(2).to_float_0a()),
+            FieldType::F64 => DecodedData::F64(model.item_2a(row, column as i32).data_1a(2).to_double_0a()),
+            FieldType::I16 => DecodedData::I16(model.item_2a(row, column as i32).data_1a(2).to_int_0a() as i16),
+            FieldType::I32 => DecodedData::I32(model.item_2a(row, column as i32).data_1a(2).to_int_0a()),
+            FieldType::I64 => DecodedData::I64(model.item_2a(row, column as i32).data_1a(2).to_long_long_0a()),
+            FieldType::OptionalI16 => DecodedData::OptionalI16(model.item_2a(row, column as i32).data_1a(2).to_int_0a() as i16),
+            FieldType::OptionalI32 => DecodedData::OptionalI32(model.item_2a(row, column as i32).data_1a(2).to_int_0a()),
+            FieldType::OptionalI64 => DecodedData::OptionalI64(model.item_2a(row, column as i32).data_1a(2).to_long_long_0a()),
+
+            // Colours need parsing to turn them into integers.
+            FieldType::ColourRGB => DecodedData::ColourRGB(QString::to_std_string(&model.item_2a(row, column as i32).text())),
+
+            // All these are just normal Strings.
+            FieldType::StringU8 => DecodedData::StringU8(QString::to_std_string(&model.item_2a(row, column as i32).text())),
+            FieldType::StringU16 => DecodedData::StringU16(QString::to_std_string(&model.item_2a(row, column as i32).text())),
+            FieldType::OptionalStringU8 => DecodedData::OptionalStringU8(QString::to_std_string(&model.item_2a(row, column as i32).text())),
+            FieldType::OptionalStringU16 => DecodedData::OptionalStringU16(QString::to_std_string(&model.item_2a(row, column as i32).text())),
+
+            // Sequences in the UI are not yet supported.
+            FieldType::SequenceU16(_) => DecodedData::SequenceU16(serde_json::from_str(&model.item_2a(row, column as i32).data_1a(ITEM_SEQUENCE_DATA).to_string().to_std_string()).unwrap()),
+            FieldType::SequenceU32(_) => {
+                let data = model.item_2a(row, column as i32).data_1a(ITEM_SEQUENCE_DATA).to_string().to_std_string();
+                info!("Sequence data: {}", data);
+                DecodedData::SequenceU32(serde_json::from_str(&data).unwrap())
+            }
+        };
+        new_row.push(item);
+    }
+
+    new_row
+}
+
 /// This function creates a new subtable from the current table.
 pub unsafe fn open_subtable(
     parent: QPtr<QWidget>,
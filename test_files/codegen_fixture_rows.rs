@@ -0,0 +1,153 @@
+// This file has been generated automatically by rpfm_lib from a schema. Do not edit it manually.
+
+/// Row of the `test_units_tables` table, generated from the definition version 3.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TestUnitsRow {
+    /// Key column.
+    pub key: String,
+    pub is_naval: bool,
+    pub f32: f32,
+    pub f64: f64,
+    pub i16: i16,
+    pub i32: i32,
+    pub i64: i64,
+    pub colour: String,
+    pub name: String,
+    pub optional_i16: i16,
+    pub optional_i32: i32,
+    pub optional_i64: i64,
+    pub optional_string_u8: String,
+    pub optional_string_u16: String,
+    pub sequence_u16: Vec<u8>,
+    pub sequence_u32: Vec<u8>,
+    pub r#type: i32,
+    pub self_: i32,
+    pub a_b: i32,
+    pub a_b_2: i32,
+}
+
+impl ::rpfm_lib::schema::codegen::TableRow for TestUnitsRow {
+    const TABLE_NAME: &'static str = "test_units_tables";
+    const VERSION: i32 = 3;
+}
+
+impl TryFrom<&[::rpfm_lib::files::table::DecodedData]> for TestUnitsRow {
+    type Error = ::rpfm_lib::error::RLibError;
+
+    fn try_from(row: &[::rpfm_lib::files::table::DecodedData]) -> Result<Self, Self::Error> {
+        if row.len() != 20 {
+            return Err(::rpfm_lib::error::RLibError::TableRowWrongFieldCount(20, row.len()));
+        }
+
+        Ok(Self {
+            key: match &row[0] {
+                ::rpfm_lib::files::table::DecodedData::StringU8(value) => value.clone(),
+                value => return Err(::rpfm_lib::error::RLibError::EncodingTableWrongFieldType("StringU8".to_owned(), ::rpfm_lib::schema::FieldType::from(value).to_string())),
+            },
+            is_naval: match &row[1] {
+                ::rpfm_lib::files::table::DecodedData::Boolean(value) => *value,
+                value => return Err(::rpfm_lib::error::RLibError::EncodingTableWrongFieldType("Boolean".to_owned(), ::rpfm_lib::schema::FieldType::from(value).to_string())),
+            },
+            f32: match &row[2] {
+                ::rpfm_lib::files::table::DecodedData::F32(value) => *value,
+                value => return Err(::rpfm_lib::error::RLibError::EncodingTableWrongFieldType("F32".to_owned(), ::rpfm_lib::schema::FieldType::from(value).to_string())),
+            },
+            f64: match &row[3] {
+                ::rpfm_lib::files::table::DecodedData::F64(value) => *value,
+                value => return Err(::rpfm_lib::error::RLibError::EncodingTableWrongFieldType("F64".to_owned(), ::rpfm_lib::schema::FieldType::from(value).to_string())),
+            },
+            i16: match &row[4] {
+                ::rpfm_lib::files::table::DecodedData::I16(value) => *value,
+                value => return Err(::rpfm_lib::error::RLibError::EncodingTableWrongFieldType("I16".to_owned(), ::rpfm_lib::schema::FieldType::from(value).to_string())),
+            },
+            i32: match &row[5] {
+                ::rpfm_lib::files::table::DecodedData::I32(value) => *value,
+                value => return Err(::rpfm_lib::error::RLibError::EncodingTableWrongFieldType("I32".to_owned(), ::rpfm_lib::schema::FieldType::from(value).to_string())),
+            },
+            i64: match &row[6] {
+                ::rpfm_lib::files::table::DecodedData::I64(value) => *value,
+                value => return Err(::rpfm_lib::error::RLibError::EncodingTableWrongFieldType("I64".to_owned(), ::rpfm_lib::schema::FieldType::from(value).to_string())),
+            },
+            colour: match &row[7] {
+                ::rpfm_lib::files::table::DecodedData::ColourRGB(value) => value.clone(),
+                value => return Err(::rpfm_lib::error::RLibError::EncodingTableWrongFieldType("ColourRGB".to_owned(), ::rpfm_lib::schema::FieldType::from(value).to_string())),
+            },
+            name: match &row[8] {
+                ::rpfm_lib::files::table::DecodedData::StringU16(value) => value.clone(),
+                value => return Err(::rpfm_lib::error::RLibError::EncodingTableWrongFieldType("StringU16".to_owned(), ::rpfm_lib::schema::FieldType::from(value).to_string())),
+            },
+            optional_i16: match &row[9] {
+                ::rpfm_lib::files::table::DecodedData::OptionalI16(value) => *value,
+                value => return Err(::rpfm_lib::error::RLibError::EncodingTableWrongFieldType("OptionalI16".to_owned(), ::rpfm_lib::schema::FieldType::from(value).to_string())),
+            },
+            optional_i32: match &row[10] {
+                ::rpfm_lib::files::table::DecodedData::OptionalI32(value) => *value,
+                value => return Err(::rpfm_lib::error::RLibError::EncodingTableWrongFieldType("OptionalI32".to_owned(), ::rpfm_lib::schema::FieldType::from(value).to_string())),
+            },
+            optional_i64: match &row[11] {
+                ::rpfm_lib::files::table::DecodedData::OptionalI64(value) => *value,
+                value => return Err(::rpfm_lib::error::RLibError::EncodingTableWrongFieldType("OptionalI64".to_owned(), ::rpfm_lib::schema::FieldType::from(value).to_string())),
+            },
+            optional_string_u8: match &row[12] {
+                ::rpfm_lib::files::table::DecodedData::OptionalStringU8(value) => value.clone(),
+                value => return Err(::rpfm_lib::error::RLibError::EncodingTableWrongFieldType("OptionalStringU8".to_owned(), ::rpfm_lib::schema::FieldType::from(value).to_string())),
+            },
+            optional_string_u16: match &row[13] {
+                ::rpfm_lib::files::table::DecodedData::OptionalStringU16(value) => value.clone(),
+                value => return Err(::rpfm_lib::error::RLibError::EncodingTableWrongFieldType("OptionalStringU16".to_owned(), ::rpfm_lib::schema::FieldType::from(value).to_string())),
+            },
+            sequence_u16: match &row[14] {
+                ::rpfm_lib::files::table::DecodedData::SequenceU16(value) => value.clone(),
+                value => return Err(::rpfm_lib::error::RLibError::EncodingTableWrongFieldType("SequenceU16".to_owned(), ::rpfm_lib::schema::FieldType::from(value).to_string())),
+            },
+            sequence_u32: match &row[15] {
+                ::rpfm_lib::files::table::DecodedData::SequenceU32(value) => value.clone(),
+                value => return Err(::rpfm_lib::error::RLibError::EncodingTableWrongFieldType("SequenceU32".to_owned(), ::rpfm_lib::schema::FieldType::from(value).to_string())),
+            },
+            r#type: match &row[16] {
+                ::rpfm_lib::files::table::DecodedData::I32(value) => *value,
+                value => return Err(::rpfm_lib::error::RLibError::EncodingTableWrongFieldType("I32".to_owned(), ::rpfm_lib::schema::FieldType::from(value).to_string())),
+            },
+            self_: match &row[17] {
+                ::rpfm_lib::files::table::DecodedData::I32(value) => *value,
+                value => return Err(::rpfm_lib::error::RLibError::EncodingTableWrongFieldType("I32".to_owned(), ::rpfm_lib::schema::FieldType::from(value).to_string())),
+            },
+            a_b: match &row[18] {
+                ::rpfm_lib::files::table::DecodedData::I32(value) => *value,
+                value => return Err(::rpfm_lib::error::RLibError::EncodingTableWrongFieldType("I32".to_owned(), ::rpfm_lib::schema::FieldType::from(value).to_string())),
+            },
+            a_b_2: match &row[19] {
+                ::rpfm_lib::files::table::DecodedData::I32(value) => *value,
+                value => return Err(::rpfm_lib::error::RLibError::EncodingTableWrongFieldType("I32".to_owned(), ::rpfm_lib::schema::FieldType::from(value).to_string())),
+            },
+        })
+    }
+}
+
+impl From<TestUnitsRow> for Vec<::rpfm_lib::files::table::DecodedData> {
+    fn from(row: TestUnitsRow) -> Self {
+        vec![
+            ::rpfm_lib::files::table::DecodedData::StringU8(row.key),
+            ::rpfm_lib::files::table::DecodedData::Boolean(row.is_naval),
+            ::rpfm_lib::files::table::DecodedData::F32(row.f32),
+            ::rpfm_lib::files::table::DecodedData::F64(row.f64),
+            ::rpfm_lib::files::table::DecodedData::I16(row.i16),
+            ::rpfm_lib::files::table::DecodedData::I32(row.i32),
+            ::rpfm_lib::files::table::DecodedData::I64(row.i64),
+            ::rpfm_lib::files::table::DecodedData::ColourRGB(row.colour),
+            ::rpfm_lib::files::table::DecodedData::StringU16(row.name),
+            ::rpfm_lib::files::table::DecodedData::OptionalI16(row.optional_i16),
+            ::rpfm_lib::files::table::DecodedData::OptionalI32(row.optional_i32),
+            ::rpfm_lib::files::table::DecodedData::OptionalI64(row.optional_i64),
+            ::rpfm_lib::files::table::DecodedData::OptionalStringU8(row.optional_string_u8),
+            ::rpfm_lib::files::table::DecodedData::OptionalStringU16(row.optional_string_u16),
+            ::rpfm_lib::files::table::DecodedData::SequenceU16(row.sequence_u16),
+            ::rpfm_lib::files::table::DecodedData::SequenceU32(row.sequence_u32),
+            ::rpfm_lib::files::table::DecodedData::I32(row.r#type),
+            ::rpfm_lib::files::table::DecodedData::I32(row.self_),
+            ::rpfm_lib::files::table::DecodedData::I32(row.a_b),
+            ::rpfm_lib::files::table::DecodedData::I32(row.a_b_2),
+        ]
+    }
+}
+